@@ -1,12 +1,21 @@
-use std::path::PathBuf;
+use std::{
+    io::{BufRead, BufReader},
+    path::PathBuf,
+    process::{exit, Stdio},
+};
 
-use anyhow::Result;
-use cargo_metadata::camino::Utf8PathBuf;
+use anyhow::{Context, Result};
+use cargo_metadata::{camino::Utf8PathBuf, Message};
 
 use crate::{
-    command::{docker::create_docker_command, local::create_local_command, utils::execute_command},
+    command::{
+        docker::create_docker_command,
+        local::{create_local_cargo_command, create_local_command},
+        utils::{execute_command, get_program_test_args},
+    },
+    manifest::sha256_hex,
     utils::{cargo_rerun_if_changed, current_datetime},
-    BuildArgs, BUILD_TARGET, HELPER_TARGET_SUBDIR,
+    BuildArgs, BuildManifest, BUILD_TARGET, HELPER_TARGET_SUBDIR,
 };
 
 /// Build a program with the specified [`BuildArgs`]. The `program_dir` is specified as an argument
@@ -48,11 +57,85 @@ pub fn execute_build_program(
 
     let target_elf_paths = generate_elf_paths(&program_metadata, Some(args))?;
 
+    // Write the build manifest alongside each ELF. The manifest is best-effort, so a failure to
+    // record it only warns, and removes the manifest of a previous build that would be stale.
+    for (target_name, elf_path) in target_elf_paths.iter() {
+        let manifest_path = BuildManifest::path(elf_path);
+        let result = std::fs::read(elf_path)
+            .with_context(|| format!("failed to read {elf_path}"))
+            .and_then(|elf| BuildManifest::new(args, &program_metadata, target_name, &elf))
+            .and_then(|manifest| manifest.save(&manifest_path));
+        if let Err(err) = result {
+            println!("cargo:warning=Failed to write the build manifest of {target_name}: {err:#}");
+            let _ = std::fs::remove_file(&manifest_path);
+        }
+    }
+
     print_elf_paths_cargo_directives(&target_elf_paths);
 
     Ok(target_elf_paths)
 }
 
+/// Compile the integration tests of a program with the specified [`BuildArgs`], without running
+/// them.
+///
+/// The test targets are compiled for the zkVM with the Succinct toolchain, so they must opt out of
+/// the default test harness, which needs unwinding, with `harness = false`.
+///
+/// # Returns
+///
+/// * `Result<Vec<(String, Utf8PathBuf)>>` - A list of mapping from test target names to the paths
+///   to the compiled tests on success, or an error on failure.
+pub fn execute_build_tests(
+    args: &BuildArgs,
+    program_dir: Option<PathBuf>,
+) -> Result<Vec<(String, Utf8PathBuf)>> {
+    if args.docker {
+        anyhow::bail!("building the tests of a program with Docker is not supported");
+    }
+
+    // If the program directory is not specified, use the current directory.
+    let program_dir = program_dir
+        .unwrap_or_else(|| std::env::current_dir().expect("Failed to get current directory."));
+    let program_dir: Utf8PathBuf =
+        program_dir.try_into().expect("Failed to convert PathBuf to Utf8PathBuf");
+
+    // Get the program metadata.
+    let program_metadata_file = program_dir.join("Cargo.toml");
+    let mut program_metadata_cmd = cargo_metadata::MetadataCommand::new();
+    let program_metadata = program_metadata_cmd.manifest_path(program_metadata_file).exec()?;
+
+    let mut cmd = create_local_cargo_command(
+        args,
+        &program_dir,
+        &program_metadata,
+        get_program_test_args(args),
+    );
+    let mut child = cmd.stdout(Stdio::piped()).spawn().context("failed to spawn command")?;
+    let tests = parse_test_executables(BufReader::new(child.stdout.take().unwrap()))?;
+
+    let result = child.wait()?;
+    if !result.success() {
+        // Error message is already printed by cargo.
+        exit(result.code().unwrap_or(1))
+    }
+
+    Ok(tests)
+}
+
+/// Collects the executables of the test targets from the JSON messages of cargo.
+fn parse_test_executables(messages: impl BufRead) -> Result<Vec<(String, Utf8PathBuf)>> {
+    let mut tests = vec![];
+    for message in Message::parse_stream(messages) {
+        if let Message::CompilerArtifact(artifact) = message? {
+            if let (true, Some(executable)) = (artifact.profile.test, artifact.executable) {
+                tests.push((artifact.target.name, executable));
+            }
+        }
+    }
+    Ok(tests)
+}
+
 /// Internal helper function to build the program with or without arguments.
 pub(crate) fn build_program_internal(path: &str, args: Option<BuildArgs>) {
     // Get the root package name and metadata.
@@ -122,7 +205,9 @@ pub fn generate_elf_paths(
 ) -> Result<Vec<(String, Utf8PathBuf)>> {
     let mut target_elf_paths = vec![];
     let packages_to_iterate = if let Some(args) = args {
-        if !args.packages.is_empty() {
+        if args.workspace {
+            metadata.workspace_members.to_vec()
+        } else if !args.packages.is_empty() {
             args.packages
                 .iter()
                 .map(|wanted_package| {
@@ -174,9 +259,132 @@ pub fn generate_elf_paths(
     Ok(target_elf_paths)
 }
 
-/// Prints cargo directives setting relevant `SP1_ELF_` environment variables.
+/// Prints cargo directives setting relevant `SP1_ELF_` environment variables, and the
+/// `SP1_ELF_MANIFEST_` and `SP1_ELF_SHA256_` ones used by
+/// [`include_elf_with_manifest!`](crate::include_elf_with_manifest).
+///
+/// An ELF without a build manifest, e.g. when the build is skipped or its manifest could not be
+/// written, gets an empty placeholder manifest in the `OUT_DIR` of the build script, so that the
+/// host crate still compiles.
 fn print_elf_paths_cargo_directives(target_elf_paths: &[(String, Utf8PathBuf)]) {
     for (target_name, elf_path) in target_elf_paths.iter() {
         println!("cargo:rustc-env=SP1_ELF_{}={}", target_name, elf_path);
+
+        if let Ok(elf) = std::fs::read(elf_path) {
+            println!("cargo:rustc-env=SP1_ELF_SHA256_{}={}", target_name, sha256_hex(&elf));
+        }
+
+        let manifest_path = BuildManifest::path(elf_path);
+        if BuildManifest::load(&manifest_path).is_ok() {
+            println!("cargo:rustc-env=SP1_ELF_MANIFEST_{}={}", target_name, manifest_path);
+            continue;
+        }
+
+        let Ok(out_dir) = std::env::var("OUT_DIR") else {
+            continue;
+        };
+        let placeholder_path =
+            std::path::Path::new(&out_dir).join(format!("{target_name}.manifest.json"));
+        if std::fs::write(&placeholder_path, "").is_ok() {
+            println!(
+                "cargo:rustc-env=SP1_ELF_MANIFEST_{}={}",
+                target_name,
+                placeholder_path.display()
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_test_executables() {
+        let messages = [
+            r#"{"reason":"compiler-artifact","package_id":"path+file:///program#fibonacci-program@0.1.0","manifest_path":"/program/Cargo.toml","target":{"kind":["lib"],"crate_types":["lib"],"name":"fibonacci_program","src_path":"/program/src/lib.rs","edition":"2021","doc":true,"doctest":true,"test":true},"profile":{"opt_level":"3","debuginfo":0,"debug_assertions":false,"overflow_checks":false,"test":false},"features":[],"filenames":["/program/target/libfibonacci_program.rlib"],"executable":null,"fresh":false}"#,
+            r#"{"reason":"compiler-artifact","package_id":"path+file:///program#fibonacci-program@0.1.0","manifest_path":"/program/Cargo.toml","target":{"kind":["test"],"crate_types":["bin"],"name":"fibonacci","src_path":"/program/tests/fibonacci.rs","edition":"2021","doc":false,"doctest":false,"test":true},"profile":{"opt_level":"3","debuginfo":0,"debug_assertions":false,"overflow_checks":false,"test":true},"features":[],"filenames":["/program/target/fibonacci-0123456789abcdef"],"executable":"/program/target/fibonacci-0123456789abcdef","fresh":false}"#,
+            "warning: unused variable: `n`",
+            r#"{"reason":"build-finished","success":true}"#,
+        ]
+        .join("\n");
+
+        let tests = parse_test_executables(messages.as_bytes()).unwrap();
+        assert_eq!(
+            tests,
+            vec![(
+                "fibonacci".to_string(),
+                Utf8PathBuf::from("/program/target/fibonacci-0123456789abcdef")
+            )]
+        );
+    }
+
+    /// Writes a guest workspace with a default member `a`, and a member `b` with an extra binary.
+    fn write_workspace(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("sp1-build-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let files = [
+            (
+                "Cargo.toml",
+                "[workspace]\nmembers = [\"a\", \"b\"]\ndefault-members = [\"a\"]\nresolver = \"2\"\n",
+            ),
+            ("a/Cargo.toml", "[package]\nname = \"a\"\nversion = \"0.1.0\"\nedition = \"2021\"\n"),
+            ("a/src/main.rs", "fn main() {}\n"),
+            ("b/Cargo.toml", "[package]\nname = \"b\"\nversion = \"0.1.0\"\nedition = \"2021\"\n"),
+            ("b/src/main.rs", "fn main() {}\n"),
+            ("b/src/bin/extra.rs", "fn main() {}\n"),
+        ];
+        for (path, contents) in files {
+            let path = dir.join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, contents).unwrap();
+        }
+        dir
+    }
+
+    /// Returns the names of the targets [`generate_elf_paths`] selects in the workspace.
+    fn selected_targets(
+        metadata: &cargo_metadata::Metadata,
+        args: Option<&BuildArgs>,
+    ) -> Vec<String> {
+        let release_dir =
+            metadata.target_directory.join(HELPER_TARGET_SUBDIR).join(BUILD_TARGET).join("release");
+        let mut targets: Vec<_> = generate_elf_paths(metadata, args)
+            .unwrap()
+            .into_iter()
+            .map(|(name, elf_path)| {
+                assert_eq!(elf_path, release_dir.join(&name));
+                name
+            })
+            .collect();
+        targets.sort();
+        targets
+    }
+
+    #[test]
+    fn test_generate_elf_paths_workspace_members() {
+        let dir = write_workspace("members");
+        let metadata = cargo_metadata::MetadataCommand::new()
+            .manifest_path(dir.join("Cargo.toml"))
+            .no_deps()
+            .exec()
+            .unwrap();
+
+        assert_eq!(selected_targets(&metadata, None), ["a"]);
+        assert_eq!(selected_targets(&metadata, Some(&BuildArgs::default())), ["a"]);
+
+        let workspace = BuildArgs { workspace: true, ..Default::default() };
+        assert_eq!(selected_targets(&metadata, Some(&workspace)), ["a", "b", "extra"]);
+
+        let package = BuildArgs { packages: vec!["b".to_string()], ..Default::default() };
+        assert_eq!(selected_targets(&metadata, Some(&package)), ["b", "extra"]);
+
+        let binary = BuildArgs { binaries: vec!["extra".to_string()], ..workspace };
+        assert_eq!(selected_targets(&metadata, Some(&binary)), ["extra"]);
+
+        let unknown = BuildArgs { packages: vec!["c".to_string()], ..Default::default() };
+        assert!(generate_elf_paths(&metadata, Some(&unknown)).is_err());
+
+        std::fs::remove_dir_all(dir).unwrap();
     }
 }
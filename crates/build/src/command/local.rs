@@ -11,6 +11,17 @@ pub(crate) fn create_local_command(
     args: &BuildArgs,
     program_dir: &Utf8PathBuf,
     program_metadata: &cargo_metadata::Metadata,
+) -> Command {
+    create_local_cargo_command(args, program_dir, program_metadata, get_program_build_args(args))
+}
+
+/// Get a cargo command run locally with the Succinct toolchain, and the environment and rust flags
+/// of the [`BuildArgs`].
+pub(crate) fn create_local_cargo_command(
+    args: &BuildArgs,
+    program_dir: &Utf8PathBuf,
+    program_metadata: &cargo_metadata::Metadata,
+    cargo_args: Vec<String>,
 ) -> Command {
     let mut command = Command::new("cargo");
     let canonicalized_program_dir =
@@ -46,7 +57,7 @@ pub(crate) fn create_local_command(
         .env("CARGO_TARGET_DIR", program_metadata.target_directory.join(HELPER_TARGET_SUBDIR))
         // TODO: remove once trim-paths is supported - https://github.com/rust-lang/rust/issues/111540
         .env("RUSTC_BOOTSTRAP", "1") // allows trim-paths.
-        .args(get_program_build_args(args));
+        .args(cargo_args);
     env::vars()
         .map(|v| v.0)
         .filter(|v| v.starts_with("CARGO_FEATURE_") || v.starts_with("CARGO_CFG_"))
@@ -22,6 +22,10 @@ pub(crate) fn get_program_build_args(args: &BuildArgs) -> Vec<String> {
 
     build_args.push("-Ztrim-paths".to_string());
 
+    if args.workspace {
+        build_args.push("--workspace".to_string());
+    }
+
     for p in &args.packages {
         build_args.push("-p".to_string());
         build_args.push(p.to_string());
@@ -48,6 +52,17 @@ pub(crate) fn get_program_build_args(args: &BuildArgs) -> Vec<String> {
     build_args
 }
 
+/// Get the arguments to compile the integration tests of the program without running them, with
+/// the arguments from the [`BuildArgs`] struct.
+pub(crate) fn get_program_test_args(args: &BuildArgs) -> Vec<String> {
+    let mut test_args = get_program_build_args(args);
+    test_args[0] = "test".to_string();
+    test_args.extend(["--test".to_string(), "*".to_string()]);
+    test_args.push("--no-run".to_string());
+    test_args.push("--message-format=json-render-diagnostics".to_string());
+    test_args
+}
+
 /// Rust flags for compilation of C libraries.
 pub(crate) fn get_rust_compiler_flags(args: &BuildArgs) -> String {
     let rust_flags =
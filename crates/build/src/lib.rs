@@ -1,8 +1,10 @@
 mod build;
 mod command;
+mod manifest;
 mod utils;
 use build::build_program_internal;
-pub use build::{execute_build_program, generate_elf_paths};
+pub use build::{execute_build_program, execute_build_tests, generate_elf_paths};
+pub use manifest::{hashes_eq, BuildManifest, ElfWithManifest};
 
 use clap::Parser;
 
@@ -46,6 +48,12 @@ pub struct BuildArgs {
     pub rustflags: Vec<String>,
     #[clap(long, action, help = "Do not activate the `default` feature")]
     pub no_default_features: bool,
+    #[clap(
+        long,
+        action,
+        help = "Build all the packages of the workspace with a single cargo invocation"
+    )]
+    pub workspace: bool,
     #[clap(long, action, help = "Ignore `rust-version` specification in packages")]
     pub ignore_rust_version: bool,
     #[clap(long, action, help = "Assert that `Cargo.lock` will remain unchanged")]
@@ -93,6 +101,7 @@ impl Default for BuildArgs {
             output_directory: DEFAULT_OUTPUT_DIR.to_string(),
             locked: false,
             no_default_features: false,
+            workspace: false,
         }
     }
 }
@@ -127,6 +136,21 @@ pub fn build_program_with_args(path: &str, args: BuildArgs) {
     build_program_internal(path, Some(args))
 }
 
+/// Builds all the programs of the guest workspace at the specified path with a single cargo
+/// invocation, if one of them or one of their dependencies changes.
+///
+/// The programs share the target directory of the workspace and are compiled in parallel. The ELF
+/// of each binary target is then available with [`include_elf!`] by its target name.
+///
+/// # Arguments
+///
+/// * `path` - A string slice that holds the path to the workspace directory.
+///
+/// Set the `SP1_SKIP_PROGRAM_BUILD` environment variable to `true` to skip building the programs.
+pub fn build_workspace(path: &str) {
+    build_program_internal(path, Some(BuildArgs { workspace: true, ..Default::default() }))
+}
+
 /// Returns the raw ELF bytes by the zkVM program target name.
 ///
 /// Note that this only works when using `sp1_build::build_program` or
@@ -142,3 +166,38 @@ macro_rules! include_elf {
         include_bytes!(env!(concat!("SP1_ELF_", $arg)))
     }};
 }
+
+/// Returns the raw ELF bytes by the zkVM program target name, together with the build manifest
+/// written alongside the ELF by `sp1_build::build_program`.
+///
+/// The expected `elf_sha256` of the program can be passed after the target name, in which case the
+/// host crate fails to compile when the built program does not match it. The verification key is
+/// derived from the ELF, so pinning the ELF hash also pins the verification key hash, which is only
+/// recorded in the manifest by `cargo prove verify-build`.
+///
+/// Like [`include_elf!`], this falls back to the last built ELF when `SP1_SKIP_PROGRAM_BUILD` is
+/// set. If that ELF has no build manifest, [`ElfWithManifest::manifest`] returns an error, while the
+/// `elf_sha256` check still applies to the embedded ELF.
+///
+/// ```ignore
+/// const FIBONACCI: ElfWithManifest = include_elf_with_manifest!(
+///     "fibonacci-program",
+///     elf_sha256 = "0x00a4b1a5ec1d2a1ab6e1a3e2b5c43fa59d6e4e6b5a4a5d1fd0d4e3b2a1c0b9a8"
+/// );
+/// ```
+#[macro_export]
+macro_rules! include_elf_with_manifest {
+    ($arg:tt $(, $field:ident = $expected:literal)* $(,)?) => {{
+        $($crate::include_elf_with_manifest!(@check $arg, $field, $expected);)*
+        $crate::ElfWithManifest::new(
+            include_bytes!(env!(concat!("SP1_ELF_", $arg))),
+            include_str!(env!(concat!("SP1_ELF_MANIFEST_", $arg))),
+        )
+    }};
+    (@check $arg:tt, elf_sha256, $expected:literal) => {
+        const _: () = assert!(
+            $crate::hashes_eq(env!(concat!("SP1_ELF_SHA256_", $arg)), $expected),
+            concat!("the ELF hash of ", $arg, " is not ", $expected)
+        );
+    };
+}
@@ -0,0 +1,229 @@
+use std::{
+    fmt::Write,
+    fs,
+    path::Path,
+    process::{Command, Stdio},
+};
+
+use anyhow::{Context, Result};
+use cargo_metadata::{
+    camino::{Utf8Path, Utf8PathBuf},
+    Metadata,
+};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::{command::docker::get_docker_image, BuildArgs};
+
+/// A record of how an ELF was built, from which a third party can rebuild it and check that it
+/// matches.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BuildManifest {
+    /// The version of `sp1-build` that built the ELF.
+    pub sp1_version: String,
+    /// The name of the binary target.
+    pub program: String,
+    /// The SHA-256 hash of the ELF, hex encoded.
+    pub elf_sha256: String,
+    /// The verification key hash of the program, as printed by `cargo prove vkey`. Only recorded by
+    /// `cargo prove verify-build`, as computing it requires the prover.
+    pub vkey_hash: Option<String>,
+    /// The Docker image the ELF was built in, or `None` for a local build.
+    pub docker_image: Option<String>,
+    /// The digest of the Docker image, which pins it even when its tag moves.
+    pub docker_image_digest: Option<String>,
+    /// The version of the Succinct Rust toolchain, as printed by `rustc --version`.
+    pub toolchain: String,
+    /// The SHA-256 hash of the workspace `Cargo.lock`, hex encoded.
+    pub cargo_lock_sha256: Option<String>,
+    /// The features activated for the build.
+    pub features: Vec<String>,
+    /// Whether the `default` feature was deactivated.
+    pub no_default_features: bool,
+    /// The extra flags `rustc` was invoked with.
+    pub rustflags: Vec<String>,
+    /// Who checked that the ELF was reproduced from the source tree.
+    pub signed_off_by: Option<String>,
+}
+
+impl BuildManifest {
+    /// Creates the manifest of an ELF built with the given [`BuildArgs`] in the workspace described
+    /// by `metadata`.
+    pub fn new(args: &BuildArgs, metadata: &Metadata, program: &str, elf: &[u8]) -> Result<Self> {
+        let cargo_lock = metadata.workspace_root.join("Cargo.lock");
+        let cargo_lock_sha256 = match fs::read(&cargo_lock) {
+            Ok(bytes) => Some(sha256_hex(&bytes)),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => None,
+            Err(err) => return Err(err).with_context(|| format!("failed to read {cargo_lock}")),
+        };
+
+        let (docker_image, docker_image_digest, toolchain) = if args.docker {
+            let image = get_docker_image(&args.tag);
+            let digest = docker_image_digest(&image);
+            let toolchain = toolchain_version(Command::new("docker").args([
+                "run",
+                "--rm",
+                "--platform",
+                "linux/amd64",
+                "-e",
+                "RUSTUP_TOOLCHAIN=succinct",
+                "--entrypoint",
+                "",
+                &image,
+                "rustc",
+                "--version",
+            ]))?;
+            (Some(image), digest, toolchain)
+        } else {
+            let toolchain = toolchain_version(
+                Command::new("rustc").env("RUSTUP_TOOLCHAIN", "succinct").arg("--version"),
+            )?;
+            (None, None, toolchain)
+        };
+
+        Ok(Self {
+            sp1_version: env!("CARGO_PKG_VERSION").to_string(),
+            program: program.to_string(),
+            elf_sha256: sha256_hex(elf),
+            vkey_hash: None,
+            docker_image,
+            docker_image_digest,
+            toolchain,
+            cargo_lock_sha256,
+            features: args.features.clone(),
+            no_default_features: args.no_default_features,
+            rustflags: args.rustflags.clone(),
+            signed_off_by: None,
+        })
+    }
+
+    /// Returns the path of the manifest written alongside the ELF at `elf_path`.
+    pub fn path(elf_path: &Utf8Path) -> Utf8PathBuf {
+        format!("{elf_path}.manifest.json").into()
+    }
+
+    /// Reads a manifest from a JSON file.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let bytes = fs::read(path).with_context(|| format!("failed to read {}", path.display()))?;
+        serde_json::from_slice(&bytes)
+            .with_context(|| format!("failed to parse the build manifest {}", path.display()))
+    }
+
+    /// Writes the manifest to a JSON file.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        fs::write(path, serde_json::to_string_pretty(self)? + "\n")
+            .with_context(|| format!("failed to write {}", path.display()))
+    }
+}
+
+/// An ELF embedded together with its build manifest by
+/// [`include_elf_with_manifest!`](crate::include_elf_with_manifest).
+#[derive(Debug, Clone, Copy)]
+pub struct ElfWithManifest {
+    /// The raw ELF bytes.
+    pub elf: &'static [u8],
+    /// The build manifest, as JSON.
+    pub manifest_json: &'static str,
+}
+
+impl ElfWithManifest {
+    #[doc(hidden)]
+    pub const fn new(elf: &'static [u8], manifest_json: &'static str) -> Self {
+        Self { elf, manifest_json }
+    }
+
+    /// Parses the build manifest, checking that it describes the embedded ELF.
+    ///
+    /// Fails when no manifest was embedded, as happens when the program build was skipped with
+    /// `SP1_SKIP_PROGRAM_BUILD` before a manifest was ever written.
+    pub fn manifest(&self) -> Result<BuildManifest> {
+        if self.manifest_json.is_empty() {
+            anyhow::bail!("no build manifest was embedded with the ELF");
+        }
+        let manifest: BuildManifest = serde_json::from_str(self.manifest_json)
+            .context("failed to parse the embedded build manifest")?;
+        let elf_sha256 = sha256_hex(self.elf);
+        if elf_sha256 != manifest.elf_sha256 {
+            anyhow::bail!(
+                "the build manifest of {} is stale: it records the ELF hash {}, but the embedded \
+                 ELF hashes to {elf_sha256}",
+                manifest.program,
+                manifest.elf_sha256
+            );
+        }
+        Ok(manifest)
+    }
+}
+
+/// Compares two hex encoded hashes, ignoring an `0x` prefix and the case.
+///
+/// This is a `const fn`, so that [`include_elf_with_manifest!`](crate::include_elf_with_manifest)
+/// can check the hashes at compile time.
+pub const fn hashes_eq(a: &str, b: &str) -> bool {
+    const fn strip_prefix(hash: &[u8]) -> &[u8] {
+        match hash {
+            [b'0', b'x' | b'X', rest @ ..] => rest,
+            _ => hash,
+        }
+    }
+
+    let (a, b) = (strip_prefix(a.as_bytes()), strip_prefix(b.as_bytes()));
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut i = 0;
+    while i < a.len() {
+        if !a[i].eq_ignore_ascii_case(&b[i]) {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// Returns the SHA-256 hash of the bytes, hex encoded.
+pub(crate) fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes).iter().fold(String::with_capacity(64), |mut hex, byte| {
+        write!(hex, "{byte:02x}").unwrap();
+        hex
+    })
+}
+
+/// Runs a `rustc --version` command and returns its output.
+fn toolchain_version(command: &mut Command) -> Result<String> {
+    let output = command
+        .stderr(Stdio::inherit())
+        .output()
+        .context("failed to get the version of the Succinct toolchain")?;
+    if !output.status.success() {
+        anyhow::bail!("failed to get the version of the Succinct toolchain: {}", output.status);
+    }
+    Ok(String::from_utf8(output.stdout)?.trim().to_string())
+}
+
+/// Returns the repository digest of a local Docker image, if it was pulled from a registry.
+fn docker_image_digest(image: &str) -> Option<String> {
+    let output = Command::new("docker")
+        .args(["image", "inspect", "--format", "{{index .RepoDigests 0}}", image])
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    let digest = String::from_utf8(output.stdout).ok()?.trim().to_string();
+    (output.status.success() && !digest.is_empty()).then_some(digest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::hashes_eq;
+
+    #[test]
+    fn test_hashes_eq() {
+        assert!(hashes_eq("0xabCD", "ABcd"));
+        assert!(hashes_eq("abcd", "0XABCD"));
+        assert!(hashes_eq("0xabcd", "0xabcd"));
+        assert!(!hashes_eq("0xabcd", "abce"));
+        assert!(!hashes_eq("abcd", "abcd00"));
+    }
+}
@@ -28,6 +28,13 @@ pub(crate) fn cargo_rerun_if_changed(metadata: &Metadata, program_dir: &Path) {
     // workspace, this will be the program's Cargo.lock.
     println!("cargo:rerun-if-changed={}", metadata.workspace_root.join("Cargo.lock").as_str());
 
+    // Re-run if any member of the workspace changes, since a workspace build compiles all of them.
+    for package in metadata.workspace_packages() {
+        if let Some(package_dir) = package.manifest_path.parent() {
+            println!("cargo:rerun-if-changed={}", package_dir.as_str());
+        }
+    }
+
     // Re-run if any local dependency changes.
     for package in &metadata.packages {
         for dependency in &package.dependencies {
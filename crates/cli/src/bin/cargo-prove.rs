@@ -2,12 +2,17 @@ use anyhow::Result;
 use clap::{Parser, Subcommand};
 use sp1_cli::{
     commands::{
-        build::BuildCmd, build_toolchain::BuildToolchainCmd,
-        install_toolchain::InstallToolchainCmd, new::NewCmd, vkey::VkeyCmd,
+        build::BuildCmd, build_toolchain::BuildToolchainCmd, disasm::DisasmCmd,
+        execute::ExecuteCmd, inspect::InspectCmd, install_toolchain::InstallToolchainCmd,
+        new::NewCmd, prove::ProveCmd, test::TestCmd, trusted_setup::TrustedSetupCmd,
+        verify::VerifyCmd, verify_build::VerifyBuildCmd, vkey::VkeyCmd,
     },
     SP1_VERSION_MESSAGE,
 };
 
+#[cfg(feature = "profiling")]
+use sp1_cli::commands::profile::ProfileCmd;
+
 #[derive(Parser)]
 #[command(name = "cargo", bin_name = "cargo")]
 pub enum Cargo {
@@ -28,6 +33,16 @@ pub enum ProveCliCommands {
     BuildToolchain(BuildToolchainCmd),
     InstallToolchain(InstallToolchainCmd),
     Vkey(VkeyCmd),
+    Execute(ExecuteCmd),
+    Prove(ProveCmd),
+    Test(TestCmd),
+    Verify(VerifyCmd),
+    VerifyBuild(VerifyBuildCmd),
+    Inspect(InspectCmd),
+    Disasm(DisasmCmd),
+    #[cfg(feature = "profiling")]
+    Profile(ProfileCmd),
+    TrustedSetup(TrustedSetupCmd),
 }
 
 fn main() -> Result<()> {
@@ -39,5 +54,15 @@ fn main() -> Result<()> {
         ProveCliCommands::BuildToolchain(cmd) => cmd.run(),
         ProveCliCommands::InstallToolchain(cmd) => cmd.run(),
         ProveCliCommands::Vkey(cmd) => cmd.run(),
+        ProveCliCommands::Execute(cmd) => cmd.run(),
+        ProveCliCommands::Prove(cmd) => cmd.run(),
+        ProveCliCommands::Test(cmd) => cmd.run(),
+        ProveCliCommands::Verify(cmd) => cmd.run(),
+        ProveCliCommands::VerifyBuild(cmd) => cmd.run(),
+        ProveCliCommands::Inspect(cmd) => cmd.run(),
+        ProveCliCommands::Disasm(cmd) => cmd.run(),
+        #[cfg(feature = "profiling")]
+        ProveCliCommands::Profile(cmd) => cmd.run(),
+        ProveCliCommands::TrustedSetup(cmd) => cmd.run(),
     }
 }
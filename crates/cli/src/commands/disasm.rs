@@ -0,0 +1,152 @@
+use std::{collections::HashMap, fs};
+
+use addr2line::{
+    gimli::{self, EndianSlice, LittleEndian},
+    Context,
+};
+use anyhow::{anyhow, Result};
+use clap::Parser;
+use goblin::elf::{sym::STT_FUNC, Elf as ElfFile};
+use regex::Regex;
+use sp1_sdk::Program;
+
+use crate::commands::vkey::Elf;
+
+#[derive(Parser)]
+#[command(
+    name = "disasm",
+    about = "Disassemble a program, interleaved with its source lines when it has debug info."
+)]
+pub struct DisasmCmd {
+    /// Path to the ELF.
+    #[clap(flatten)]
+    elf: Elf,
+
+    /// Only disassemble the functions whose demangled name matches this regex.
+    #[arg(long)]
+    function: Option<String>,
+
+    /// Do not interleave the source lines.
+    #[arg(long)]
+    no_source: bool,
+}
+
+/// A function of the program.
+struct Function {
+    start: u32,
+    end: u32,
+    name: String,
+}
+
+impl DisasmCmd {
+    pub fn run(&self) -> Result<()> {
+        let bytes = self.elf.read()?;
+        let elf = ElfFile::parse(&bytes)?;
+        let program =
+            Program::from(&bytes).map_err(|err| anyhow!("failed to load program: {err}"))?;
+        let filter = self.function.as_deref().map(Regex::new).transpose()?;
+
+        let mut functions = elf
+            .syms
+            .iter()
+            .filter(|sym| sym.st_type() == STT_FUNC && sym.st_size > 0)
+            .filter_map(|sym| {
+                let name = elf.strtab.get_at(sym.st_name)?;
+                Some(Function {
+                    start: sym.st_value as u32,
+                    end: (sym.st_value + sym.st_size) as u32,
+                    name: format!("{:#}", rustc_demangle::demangle(name)),
+                })
+            })
+            .collect::<Vec<_>>();
+        functions.sort_by_key(|function| function.start);
+
+        let dwarf = if self.no_source { None } else { load_dwarf(&elf, &bytes)? };
+        let mut sources = SourceCache::default();
+        let mut current_function = None;
+        let mut current_line = None;
+
+        for insn in program.disassemble() {
+            let index = functions.partition_point(|function| function.start <= insn.pc);
+            let function = index
+                .checked_sub(1)
+                .map(|index| &functions[index])
+                .filter(|function| insn.pc < function.end);
+
+            if let Some(filter) = &filter {
+                if !function.is_some_and(|function| filter.is_match(&function.name)) {
+                    continue;
+                }
+            }
+
+            let function_start = function.map(|function| function.start);
+            if function_start != current_function {
+                current_function = function_start;
+                current_line = None;
+                if let Some(function) = function {
+                    println!("\n{:08x} <{}>:", function.start, function.name);
+                }
+            }
+
+            if let Some(dwarf) = &dwarf {
+                let location = dwarf.find_location(insn.pc as u64)?;
+                if let Some((file, line)) = location.and_then(|loc| Some((loc.file?, loc.line?))) {
+                    if current_line != Some((file, line)) {
+                        current_line = Some((file, line));
+                        match sources.line(file, line) {
+                            Some(source) => println!("  ; {file}:{line}: {}", source.trim()),
+                            None => println!("  ; {file}:{line}"),
+                        }
+                    }
+                }
+            }
+
+            match insn.syscall {
+                Some(syscall) => {
+                    println!("  {:08x}:  {:?} ; syscall {syscall:?}", insn.pc, insn.instruction)
+                }
+                None => println!("  {:08x}:  {:?}", insn.pc, insn.instruction),
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Loads the DWARF debug info of the ELF, if it has any.
+fn load_dwarf<'a>(
+    elf: &ElfFile<'a>,
+    bytes: &'a [u8],
+) -> Result<Option<Context<EndianSlice<'a, LittleEndian>>>> {
+    let section_data = |name: &str| {
+        elf.section_headers
+            .iter()
+            .find(|header| elf.shdr_strtab.get_at(header.sh_name) == Some(name))
+            .and_then(|header| header.file_range())
+            .and_then(|range| bytes.get(range))
+    };
+    if section_data(".debug_info").is_none() {
+        return Ok(None);
+    }
+
+    let dwarf = gimli::Dwarf::load(|id| -> Result<_, gimli::Error> {
+        Ok(EndianSlice::new(section_data(id.name()).unwrap_or_default(), LittleEndian))
+    })?;
+    Ok(Some(Context::from_dwarf(dwarf)?))
+}
+
+/// The lines of the source files, read on demand.
+#[derive(Default)]
+struct SourceCache {
+    files: HashMap<String, Option<Vec<String>>>,
+}
+
+impl SourceCache {
+    /// Returns the given line of a file, if the file is readable.
+    fn line(&mut self, file: &str, line: u32) -> Option<&str> {
+        let lines = self.files.entry(file.to_string()).or_insert_with(|| {
+            fs::read_to_string(file).ok().map(|source| source.lines().map(String::from).collect())
+        });
+        lines.as_ref()?.get((line as usize).checked_sub(1)?).map(String::as_str)
+    }
+}
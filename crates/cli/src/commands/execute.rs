@@ -0,0 +1,119 @@
+use std::{fs, path::PathBuf};
+
+use anyhow::{Context, Result};
+use clap::{Args, Parser, ValueEnum};
+use sp1_sdk::{ExecutionError, ProverClient, SP1Stdin};
+
+use crate::commands::vkey::Elf;
+
+#[derive(Parser)]
+#[command(name = "execute", about = "Execute a program and print its public values and report.")]
+pub struct ExecuteCmd {
+    /// Path to the ELF.
+    #[clap(flatten)]
+    elf: Elf,
+
+    #[clap(flatten)]
+    stdin: StdinArgs,
+
+    /// The maximum number of cycles to execute the program for.
+    #[arg(long)]
+    max_cycles: Option<u64>,
+}
+
+/// The format of a stdin file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum StdinFormat {
+    /// A bincode-serialized `SP1Stdin`.
+    Bin,
+    /// A JSON-serialized `SP1Stdin`.
+    Json,
+    /// One hex-encoded input per line, each read by the program with `sp1_zkvm::io::read_vec`.
+    Hex,
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct StdinArgs {
+    /// The file to read the stdin of the program from. The program gets an empty stdin if this is
+    /// not set.
+    #[arg(long = "stdin")]
+    path: Option<PathBuf>,
+    /// The format of the stdin file, which is inferred from its extension by default.
+    #[arg(long, value_enum)]
+    stdin_format: Option<StdinFormat>,
+}
+
+impl StdinArgs {
+    /// Reads the stdin of the program.
+    pub fn read(&self) -> Result<SP1Stdin> {
+        let Some(path) = &self.path else {
+            return Ok(SP1Stdin::new());
+        };
+        let format = match self.stdin_format {
+            Some(format) => format,
+            None => match path.extension().and_then(|ext| ext.to_str()) {
+                Some("json") => StdinFormat::Json,
+                Some("hex") => StdinFormat::Hex,
+                _ => StdinFormat::Bin,
+            },
+        };
+
+        let bytes =
+            fs::read(path).with_context(|| format!("failed to read stdin {}", path.display()))?;
+        let stdin = match format {
+            StdinFormat::Bin => bincode::deserialize(&bytes)?,
+            StdinFormat::Json => serde_json::from_slice(&bytes)?,
+            StdinFormat::Hex => {
+                let mut stdin = SP1Stdin::new();
+                for line in String::from_utf8(bytes)?.lines().map(str::trim) {
+                    if !line.is_empty() {
+                        stdin.write_vec(hex::decode(line.trim_start_matches("0x"))?);
+                    }
+                }
+                stdin
+            }
+        };
+
+        Ok(stdin)
+    }
+}
+
+impl ExecuteCmd {
+    pub fn run(&self) -> Result<()> {
+        let elf = self.elf.read()?;
+        let stdin = self.stdin.read()?;
+
+        let prover = ProverClient::builder().cpu().build();
+        let mut execute = prover.execute(&elf, &stdin);
+        if let Some(max_cycles) = self.max_cycles {
+            execute = execute.cycle_limit(max_cycles);
+        }
+
+        let (public_values, report) = match execute.run() {
+            Ok(output) => output,
+            Err(err) => match err.downcast_ref::<ExecutionError>() {
+                Some(ExecutionError::HaltWithNonZeroExitCode(code)) => {
+                    eprintln!("program exited with code {code}");
+                    std::process::exit(*code as i32);
+                }
+                _ => return Err(err),
+            },
+        };
+
+        println!("Public values:\n{}", public_values.raw());
+        println!();
+        println!("Cycles: {}", report.total_instruction_count());
+        println!("Touched memory addresses: {}", report.touched_memory_addresses);
+        print!("{report}");
+        if !report.cycle_tracker.is_empty() {
+            let mut cycle_tracker = report.cycle_tracker.iter().collect::<Vec<_>>();
+            cycle_tracker.sort();
+            println!("cycle tracker:");
+            for (name, cycles) in cycle_tracker {
+                println!("  {name}: {cycles}");
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,178 @@
+use std::{fs, path::PathBuf};
+
+use anyhow::{anyhow, Context, Result};
+use clap::{Args, Parser, Subcommand};
+use goblin::elf::{program_header::pt_to_str, Elf as ElfFile};
+use sp1_sdk::{
+    HashableKey, Program, ProverClient, SP1Proof, SP1ProofMode, SP1ProofWithPublicValues,
+    SP1VerifyingKey,
+};
+
+use crate::commands::{prove::proof_mode_name, vkey::Elf};
+
+#[derive(Parser)]
+#[command(name = "inspect", about = "Inspect a proof, an ELF or a verifying key.")]
+pub struct InspectCmd {
+    #[clap(subcommand)]
+    command: InspectCommands,
+}
+
+#[derive(Subcommand)]
+enum InspectCommands {
+    /// Inspect a proof saved with `SP1ProofWithPublicValues::save`.
+    Proof(PathArgs),
+    /// Inspect an ELF.
+    Elf(ElfArgs),
+    /// Inspect a verifying key.
+    Vkey(VkeyArgs),
+}
+
+#[derive(Args)]
+struct PathArgs {
+    /// The path to the file.
+    path: PathBuf,
+}
+
+#[derive(Args)]
+#[group(required = true, multiple = false)]
+struct VkeyArgs {
+    /// The path to a bincode-serialized `SP1VerifyingKey`
+    #[arg(long)]
+    vkey: Option<PathBuf>,
+    /// The path to the ELF file, whose verifying key is computed
+    #[arg(long = "elf")]
+    path: Option<String>,
+    /// The crate used to generate the ELF file
+    #[arg(long)]
+    program: Option<String>,
+}
+
+#[derive(Args)]
+struct ElfArgs {
+    /// Path to the ELF.
+    #[clap(flatten)]
+    elf: Elf,
+
+    /// Print the symbol table.
+    #[arg(long)]
+    symbols: bool,
+}
+
+impl InspectCmd {
+    pub fn run(&self) -> Result<()> {
+        match &self.command {
+            InspectCommands::Proof(args) => {
+                inspect_proof(&SP1ProofWithPublicValues::load(&args.path)?)
+            }
+            InspectCommands::Elf(args) => inspect_elf(&args.elf.read()?, args.symbols),
+            InspectCommands::Vkey(args) => {
+                let vk = if let Some(path) = &args.vkey {
+                    let bytes = fs::read(path)
+                        .with_context(|| format!("failed to read {}", path.display()))?;
+                    bincode::deserialize(&bytes)?
+                } else {
+                    let elf = Elf { path: args.path.clone(), program: args.program.clone() };
+                    ProverClient::from_env().setup(&elf.read()?).1
+                };
+                inspect_vkey(&vk);
+                Ok(())
+            }
+        }
+    }
+}
+
+fn inspect_proof(proof: &SP1ProofWithPublicValues) -> Result<()> {
+    println!("Mode: {}", proof_mode_name(SP1ProofMode::from(&proof.proof)));
+    println!("SP1 version: {}", proof.sp1_version);
+    println!("Public values: {}", proof.public_values.raw());
+    println!("Public values digest (sha256): 0x{}", hex::encode(proof.public_values.hash()));
+    println!("Public values digest (bn254): {}", proof.public_values.hash_bn254());
+
+    match &proof.proof {
+        SP1Proof::Core(shards) => {
+            println!("Shards: {}", shards.len());
+            for (i, shard) in shards.iter().enumerate() {
+                println!("Shard {i}:");
+                for (chip, log_degree) in shard.shape().chip_information {
+                    println!("  {chip}: 2^{log_degree}");
+                }
+            }
+        }
+        SP1Proof::Compressed(proof) => {
+            println!("Recursion vkey hash: {}", proof.vk.bytes32());
+            println!("Shape:");
+            for (chip, log_degree) in proof.proof.shape().chip_information {
+                println!("  {chip}: 2^{log_degree}");
+            }
+        }
+        SP1Proof::Plonk(proof) => {
+            println!("Public inputs: [{}]", proof.public_inputs.join(", "));
+            println!("Plonk vkey hash: 0x{}", hex::encode(proof.plonk_vkey_hash));
+        }
+        SP1Proof::Groth16(proof) => {
+            println!("Public inputs: [{}]", proof.public_inputs.join(", "));
+            println!("Groth16 vkey hash: 0x{}", hex::encode(proof.groth16_vkey_hash));
+        }
+        SP1Proof::Fflonk(proof) => {
+            println!("Public inputs: [{}]", proof.public_inputs.join(", "));
+            println!("Fflonk vkey hash: 0x{}", hex::encode(proof.fflonk_vkey_hash));
+        }
+    }
+
+    Ok(())
+}
+
+fn inspect_elf(bytes: &[u8], symbols: bool) -> Result<()> {
+    let elf = ElfFile::parse(bytes)?;
+    let program = Program::from(bytes).map_err(|err| anyhow!("failed to load program: {err}"))?;
+
+    println!("Entry point: 0x{:08x}", elf.entry);
+    println!("Segments:");
+    for header in &elf.program_headers {
+        println!(
+            "  {:<12} vaddr 0x{:08x} filesz 0x{:08x} memsz 0x{:08x}",
+            pt_to_str(header.p_type),
+            header.p_vaddr,
+            header.p_filesz,
+            header.p_memsz
+        );
+    }
+    println!("Instructions: {}", program.instructions.len());
+    println!("Program base: 0x{:08x}", program.pc_base);
+    println!(
+        "Memory image: {} words ({} bytes)",
+        program.memory_image.len(),
+        program.memory_image.len() * 4
+    );
+
+    if symbols {
+        let mut symbols = elf
+            .syms
+            .iter()
+            .filter(|sym| sym.st_size > 0)
+            .filter_map(|sym| Some((sym.st_value, sym.st_size, elf.strtab.get_at(sym.st_name)?)))
+            .collect::<Vec<_>>();
+        symbols.sort();
+        println!("Symbols:");
+        for (address, size, name) in symbols {
+            println!("  0x{address:08x} {size:>8} {:#}", rustc_demangle::demangle(name));
+        }
+    }
+
+    Ok(())
+}
+
+fn inspect_vkey(vk: &SP1VerifyingKey) {
+    let hash_babybear = vk.hash_babybear().iter().map(ToString::to_string).collect::<Vec<_>>();
+
+    println!("Verification Key Hash: {}", vk.bytes32());
+    println!("BabyBear hash: [{}]", hash_babybear.join(", "));
+    println!("Start pc: {}", vk.vk.pc_start);
+    println!("Preprocessed chips:");
+    let mut chips = vk.vk.chip_ordering.iter().collect::<Vec<_>>();
+    chips.sort_by_key(|(_, index)| **index);
+    for (name, index) in chips {
+        let (_, _, dimensions) = &vk.vk.chip_information[*index];
+        println!("  {index:>3} {name}: width {} height {}", dimensions.width, dimensions.height);
+    }
+}
@@ -1,5 +1,15 @@
 pub mod build;
 pub mod build_toolchain;
+pub mod disasm;
+pub mod execute;
+pub mod inspect;
 pub mod install_toolchain;
 pub mod new;
+#[cfg(feature = "profiling")]
+pub mod profile;
+pub mod prove;
+pub mod test;
+pub mod trusted_setup;
+pub mod verify;
+pub mod verify_build;
 pub mod vkey;
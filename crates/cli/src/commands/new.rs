@@ -1,5 +1,5 @@
-use anyhow::Result;
-use clap::{Args, Parser};
+use anyhow::{bail, Result};
+use clap::{Args, Parser, ValueEnum};
 use std::{
     fs,
     path::Path,
@@ -17,6 +17,24 @@ struct TemplateType {
     /// Use the `evm` template which includes Solidity smart contracts for onchain integration.
     #[arg(long)]
     evm: bool,
+
+    /// Generate the project from a template embedded in the CLI, without network access.
+    #[arg(long, value_enum)]
+    template: Option<Template>,
+}
+
+/// The templates embedded in the CLI.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Template {
+    /// A program and a script executing and proving it.
+    Script,
+    /// A program committing ABI-encoded public values, and a script generating a Solidity
+    /// contract verifying its proofs, with Foundry tests.
+    Evm,
+    /// A program, and an aggregation program verifying several of its proofs.
+    Aggregation,
+    /// A library-only guest crate with zkVM tests, and a crate of types shared with the host.
+    Lib,
 }
 
 #[derive(Parser)]
@@ -38,6 +56,10 @@ const TEMPLATE_REPOSITORY_URL: &str = "https://github.com/succinctlabs/sp1-proje
 
 impl NewCmd {
     pub fn run(&self) -> Result<()> {
+        if let Some(template) = self.template.template {
+            return self.generate(template);
+        }
+
         let root = Path::new(&self.name);
 
         // Create the root directory if it doesn't exist.
@@ -99,4 +121,622 @@ impl NewCmd {
 
         Ok(())
     }
+
+    /// Generates the project from an embedded template.
+    fn generate(&self, template: Template) -> Result<()> {
+        let root = Path::new(&self.name);
+        if root.exists() && fs::read_dir(root)?.next().is_some() {
+            bail!("the directory {} already exists and is not empty", root.display());
+        }
+
+        let template_name = template.to_possible_value().expect("no skipped templates");
+        println!(
+            "   \x1b[1m{}\x1b[0m {} template",
+            Paint::green("Generating"),
+            template_name.get_name()
+        );
+
+        template.write(root, &self.name)?;
+
+        if template == Template::Evm {
+            println!(
+                "    \x1b[1m{}\x1b[0m Install the contract dependencies with `forge install foundry-rs/forge-std succinctlabs/sp1-contracts --no-git` in {}/contracts",
+                Paint::yellow("Note:"),
+                self.name
+            );
+            println!(
+                "    \x1b[1m{}\x1b[0m The contract and its Foundry test are generated with the first Groth16 proof, by `cargo run --release -- --prove` in {}/script",
+                Paint::yellow("Note:"),
+                self.name
+            );
+        }
+
+        println!(
+            " \x1b[1m{}\x1b[0m {} ({})",
+            Paint::green("Initialized"),
+            self.name,
+            fs::canonicalize(root)?.display()
+        );
+
+        Ok(())
+    }
+}
+
+impl Template {
+    /// The files of the template, as pairs of a path relative to the project root and contents.
+    ///
+    /// The contents may use the `{{PROJECT_NAME}}` and `{{SP1_VERSION}}` placeholders.
+    fn files(self) -> Vec<(&'static str, &'static str)> {
+        match self {
+            Template::Script => vec![
+                (".gitignore", GITIGNORE),
+                ("README.md", SCRIPT_README),
+                ("Cargo.toml", SCRIPT_WORKSPACE),
+                ("program/Cargo.toml", FIBONACCI_PROGRAM_MANIFEST),
+                ("program/src/main.rs", FIBONACCI_PROGRAM),
+                ("script/Cargo.toml", SCRIPT_MANIFEST),
+                ("script/build.rs", SCRIPT_BUILD),
+                ("script/src/main.rs", SCRIPT_MAIN),
+            ],
+            Template::Evm => vec![
+                (".gitignore", GITIGNORE),
+                ("README.md", EVM_README),
+                ("Cargo.toml", EVM_WORKSPACE),
+                ("lib/Cargo.toml", EVM_LIB_MANIFEST),
+                ("lib/src/lib.rs", EVM_LIB),
+                ("program/Cargo.toml", EVM_PROGRAM_MANIFEST),
+                ("program/src/main.rs", EVM_PROGRAM),
+                ("script/Cargo.toml", EVM_SCRIPT_MANIFEST),
+                ("script/build.rs", SCRIPT_BUILD),
+                ("script/src/main.rs", EVM_SCRIPT_MAIN),
+                ("contracts/.gitignore", CONTRACTS_GITIGNORE),
+                ("contracts/foundry.toml", FOUNDRY_TOML),
+            ],
+            Template::Aggregation => vec![
+                (".gitignore", GITIGNORE),
+                ("README.md", AGGREGATION_README),
+                ("Cargo.toml", AGGREGATION_WORKSPACE),
+                ("programs/fibonacci/Cargo.toml", FIBONACCI_PROGRAM_MANIFEST),
+                ("programs/fibonacci/src/main.rs", FIBONACCI_PROGRAM),
+                ("programs/aggregation/Cargo.toml", AGGREGATION_PROGRAM_MANIFEST),
+                ("programs/aggregation/src/main.rs", AGGREGATION_PROGRAM),
+                ("script/Cargo.toml", SCRIPT_MANIFEST),
+                ("script/build.rs", AGGREGATION_SCRIPT_BUILD),
+                ("script/src/main.rs", AGGREGATION_SCRIPT_MAIN),
+            ],
+            Template::Lib => vec![
+                (".gitignore", GITIGNORE),
+                ("README.md", LIB_README),
+                ("Cargo.toml", LIB_WORKSPACE),
+                ("types/Cargo.toml", TYPES_MANIFEST),
+                ("types/src/lib.rs", TYPES_LIB),
+                ("guest/Cargo.toml", GUEST_MANIFEST),
+                ("guest/src/lib.rs", GUEST_LIB),
+                ("guest/tests/zkvm.rs", GUEST_TESTS),
+            ],
+        }
+    }
+
+    /// Writes the files of the template to `root`, for the project `name`.
+    fn write(self, root: &Path, name: &str) -> Result<()> {
+        for (path, contents) in self.files() {
+            let path = root.join(path);
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            let contents = contents
+                .replace("{{PROJECT_NAME}}", name)
+                .replace("{{SP1_VERSION}}", env!("CARGO_PKG_VERSION"));
+            fs::write(path, contents)?;
+        }
+        Ok(())
+    }
+}
+
+const GITIGNORE: &str = r#"target/
+*.bin
+"#;
+
+const FIBONACCI_PROGRAM_MANIFEST: &str = r#"[package]
+name = "fibonacci-program"
+version = "0.1.0"
+edition = "2021"
+publish = false
+
+[dependencies]
+sp1-zkvm = "{{SP1_VERSION}}"
+"#;
+
+const FIBONACCI_PROGRAM: &str = r#"//! A program that takes a number `n` as input, and commits the `n`th and `n+1`th fibonacci
+//! numbers.
+
+// These two lines are necessary for the program to properly compile.
+//
+// Under the hood, we wrap your main function with some extra code so that it behaves properly
+// inside the zkVM.
+#![no_main]
+sp1_zkvm::entrypoint!(main);
+
+pub fn main() {
+    // Read an input to the program.
+    let n = sp1_zkvm::io::read::<u32>();
+
+    // Compute the fibonacci numbers, using normal Rust code.
+    let (mut a, mut b) = (0u32, 1u32);
+    for _ in 0..n {
+        (a, b) = (b, (a + b) % 7919);
+    }
+
+    // Commit the public values of the program.
+    sp1_zkvm::io::commit(&n);
+    sp1_zkvm::io::commit(&a);
+    sp1_zkvm::io::commit(&b);
+}
+"#;
+
+const SCRIPT_BUILD: &str = r#"fn main() {
+    sp1_build::build_program("../program");
+}
+"#;
+
+const SCRIPT_MANIFEST: &str = r#"[package]
+name = "script"
+version = "0.1.0"
+edition = "2021"
+publish = false
+
+[dependencies]
+sp1-sdk = "{{SP1_VERSION}}"
+
+[build-dependencies]
+sp1-build = "{{SP1_VERSION}}"
+"#;
+
+const SCRIPT_WORKSPACE: &str = r#"[workspace]
+members = ["program", "script"]
+resolver = "2"
+"#;
+
+const SCRIPT_README: &str = r#"# {{PROJECT_NAME}}
+
+An SP1 program and a script executing and proving it.
+
+Execute the program:
+
+```sh
+cd script
+cargo run --release
+```
+
+Generate and verify a proof of the program:
+
+```sh
+cd script
+cargo run --release -- --prove
+```
+"#;
+
+const SCRIPT_MAIN: &str = r#"//! Executes the program, or proves it with `--prove`.
+
+use sp1_sdk::{include_elf, HashableKey, ProverClient, SP1Stdin};
+
+/// The ELF of the program, built by `build.rs`.
+const FIBONACCI_ELF: &[u8] = include_elf!("fibonacci-program");
+
+fn main() {
+    sp1_sdk::utils::setup_logger();
+    let prove = std::env::args().any(|arg| arg == "--prove");
+
+    let client = ProverClient::from_env();
+    let mut stdin = SP1Stdin::new();
+    stdin.write(&20u32);
+
+    if !prove {
+        let (mut public_values, report) =
+            client.execute(FIBONACCI_ELF, &stdin).run().expect("execution failed");
+        let (n, a, b) =
+            (public_values.read::<u32>(), public_values.read::<u32>(), public_values.read::<u32>());
+        println!("fibonacci({n}) = {a}, fibonacci({}) = {b}", n + 1);
+        println!("Cycles: {}", report.total_instruction_count());
+        return;
+    }
+
+    let (pk, vk) = client.setup(FIBONACCI_ELF);
+    let proof = client.prove(&pk, &stdin).compressed().run().expect("proving failed");
+    client.verify(&proof, &vk).expect("verification failed");
+    println!("Verified the proof of the program {}", vk.bytes32());
+}
+"#;
+
+const EVM_WORKSPACE: &str = r#"[workspace]
+members = ["lib", "program", "script"]
+resolver = "2"
+"#;
+
+const EVM_README: &str = r#"# {{PROJECT_NAME}}
+
+An SP1 program whose proofs are verified onchain by a Solidity contract.
+
+The public values of the program are defined once in `lib`, committed with their Solidity ABI
+encoding by the program, and decoded by the contract generated by the script.
+
+Execute the program:
+
+```sh
+cd script
+cargo run --release
+```
+
+Generate a Groth16 proof, along with the contract in `contracts/src`, its Foundry test and the
+proof fixture the test reads:
+
+```sh
+cd script
+cargo run --release -- --prove
+```
+
+The contracts directory only holds the Foundry configuration until then, so `forge test` has no
+test to run before the first proof is generated. Then run the Foundry tests:
+
+```sh
+cd contracts
+forge install foundry-rs/forge-std succinctlabs/sp1-contracts --no-git
+forge test
+```
+"#;
+
+const EVM_LIB_MANIFEST: &str = r#"[package]
+name = "fibonacci-lib"
+version = "0.1.0"
+edition = "2021"
+publish = false
+
+[dependencies]
+sp1-primitives = "{{SP1_VERSION}}"
+"#;
+
+const EVM_LIB: &str = r#"//! The public values of the program, shared by the program and the script.
+
+use sp1_primitives::public_values;
+
+public_values! {
+    /// The public values committed by the program.
+    pub struct PublicValues {
+        pub n: u32,
+        pub a: u32,
+        pub b: u32,
+    }
+}
+
+/// Computes the `n`th and `n+1`th fibonacci numbers, modulo 7919.
+pub fn fibonacci(n: u32) -> (u32, u32) {
+    let (mut a, mut b) = (0u32, 1u32);
+    for _ in 0..n {
+        (a, b) = (b, (a + b) % 7919);
+    }
+    (a, b)
+}
+"#;
+
+const EVM_PROGRAM_MANIFEST: &str = r#"[package]
+name = "fibonacci-program"
+version = "0.1.0"
+edition = "2021"
+publish = false
+
+[dependencies]
+fibonacci-lib = { path = "../lib" }
+sp1-zkvm = { version = "{{SP1_VERSION}}", features = ["abi"] }
+"#;
+
+const EVM_PROGRAM: &str = r#"//! A program that takes a number `n` as input, and commits the `n`th and `n+1`th fibonacci
+//! numbers with their Solidity ABI encoding.
+
+#![no_main]
+sp1_zkvm::entrypoint!(main);
+
+use fibonacci_lib::{fibonacci, PublicValues};
+
+pub fn main() {
+    let n = sp1_zkvm::io::read::<u32>();
+    let (a, b) = fibonacci(n);
+    sp1_zkvm::io::commit_abi(&PublicValues { n, a, b });
+}
+"#;
+
+const EVM_SCRIPT_MANIFEST: &str = r#"[package]
+name = "script"
+version = "0.1.0"
+edition = "2021"
+publish = false
+
+[dependencies]
+fibonacci-lib = { path = "../lib" }
+sp1-sdk = "{{SP1_VERSION}}"
+
+[build-dependencies]
+sp1-build = "{{SP1_VERSION}}"
+"#;
+
+const EVM_SCRIPT_MAIN: &str = r#"//! Executes the program, or proves it with `--prove` and exports the contract verifying its
+//! proofs, along with a Foundry test and its proof fixture.
+
+use fibonacci_lib::PublicValues;
+use sp1_sdk::{include_elf, solidity::SolidityGateway, ProverClient, SP1Stdin};
+
+/// The ELF of the program, built by `build.rs`.
+const FIBONACCI_ELF: &[u8] = include_elf!("fibonacci-program");
+
+fn main() {
+    sp1_sdk::utils::setup_logger();
+    let prove = std::env::args().any(|arg| arg == "--prove");
+
+    let client = ProverClient::from_env();
+    let mut stdin = SP1Stdin::new();
+    stdin.write(&20u32);
+
+    if !prove {
+        let (public_values, report) =
+            client.execute(FIBONACCI_ELF, &stdin).run().expect("execution failed");
+        let values = public_values.read_abi::<PublicValues>().expect("invalid public values");
+        println!("{values:?}");
+        println!("Cycles: {}", report.total_instruction_count());
+        return;
+    }
+
+    let (pk, vk) = client.setup(FIBONACCI_ELF);
+    let proof = client.prove(&pk, &stdin).groth16().run().expect("proving failed");
+    client.verify(&proof, &vk).expect("verification failed");
+
+    let gateway = SolidityGateway::new("Fibonacci", &vk).schema::<PublicValues>();
+    gateway.export("../contracts").expect("failed to export the contract");
+    gateway.export_fixture(&proof, "../contracts").expect("failed to export the fixture");
+    println!("Exported the contract and its fixture to ../contracts");
+}
+"#;
+
+const CONTRACTS_GITIGNORE: &str = r#"cache/
+out/
+lib/
+"#;
+
+const FOUNDRY_TOML: &str = r#"[profile.default]
+src = "src"
+out = "out"
+libs = ["lib"]
+fs_permissions = [{ access = "read", path = "./test/fixtures" }]
+remappings = [
+  "@sp1-contracts/=lib/sp1-contracts/contracts/src/",
+  "forge-std/=lib/forge-std/src/",
+]
+"#;
+
+const AGGREGATION_WORKSPACE: &str = r#"[workspace]
+members = ["programs/fibonacci", "programs/aggregation", "script"]
+resolver = "2"
+"#;
+
+const AGGREGATION_README: &str = r#"# {{PROJECT_NAME}}
+
+An SP1 program, and an aggregation program verifying several of its proofs, so that a single
+proof attests to all of them.
+
+Prove the program on several inputs, then aggregate the proofs:
+
+```sh
+cd script
+cargo run --release
+```
+"#;
+
+const AGGREGATION_PROGRAM_MANIFEST: &str = r#"[package]
+name = "aggregation-program"
+version = "0.1.0"
+edition = "2021"
+publish = false
+
+[dependencies]
+sha2 = "0.10.8"
+sp1-zkvm = { version = "{{SP1_VERSION}}", features = ["verify"] }
+"#;
+
+const AGGREGATION_PROGRAM: &str = r#"//! A program that verifies the proofs of other programs, and commits their verification keys
+//! and public values.
+
+#![no_main]
+sp1_zkvm::entrypoint!(main);
+
+use sha2::{Digest, Sha256};
+
+pub fn main() {
+    let vkeys = sp1_zkvm::io::read::<Vec<[u32; 8]>>();
+    let public_values = sp1_zkvm::io::read::<Vec<Vec<u8>>>();
+    assert_eq!(vkeys.len(), public_values.len());
+
+    // The proofs themselves are not read by the program: they are witnessed by the prover, which
+    // checks them when it proves the aggregation.
+    for (vkey, public_values) in vkeys.iter().zip(&public_values) {
+        let digest = Sha256::digest(public_values);
+        sp1_zkvm::lib::verify::verify_sp1_proof(vkey, &digest.into());
+    }
+
+    sp1_zkvm::io::commit(&vkeys);
+    sp1_zkvm::io::commit(&public_values);
+}
+"#;
+
+const AGGREGATION_SCRIPT_BUILD: &str = r#"fn main() {
+    sp1_build::build_program("../programs/fibonacci");
+    sp1_build::build_program("../programs/aggregation");
+}
+"#;
+
+const AGGREGATION_SCRIPT_MAIN: &str = r#"//! Proves the program on several inputs, then aggregates the proofs.
+
+use sp1_sdk::{include_elf, HashableKey, ProverClient, SP1Proof, SP1Stdin};
+
+/// The ELF of the program, built by `build.rs`.
+const FIBONACCI_ELF: &[u8] = include_elf!("fibonacci-program");
+
+/// The ELF of the aggregation program, built by `build.rs`.
+const AGGREGATION_ELF: &[u8] = include_elf!("aggregation-program");
+
+fn main() {
+    sp1_sdk::utils::setup_logger();
+
+    let client = ProverClient::from_env();
+    let (fibonacci_pk, fibonacci_vk) = client.setup(FIBONACCI_ELF);
+    let (aggregation_pk, aggregation_vk) = client.setup(AGGREGATION_ELF);
+
+    // Generate compressed proofs of the program, which can be verified by another program.
+    let proofs = [10u32, 20, 30]
+        .iter()
+        .map(|n| {
+            let mut stdin = SP1Stdin::new();
+            stdin.write(n);
+            client.prove(&fibonacci_pk, &stdin).compressed().run().expect("proving failed")
+        })
+        .collect::<Vec<_>>();
+
+    // Write the verification keys and public values read by the aggregation program, and the
+    // proofs witnessed by the prover.
+    let mut stdin = SP1Stdin::new();
+    stdin.write(&vec![fibonacci_vk.hash_u32(); proofs.len()]);
+    stdin.write(&proofs.iter().map(|proof| proof.public_values.to_vec()).collect::<Vec<_>>());
+    for proof in proofs {
+        let SP1Proof::Compressed(proof) = proof.proof else { unreachable!() };
+        stdin.write_proof(*proof, fibonacci_vk.vk.clone());
+    }
+
+    let proof = client.prove(&aggregation_pk, &stdin).compressed().run().expect("proving failed");
+    client.verify(&proof, &aggregation_vk).expect("verification failed");
+    println!("Verified the aggregated proof of the program {}", aggregation_vk.bytes32());
+}
+"#;
+
+const LIB_WORKSPACE: &str = r#"[workspace]
+members = ["types", "guest"]
+resolver = "2"
+"#;
+
+const LIB_README: &str = r#"# {{PROJECT_NAME}}
+
+A library-only guest crate, for logic shared by several SP1 programs, and a crate of types shared
+by the guest and the host.
+
+The tests in `guest/tests/zkvm.rs` run inside the zkVM:
+
+```sh
+cargo prove test
+```
+"#;
+
+const TYPES_MANIFEST: &str = r#"[package]
+name = "shared-types"
+version = "0.1.0"
+edition = "2021"
+publish = false
+
+[dependencies]
+serde = { version = "1.0", features = ["derive"] }
+"#;
+
+const TYPES_LIB: &str = r#"//! The types exchanged by the programs and the host, through the stdin and the public values.
+
+use serde::{Deserialize, Serialize};
+
+/// The input of a fibonacci computation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FibonacciInput {
+    pub n: u32,
+}
+
+/// The output of a fibonacci computation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FibonacciOutput {
+    /// The `n`th fibonacci number, modulo 7919.
+    pub a: u32,
+    /// The `n+1`th fibonacci number, modulo 7919.
+    pub b: u32,
+}
+"#;
+
+const GUEST_MANIFEST: &str = r#"[package]
+name = "guest-lib"
+version = "0.1.0"
+edition = "2021"
+publish = false
+
+[dependencies]
+shared-types = { path = "../types" }
+
+[dev-dependencies]
+sp1-zkvm = "{{SP1_VERSION}}"
+
+# The tests run inside the zkVM with `cargo prove test`, which needs them to opt out of the default
+# test harness.
+[[test]]
+name = "zkvm"
+harness = false
+"#;
+
+const GUEST_LIB: &str = r#"//! Logic shared by several SP1 programs.
+
+use shared_types::{FibonacciInput, FibonacciOutput};
+
+/// Computes the `n`th and `n+1`th fibonacci numbers, modulo 7919.
+pub fn fibonacci(input: FibonacciInput) -> FibonacciOutput {
+    let (mut a, mut b) = (0u32, 1u32);
+    for _ in 0..input.n {
+        (a, b) = (b, (a + b) % 7919);
+    }
+    FibonacciOutput { a, b }
+}
+"#;
+
+const GUEST_TESTS: &str = r#"//! Tests of the guest library run inside the zkVM, with `cargo prove test`.
+
+#![no_main]
+
+use guest_lib::fibonacci;
+use shared_types::{FibonacciInput, FibonacciOutput};
+
+sp1_zkvm::test_harness! {
+    #[test]
+    fn fibonacci_of_zero() {
+        assert_eq!(fibonacci(FibonacciInput { n: 0 }), FibonacciOutput { a: 0, b: 1 });
+    }
+
+    #[test]
+    fn fibonacci_of_ten() {
+        assert_eq!(fibonacci(FibonacciInput { n: 10 }), FibonacciOutput { a: 55, b: 89 });
+    }
+}
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_templates_are_valid_workspaces() {
+        for template in Template::value_variants() {
+            let name = format!("sp1-template-{}", template.to_possible_value().unwrap().get_name());
+            let root = std::env::temp_dir().join(format!("{name}-{}", std::process::id()));
+            let _ = fs::remove_dir_all(&root);
+            template.write(&root, &name).unwrap();
+
+            // The dependencies are not resolved, as that would need network access.
+            let metadata = cargo_metadata::MetadataCommand::new()
+                .manifest_path(root.join("Cargo.toml"))
+                .no_deps()
+                .exec()
+                .unwrap_or_else(|err| panic!("invalid {template:?} template: {err}"));
+            let members =
+                template.files().iter().filter(|(path, _)| path.ends_with("/Cargo.toml")).count();
+            assert_eq!(metadata.workspace_members.len(), members, "{template:?}");
+
+            fs::remove_dir_all(&root).unwrap();
+        }
+    }
 }
@@ -0,0 +1,244 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Write,
+    fs,
+    path::PathBuf,
+};
+
+use anyhow::{Context, Result};
+use clap::Parser;
+use prettytable::{row, Table};
+use sp1_sdk::{ExecutionError, Profile, ProverClient};
+
+use crate::commands::{execute::StdinArgs, vkey::Elf};
+
+#[derive(Parser)]
+#[command(
+    name = "profile",
+    about = "Profile a program, writing a flamegraph and printing its hottest functions.",
+    long_about = "Profile a program, writing a flamegraph and printing its hottest functions.\n\n\
+    The output directory gets `profile.folded`, the sampled call stacks in the folded format, and \
+    `flamegraph.svg`. Passing the `profile.folded` of an earlier run as the baseline prints the \
+    functions whose cycles changed the most."
+)]
+pub struct ProfileCmd {
+    /// Path to the ELF.
+    #[clap(flatten)]
+    elf: Elf,
+
+    #[clap(flatten)]
+    stdin: StdinArgs,
+
+    /// The directory to write the profile to.
+    #[arg(long, default_value = "profile")]
+    output_dir: PathBuf,
+
+    /// The number of cycles between samples of the call stack.
+    #[arg(long, default_value_t = 1)]
+    sample_rate: u64,
+
+    /// The number of functions to print.
+    #[arg(long, default_value_t = 20)]
+    top: usize,
+
+    /// The `profile.folded` of an earlier run to compare the profile with.
+    #[arg(long)]
+    baseline: Option<PathBuf>,
+}
+
+impl ProfileCmd {
+    pub fn run(&self) -> Result<()> {
+        let elf = self.elf.read()?;
+        let stdin = self.stdin.read()?;
+
+        let prover = ProverClient::builder().cpu().build();
+        let profile = match prover.execute(&elf, &stdin).run_profiled(self.sample_rate) {
+            Ok((_, _, profile)) => profile,
+            Err(err) => match err.downcast_ref::<ExecutionError>() {
+                Some(ExecutionError::HaltWithNonZeroExitCode(code)) => {
+                    eprintln!("program exited with code {code}");
+                    std::process::exit(*code as i32);
+                }
+                _ => return Err(err),
+            },
+        };
+
+        fs::create_dir_all(&self.output_dir).context("failed to create output directory")?;
+        let folded_path = self.output_dir.join("profile.folded");
+        fs::write(&folded_path, profile.folded()).context("failed to write folded stacks")?;
+        let svg_path = self.output_dir.join("flamegraph.svg");
+        fs::write(&svg_path, flamegraph(&profile)).context("failed to write flamegraph")?;
+        println!("Wrote {} and {}", folded_path.display(), svg_path.display());
+
+        self.print_functions(&profile);
+        print_syscalls(&profile);
+
+        if let Some(baseline) = &self.baseline {
+            let folded = fs::read_to_string(baseline)
+                .with_context(|| format!("failed to read {}", baseline.display()))?;
+            self.print_diff(&Profile::from_folded(&folded)?, &profile);
+        }
+
+        Ok(())
+    }
+
+    fn print_functions(&self, profile: &Profile) {
+        let total = profile.total_cycles();
+        let mut table = Table::new();
+        table.set_titles(row!["Function", "Self cycles", "Self %", "Total cycles", "Total %"]);
+        for function in profile.functions().into_iter().take(self.top) {
+            table.add_row(row![
+                function.name,
+                r->function.self_cycles,
+                r->percentage(function.self_cycles, total),
+                r->function.total_cycles,
+                r->percentage(function.total_cycles, total),
+            ]);
+        }
+        println!("\nHottest functions ({total} sampled cycles):");
+        table.printstd();
+    }
+
+    fn print_diff(&self, baseline: &Profile, profile: &Profile) {
+        let mut cycles = HashMap::<String, (u64, u64)>::new();
+        for function in baseline.functions() {
+            cycles.entry(function.name).or_default().0 = function.self_cycles;
+        }
+        for function in profile.functions() {
+            cycles.entry(function.name).or_default().1 = function.self_cycles;
+        }
+        let mut changes =
+            cycles.into_iter().filter(|(_, (old, new))| old != new).collect::<Vec<_>>();
+        changes.sort_by_key(|(name, (old, new))| {
+            (std::cmp::Reverse(old.abs_diff(*new)), name.clone())
+        });
+
+        let mut table = Table::new();
+        table.set_titles(row!["Function", "Baseline", "Current", "Change"]);
+        for (name, (old, new)) in changes.into_iter().take(self.top) {
+            table.add_row(row![name, r->old, r->new, r->change(old, new)]);
+        }
+        let (old, new) = (baseline.total_cycles(), profile.total_cycles());
+        println!("\nChanges from the baseline (self cycles, {} in total):", change(old, new));
+        table.printstd();
+    }
+}
+
+fn print_syscalls(profile: &Profile) {
+    if profile.syscalls.is_empty() {
+        return;
+    }
+
+    let mut table = Table::new();
+    table.set_titles(row!["Syscall", "Calls"]);
+    for syscall in &profile.syscalls {
+        table.add_row(row![format!("{:?}", syscall.syscall), r->syscall.calls]);
+    }
+    println!("\nSyscalls:");
+    table.printstd();
+}
+
+fn percentage(cycles: u64, total: u64) -> String {
+    format!("{:.2}%", cycles as f64 * 100.0 / total.max(1) as f64)
+}
+
+fn change(old: u64, new: u64) -> String {
+    let delta = new as i128 - old as i128;
+    if old == 0 {
+        format!("{delta:+}")
+    } else {
+        format!("{delta:+} ({:+.2}%)", delta as f64 * 100.0 / old as f64)
+    }
+}
+
+/// A node of the call tree.
+#[derive(Default)]
+struct Node {
+    cycles: u64,
+    children: BTreeMap<String, Node>,
+}
+
+const WIDTH: f64 = 1200.0;
+const FRAME_HEIGHT: f64 = 16.0;
+const MARGIN: f64 = 10.0;
+const FONT_WIDTH: f64 = 7.0;
+
+/// Renders the profile as an SVG flamegraph, with the outermost functions at the bottom.
+fn flamegraph(profile: &Profile) -> String {
+    let mut root = Node::default();
+    let mut depth = 0;
+    for (stack, cycles) in &profile.stacks {
+        root.cycles += cycles;
+        let mut node = &mut root;
+        for name in stack {
+            node = node.children.entry(name.clone()).or_default();
+            node.cycles += cycles;
+        }
+        depth = depth.max(stack.len());
+    }
+
+    let height = (depth + 1) as f64 * FRAME_HEIGHT + 2.0 * MARGIN;
+    let mut svg = String::new();
+    writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{WIDTH}" height="{height}" font-family="monospace" font-size="11">"#
+    )
+    .unwrap();
+    let total = root.cycles.max(1) as f64;
+    let scale = (WIDTH - 2.0 * MARGIN) / total;
+    write_frame(&mut svg, "all", &root, 0, MARGIN, height, scale, total);
+    svg.push_str("</svg>\n");
+    svg
+}
+
+#[allow(clippy::too_many_arguments)]
+fn write_frame(
+    svg: &mut String,
+    name: &str,
+    node: &Node,
+    depth: usize,
+    x: f64,
+    height: f64,
+    scale: f64,
+    total: f64,
+) {
+    let width = node.cycles as f64 * scale;
+    if width < 0.1 {
+        return;
+    }
+
+    let y = height - MARGIN - (depth + 1) as f64 * FRAME_HEIGHT;
+    let hash =
+        name.bytes().fold(0u32, |hash, byte| hash.wrapping_mul(31).wrapping_add(byte as u32));
+    let (green, blue) = (100 + hash % 130, hash / 130 % 60);
+    writeln!(
+        svg,
+        r#"<g><title>{} ({} cycles, {:.2}%)</title><rect x="{x:.2}" y="{y:.2}" width="{width:.2}" height="{}" fill="rgb(230,{green},{blue})" rx="2"/>"#,
+        escape(name),
+        node.cycles,
+        node.cycles as f64 * 100.0 / total,
+        FRAME_HEIGHT - 1.0,
+    )
+    .unwrap();
+    let chars = ((width - 6.0) / FONT_WIDTH) as usize;
+    if chars >= 3 {
+        let label = if name.chars().count() > chars {
+            format!("{}..", name.chars().take(chars - 2).collect::<String>())
+        } else {
+            name.to_string()
+        };
+        let label = escape(&label);
+        writeln!(svg, r#"<text x="{:.2}" y="{:.2}">{label}</text>"#, x + 3.0, y + 11.0).unwrap();
+    }
+    svg.push_str("</g>\n");
+
+    let mut child_x = x;
+    for (child_name, child) in &node.children {
+        write_frame(svg, child_name, child, depth + 1, child_x, height, scale, total);
+        child_x += child.cycles as f64 * scale;
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
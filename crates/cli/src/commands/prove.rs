@@ -0,0 +1,86 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use clap::{Parser, ValueEnum};
+use sp1_sdk::{HashableKey, ProverClient, SP1ProofMode};
+
+use crate::commands::{execute::StdinArgs, vkey::Elf};
+
+#[derive(Parser)]
+#[command(name = "prove", about = "Prove a program and save the proof to a file.")]
+pub struct ProveCmd {
+    /// Path to the ELF.
+    #[clap(flatten)]
+    elf: Elf,
+
+    #[clap(flatten)]
+    stdin: StdinArgs,
+
+    /// The proof mode.
+    #[arg(long, value_enum, default_value_t = ProofMode::Compressed)]
+    mode: ProofMode,
+
+    /// The file to save the proof to.
+    #[arg(long, short)]
+    output: PathBuf,
+
+    /// Print the result as JSON.
+    #[arg(long)]
+    json: bool,
+}
+
+/// The proof modes, as named on the command line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ProofMode {
+    Core,
+    Compressed,
+    Plonk,
+    Groth16,
+    Fflonk,
+}
+
+impl From<ProofMode> for SP1ProofMode {
+    fn from(mode: ProofMode) -> Self {
+        match mode {
+            ProofMode::Core => SP1ProofMode::Core,
+            ProofMode::Compressed => SP1ProofMode::Compressed,
+            ProofMode::Plonk => SP1ProofMode::Plonk,
+            ProofMode::Groth16 => SP1ProofMode::Groth16,
+            ProofMode::Fflonk => SP1ProofMode::Fflonk,
+        }
+    }
+}
+
+impl ProveCmd {
+    pub fn run(&self) -> Result<()> {
+        let elf = self.elf.read()?;
+        let stdin = self.stdin.read()?;
+
+        let client = ProverClient::from_env();
+        let (pk, vk) = client.setup(&elf);
+        let proof = client.prove(&pk, &stdin).mode(self.mode.into()).run()?;
+        proof.save(&self.output)?;
+
+        if self.json {
+            let output = serde_json::json!({
+                "mode": proof_mode_name(SP1ProofMode::from(&proof.proof)),
+                "proof": self.output,
+                "vkeyHash": vk.bytes32(),
+                "publicValues": proof.public_values.raw(),
+                "sp1Version": proof.sp1_version,
+            });
+            println!("{}", serde_json::to_string_pretty(&output)?);
+        } else {
+            println!("Saved proof to {}", self.output.display());
+            println!("Verification Key Hash:\n{}", vk.bytes32());
+            println!("Public values:\n{}", proof.public_values.raw());
+        }
+
+        Ok(())
+    }
+}
+
+/// The name of a proof mode on the command line.
+pub fn proof_mode_name(mode: SP1ProofMode) -> String {
+    format!("{mode:?}").to_lowercase()
+}
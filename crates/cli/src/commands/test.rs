@@ -0,0 +1,127 @@
+use std::{
+    fs,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+    thread,
+};
+
+use anyhow::{Context, Result};
+use clap::Parser;
+use sp1_build::{execute_build_tests, BuildArgs};
+use sp1_sdk::{GuestTestOutcome, ProverClient};
+
+use crate::commands::execute::StdinArgs;
+
+#[derive(Parser)]
+#[command(
+    name = "test",
+    about = "Compile the integration tests of a program for the zkVM and run them in the executor.",
+    long_about = "Compile the integration tests of a program for the zkVM and run them in the \
+    executor.\n\n\
+    Each test target must set `harness = false` and declare its `#[test]` functions with \
+    `sp1_zkvm::test_harness!`. Every test runs in its own execution, so a failed assertion only \
+    fails its test, which is reported with its panic message and cycle count."
+)]
+pub struct TestCmd {
+    #[clap(flatten)]
+    build_args: BuildArgs,
+
+    #[clap(flatten)]
+    stdin: StdinArgs,
+
+    /// Only run the tests whose name, prefixed by the test target, contains this string.
+    filter: Option<String>,
+
+    /// The number of tests to run in parallel. Defaults to the number of CPUs.
+    #[arg(long, short)]
+    jobs: Option<usize>,
+
+    /// The maximum number of cycles to execute each test for.
+    #[arg(long)]
+    max_cycles: Option<u64>,
+}
+
+impl TestCmd {
+    pub fn run(&self) -> Result<()> {
+        let prover = ProverClient::builder().cpu().build();
+        let stdin = self.stdin.read()?;
+
+        // List the tests of every test target.
+        let mut elfs = vec![];
+        let mut tests = vec![];
+        for (target, path) in execute_build_tests(&self.build_args, None)? {
+            let elf = fs::read(&path).with_context(|| format!("failed to read {path}"))?;
+            for name in prover
+                .list_guest_tests(&elf)
+                .with_context(|| format!("failed to list the tests of {target}"))?
+            {
+                let id = format!("{target}::{name}");
+                if self.filter.as_ref().map_or(true, |filter| id.contains(filter.as_str())) {
+                    tests.push((id, elfs.len(), name));
+                }
+            }
+            elfs.push(elf);
+        }
+        let jobs = self
+            .jobs
+            .unwrap_or_else(|| thread::available_parallelism().map_or(1, |jobs| jobs.get()))
+            .max(1);
+
+        println!("\nrunning {} tests", tests.len());
+        let next = AtomicUsize::new(0);
+        let failures = Mutex::new(vec![]);
+        thread::scope(|scope| -> Result<()> {
+            let workers = (0..jobs.min(tests.len()))
+                .map(|_| {
+                    scope.spawn(|| -> Result<()> {
+                        while let Some((id, elf, name)) =
+                            tests.get(next.fetch_add(1, Ordering::Relaxed))
+                        {
+                            let result = prover.run_guest_test(
+                                &elfs[*elf],
+                                name,
+                                &stdin,
+                                self.max_cycles,
+                            )?;
+                            let cycles = result.cycles.map(|cycles| format!(" ({cycles} cycles)"));
+                            let cycles = cycles.unwrap_or_default();
+                            match result.outcome {
+                                GuestTestOutcome::Passed => println!("test {id} ... ok{cycles}"),
+                                GuestTestOutcome::Panicked(reason)
+                                | GuestTestOutcome::Failed(reason) => {
+                                    println!("test {id} ... FAILED{cycles}");
+                                    failures.lock().unwrap().push((id.clone(), reason));
+                                }
+                            }
+                        }
+                        Ok(())
+                    })
+                })
+                .collect::<Vec<_>>();
+            workers.into_iter().try_for_each(|worker| worker.join().unwrap())
+        })?;
+
+        let mut failures = failures.into_inner().unwrap();
+        failures.sort();
+        if !failures.is_empty() {
+            println!("\nfailures:");
+            for (id, reason) in &failures {
+                println!("\n---- {id} ----\n{reason}");
+            }
+        }
+
+        let result = if failures.is_empty() { "ok" } else { "FAILED" };
+        println!(
+            "\ntest result: {result}. {} passed; {} failed",
+            tests.len() - failures.len(),
+            failures.len()
+        );
+        if !failures.is_empty() {
+            std::process::exit(101);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,162 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use clap::{Args, Parser, Subcommand};
+use sp1_sdk::{
+    artifacts::{build_groth16_bn254_ceremony_circuit_with_dummy, Groth16Bn254Ceremony},
+    SecurityPreset,
+};
+
+#[derive(Parser)]
+#[command(
+    name = "trusted-setup",
+    about = "Run a trusted-setup ceremony for the Groth16 wrap circuit.",
+    long_about = "Run a trusted-setup ceremony for the Groth16 wrap circuit.\n\n\
+    Phase 1 is a powers of tau ceremony and phase 2 is specific to the circuit. Each phase starts \
+    from initial parameters, which participants contribute to one after the other. Verification \
+    takes the initial parameters followed by every contribution, in order. Finalizing writes the \
+    groth16 artifacts to the build directory, which can then be used as the groth16 circuit \
+    artifacts."
+)]
+pub struct TrustedSetupCmd {
+    #[clap(subcommand)]
+    command: TrustedSetupCommands,
+}
+
+#[derive(Subcommand)]
+enum TrustedSetupCommands {
+    /// Write the wrap circuit to the build directory.
+    Circuit(CircuitArgs),
+    /// Write the initial phase-1 parameters.
+    InitPhase1(InitPhase1Args),
+    /// Contribute to the phase-1 parameters.
+    ContributePhase1(ContributeArgs),
+    /// Verify the phase-1 contributions.
+    VerifyPhase1(VerifyPhase1Args),
+    /// Write the initial phase-2 parameters for the circuit in the build directory.
+    InitPhase2(InitPhase2Args),
+    /// Contribute to the phase-2 parameters.
+    ContributePhase2(ContributeArgs),
+    /// Verify the phase-2 contributions.
+    VerifyPhase2(Phase2ContributionsArgs),
+    /// Verify the phase-2 contributions and write the groth16 artifacts to the build directory.
+    Finalize(Phase2ContributionsArgs),
+}
+
+#[derive(Args)]
+struct CircuitArgs {
+    /// The directory to write the circuit to.
+    #[arg(long)]
+    build_dir: PathBuf,
+    /// The security preset of the wrap circuit.
+    #[arg(long, default_value_t = SecurityPreset::Standard)]
+    security_preset: SecurityPreset,
+}
+
+#[derive(Args)]
+struct InitPhase1Args {
+    /// The log2 of the maximum number of constraints of the circuit.
+    #[arg(long)]
+    power: u32,
+    /// The file to write the parameters to.
+    #[arg(long)]
+    output: PathBuf,
+}
+
+#[derive(Args)]
+struct ContributeArgs {
+    /// The parameters to contribute to.
+    #[arg(long)]
+    input: PathBuf,
+    /// The file to write the contribution to.
+    #[arg(long)]
+    output: PathBuf,
+}
+
+#[derive(Args)]
+struct VerifyPhase1Args {
+    /// The initial parameters followed by every contribution, in order.
+    #[arg(required = true, num_args = 2..)]
+    contributions: Vec<PathBuf>,
+}
+
+#[derive(Args)]
+struct InitPhase2Args {
+    /// The directory containing the circuit.
+    #[arg(long)]
+    build_dir: PathBuf,
+    /// The final phase-1 parameters.
+    #[arg(long)]
+    phase1: PathBuf,
+    /// The file to write the parameters to.
+    #[arg(long)]
+    output: PathBuf,
+}
+
+#[derive(Args)]
+struct Phase2ContributionsArgs {
+    /// The directory containing the circuit.
+    #[arg(long)]
+    build_dir: PathBuf,
+    /// The final phase-1 parameters.
+    #[arg(long)]
+    phase1: PathBuf,
+    /// The initial parameters followed by every contribution, in order.
+    #[arg(required = true, num_args = 2..)]
+    contributions: Vec<PathBuf>,
+}
+
+impl TrustedSetupCmd {
+    pub fn run(&self) -> Result<()> {
+        match &self.command {
+            TrustedSetupCommands::Circuit(args) => {
+                build_groth16_bn254_ceremony_circuit_with_dummy(
+                    &args.build_dir,
+                    args.security_preset,
+                );
+            }
+            TrustedSetupCommands::InitPhase1(args) => {
+                Groth16Bn254Ceremony::init_phase1(args.power, &args.output)?;
+            }
+            TrustedSetupCommands::ContributePhase1(args) => {
+                Groth16Bn254Ceremony::contribute_phase1(&args.input, &args.output)?;
+                println!("Contribution written to {}", args.output.display());
+            }
+            TrustedSetupCommands::VerifyPhase1(args) => {
+                Groth16Bn254Ceremony::verify_phase1(&args.contributions)?;
+                println!("All {} phase-1 contributions are valid", args.contributions.len() - 1);
+            }
+            TrustedSetupCommands::InitPhase2(args) => {
+                if !args.build_dir.join("constraints.json").exists() {
+                    anyhow::bail!(
+                        "no circuit in {}, run `cargo prove trusted-setup circuit` first",
+                        args.build_dir.display()
+                    );
+                }
+                Groth16Bn254Ceremony::init_phase2(&args.build_dir, &args.phase1, &args.output)?;
+            }
+            TrustedSetupCommands::ContributePhase2(args) => {
+                Groth16Bn254Ceremony::contribute_phase2(&args.input, &args.output)?;
+                println!("Contribution written to {}", args.output.display());
+            }
+            TrustedSetupCommands::VerifyPhase2(args) => {
+                Groth16Bn254Ceremony::verify_phase2(
+                    &args.build_dir,
+                    &args.phase1,
+                    &args.contributions,
+                )?;
+                println!("All {} phase-2 contributions are valid", args.contributions.len() - 1);
+            }
+            TrustedSetupCommands::Finalize(args) => {
+                Groth16Bn254Ceremony::finalize(
+                    args.build_dir.clone(),
+                    &args.phase1,
+                    &args.contributions,
+                )?;
+                println!("Groth16 artifacts written to {}", args.build_dir.display());
+            }
+        }
+
+        Ok(())
+    }
+}
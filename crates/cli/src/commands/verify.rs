@@ -0,0 +1,103 @@
+use std::path::PathBuf;
+
+use anyhow::{bail, Result};
+use clap::{Args, Parser};
+use sp1_sdk::{HashableKey, ProverClient, SP1Proof, SP1ProofMode, SP1ProofWithPublicValues};
+use sp1_verifier::{Groth16Verifier, PlonkVerifier, GROTH16_VK_BYTES, PLONK_VK_BYTES};
+
+use crate::commands::{prove::proof_mode_name, vkey::Elf};
+
+#[derive(Parser)]
+#[command(
+    name = "verify",
+    about = "Verify a saved proof against a program or a verification key hash.",
+    long_about = "Verify a saved proof against a program or a verification key hash.\n\n\
+    Proofs of every mode can be verified against the ELF of the program. Only Plonk and Groth16 \
+    proofs can be verified against the verification key hash alone."
+)]
+pub struct VerifyCmd {
+    /// The file the proof was saved to.
+    #[arg(long)]
+    proof: PathBuf,
+
+    #[clap(flatten)]
+    key: VerificationKey,
+
+    /// Print the result as JSON.
+    #[arg(long)]
+    json: bool,
+}
+
+#[derive(Debug, Clone, Args)]
+#[group(required = true, multiple = false)]
+struct VerificationKey {
+    /// The path to the ELF file
+    #[arg(long = "elf")]
+    path: Option<String>,
+    /// The crate used to generate the ELF file
+    #[arg(long)]
+    program: Option<String>,
+    /// The verification key hash of the program, as printed by `cargo prove vkey`
+    #[arg(long)]
+    vkey_hash: Option<String>,
+}
+
+impl VerifyCmd {
+    pub fn run(&self) -> Result<()> {
+        let proof = SP1ProofWithPublicValues::load(&self.proof)?;
+        let mode = SP1ProofMode::from(&proof.proof);
+
+        let (vkey_hash, result) = match &self.key.vkey_hash {
+            Some(vkey_hash) => (vkey_hash.clone(), verify_with_vkey_hash(&proof, vkey_hash)),
+            None => {
+                let elf = Elf { path: self.key.path.clone(), program: self.key.program.clone() };
+                let client = ProverClient::from_env();
+                let (_, vk) = client.setup(&elf.read()?);
+                (vk.bytes32(), client.verify(&proof, &vk).map_err(Into::into))
+            }
+        };
+
+        if self.json {
+            let output = serde_json::json!({
+                "valid": result.is_ok(),
+                "mode": proof_mode_name(mode),
+                "vkeyHash": vkey_hash,
+                "publicValues": proof.public_values.raw(),
+                "sp1Version": proof.sp1_version,
+                "error": result.as_ref().err().map(ToString::to_string),
+            });
+            println!("{}", serde_json::to_string_pretty(&output)?);
+        } else if let Err(err) = &result {
+            eprintln!("Proof is invalid: {err}");
+        } else {
+            println!("Proof is valid.");
+            println!("Public values:\n{}", proof.public_values.raw());
+        }
+
+        if result.is_err() {
+            std::process::exit(1);
+        }
+
+        Ok(())
+    }
+}
+
+/// Verifies a Plonk or Groth16 proof against the verification key hash of the program, with the
+/// BN254 verifying keys of this SP1 version.
+fn verify_with_vkey_hash(proof: &SP1ProofWithPublicValues, vkey_hash: &str) -> Result<()> {
+    let public_values = proof.public_values.as_slice();
+    match &proof.proof {
+        SP1Proof::Plonk(_) => {
+            PlonkVerifier::verify(&proof.bytes(), public_values, vkey_hash, &PLONK_VK_BYTES)?;
+        }
+        SP1Proof::Groth16(_) => {
+            Groth16Verifier::verify(&proof.bytes(), public_values, vkey_hash, &GROTH16_VK_BYTES)?;
+        }
+        _ => bail!(
+            "{} proofs can only be verified against the program, pass --elf or --program",
+            proof_mode_name(SP1ProofMode::from(&proof.proof))
+        ),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,149 @@
+use std::{fs, path::PathBuf};
+
+use anyhow::{bail, Context, Result};
+use clap::{Args, Parser};
+use sp1_build::{execute_build_program, hashes_eq, BuildArgs, BuildManifest};
+use sp1_sdk::{HashableKey, ProverClient};
+
+#[derive(Parser)]
+#[command(
+    name = "verify-build",
+    about = "Rebuild a program in Docker and check that it matches the expected ELF or vkey hash.",
+    long_about = "Rebuild a program in Docker and check that it matches the expected ELF or vkey \
+    hash.\n\n\
+    The program is built in the Docker image pinned by --tag, so anyone with the source tree can \
+    reproduce it. When the hashes match, a build manifest recording the toolchain, the Cargo.lock \
+    hash, the features and the rustflags is written, so the check can be repeated later."
+)]
+pub struct VerifyBuildCmd {
+    #[clap(flatten)]
+    build_args: BuildArgs,
+
+    #[clap(flatten)]
+    expected: Expected,
+
+    /// The file to write the build manifest to.
+    #[arg(long, default_value = "build-manifest.json")]
+    manifest: PathBuf,
+
+    /// The name recorded in the manifest as having checked the build.
+    #[arg(long)]
+    signed_off_by: Option<String>,
+}
+
+#[derive(Debug, Clone, Args)]
+#[group(required = true, multiple = true)]
+struct Expected {
+    /// The expected verification key hash, as printed by `cargo prove vkey`
+    #[arg(long)]
+    vkey_hash: Option<String>,
+    /// The expected SHA-256 hash of the ELF, hex encoded
+    #[arg(long)]
+    elf_hash: Option<String>,
+}
+
+impl VerifyBuildCmd {
+    pub fn run(&self) -> Result<()> {
+        let args = BuildArgs { docker: true, ..self.build_args.clone() };
+        let elf_paths = execute_build_program(&args, None)?;
+        let [(_, elf_path)] = elf_paths.as_slice() else {
+            let targets = elf_paths.iter().map(|(target, _)| target.as_str());
+            bail!(
+                "the program has several targets ({}), pass one of them with --bin",
+                targets.collect::<Vec<_>>().join(", ")
+            );
+        };
+        let mut manifest = BuildManifest::load(BuildManifest::path(elf_path))?;
+
+        // The verification key hash is recorded here rather than by the build, as it requires the
+        // prover.
+        let elf = fs::read(elf_path).with_context(|| format!("failed to read {elf_path}"))?;
+        let (_, vk) = ProverClient::from_env().setup(&elf);
+        let vkey_hash = vk.vk.bytes32();
+        manifest.vkey_hash = Some(vkey_hash.clone());
+
+        println!("Toolchain: {}", manifest.toolchain);
+        if let Some(image) = &manifest.docker_image {
+            println!("Docker image: {image}");
+        }
+        println!("ELF hash: {}", manifest.elf_sha256);
+        println!("Verification Key Hash: {vkey_hash}");
+
+        self.expected.check(&manifest)?;
+
+        manifest.signed_off_by = self.signed_off_by.clone();
+        manifest.save(&self.manifest)?;
+        println!("Build matches. Wrote the build manifest to {}", self.manifest.display());
+
+        Ok(())
+    }
+}
+
+impl Expected {
+    /// Checks the hashes recorded in the manifest against the expected ones, failing with the list
+    /// of mismatches.
+    fn check(&self, manifest: &BuildManifest) -> Result<()> {
+        let mut mismatches = vec![];
+        if let Some(expected) = &self.elf_hash {
+            if !hashes_eq(&manifest.elf_sha256, expected) {
+                mismatches
+                    .push(format!("ELF hash is {}, expected {expected}", manifest.elf_sha256));
+            }
+        }
+        if let Some(expected) = &self.vkey_hash {
+            match &manifest.vkey_hash {
+                Some(vkey_hash) if hashes_eq(vkey_hash, expected) => {}
+                Some(vkey_hash) => {
+                    mismatches.push(format!("vkey hash is {vkey_hash}, expected {expected}"))
+                }
+                None => mismatches.push(format!("vkey hash is unknown, expected {expected}")),
+            }
+        }
+
+        if !mismatches.is_empty() {
+            bail!("the build does not match:\n  {}", mismatches.join("\n  "));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manifest() -> BuildManifest {
+        BuildManifest {
+            sp1_version: "4.0.0".to_string(),
+            program: "fibonacci-program".to_string(),
+            elf_sha256: "abcd".to_string(),
+            vkey_hash: Some("0x00ef".to_string()),
+            docker_image: None,
+            docker_image_digest: None,
+            toolchain: "rustc 1.81.0".to_string(),
+            cargo_lock_sha256: None,
+            features: vec![],
+            no_default_features: false,
+            rustflags: vec![],
+            signed_off_by: None,
+        }
+    }
+
+    #[test]
+    fn test_check_expected_hashes() {
+        let expected = |elf_hash: Option<&str>, vkey_hash: Option<&str>| Expected {
+            elf_hash: elf_hash.map(str::to_string),
+            vkey_hash: vkey_hash.map(str::to_string),
+        };
+
+        expected(Some("0xABCD"), Some("00EF")).check(&manifest()).unwrap();
+        expected(None, Some("0x00ef")).check(&manifest()).unwrap();
+
+        let err = expected(Some("abce"), Some("0x00ee")).check(&manifest()).unwrap_err();
+        let err = err.to_string();
+        assert!(err.contains("ELF hash is abcd, expected abce"), "{err}");
+        assert!(err.contains("vkey hash is 0x00ef, expected 0x00ee"), "{err}");
+
+        let unknown = BuildManifest { vkey_hash: None, ..manifest() };
+        assert!(expected(Some("abcd"), Some("0x00ef")).check(&unknown).is_err());
+    }
+}
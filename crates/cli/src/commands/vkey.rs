@@ -1,6 +1,9 @@
-use std::{fs::File, io::Read};
+use std::{
+    fs::{self, File},
+    io::Read,
+};
 
-use anyhow::Result;
+use anyhow::{bail, Context, Result};
 use clap::{Args, Parser};
 use sp1_build::{generate_elf_paths, BuildArgs};
 use sp1_sdk::{HashableKey, ProverClient};
@@ -18,28 +21,49 @@ pub struct VkeyCmd {
 pub struct Elf {
     /// The path to the ELF file
     #[arg(long = "elf")]
-    path: Option<String>,
+    pub(crate) path: Option<String>,
     /// The crate used to generate the ELF file
     #[arg(long)]
-    program: Option<String>,
+    pub(crate) program: Option<String>,
 }
 
-impl VkeyCmd {
-    pub fn run(&self) -> Result<()> {
-        let elf_paths = if let Some(path) = &self.elf.path {
-            vec![(None, path.clone())]
-        } else if let Some(program) = &self.elf.program {
+impl Elf {
+    /// Returns the paths of the ELF files, along with the targets they were built for when
+    /// `--program` is used.
+    pub fn paths(&self) -> Result<Vec<(Option<String>, String)>> {
+        if let Some(path) = &self.path {
+            Ok(vec![(None, path.clone())])
+        } else if let Some(program) = &self.program {
             let metadata_cmd = cargo_metadata::MetadataCommand::new();
             let metadata = metadata_cmd.exec()?;
             let build_args = BuildArgs { packages: vec![program.clone()], ..Default::default() };
 
-            generate_elf_paths(&metadata, Some(&build_args))?
+            Ok(generate_elf_paths(&metadata, Some(&build_args))?
                 .into_iter()
                 .map(|(target, path)| (Some(target), path.to_string()))
-                .collect()
+                .collect())
         } else {
             unreachable!()
+        }
+    }
+
+    /// Reads the ELF file, which must be unique.
+    pub fn read(&self) -> Result<Vec<u8>> {
+        let paths = self.paths()?;
+        let [(_, path)] = paths.as_slice() else {
+            let targets = paths.iter().filter_map(|(target, _)| target.as_deref());
+            bail!(
+                "the program has several targets ({}), pass one of them with --elf",
+                targets.collect::<Vec<_>>().join(", ")
+            );
         };
+        fs::read(path).with_context(|| format!("failed to read the ELF at {path}"))
+    }
+}
+
+impl VkeyCmd {
+    pub fn run(&self) -> Result<()> {
+        let elf_paths = self.elf.paths()?;
 
         for (target, elf_path) in elf_paths {
             // Read the elf file contents
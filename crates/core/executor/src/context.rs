@@ -5,6 +5,7 @@ use hashbrown::HashMap;
 
 use crate::{
     hook::{hookify, BoxedHook, HookEnv, HookRegistry},
+    monitor::{CancellationToken, ProgressObserver, ProveMonitor, ProveProgress},
     subproof::SubproofVerifier,
 };
 
@@ -24,6 +25,9 @@ pub struct SP1Context<'a> {
 
     /// Skip deferred proof verification.
     pub skip_deferred_proof_verification: bool,
+
+    /// The monitor reporting the progress of a proof and checking for its cancellation.
+    pub monitor: ProveMonitor,
 }
 
 /// A builder for [`SP1Context`].
@@ -34,6 +38,8 @@ pub struct SP1ContextBuilder<'a> {
     subproof_verifier: Option<Arc<dyn SubproofVerifier + 'a>>,
     max_cycles: Option<u64>,
     skip_deferred_proof_verification: bool,
+    progress_observer: Option<ProgressObserver>,
+    cancellation_token: Option<CancellationToken>,
 }
 
 impl<'a> SP1Context<'a> {
@@ -73,11 +79,16 @@ impl<'a> SP1ContextBuilder<'a> {
         let subproof_verifier = take(&mut self.subproof_verifier);
         let cycle_limit = take(&mut self.max_cycles);
         let skip_deferred_proof_verification = take(&mut self.skip_deferred_proof_verification);
+        let monitor = ProveMonitor::new(
+            take(&mut self.progress_observer),
+            take(&mut self.cancellation_token).unwrap_or_default(),
+        );
         SP1Context {
             hook_registry,
             subproof_verifier,
             max_cycles: cycle_limit,
             skip_deferred_proof_verification,
+            monitor,
         }
     }
 
@@ -126,6 +137,23 @@ impl<'a> SP1ContextBuilder<'a> {
         self.skip_deferred_proof_verification = skip;
         self
     }
+
+    /// Set a callback invoked with the progress of a proof.
+    ///
+    /// The callback is invoked from the worker threads of the prover, so it should return quickly.
+    pub fn progress_observer(
+        &mut self,
+        observer: impl Fn(&ProveProgress) + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.progress_observer = Some(Arc::new(observer));
+        self
+    }
+
+    /// Set the token used to cancel a proof.
+    pub fn cancellation_token(&mut self, token: CancellationToken) -> &mut Self {
+        self.cancellation_token = Some(token);
+        self
+    }
 }
 
 #[cfg(test)]
@@ -21,7 +21,7 @@ use crate::{
 };
 
 #[cfg(feature = "profiling")]
-use crate::profiler::Profiler;
+use crate::profiler::{Profile, Profiler, ProfilerError};
 #[cfg(feature = "profiling")]
 use std::{fs::File, io::BufWriter};
 
@@ -113,9 +113,10 @@ pub struct Executor<'a> {
 
     /// The ZKVM program profiler.
     ///
-    /// Keeps track of the number of cycles spent in each function.
+    /// Keeps track of the number of cycles spent in each function, and writes the profile to the
+    /// file set by `TRACE_FILE` when there is one.
     #[cfg(feature = "profiling")]
-    pub profiler: Option<(Profiler, BufWriter<File>)>,
+    pub profiler: Option<(Profiler, Option<BufWriter<File>>)>,
 
     /// The state of the runtime when in unconstrained mode.
     pub unconstrained_state: ForkState,
@@ -230,7 +231,7 @@ impl<'a> Executor<'a> {
                 this.profiler = Some((
                     Profiler::new(elf_bytes, sample_rate as u64)
                         .expect("Failed to create profiler"),
-                    trace_buf,
+                    Some(trace_buf),
                 ));
             }
 
@@ -238,6 +239,40 @@ impl<'a> Executor<'a> {
         }
     }
 
+    /// Profile the execution of the program, sampling the call stack every `sample_rate` cycles.
+    ///
+    /// The profile is read with [`Self::take_profile`] once the program has run.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the ELF cannot be parsed.
+    #[cfg(feature = "profiling")]
+    pub fn enable_profiler(
+        &mut self,
+        elf_bytes: &[u8],
+        sample_rate: u64,
+    ) -> Result<(), ProfilerError> {
+        self.profiler = Some((Profiler::new(elf_bytes, sample_rate)?, None));
+        Ok(())
+    }
+
+    /// Take the profile of the execution, if [`Self::enable_profiler`] was called.
+    #[cfg(feature = "profiling")]
+    pub fn take_profile(&mut self) -> Option<Profile> {
+        self.profiler.take().map(|(profiler, _)| profiler.profile())
+    }
+
+    /// Write the profile to the file set by `TRACE_FILE`, if there is one.
+    #[cfg(feature = "profiling")]
+    fn write_profile(&mut self) {
+        if let Some((_, writer)) = &mut self.profiler {
+            if let Some(writer) = writer.take() {
+                let (profiler, _) = self.profiler.take().unwrap();
+                profiler.write(writer).expect("Failed to write profile to output file");
+            }
+        }
+    }
+
     /// Create a new runtime from a program, options, and a context.
     ///
     /// Note: This function *will not* set up the profiler.
@@ -972,6 +1007,13 @@ impl<'a> Executor<'a> {
                     self.report.syscall_counts[syscall] += 1;
                 }
 
+                #[cfg(feature = "profiling")]
+                if let Some((ref mut profiler, _)) = self.profiler {
+                    if !self.unconstrained {
+                        profiler.record_syscall(syscall);
+                    }
+                }
+
                 // `hint_slice` is allowed in unconstrained mode since it is used to write the hint.
                 // Other syscalls are not allowed because they can lead to non-deterministic
                 // behavior, especially since many syscalls modify memory in place,
@@ -1506,9 +1548,7 @@ impl<'a> Executor<'a> {
         while !self.execute()? {}
 
         #[cfg(feature = "profiling")]
-        if let Some((profiler, writer)) = self.profiler.take() {
-            profiler.write(writer).expect("Failed to write profile to output file");
-        }
+        self.write_profile();
 
         Ok(())
     }
@@ -1524,9 +1564,7 @@ impl<'a> Executor<'a> {
         while !self.execute()? {}
 
         #[cfg(feature = "profiling")]
-        if let Some((profiler, writer)) = self.profiler.take() {
-            profiler.write(writer).expect("Failed to write profile to output file");
-        }
+        self.write_profile();
 
         Ok(())
     }
@@ -1767,6 +1805,24 @@ mod tests {
         runtime.run().unwrap();
     }
 
+    #[test]
+    #[cfg(feature = "profiling")]
+    fn test_profile_cycles() {
+        let program = secp256r1_add_program();
+        let mut runtime = Executor::new(program, SP1CoreOpts::default());
+        runtime.print_report = true;
+        runtime.enable_profiler(test_artifacts::SECP256R1_ADD_ELF, 1).unwrap();
+        runtime.run().unwrap();
+        let profile = runtime.take_profile().unwrap();
+
+        // The stacks and the syscalls count executed instructions, as the report does.
+        assert_eq!(profile.total_cycles(), runtime.report.total_instruction_count());
+        assert!(!profile.syscalls.is_empty());
+        for syscall in &profile.syscalls {
+            assert_eq!(syscall.calls, runtime.report.syscall_counts[syscall.syscall]);
+        }
+    }
+
     #[test]
     fn test_secp256r1_double_program_run() {
         let program = secp256r1_double_program();
@@ -25,6 +25,9 @@ pub const FD_ECRECOVER_HOOK_2: u32 = 7;
 /// The file descriptor through which to access `hook_ed_decompress`.
 pub const FD_EDDECOMPRESS: u32 = 8;
 
+/// The file descriptor through which a failing guest test reports its panic message.
+pub const FD_TEST_PANIC: u32 = 9;
+
 /// A runtime hook. May be called during execution by writing to a specified file descriptor,
 /// accepting and returning arbitrary data.
 pub trait Hook {
@@ -259,6 +262,7 @@ pub mod tests {
         use sp1_zkvm::lib::io;
         assert_eq!(FD_ECRECOVER_HOOK, io::K1_ECRECOVER_HOOK);
         assert_eq!(R1_ECRECOVER_HOOK, io::R1_ECRECOVER_HOOK);
+        assert_eq!(FD_TEST_PANIC, io::FD_TEST_PANIC);
     }
 
     #[test]
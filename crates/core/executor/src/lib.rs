@@ -28,6 +28,7 @@ mod hook;
 mod instruction;
 mod io;
 mod memory;
+mod monitor;
 mod opcode;
 #[cfg(feature = "profiling")]
 mod profiler;
@@ -48,6 +49,7 @@ pub use context::*;
 pub use executor::*;
 pub use hook::*;
 pub use instruction::*;
+pub use monitor::*;
 pub use opcode::*;
 pub use program::*;
 pub use record::*;
@@ -57,3 +59,6 @@ pub use report::*;
 pub use shape::*;
 pub use state::*;
 pub use utils::*;
+
+#[cfg(feature = "profiling")]
+pub use profiler::{FunctionProfile, Profile, ProfilerError, SyscallProfile};
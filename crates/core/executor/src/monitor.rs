@@ -0,0 +1,271 @@
+use std::{
+    fmt,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+/// The stage of the generation of a proof.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ProofStage {
+    /// Executing the program and proving its shards.
+    #[default]
+    Core,
+    /// Reducing the shard proofs into a single proof with the compress tree.
+    Compress,
+    /// Shrinking the compressed proof.
+    Shrink,
+    /// Wrapping the shrunk proof into a STARK proven over a SNARK-friendly field.
+    Wrap,
+    /// Proving the wrapped proof with a Groth16 or PLONK circuit.
+    Snark,
+}
+
+/// A snapshot of the progress of a proof, passed to the observer of a [`ProveMonitor`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ProveProgress {
+    /// The current stage.
+    pub stage: ProofStage,
+    /// The number of shards traced from the execution so far.
+    pub shards_executed: usize,
+    /// Whether the execution finished, in which case `shards_executed` is the total number of
+    /// shards.
+    pub execution_finished: bool,
+    /// The number of shards proven so far.
+    pub shards_proven: usize,
+    /// The height of the highest node of the compress tree proven so far.
+    pub compress_level: usize,
+    /// The number of nodes of the compress tree proven so far.
+    pub compress_nodes_proven: usize,
+    /// The total number of nodes of the compress tree.
+    pub compress_nodes: usize,
+    /// The time since the monitor was created.
+    pub elapsed: Duration,
+    /// The estimated time until the current stage finishes, if it can be estimated.
+    pub eta: Option<Duration>,
+}
+
+/// A token used to cancel the generation of a proof.
+///
+/// Cloned tokens share their state, so a proof can be cancelled from another thread.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+    parent: Option<Arc<CancellationToken>>,
+}
+
+impl CancellationToken {
+    /// Create a new [`CancellationToken`].
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a token which is cancelled with this one, but can also be cancelled on its own.
+    #[must_use]
+    pub fn child(&self) -> Self {
+        Self { cancelled: Arc::default(), parent: Some(Arc::new(self.clone())) }
+    }
+
+    /// Cancel the proofs using this token.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Whether the token was cancelled.
+    #[must_use]
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+            || self.parent.as_ref().is_some_and(|parent| parent.is_cancelled())
+    }
+}
+
+/// A callback invoked with the progress of a proof.
+pub type ProgressObserver = Arc<dyn Fn(&ProveProgress) + Send + Sync>;
+
+/// Reports the progress of a proof to an observer and tells the prover whether to stop.
+///
+/// The monitor is shared with the worker threads of the prover, so every method takes `&self`.
+#[derive(Clone, Default)]
+pub struct ProveMonitor {
+    observer: Option<ProgressObserver>,
+    token: CancellationToken,
+    state: Arc<Mutex<MonitorState>>,
+}
+
+struct MonitorState {
+    progress: ProveProgress,
+    start: Instant,
+    stage_start: Instant,
+}
+
+impl Default for MonitorState {
+    fn default() -> Self {
+        let now = Instant::now();
+        Self { progress: ProveProgress::default(), start: now, stage_start: now }
+    }
+}
+
+impl ProveMonitor {
+    /// Create a new [`ProveMonitor`] with an optional observer and a cancellation token.
+    #[must_use]
+    pub fn new(observer: Option<ProgressObserver>, token: CancellationToken) -> Self {
+        Self { observer, token, state: Arc::default() }
+    }
+
+    /// The cancellation token of the monitor.
+    #[must_use]
+    pub fn token(&self) -> &CancellationToken {
+        &self.token
+    }
+
+    /// Whether the proof was cancelled.
+    #[must_use]
+    pub fn is_cancelled(&self) -> bool {
+        self.token.is_cancelled()
+    }
+
+    /// The latest progress of the proof.
+    #[must_use]
+    pub fn progress(&self) -> ProveProgress {
+        self.state.lock().unwrap().progress
+    }
+
+    /// Report that the proof entered a new stage.
+    pub fn stage(&self, stage: ProofStage) {
+        self.update(|state| {
+            state.progress.stage = stage;
+            state.stage_start = Instant::now();
+        });
+    }
+
+    /// Report that `count` more shards were traced from the execution.
+    pub fn shards_executed(&self, count: usize) {
+        self.update(|state| state.progress.shards_executed += count);
+    }
+
+    /// Report that the execution finished and all shards were traced.
+    pub fn execution_finished(&self) {
+        self.update(|state| {
+            state.progress.execution_finished = true;
+            // Shards are proven once all of them are traced, so start estimating from here.
+            state.stage_start = Instant::now();
+        });
+    }
+
+    /// Report that a shard was proven.
+    pub fn shard_proven(&self) {
+        self.update(|state| state.progress.shards_proven += 1);
+    }
+
+    /// Report that the compress tree with `nodes` nodes started.
+    pub fn compress_started(&self, nodes: usize) {
+        self.update(|state| {
+            state.progress.stage = ProofStage::Compress;
+            state.progress.compress_nodes = nodes;
+            state.stage_start = Instant::now();
+        });
+    }
+
+    /// Report that a node of the compress tree at the given height was proven.
+    pub fn compress_node_proven(&self, level: usize) {
+        self.update(|state| {
+            state.progress.compress_nodes_proven += 1;
+            state.progress.compress_level = state.progress.compress_level.max(level);
+        });
+    }
+
+    fn update(&self, f: impl FnOnce(&mut MonitorState)) {
+        let progress = {
+            let mut state = self.state.lock().unwrap();
+            f(&mut state);
+            let (done, total) = match state.progress.stage {
+                ProofStage::Core if state.progress.execution_finished => {
+                    (state.progress.shards_proven, state.progress.shards_executed)
+                }
+                ProofStage::Compress => {
+                    (state.progress.compress_nodes_proven, state.progress.compress_nodes)
+                }
+                _ => (0, 0),
+            };
+            state.progress.elapsed = state.start.elapsed();
+            state.progress.eta = (done > 0 && total >= done)
+                .then(|| state.stage_start.elapsed().mul_f64((total - done) as f64 / done as f64));
+            state.progress
+        };
+        if let Some(observer) = &self.observer {
+            observer(&progress);
+        }
+    }
+}
+
+impl fmt::Debug for ProveMonitor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ProveMonitor")
+            .field("observer", &self.observer.is_some())
+            .field("token", &self.token)
+            .field("progress", &self.progress())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicUsize;
+
+    use super::*;
+
+    #[test]
+    fn cancel_shared_token() {
+        let monitor = ProveMonitor::default();
+        let token = monitor.token().clone();
+        assert!(!monitor.is_cancelled());
+        token.cancel();
+        assert!(monitor.is_cancelled());
+    }
+
+    #[test]
+    fn cancel_child_token() {
+        let token = CancellationToken::new();
+        let child = token.child();
+        child.cancel();
+        assert!(child.is_cancelled());
+        assert!(!token.is_cancelled());
+
+        let child = token.child();
+        token.cancel();
+        assert!(child.is_cancelled());
+    }
+
+    #[test]
+    fn observe_progress() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let observer = {
+            let calls = Arc::clone(&calls);
+            Arc::new(move |_: &ProveProgress| {
+                calls.fetch_add(1, Ordering::Relaxed);
+            })
+        };
+        let monitor = ProveMonitor::new(Some(observer), CancellationToken::new());
+
+        monitor.shards_executed(3);
+        assert_eq!(monitor.progress().eta, None);
+        monitor.execution_finished();
+        monitor.shard_proven();
+        let progress = monitor.progress();
+        assert_eq!(progress.shards_executed, 3);
+        assert_eq!(progress.shards_proven, 1);
+        assert!(progress.eta.is_some());
+
+        monitor.compress_started(5);
+        monitor.compress_node_proven(0);
+        monitor.compress_node_proven(1);
+        let progress = monitor.progress();
+        assert_eq!(progress.stage, ProofStage::Compress);
+        assert_eq!(progress.compress_level, 1);
+        assert_eq!(progress.compress_nodes_proven, 2);
+        assert_eq!(calls.load(Ordering::Relaxed), 6);
+    }
+}
@@ -1,9 +1,11 @@
-use gecko_profile::{Frame, ProfileBuilder, StringIndex, ThreadBuilder};
+use gecko_profile::{Frame, ProfileBuilder, ThreadBuilder};
 use goblin::elf::{sym::STT_FUNC, Elf};
 use indicatif::{ProgressBar, ProgressStyle};
 use rustc_demangle::demangle;
 use std::collections::HashMap;
 
+use crate::syscalls::SyscallCode;
+
 #[derive(Debug, thiserror::Error)]
 pub enum ProfilerError {
     #[error("Failed to read ELF file {}", .0)]
@@ -12,6 +14,8 @@ pub enum ProfilerError {
     Elf(#[from] goblin::error::Error),
     #[error("Failed to serialize samples {}", .0)]
     Serde(#[from] serde_json::Error),
+    #[error("Invalid folded stack line: {}", .0)]
+    Folded(String),
 }
 
 /// During execution, the profiler always keeps track of the callstack
@@ -22,23 +26,25 @@ pub struct Profiler {
     start_lookup: HashMap<u64, usize>,
     /// the start and end of the function
     function_ranges: Vec<(u64, u64, Frame)>,
+    /// the demangled names of the functions, indexed like `function_ranges`
+    function_names: Vec<String>,
 
-    /// the current known call stack
-    function_stack: Vec<Frame>,
-    /// useful for quick search as to not count recursive calls
+    /// the current known call stack, as indices in `function_ranges`
     function_stack_indices: Vec<usize>,
     /// The call stacks code ranges, useful for keeping track of unwinds
     function_stack_ranges: Vec<(u64, u64)>,
     /// The deepest function code range
     current_function_range: (u64, u64),
 
-    main_idx: Option<StringIndex>,
+    main_idx: Option<usize>,
     builder: ThreadBuilder,
     samples: Vec<Sample>,
+    /// The number of calls of each syscall.
+    syscalls: HashMap<SyscallCode, u64>,
 }
 
 struct Sample {
-    stack: Vec<Frame>,
+    stack: Vec<usize>,
 }
 
 impl Profiler {
@@ -47,6 +53,7 @@ impl Profiler {
 
         let mut start_lookup = HashMap::new();
         let mut function_ranges = Vec::new();
+        let mut function_names = Vec::new();
         let mut builder = ThreadBuilder::new(1, 0, std::time::Instant::now(), false, false);
 
         // We need to extract all the functions from the ELF file
@@ -65,12 +72,13 @@ impl Profiler {
                 // around a usize
                 let demangled_name = demangled_name.to_string();
                 let string_idx = builder.intern_string(&demangled_name);
-                if main_idx.is_none() && demangled_name == "main" {
-                    main_idx = Some(string_idx);
-                }
 
                 let start_idx = function_ranges.len();
+                if main_idx.is_none() && demangled_name == "main" {
+                    main_idx = Some(start_idx);
+                }
                 function_ranges.push((start_address, end_address, Frame::Label(string_idx)));
+                function_names.push(demangled_name);
                 start_lookup.insert(start_address, start_idx);
             }
         }
@@ -82,10 +90,11 @@ impl Profiler {
             samples: Vec::new(),
             start_lookup,
             function_ranges,
-            function_stack: Vec::new(),
+            function_names,
             function_stack_indices: Vec::new(),
             function_stack_ranges: Vec::new(),
             current_function_range: (0, 0),
+            syscalls: HashMap::new(),
         })
     }
 
@@ -93,7 +102,7 @@ impl Profiler {
         // We are still in the current function.
         if pc > self.current_function_range.0 && pc <= self.current_function_range.1 {
             if clk % self.sample_rate == 0 {
-                self.samples.push(Sample { stack: self.function_stack.clone() });
+                self.samples.push(Sample { stack: self.function_stack_indices.clone() });
             }
 
             return;
@@ -104,10 +113,9 @@ impl Profiler {
             // Jump to a new function (not recursive).
             if !self.function_stack_indices.contains(f) {
                 self.function_stack_indices.push(*f);
-                let (start, end, name) = self.function_ranges.get(*f).unwrap();
+                let (start, end, _) = self.function_ranges.get(*f).unwrap();
                 self.current_function_range = (*start, *end);
                 self.function_stack_ranges.push((*start, *end));
-                self.function_stack.push(name.clone());
             }
         } else {
             // This means pc now points to an instruction that is
@@ -130,7 +138,6 @@ impl Profiler {
 
             // Unwinding until the parent.
             if unwind_found {
-                self.function_stack.truncate(unwind_point + 1);
                 self.function_stack_ranges.truncate(unwind_point + 1);
                 self.function_stack_indices.truncate(unwind_point + 1);
             }
@@ -140,10 +147,39 @@ impl Profiler {
         }
 
         if clk % self.sample_rate == 0 {
-            self.samples.push(Sample { stack: self.function_stack.clone() });
+            self.samples.push(Sample { stack: self.function_stack_indices.clone() });
         }
     }
 
+    /// Records a call to a syscall.
+    pub(super) fn record_syscall(&mut self, syscall: SyscallCode) {
+        *self.syscalls.entry(syscall).or_default() += 1;
+    }
+
+    /// The profile of the samples captured so far.
+    pub(super) fn profile(&self) -> Profile {
+        let mut stacks = HashMap::<&[usize], u64>::new();
+        for sample in &self.samples {
+            *stacks.entry(&sample.stack).or_default() += self.sample_rate;
+        }
+        let mut stacks = stacks
+            .into_iter()
+            .map(|(stack, cycles)| {
+                (stack.iter().map(|&idx| self.function_names[idx].clone()).collect(), cycles)
+            })
+            .collect::<Vec<_>>();
+        stacks.sort();
+
+        let mut syscalls = self
+            .syscalls
+            .iter()
+            .map(|(&syscall, &calls)| SyscallProfile { syscall, calls })
+            .collect::<Vec<_>>();
+        syscalls.sort_by_key(|profile| (std::cmp::Reverse(profile.calls), profile.syscall));
+
+        Profile { stacks, syscalls }
+    }
+
     /// Write the captured samples so far to the `std::io::Write`. This will output a JSON gecko
     /// profile.
     pub(super) fn write(mut self, writer: impl std::io::Write) -> Result<(), ProfilerError> {
@@ -174,9 +210,10 @@ impl Profiler {
         for sample in self.samples.drain(..) {
             pb.inc(1);
 
+            let function_ranges = &self.function_ranges;
             self.builder.add_sample(
                 last_known_time,
-                sample.stack.into_iter(),
+                sample.stack.into_iter().map(|idx| function_ranges[idx].2.clone()),
                 // We don't have a way to know the duration of each sample, so we just use 1us for
                 // all instructions.
                 std::time::Duration::from_micros(self.sample_rate),
@@ -206,15 +243,7 @@ impl Profiler {
             return;
         };
 
-        let main_count = self
-            .samples
-            .iter()
-            .filter(|s| {
-                s.stack
-                    .iter()
-                    .any(|f| if let Frame::Label(idx) = f { *idx == main_idx } else { false })
-            })
-            .count();
+        let main_count = self.samples.iter().filter(|s| s.stack.contains(&main_idx)).count();
 
         #[allow(clippy::cast_precision_loss)]
         let main_ratio = main_count as f64 / self.samples.len() as f64;
@@ -226,3 +255,129 @@ impl Profiler {
         }
     }
 }
+
+/// The cycles spent in each call stack of a profiled program.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Profile {
+    /// The sampled call stacks, from the outermost function, with the cycles spent in each.
+    pub stacks: Vec<(Vec<String>, u64)>,
+    /// The calls to each syscall, by decreasing number of calls.
+    pub syscalls: Vec<SyscallProfile>,
+}
+
+/// The calls to a syscall in a profiled program.
+///
+/// The cycles of a profile are executed instructions, so each call is a single cycle, its
+/// `ecall` instruction, which is counted in the call stack of the function making it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SyscallProfile {
+    /// The syscall.
+    pub syscall: SyscallCode,
+    /// The number of calls, which is also the number of cycles spent in them.
+    pub calls: u64,
+}
+
+/// The cycles spent in a function of a profiled program.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FunctionProfile {
+    /// The demangled name of the function.
+    pub name: String,
+    /// The cycles spent in the function itself.
+    pub self_cycles: u64,
+    /// The cycles spent in the function and its callees.
+    pub total_cycles: u64,
+}
+
+impl Profile {
+    /// The call stacks in the folded format read by flamegraph tools, with one `a;b;c cycles` line
+    /// per stack.
+    #[must_use]
+    pub fn folded(&self) -> String {
+        self.stacks
+            .iter()
+            .filter(|(stack, _)| !stack.is_empty())
+            .map(|(stack, cycles)| format!("{} {cycles}\n", stack.join(";")))
+            .collect()
+    }
+
+    /// Parses call stacks in the folded format, as written by [`Profile::folded`].
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if a line is not a stack followed by a number of cycles.
+    pub fn from_folded(folded: &str) -> Result<Self, ProfilerError> {
+        let stacks = folded
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| {
+                let (stack, cycles) = line
+                    .rsplit_once(' ')
+                    .and_then(|(stack, cycles)| Some((stack, cycles.parse().ok()?)))
+                    .ok_or_else(|| ProfilerError::Folded(line.to_string()))?;
+                Ok((stack.split(';').map(String::from).collect(), cycles))
+            })
+            .collect::<Result<_, ProfilerError>>()?;
+
+        Ok(Self { stacks, syscalls: Vec::new() })
+    }
+
+    /// The total number of sampled cycles.
+    #[must_use]
+    pub fn total_cycles(&self) -> u64 {
+        self.stacks.iter().map(|(_, cycles)| cycles).sum()
+    }
+
+    /// The cycles spent in each function, by decreasing number of cycles spent in the function
+    /// itself.
+    #[must_use]
+    pub fn functions(&self) -> Vec<FunctionProfile> {
+        let mut functions = HashMap::<&str, (u64, u64)>::new();
+        for (stack, cycles) in &self.stacks {
+            for (depth, name) in stack.iter().enumerate() {
+                // Recursive calls are only counted once in the total.
+                if stack[..depth].contains(name) {
+                    continue;
+                }
+                functions.entry(name).or_default().1 += cycles;
+            }
+            if let Some(name) = stack.last() {
+                functions.entry(name).or_default().0 += cycles;
+            }
+        }
+
+        let mut functions = functions
+            .into_iter()
+            .map(|(name, (self_cycles, total_cycles))| FunctionProfile {
+                name: name.to_string(),
+                self_cycles,
+                total_cycles,
+            })
+            .collect::<Vec<_>>();
+        functions.sort_by(|a, b| b.self_cycles.cmp(&a.self_cycles).then(a.name.cmp(&b.name)));
+        functions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_folded_round_trip() {
+        let stacks = vec![
+            (vec!["main".to_string()], 10),
+            (vec!["main".to_string(), "hash".to_string()], 30),
+            (vec!["main".to_string(), "fib".to_string(), "fib".to_string()], 20),
+        ];
+        let profile = Profile { stacks, syscalls: Vec::new() };
+        assert_eq!(Profile::from_folded(&profile.folded()).unwrap(), profile);
+        assert_eq!(profile.total_cycles(), 60);
+
+        let functions = profile.functions();
+        let cycles = functions
+            .iter()
+            .map(|f| (f.name.as_str(), f.self_cycles, f.total_cycles))
+            .collect::<Vec<_>>();
+        assert_eq!(cycles, [("hash", 30, 30), ("fib", 20, 20), ("main", 10, 60)]);
+    }
+}
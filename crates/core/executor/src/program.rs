@@ -2,15 +2,17 @@
 
 use std::{fs::File, io::Read};
 
-use hashbrown::HashMap;
+use hashbrown::{HashMap, HashSet};
 use p3_field::Field;
 use serde::{Deserialize, Serialize};
 use sp1_stark::air::{MachineAir, MachineProgram};
+use strum::IntoEnumIterator;
 
 use crate::{
     disassembler::{transpile, Elf},
     instruction::Instruction,
-    CoreShape,
+    syscalls::SyscallCode,
+    CoreShape, Opcode, Register,
 };
 
 /// A program that can be executed by the SP1 zkVM.
@@ -96,6 +98,86 @@ impl Program {
         let idx = ((pc - self.pc_base) / 4) as usize;
         &self.instructions[idx]
     }
+
+    /// Disassemble the instructions of the program along with their addresses.
+    ///
+    /// The syscall invoked by an `ecall` is resolved when the value of `t0` is set by constant
+    /// instructions earlier in the same basic block, which is how the toolchain emits syscalls.
+    #[must_use]
+    pub fn disassemble(&self) -> Vec<DisassembledInstruction> {
+        let pc = |i: usize| self.pc_base + 4 * i as u32;
+
+        // The targets of direct branches and jumps start a new basic block.
+        let targets = self
+            .instructions
+            .iter()
+            .enumerate()
+            .filter_map(|(i, instruction)| match instruction.opcode {
+                Opcode::JAL => Some(pc(i).wrapping_add(instruction.op_b)),
+                _ if instruction.is_branch_instruction() => {
+                    Some(pc(i).wrapping_add(instruction.op_c))
+                }
+                _ => None,
+            })
+            .collect::<HashSet<_>>();
+
+        let mut registers = [None; 32];
+        let mut disassembly = Vec::with_capacity(self.instructions.len());
+        for (i, instruction) in self.instructions.iter().enumerate() {
+            if targets.contains(&pc(i)) {
+                registers = [None; 32];
+            }
+            registers[Register::X0 as usize] = Some(0);
+
+            let syscall = if instruction.is_ecall_instruction() {
+                registers[Register::X5 as usize].and_then(|code: u32| {
+                    SyscallCode::iter().find(|syscall| *syscall as u32 == code)
+                })
+            } else {
+                None
+            };
+            disassembly.push(DisassembledInstruction {
+                pc: pc(i),
+                instruction: *instruction,
+                syscall,
+            });
+
+            let operand = |value: u32, imm: bool| {
+                if imm {
+                    Some(value)
+                } else {
+                    registers[value as usize]
+                }
+            };
+            let value = match instruction.opcode {
+                Opcode::ADD => operand(instruction.op_b, instruction.imm_b)
+                    .zip(operand(instruction.op_c, instruction.imm_c))
+                    .map(|(b, c)| b.wrapping_add(c)),
+                _ => None,
+            };
+            if instruction.is_jump_instruction() {
+                // The next instruction is only reached by a jump.
+                registers = [None; 32];
+            } else if !instruction.is_branch_instruction()
+                && !matches!(instruction.opcode, Opcode::SB | Opcode::SH | Opcode::SW)
+            {
+                registers[instruction.op_a as usize] = value;
+            }
+        }
+
+        disassembly
+    }
+}
+
+/// An instruction of a disassembled [`Program`].
+#[derive(Debug, Clone, Copy)]
+pub struct DisassembledInstruction {
+    /// The address of the instruction.
+    pub pc: u32,
+    /// The instruction.
+    pub instruction: Instruction,
+    /// The syscall invoked by the instruction, if it is an `ecall` whose syscall is known.
+    pub syscall: Option<SyscallCode>,
 }
 
 impl<F: Field> MachineProgram<F> for Program {
@@ -103,3 +185,46 @@ impl<F: Field> MachineProgram<F> for Program {
         F::from_canonical_u32(self.pc_start)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_disassemble_syscalls() {
+        let instructions = vec![
+            // li t0, COMMIT
+            Instruction::new(Opcode::ADD, 5, 0, 0x10, false, true),
+            Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+            // li t0, SHA_COMPRESS, as lui and addi
+            Instruction::new(Opcode::ADD, 5, 0, 0x10000, true, true),
+            Instruction::new(Opcode::ADD, 5, 5, 0x106, false, true),
+            Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+            // The ecall writes t0.
+            Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+            // li t0, HALT, followed by a jump to the ecall.
+            Instruction::new(Opcode::ADD, 5, 0, 0, false, true),
+            Instruction::new(Opcode::JAL, 0, 4, 0, true, true),
+            Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+        ];
+        let program = Program::new(instructions, 0x1000, 0x1000);
+
+        let disassembly = program.disassemble();
+        let syscalls = disassembly.iter().map(|insn| insn.syscall).collect::<Vec<_>>();
+        assert_eq!(
+            syscalls,
+            [
+                None,
+                Some(SyscallCode::COMMIT),
+                None,
+                None,
+                Some(SyscallCode::SHA_COMPRESS),
+                None,
+                None,
+                None,
+                None,
+            ]
+        );
+        assert_eq!(disassembly[8].pc, 0x1020);
+    }
+}
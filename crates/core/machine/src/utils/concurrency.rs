@@ -1,4 +1,12 @@
-use std::sync::{Condvar, Mutex};
+use std::{
+    sync::{Condvar, Mutex},
+    time::Duration,
+};
+
+use sp1_core_executor::CancellationToken;
+
+/// How often a waiting thread checks whether its cancellation token was cancelled.
+pub const CANCELLATION_POLL_INTERVAL: Duration = Duration::from_millis(100);
 
 /// A turn-based synchronization primitive.
 pub struct TurnBasedSync {
@@ -20,6 +28,20 @@ impl TurnBasedSync {
         }
     }
 
+    /// Waits for the current turn to be equal to the given turn, or for the token to be cancelled.
+    ///
+    /// Returns `false` if the token was cancelled before it was our turn.
+    pub fn wait_for_turn_or_cancel(&self, my_turn: usize, token: &CancellationToken) -> bool {
+        let mut turn = self.current_turn.lock().unwrap();
+        while *turn != my_turn {
+            if token.is_cancelled() {
+                return false;
+            }
+            turn = self.cv.wait_timeout(turn, CANCELLATION_POLL_INTERVAL).unwrap().0;
+        }
+        true
+    }
+
     /// Advances the current turn.
     pub fn advance_turn(&self) {
         let mut turn = self.current_turn.lock().unwrap();
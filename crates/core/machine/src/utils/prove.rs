@@ -15,7 +15,7 @@ use serde::{de::DeserializeOwned, Serialize};
 use size::Size;
 use sp1_stark::{
     air::InteractionScope, baby_bear_poseidon2::BabyBearPoseidon2, MachineProvingKey,
-    MachineVerificationError,
+    MachineVerificationError, SecurityPreset,
 };
 use std::thread::ScopedJoinHandle;
 use thiserror::Error;
@@ -55,6 +55,10 @@ pub enum SP1CoreProverError {
     IoError(io::Error),
     #[error("serialization error: {0}")]
     SerializationError(bincode::Error),
+    #[error("proof generation was cancelled")]
+    Cancelled,
+    #[error("the prover was initialized with the {expected} security preset, not {actual}")]
+    SecurityPresetMismatch { expected: SecurityPreset, actual: SecurityPreset },
 }
 
 pub fn prove_simple<SC: StarkGenericConfig, P: MachineProver<SC, RiscvAir<SC::Val>>>(
@@ -144,6 +148,7 @@ where
     PcsProverData<SC>: Send + Sync,
 {
     // Setup the runtime.
+    let monitor = context.monitor.clone();
     let mut runtime = Executor::with_context(program.clone(), opts, context);
     runtime.maximal_shapes = shape_config
         .map(|config| config.maximal_core_shapes().into_iter().map(|s| s.inner).collect());
@@ -166,8 +171,10 @@ where
         let checkpoint_generator_span = tracing::Span::current().clone();
         let (checkpoints_tx, checkpoints_rx) =
             sync_channel::<(usize, File, bool)>(opts.checkpoints_channel_capacity);
+        let checkpoint_generator_monitor = monitor.clone();
         let checkpoint_generator_handle: ScopedJoinHandle<Result<_, SP1CoreProverError>> =
             s.spawn(move || {
+                let monitor = checkpoint_generator_monitor;
                 let _span = checkpoint_generator_span.enter();
                 tracing::debug_span!("checkpoint generator").in_scope(|| {
                     let mut index = 0;
@@ -176,6 +183,11 @@ where
                         let span = tracing::debug_span!("batch");
                         let _span = span.enter();
 
+                        // Stop executing if the proof was cancelled.
+                        if monitor.is_cancelled() {
+                            return Err(SP1CoreProverError::Cancelled);
+                        }
+
                         // Execute the runtime until we reach a checkpoint.
                         let (checkpoint, done) = runtime
                             .execute_state(false)
@@ -226,6 +238,7 @@ where
             let state = Arc::clone(&state);
             let deferred = Arc::clone(&deferred);
             let program = program.clone();
+            let monitor = monitor.clone();
 
             let span = tracing::Span::current().clone();
 
@@ -237,6 +250,11 @@ where
                         let received = { checkpoints_rx.lock().unwrap().recv() };
 
                         if let Ok((index, mut checkpoint, done)) = received {
+                            // Drain the remaining checkpoints if the proof was cancelled.
+                            if monitor.is_cancelled() {
+                                continue;
+                            }
+
                             // Trace the checkpoint and reconstruct the execution records.
                             let (mut records, _) = tracing::debug_span!("trace checkpoint")
                                 .in_scope(|| {
@@ -252,7 +270,9 @@ where
 
                             // Wait for our turn to update the state.
                             tracing::debug!("waiting for turn {}", index);
-                            record_gen_sync.wait_for_turn(index);
+                            if !record_gen_sync.wait_for_turn_or_cancel(index, monitor.token()) {
+                                continue;
+                            }
 
                             // Update the public values & prover state for the shards which contain
                             // "cpu events".
@@ -306,6 +326,7 @@ where
 
                             // Let another worker update the state.
                             record_gen_sync.advance_turn();
+                            monitor.shards_executed(records.len());
 
                             // Fix the shape of the records.
                             if let Some(shape_config) = shape_config {
@@ -327,7 +348,9 @@ where
                             });
 
                             // Wait for our turn.
-                            trace_gen_sync.wait_for_turn(index);
+                            if !trace_gen_sync.wait_for_turn_or_cancel(index, monitor.token()) {
+                                continue;
+                            }
 
                             // Send the records to the phase 1 prover.
                             let chunked_records = chunk_vec(records, opts.shard_batch_size);
@@ -359,10 +382,15 @@ where
 
         // Spawn the phase 1 prover thread.
         let phase_1_prover_span = tracing::Span::current().clone();
+        let phase_1_prover_monitor = monitor.clone();
         let phase_1_prover_handle = s.spawn(move || {
             let _span = phase_1_prover_span.enter();
             tracing::debug_span!("phase 1 prover").in_scope(|| {
                 for (records, traces) in p1_records_and_traces_rx.iter() {
+                    // Drain the remaining records if the proof was cancelled.
+                    if phase_1_prover_monitor.is_cancelled() {
+                        continue;
+                    }
                     tracing::debug_span!("batch").in_scope(|| {
                         let span = tracing::Span::current().clone();
 
@@ -416,7 +444,7 @@ where
         });
 
         // Wait until the checkpoint generator handle has fully finished.
-        let public_values_stream = checkpoint_generator_handle.join().unwrap().unwrap();
+        let public_values_stream = checkpoint_generator_handle.join().unwrap();
 
         // Wait until the records and traces have been fully generated.
         p1_record_and_trace_gen_handles.into_iter().for_each(|handle| handle.join().unwrap());
@@ -424,6 +452,13 @@ where
         // Wait until the phase 1 prover has completely finished.
         let mut challenger = phase_1_prover_handle.join().unwrap();
 
+        // Stop before the second phase if the execution failed or the proof was cancelled.
+        let public_values_stream = public_values_stream?;
+        if monitor.is_cancelled() {
+            return Err(SP1CoreProverError::Cancelled);
+        }
+        monitor.execution_finished();
+
         // Sample for the global permutation challenges.
         // Obtain the challenges used for the global permutation argument.
         let mut global_permutation_challenges: Vec<SC::Challenge> = Vec::new();
@@ -458,6 +493,7 @@ where
             let state = Arc::clone(&state);
             let deferred = Arc::clone(&deferred);
             let program = program.clone();
+            let monitor = monitor.clone();
 
             let span = tracing::Span::current().clone();
 
@@ -468,6 +504,11 @@ where
                 let _span = span.enter();
                 tracing::debug_span!("phase 2 trace generation").in_scope(|| {
                     loop {
+                        // Stop tracing if the proof was cancelled.
+                        if monitor.is_cancelled() {
+                            break;
+                        }
+
                         // Receive the latest checkpoint.
                         let received = { checkpoints.lock().unwrap().pop_front() };
                         if let Some((index, mut checkpoint, done)) = received {
@@ -486,7 +527,9 @@ where
                             reset_seek(&mut checkpoint);
 
                             // Wait for our turn to update the state.
-                            record_gen_sync.wait_for_turn(index);
+                            if !record_gen_sync.wait_for_turn_or_cancel(index, monitor.token()) {
+                                continue;
+                            }
 
                             // Update the public values & prover state for the shards which contain
                             // "cpu events".
@@ -572,7 +615,9 @@ where
                                     .collect::<Vec<_>>();
                             });
 
-                            trace_gen_sync.wait_for_turn(index);
+                            if !trace_gen_sync.wait_for_turn_or_cancel(index, monitor.token()) {
+                                continue;
+                            }
 
                             // Send the records to the phase 2 prover.
                             let chunked_records = chunk_vec(records, opts.shard_batch_size);
@@ -607,11 +652,17 @@ where
 
         // Spawn the phase 2 prover thread.
         let p2_prover_span = tracing::Span::current().clone();
+        let p2_prover_monitor = monitor.clone();
         let p2_prover_handle = s.spawn(move || {
             let _span = p2_prover_span.enter();
+            let monitor = p2_prover_monitor;
             let mut shard_proofs = Vec::new();
             tracing::debug_span!("phase 2 prover").in_scope(|| {
                 for (records, traces) in p2_records_and_traces_rx.into_iter() {
+                    // Drain the remaining records if the proof was cancelled.
+                    if monitor.is_cancelled() {
+                        continue;
+                    }
                     tracing::debug_span!("batch").in_scope(|| {
                         let span = tracing::Span::current().clone();
                         shard_proofs.par_extend(
@@ -639,6 +690,7 @@ where
                                         )
                                         .unwrap();
                                     opening_span.exit();
+                                    monitor.shard_proven();
 
                                     #[cfg(debug_assertions)]
                                     {
@@ -664,6 +716,9 @@ where
 
         // Wait until the phase 2 prover has finished.
         let shard_proofs = p2_prover_handle.join().unwrap();
+        if monitor.is_cancelled() {
+            return Err(SP1CoreProverError::Cancelled);
+        }
 
         // Log some of the `ExecutionReport` information.
         let report_aggregate = report_aggregate.lock().unwrap();
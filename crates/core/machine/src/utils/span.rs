@@ -26,7 +26,15 @@ where
     where
         T: Hash + Eq,
     {
-        self.current_span.cts.entry(item_name.into()).and_modify(|x| *x += 1).or_insert(1);
+        self.items(item_name, 1)
+    }
+
+    /// Add `ct` occurrences of an item to this span.
+    pub fn items(&mut self, item_name: impl Into<T>, ct: usize) -> &mut Self
+    where
+        T: Hash + Eq,
+    {
+        self.current_span.cts.entry(item_name.into()).and_modify(|x| *x += ct).or_insert(ct);
         self
     }
 
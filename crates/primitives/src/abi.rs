@@ -0,0 +1,603 @@
+//! A schema for public values encoded with the Solidity ABI.
+//!
+//! Public values are usually written with bincode, while Solidity contracts read them with
+//! `abi.decode`. The [`public_values!`](crate::public_values) macro defines a struct once and
+//! derives from it the ABI encoding a program commits, the strict decoder the host reads the
+//! public values with, and the fields of the matching Solidity struct.
+//!
+//! ```
+//! use sp1_primitives::{abi::PublicValuesSchema, public_values};
+//!
+//! public_values! {
+//!     pub struct PublicValuesStruct {
+//!         pub n: u32,
+//!         pub a: u32,
+//!         pub b: u32,
+//!     }
+//! }
+//!
+//! let values = PublicValuesStruct { n: 10, a: 34, b: 55 };
+//! let bytes = values.abi_encode();
+//! assert_eq!(PublicValuesStruct::abi_decode(&bytes).unwrap(), values);
+//! ```
+//!
+//! # Schema id
+//! The public values are committed with the id of their schema, the keccak256 hash of its
+//! signature such as `PublicValuesStruct(uint32 n,uint32 a,uint32 b)`, as the ABI encoding of
+//! `(bytes32 schemaId, PublicValuesStruct values)`. The decoder checks the id, so values committed
+//! with another schema are rejected even if their encodings match, and the committed-values digest
+//! the verifier checks covers the id. A Solidity contract decodes them with
+//! `abi.decode(publicValues, (bytes32, PublicValuesStruct))` and checks the id.
+
+use std::fmt;
+
+use sha3::{Digest, Keccak256};
+
+/// The size of an ABI word in bytes.
+pub const WORD_SIZE: usize = 32;
+
+/// An error that occurs when decoding ABI-encoded public values.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AbiError {
+    /// The data ended before the value.
+    UnexpectedEnd,
+    /// A word is not a valid encoding of a value of its type.
+    InvalidWord(&'static str),
+    /// A string is not valid UTF-8.
+    InvalidUtf8,
+    /// The data decodes to a value, but is not the encoding of that value, for example because of
+    /// trailing bytes.
+    NonCanonical,
+    /// The data was committed with another schema.
+    SchemaMismatch,
+}
+
+impl fmt::Display for AbiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnexpectedEnd => write!(f, "unexpected end of data"),
+            Self::InvalidWord(ty) => write!(f, "invalid {ty} word"),
+            Self::InvalidUtf8 => write!(f, "invalid utf-8 string"),
+            Self::NonCanonical => write!(f, "data is not the canonical encoding of the value"),
+            Self::SchemaMismatch => write!(f, "data was committed with another schema"),
+        }
+    }
+}
+
+impl std::error::Error for AbiError {}
+
+/// A type with a Solidity ABI encoding.
+///
+/// The encoding of a static type is a single word, written in the head of the enclosing tuple.
+/// The encoding of a dynamic type is written in the tail of the enclosing tuple, and the head
+/// holds its offset.
+pub trait SolType: Sized {
+    /// Whether the type is dynamic.
+    const DYNAMIC: bool;
+
+    /// The name of the type in Solidity.
+    fn sol_type() -> String;
+
+    /// Appends the encoding of the value.
+    fn encode(&self, out: &mut Vec<u8>);
+
+    /// Decodes a value whose encoding starts at the beginning of `data`.
+    fn decode(data: &[u8]) -> Result<Self, AbiError>;
+}
+
+/// Public values with a Solidity ABI encoding, defined with [`public_values!`](crate::public_values).
+///
+/// The encoding is that of `abi.encode(schemaId, values)` for the matching Solidity struct, so a
+/// contract reads them with `abi.decode(publicValues, (bytes32, PublicValues))`, see the
+/// [module documentation](self).
+pub trait PublicValuesSchema: Sized {
+    /// Whether the struct has a dynamic field.
+    const DYNAMIC: bool;
+
+    /// The name of the struct.
+    fn name() -> &'static str;
+
+    /// The fields of the struct, as pairs of a name and a Solidity type.
+    fn fields() -> Vec<(&'static str, String)>;
+
+    /// Appends the encoding of the fields as a tuple.
+    fn encode_tuple(&self, out: &mut Vec<u8>);
+
+    /// Decodes the fields from a tuple starting at the beginning of `data`.
+    fn decode_tuple(data: &[u8]) -> Result<Self, AbiError>;
+
+    /// The signature of the schema, such as `PublicValuesStruct(uint32 n,uint32 a,uint32 b)`.
+    fn signature() -> String {
+        let fields = Self::fields().into_iter().map(|(name, ty)| format!("{ty} {name}"));
+        format!("{}({})", Self::name(), fields.collect::<Vec<_>>().join(","))
+    }
+
+    /// The id of the schema committed with the public values: the keccak256 hash of its signature.
+    fn schema_id() -> [u8; 32] {
+        Keccak256::digest(Self::signature().as_bytes()).into()
+    }
+
+    /// The ABI encoding of the schema id and the public values, which the program commits.
+    fn abi_encode(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        Self::schema_id().encode(&mut out);
+        if Self::DYNAMIC {
+            write_usize(&mut out, 2 * WORD_SIZE);
+        }
+        self.encode_tuple(&mut out);
+        out
+    }
+
+    /// Decodes ABI-encoded public values.
+    ///
+    /// The decoding is strict: `data` must start with the id of the schema and be exactly the
+    /// encoding of the decoded value, so values committed with another schema fail to decode.
+    fn abi_decode(data: &[u8]) -> Result<Self, AbiError> {
+        if <[u8; 32]>::decode(data)? != Self::schema_id() {
+            return Err(AbiError::SchemaMismatch);
+        }
+        let tuple = if Self::DYNAMIC {
+            if read_usize(&data[WORD_SIZE..])? != 2 * WORD_SIZE {
+                return Err(AbiError::NonCanonical);
+            }
+            &data[2 * WORD_SIZE..]
+        } else {
+            &data[WORD_SIZE..]
+        };
+        let value = Self::decode_tuple(tuple)?;
+        if value.abi_encode() != data {
+            return Err(AbiError::NonCanonical);
+        }
+        Ok(value)
+    }
+}
+
+/// Encodes the fields of a tuple.
+pub struct TupleEncoder {
+    head_len: usize,
+    head: Vec<u8>,
+    tail: Vec<u8>,
+}
+
+impl TupleEncoder {
+    /// Creates an encoder for a tuple of `len` fields.
+    pub fn new(len: usize) -> Self {
+        Self { head_len: len * WORD_SIZE, head: Vec::new(), tail: Vec::new() }
+    }
+
+    /// Encodes the next field.
+    pub fn push<T: SolType>(&mut self, value: &T) {
+        if T::DYNAMIC {
+            write_usize(&mut self.head, self.head_len + self.tail.len());
+            value.encode(&mut self.tail);
+        } else {
+            value.encode(&mut self.head);
+        }
+    }
+
+    /// Appends the encoding of the tuple.
+    pub fn finish(self, out: &mut Vec<u8>) {
+        out.extend(self.head);
+        out.extend(self.tail);
+    }
+}
+
+/// Decodes the fields of a tuple.
+pub struct TupleDecoder<'a> {
+    data: &'a [u8],
+    index: usize,
+}
+
+impl<'a> TupleDecoder<'a> {
+    /// Creates a decoder for a tuple starting at the beginning of `data`.
+    pub fn new(data: &'a [u8]) -> Self {
+        Self { data, index: 0 }
+    }
+
+    /// Decodes the next field.
+    pub fn decode<T: SolType>(&mut self) -> Result<T, AbiError> {
+        let head = self.data.get(self.index * WORD_SIZE..).ok_or(AbiError::UnexpectedEnd)?;
+        self.index += 1;
+        if T::DYNAMIC {
+            let offset = read_usize(head)?;
+            T::decode(self.data.get(offset..).ok_or(AbiError::UnexpectedEnd)?)
+        } else {
+            T::decode(head)
+        }
+    }
+}
+
+/// Defines a struct of public values with a Solidity ABI encoding.
+///
+/// The fields are encoded in order, and their types must implement [`SolType`](crate::abi::SolType):
+/// integers up to 128 bits, `bool`, [`Address`](crate::abi::Address), `[u8; 32]` as `bytes32`,
+/// [`Bytes`](crate::abi::Bytes), `String` and `Vec` of those as dynamic arrays. The struct derives
+/// `Debug`, `Clone`, `PartialEq` and `Eq`.
+#[macro_export]
+macro_rules! public_values {
+    (
+        $(#[$meta:meta])*
+        $vis:vis struct $name:ident {
+            $($(#[$field_meta:meta])* $field_vis:vis $field:ident : $ty:ty),* $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, PartialEq, Eq)]
+        $vis struct $name {
+            $($(#[$field_meta])* $field_vis $field: $ty,)*
+        }
+
+        impl $crate::abi::PublicValuesSchema for $name {
+            const DYNAMIC: bool = false $(|| <$ty as $crate::abi::SolType>::DYNAMIC)*;
+
+            fn name() -> &'static str {
+                ::core::stringify!($name)
+            }
+
+            fn fields() -> ::std::vec::Vec<(&'static str, ::std::string::String)> {
+                ::std::vec![
+                    $((::core::stringify!($field), <$ty as $crate::abi::SolType>::sol_type())),*
+                ]
+            }
+
+            fn encode_tuple(&self, out: &mut ::std::vec::Vec<u8>) {
+                let len = [$(::core::stringify!($field)),*].len();
+                let mut encoder = $crate::abi::TupleEncoder::new(len);
+                $(encoder.push(&self.$field);)*
+                encoder.finish(out);
+            }
+
+            fn decode_tuple(data: &[u8]) -> ::core::result::Result<Self, $crate::abi::AbiError> {
+                let mut decoder = $crate::abi::TupleDecoder::new(data);
+                ::core::result::Result::Ok(Self { $($field: decoder.decode()?,)* })
+            }
+        }
+    };
+}
+
+/// A Solidity `address`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Address(pub [u8; 20]);
+
+/// A Solidity `bytes`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Bytes(pub Vec<u8>);
+
+fn word(data: &[u8]) -> Result<&[u8; WORD_SIZE], AbiError> {
+    data.get(..WORD_SIZE).ok_or(AbiError::UnexpectedEnd).map(|word| word.try_into().unwrap())
+}
+
+fn write_usize(out: &mut Vec<u8>, value: usize) {
+    (value as u64).encode(out);
+}
+
+fn read_usize(data: &[u8]) -> Result<usize, AbiError> {
+    let value = u64::decode(data)?;
+    usize::try_from(value).map_err(|_| AbiError::UnexpectedEnd)
+}
+
+/// Appends `bytes` padded with zeros to a multiple of the word size.
+fn write_padded(out: &mut Vec<u8>, bytes: &[u8]) {
+    out.extend_from_slice(bytes);
+    out.resize(out.len() + (WORD_SIZE - bytes.len() % WORD_SIZE) % WORD_SIZE, 0);
+}
+
+/// Reads a length-prefixed byte string.
+fn read_bytes(data: &[u8]) -> Result<&[u8], AbiError> {
+    let len = read_usize(data)?;
+    data[WORD_SIZE..].get(..len).ok_or(AbiError::UnexpectedEnd)
+}
+
+macro_rules! impl_uint {
+    ($($ty:ty),*) => {
+        $(
+            impl SolType for $ty {
+                const DYNAMIC: bool = false;
+
+                fn sol_type() -> String {
+                    format!("uint{}", <$ty>::BITS)
+                }
+
+                fn encode(&self, out: &mut Vec<u8>) {
+                    let bytes = self.to_be_bytes();
+                    out.resize(out.len() + WORD_SIZE - bytes.len(), 0);
+                    out.extend_from_slice(&bytes);
+                }
+
+                fn decode(data: &[u8]) -> Result<Self, AbiError> {
+                    let word = word(data)?;
+                    let (padding, bytes) = word.split_at(WORD_SIZE - std::mem::size_of::<$ty>());
+                    if padding.iter().any(|&byte| byte != 0) {
+                        return Err(AbiError::InvalidWord("uint"));
+                    }
+                    Ok(<$ty>::from_be_bytes(bytes.try_into().unwrap()))
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_int {
+    ($($ty:ty),*) => {
+        $(
+            impl SolType for $ty {
+                const DYNAMIC: bool = false;
+
+                fn sol_type() -> String {
+                    format!("int{}", <$ty>::BITS)
+                }
+
+                fn encode(&self, out: &mut Vec<u8>) {
+                    let bytes = self.to_be_bytes();
+                    let sign = if *self < 0 { 0xff } else { 0 };
+                    out.resize(out.len() + WORD_SIZE - bytes.len(), sign);
+                    out.extend_from_slice(&bytes);
+                }
+
+                fn decode(data: &[u8]) -> Result<Self, AbiError> {
+                    let word = word(data)?;
+                    let (padding, bytes) = word.split_at(WORD_SIZE - std::mem::size_of::<$ty>());
+                    let value = <$ty>::from_be_bytes(bytes.try_into().unwrap());
+                    let sign = if value < 0 { 0xff } else { 0 };
+                    if padding.iter().any(|&byte| byte != sign) {
+                        return Err(AbiError::InvalidWord("int"));
+                    }
+                    Ok(value)
+                }
+            }
+        )*
+    };
+}
+
+impl_uint!(u8, u16, u32, u64, u128);
+impl_int!(i8, i16, i32, i64, i128);
+
+impl SolType for bool {
+    const DYNAMIC: bool = false;
+
+    fn sol_type() -> String {
+        "bool".to_string()
+    }
+
+    fn encode(&self, out: &mut Vec<u8>) {
+        u8::from(*self).encode(out);
+    }
+
+    fn decode(data: &[u8]) -> Result<Self, AbiError> {
+        let (padding, value) = word(data)?.split_at(WORD_SIZE - 1);
+        match value[0] {
+            0 | 1 if padding.iter().all(|&byte| byte == 0) => Ok(value[0] == 1),
+            _ => Err(AbiError::InvalidWord("bool")),
+        }
+    }
+}
+
+impl SolType for Address {
+    const DYNAMIC: bool = false;
+
+    fn sol_type() -> String {
+        "address".to_string()
+    }
+
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.resize(out.len() + WORD_SIZE - self.0.len(), 0);
+        out.extend_from_slice(&self.0);
+    }
+
+    fn decode(data: &[u8]) -> Result<Self, AbiError> {
+        let word = word(data)?;
+        let (padding, address) = word.split_at(WORD_SIZE - 20);
+        if padding.iter().any(|&byte| byte != 0) {
+            return Err(AbiError::InvalidWord("address"));
+        }
+        Ok(Self(address.try_into().unwrap()))
+    }
+}
+
+impl SolType for [u8; 32] {
+    const DYNAMIC: bool = false;
+
+    fn sol_type() -> String {
+        "bytes32".to_string()
+    }
+
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(self);
+    }
+
+    fn decode(data: &[u8]) -> Result<Self, AbiError> {
+        word(data).copied()
+    }
+}
+
+impl SolType for Bytes {
+    const DYNAMIC: bool = true;
+
+    fn sol_type() -> String {
+        "bytes".to_string()
+    }
+
+    fn encode(&self, out: &mut Vec<u8>) {
+        write_usize(out, self.0.len());
+        write_padded(out, &self.0);
+    }
+
+    fn decode(data: &[u8]) -> Result<Self, AbiError> {
+        read_bytes(data).map(|bytes| Self(bytes.to_vec()))
+    }
+}
+
+impl SolType for String {
+    const DYNAMIC: bool = true;
+
+    fn sol_type() -> String {
+        "string".to_string()
+    }
+
+    fn encode(&self, out: &mut Vec<u8>) {
+        write_usize(out, self.len());
+        write_padded(out, self.as_bytes());
+    }
+
+    fn decode(data: &[u8]) -> Result<Self, AbiError> {
+        let bytes = read_bytes(data)?;
+        String::from_utf8(bytes.to_vec()).map_err(|_| AbiError::InvalidUtf8)
+    }
+}
+
+impl<T: SolType> SolType for Vec<T> {
+    const DYNAMIC: bool = true;
+
+    fn sol_type() -> String {
+        format!("{}[]", T::sol_type())
+    }
+
+    fn encode(&self, out: &mut Vec<u8>) {
+        write_usize(out, self.len());
+        let mut encoder = TupleEncoder::new(self.len());
+        for element in self {
+            encoder.push(element);
+        }
+        encoder.finish(out);
+    }
+
+    fn decode(data: &[u8]) -> Result<Self, AbiError> {
+        let len = read_usize(data)?;
+        let elements = &data[WORD_SIZE..];
+        // Each element takes at least a word, which bounds the allocation.
+        if len > elements.len() / WORD_SIZE {
+            return Err(AbiError::UnexpectedEnd);
+        }
+        let mut decoder = TupleDecoder::new(elements);
+        (0..len).map(|_| decoder.decode()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    crate::public_values! {
+        struct StaticValues {
+            n: u32,
+            negative: i64,
+            flag: bool,
+            owner: Address,
+        }
+    }
+
+    crate::public_values! {
+        struct DynamicValues {
+            n: u32,
+            data: Bytes,
+            names: Vec<String>,
+        }
+    }
+
+    fn words(words: &[&str]) -> Vec<u8> {
+        words.iter().flat_map(|word| hex::decode(format!("{word:0>64}")).unwrap()).collect()
+    }
+
+    #[test]
+    fn test_static_encoding() {
+        let values = StaticValues { n: 7, negative: -2, flag: true, owner: Address([0x11; 20]) };
+        let schema_id = hex::encode(StaticValues::schema_id());
+        let expected =
+            words(&[&schema_id, "07", &format!("{}fe", "ff".repeat(31)), "01", &"11".repeat(20)]);
+        assert_eq!(values.abi_encode(), expected);
+        assert_eq!(StaticValues::abi_decode(&expected).unwrap(), values);
+        assert_eq!(
+            StaticValues::fields(),
+            vec![
+                ("n", "uint32".to_string()),
+                ("negative", "int64".to_string()),
+                ("flag", "bool".to_string()),
+                ("owner", "address".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_dynamic_encoding() {
+        let values = DynamicValues {
+            n: 1,
+            data: Bytes(vec![0x12, 0x34]),
+            names: vec!["a".to_string(), "b".to_string()],
+        };
+        let schema_id = hex::encode(DynamicValues::schema_id());
+        let expected = words(&[
+            // The schema id and the offset of the struct.
+            &schema_id,
+            "40",
+            // The head of the struct.
+            "01",
+            "60",
+            "a0",
+            // The bytes.
+            "02",
+            &format!("{:0<64}", "1234"),
+            // The array, with the offsets of its elements.
+            "02",
+            "40",
+            "80",
+            "01",
+            &format!("{:0<64}", "61"),
+            "01",
+            &format!("{:0<64}", "62"),
+        ]);
+        assert_eq!(values.abi_encode(), expected);
+        assert_eq!(DynamicValues::abi_decode(&expected).unwrap(), values);
+        assert_eq!(DynamicValues::fields()[2], ("names", "string[]".to_string()));
+    }
+
+    #[test]
+    fn test_strict_decoding() {
+        let values = StaticValues { n: 7, negative: -2, flag: true, owner: Address([0x11; 20]) };
+        let bytes = values.abi_encode();
+
+        // Trailing bytes.
+        let mut trailing = bytes.clone();
+        trailing.push(0);
+        assert_eq!(StaticValues::abi_decode(&trailing), Err(AbiError::NonCanonical));
+
+        // Values out of the range of the type.
+        let mut overflow = bytes.clone();
+        overflow[WORD_SIZE + 27] = 1;
+        assert_eq!(StaticValues::abi_decode(&overflow), Err(AbiError::InvalidWord("uint")));
+
+        // A schema that does not match.
+        assert_eq!(DynamicValues::abi_decode(&bytes), Err(AbiError::SchemaMismatch));
+        assert_eq!(StaticValues::abi_decode(&bytes[..64]), Err(AbiError::UnexpectedEnd));
+    }
+
+    #[test]
+    fn test_schema_id() {
+        assert_eq!(
+            StaticValues::signature(),
+            "StaticValues(uint32 n,int64 negative,bool flag,address owner)"
+        );
+        assert_eq!(
+            hex::encode(StaticValues::schema_id()),
+            "e3de2d48d584ff503bf9c646705fa5af52c55ef2f3b0fb0fe4a266ddbf9802b8"
+        );
+    }
+
+    #[test]
+    fn test_schema_with_same_encoding() {
+        crate::public_values! {
+            struct Swapped {
+                m: u32,
+                positive: i64,
+                set: bool,
+                recipient: Address,
+            }
+        }
+
+        // The fields have the same encoding, but the committed schema id does not match.
+        let values = StaticValues { n: 7, negative: -2, flag: true, owner: Address([0x11; 20]) };
+        let bytes = values.abi_encode();
+        let swapped = Swapped { m: 7, positive: -2, set: true, recipient: Address([0x11; 20]) };
+        assert_eq!(bytes[WORD_SIZE..], swapped.abi_encode()[WORD_SIZE..]);
+        assert_eq!(Swapped::abi_decode(&bytes), Err(AbiError::SchemaMismatch));
+    }
+}
@@ -1,4 +1,7 @@
-use crate::types::Buffer;
+use crate::{
+    abi::{AbiError, PublicValuesSchema},
+    types::Buffer,
+};
 use num_bigint::BigUint;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use sha2::{Digest, Sha256};
@@ -37,6 +40,14 @@ impl SP1PublicValues {
         self.buffer.read()
     }
 
+    /// Decode the public values with an ABI schema.
+    ///
+    /// Unlike [`Self::read`], this decodes the whole buffer, which must be exactly the ABI
+    /// encoding committed with the schema.
+    pub fn read_abi<T: PublicValuesSchema>(&self) -> Result<T, AbiError> {
+        T::abi_decode(self.as_slice())
+    }
+
     /// Read a slice of bytes from the buffer.
     pub fn read_slice(&mut self, slice: &mut [u8]) {
         self.buffer.read_slice(slice);
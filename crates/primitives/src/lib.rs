@@ -3,9 +3,10 @@
 
 use lazy_static::lazy_static;
 use p3_baby_bear::{BabyBear, DiffusionMatrixBabyBear};
-use p3_field::AbstractField;
+use p3_field::{AbstractField, PrimeField32};
 use p3_poseidon2::{Poseidon2, Poseidon2ExternalMatrixGeneral};
 
+pub mod abi;
 pub mod consts;
 pub mod io;
 pub mod types;
@@ -1165,3 +1166,32 @@ pub fn hash_deferred_proof(
     inputs.extend_from_slice(pv_digest);
     poseidon2_hash(inputs.to_vec())
 }
+
+/// The domain separation tag of the verifying key digests of foreign proofs.
+pub const FOREIGN_VK_DIGEST_TAG: &[u8; 16] = b"sp1-foreign-vkey";
+
+/// Hash the digest of the verifying key of a foreign proof into the verifying key digest that a
+/// program passes to `verify_sp1_proof`.
+///
+/// The digest is hashed with Poseidon2 after [`FOREIGN_VK_DIGEST_TAG`], with one element per byte
+/// of the tag, so that a foreign proof is never accumulated under the digest of an SP1 program.
+pub fn hash_foreign_vk(vk_digest: &[BabyBear; 8]) -> [BabyBear; 8] {
+    let mut inputs = Vec::with_capacity(FOREIGN_VK_DIGEST_TAG.len() + 8);
+    inputs.extend(FOREIGN_VK_DIGEST_TAG.iter().map(|byte| BabyBear::from_canonical_u8(*byte)));
+    inputs.extend_from_slice(vk_digest);
+    poseidon2_hash(inputs)
+}
+
+/// Hash the public values of a foreign proof into the public values digest that a program passes
+/// to `verify_sp1_proof`.
+///
+/// The public values are hashed with Poseidon2, and each element of the hash is written as four
+/// little-endian bytes.
+pub fn hash_foreign_public_values(public_values: &[BabyBear]) -> [u8; 32] {
+    let digest = poseidon2_hash(public_values.to_vec());
+    let mut bytes = [0u8; 32];
+    for (chunk, element) in bytes.chunks_exact_mut(4).zip(digest) {
+        chunk.copy_from_slice(&element.as_canonical_u32().to_le_bytes());
+    }
+    bytes
+}
@@ -3,14 +3,18 @@ use std::path::PathBuf;
 use clap::Parser;
 use sp1_core_machine::utils::setup_logger;
 use sp1_prover::{
-    components::CpuProverComponents, shapes::build_vk_map_to_file, REDUCE_BATCH_SIZE,
+    components::CpuProverComponents,
+    shapes::{build_vk_map_to_file, vk_map_dir},
+    REDUCE_BATCH_SIZE,
 };
+use sp1_stark::SecurityPreset;
 
 #[derive(Parser, Debug)]
 #[clap(author, version, about, long_about = None)]
 struct Args {
+    /// Defaults to the directory the prover reads the vk map of the preset from.
     #[clap(short, long)]
-    build_dir: PathBuf,
+    build_dir: Option<PathBuf>,
     #[clap(short, long, default_value_t = false)]
     dummy: bool,
     #[clap(short, long, default_value_t = REDUCE_BATCH_SIZE)]
@@ -23,6 +27,8 @@ struct Args {
     start: Option<usize>,
     #[clap(short, long)]
     end: Option<usize>,
+    #[clap(long, default_value_t = SecurityPreset::Standard)]
+    security_preset: SecurityPreset,
 }
 
 fn main() {
@@ -30,7 +36,8 @@ fn main() {
     let args = Args::parse();
 
     let reduce_batch_size = args.reduce_batch_size;
-    let build_dir = args.build_dir;
+    let security_preset = args.security_preset;
+    let build_dir = args.build_dir.unwrap_or_else(|| vk_map_dir(security_preset));
     let dummy = args.dummy;
     let num_compiler_workers = args.num_compiler_workers;
     let num_setup_workers = args.num_setup_workers;
@@ -45,6 +52,7 @@ fn main() {
         num_setup_workers,
         range_start,
         range_end,
+        security_preset,
     )
     .unwrap();
 }
@@ -3,16 +3,19 @@ use std::path::PathBuf;
 use clap::Parser;
 use sp1_core_machine::utils::setup_logger;
 use sp1_prover::build::build_plonk_bn254_artifacts_with_dummy;
+use sp1_stark::SecurityPreset;
 
 #[derive(Parser, Debug)]
 #[clap(author, version, about, long_about = None)]
 struct Args {
     #[clap(short, long)]
     build_dir: PathBuf,
+    #[clap(long, default_value_t = SecurityPreset::Standard)]
+    security_preset: SecurityPreset,
 }
 
 pub fn main() {
     setup_logger();
     let args = Args::parse();
-    build_plonk_bn254_artifacts_with_dummy(args.build_dir);
+    build_plonk_bn254_artifacts_with_dummy(args.build_dir, args.security_preset);
 }
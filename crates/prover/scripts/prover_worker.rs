@@ -0,0 +1,28 @@
+use clap::Parser;
+use sp1_core_machine::utils::setup_logger;
+use sp1_prover::{
+    components::CpuProverComponents,
+    distributed::{Endpoint, ProverWorker, DEFAULT_MAX_SESSIONS},
+    SP1Prover,
+};
+
+#[derive(Parser, Debug)]
+#[clap(author, version, about, long_about = None)]
+struct Args {
+    /// The endpoint to listen on, e.g. `tcp://10.0.0.2:3000` or `unix:///tmp/sp1-worker.sock`.
+    /// Sessions are not authenticated, so only listen on a trusted network.
+    #[clap(short, long)]
+    listen: Endpoint,
+
+    /// The number of sessions served concurrently.
+    #[clap(long, default_value_t = DEFAULT_MAX_SESSIONS)]
+    max_sessions: usize,
+}
+
+fn main() {
+    setup_logger();
+    let args = Args::parse();
+
+    let prover = SP1Prover::<CpuProverComponents>::new();
+    ProverWorker::new(prover).with_max_sessions(args.max_sessions).serve(&args.listen).unwrap();
+}
@@ -2,7 +2,7 @@ use std::{borrow::Borrow, path::PathBuf};
 
 use p3_baby_bear::BabyBear;
 use sp1_core_executor::SP1Context;
-use sp1_core_machine::io::SP1Stdin;
+use sp1_core_machine::{io::SP1Stdin, utils::Span, SP1_CIRCUIT_VERSION};
 use sp1_recursion_circuit::{
     hash::FieldHasherVariable,
     machine::{SP1CompressWitnessValues, SP1WrapVerifier},
@@ -11,6 +11,7 @@ use sp1_recursion_compiler::{
     config::OuterConfig,
     constraints::{Constraint, ConstraintCompiler},
     ir::Builder,
+    profile::SpanMarkers,
 };
 
 use sp1_recursion_core::air::RecursionPublicValues;
@@ -18,8 +19,11 @@ pub use sp1_recursion_core::stark::sp1_dev_mode;
 
 pub use sp1_recursion_circuit::witness::{OuterWitness, Witnessable};
 
+#[cfg(feature = "ark-groth16")]
+use sp1_recursion_gnark_ffi::ark::Groth16Bn254ArkProver;
+pub use sp1_recursion_gnark_ffi::Groth16Bn254Ceremony;
 use sp1_recursion_gnark_ffi::{Groth16Bn254Prover, PlonkBn254Prover};
-use sp1_stark::{SP1ProverOpts, ShardProof, StarkVerifyingKey};
+use sp1_stark::{SP1ProverOpts, SecurityPreset, ShardProof, StarkVerifyingKey};
 
 use crate::{
     utils::{babybear_bytes_to_bn254, babybears_to_bn254, words_to_bytes},
@@ -30,10 +34,11 @@ use crate::{
 pub fn try_build_plonk_bn254_artifacts_dev(
     template_vk: &StarkVerifyingKey<OuterSC>,
     template_proof: &ShardProof<OuterSC>,
+    security_preset: SecurityPreset,
 ) -> PathBuf {
-    let build_dir = plonk_bn254_artifacts_dev_dir();
+    let build_dir = plonk_bn254_artifacts_dev_dir(security_preset);
     println!("[sp1] building plonk bn254 artifacts in development mode");
-    build_plonk_bn254_artifacts(template_vk, template_proof, &build_dir);
+    build_plonk_bn254_artifacts(template_vk, template_proof, security_preset, &build_dir);
     build_dir
 }
 
@@ -41,21 +46,111 @@ pub fn try_build_plonk_bn254_artifacts_dev(
 pub fn try_build_groth16_bn254_artifacts_dev(
     template_vk: &StarkVerifyingKey<OuterSC>,
     template_proof: &ShardProof<OuterSC>,
+    security_preset: SecurityPreset,
 ) -> PathBuf {
-    let build_dir = groth16_bn254_artifacts_dev_dir();
+    let build_dir = groth16_bn254_artifacts_dev_dir(security_preset);
     println!("[sp1] building groth16 bn254 artifacts in development mode");
-    build_groth16_bn254_artifacts(template_vk, template_proof, &build_dir);
+    build_groth16_bn254_artifacts(template_vk, template_proof, security_preset, &build_dir);
     build_dir
 }
 
+/// Tries to build the fflonk bn254 artifacts in the current environment.
+///
+/// There are no released fflonk artifacts, so they are built locally from the powers of tau file
+/// set with `SP1_FFLONK_PTAU`, and reused unless in development mode.
+#[cfg(feature = "fflonk")]
+pub fn try_build_fflonk_bn254_artifacts(
+    template_vk: &StarkVerifyingKey<OuterSC>,
+    template_proof: &ShardProof<OuterSC>,
+    security_preset: SecurityPreset,
+) -> anyhow::Result<PathBuf> {
+    let build_dir = fflonk_bn254_artifacts_dir(security_preset);
+    if !sp1_dev_mode() && build_dir.join("fflonk_vk.bin").exists() {
+        return Ok(build_dir);
+    }
+    println!("[sp1] building fflonk bn254 artifacts");
+    build_fflonk_bn254_artifacts(template_vk, template_proof, security_preset, &build_dir)?;
+    Ok(build_dir)
+}
+
+/// Tries to build the arkworks groth16 bn254 artifacts in the current environment.
+///
+/// The keys are imported from the ceremony `.zkey` file set with `SP1_GROTH16_ZKEY`, see
+/// [`Groth16Bn254ArkProver::build`], and reused unless in development mode.
+#[cfg(feature = "ark-groth16")]
+pub fn try_build_groth16_bn254_ark_artifacts(
+    template_vk: &StarkVerifyingKey<OuterSC>,
+    template_proof: &ShardProof<OuterSC>,
+    security_preset: SecurityPreset,
+) -> anyhow::Result<PathBuf> {
+    let build_dir = groth16_bn254_ark_artifacts_dir(security_preset);
+    if !sp1_dev_mode() && build_dir.join("groth16_vk.bin").exists() {
+        return Ok(build_dir);
+    }
+    println!("[sp1] building arkworks groth16 bn254 artifacts");
+    build_groth16_bn254_ark_artifacts(template_vk, template_proof, security_preset, &build_dir)?;
+    Ok(build_dir)
+}
+
+/// Whether the circuit artifacts are built locally instead of being installed, which is the case
+/// in development mode and for the presets without released artifacts.
+pub fn use_dev_artifacts(security_preset: SecurityPreset) -> bool {
+    sp1_dev_mode() || security_preset != SecurityPreset::Standard
+}
+
 /// Gets the directory where the PLONK artifacts are installed in development mode.
-pub fn plonk_bn254_artifacts_dev_dir() -> PathBuf {
-    dirs::home_dir().unwrap().join(".sp1").join("circuits").join("dev")
+pub fn plonk_bn254_artifacts_dev_dir(security_preset: SecurityPreset) -> PathBuf {
+    artifacts_dev_dir(security_preset)
 }
 
 /// Gets the directory where the groth16 artifacts are installed in development mode.
-pub fn groth16_bn254_artifacts_dev_dir() -> PathBuf {
-    dirs::home_dir().unwrap().join(".sp1").join("circuits").join("dev")
+pub fn groth16_bn254_artifacts_dev_dir(security_preset: SecurityPreset) -> PathBuf {
+    artifacts_dev_dir(security_preset)
+}
+
+/// Gets the directory where the fflonk artifacts are built, which is versioned outside of
+/// development mode.
+pub fn fflonk_bn254_artifacts_dir(security_preset: SecurityPreset) -> PathBuf {
+    if sp1_dev_mode() {
+        return artifacts_dev_dir(security_preset).join("fflonk");
+    }
+    let dir = dirs::home_dir()
+        .unwrap()
+        .join(".sp1")
+        .join("circuits")
+        .join("fflonk")
+        .join(SP1_CIRCUIT_VERSION);
+    match security_preset {
+        SecurityPreset::Standard => dir,
+        _ => dir.join(security_preset.name()),
+    }
+}
+
+/// Gets the directory where the arkworks groth16 artifacts are built, which is versioned outside
+/// of development mode.
+#[cfg(feature = "ark-groth16")]
+pub fn groth16_bn254_ark_artifacts_dir(security_preset: SecurityPreset) -> PathBuf {
+    if sp1_dev_mode() {
+        return artifacts_dev_dir(security_preset).join("groth16-ark");
+    }
+    let dir = dirs::home_dir()
+        .unwrap()
+        .join(".sp1")
+        .join("circuits")
+        .join("groth16-ark")
+        .join(SP1_CIRCUIT_VERSION);
+    match security_preset {
+        SecurityPreset::Standard => dir,
+        _ => dir.join(security_preset.name()),
+    }
+}
+
+fn artifacts_dev_dir(security_preset: SecurityPreset) -> PathBuf {
+    let dir = dirs::home_dir().unwrap().join(".sp1").join("circuits").join("dev");
+    match security_preset {
+        SecurityPreset::Standard => dir,
+        _ => dir.join(security_preset.name()),
+    }
 }
 
 /// Build the plonk bn254 artifacts to the given directory for the given verification key and
@@ -63,11 +158,13 @@ pub fn groth16_bn254_artifacts_dev_dir() -> PathBuf {
 pub fn build_plonk_bn254_artifacts(
     template_vk: &StarkVerifyingKey<OuterSC>,
     template_proof: &ShardProof<OuterSC>,
+    security_preset: SecurityPreset,
     build_dir: impl Into<PathBuf>,
 ) {
     let build_dir = build_dir.into();
     std::fs::create_dir_all(&build_dir).expect("failed to create build directory");
-    let (constraints, witness) = build_constraints_and_witness(template_vk, template_proof);
+    let (constraints, witness) =
+        build_constraints_and_witness(template_vk, template_proof, security_preset);
     PlonkBn254Prover::build(constraints, witness, build_dir);
 }
 
@@ -76,20 +173,79 @@ pub fn build_plonk_bn254_artifacts(
 pub fn build_groth16_bn254_artifacts(
     template_vk: &StarkVerifyingKey<OuterSC>,
     template_proof: &ShardProof<OuterSC>,
+    security_preset: SecurityPreset,
     build_dir: impl Into<PathBuf>,
 ) {
     let build_dir = build_dir.into();
     std::fs::create_dir_all(&build_dir).expect("failed to create build directory");
-    let (constraints, witness) = build_constraints_and_witness(template_vk, template_proof);
+    let (constraints, witness) =
+        build_constraints_and_witness(template_vk, template_proof, security_preset);
     Groth16Bn254Prover::build(constraints, witness, build_dir);
 }
 
+/// Build the arkworks groth16 bn254 artifacts to the given directory for the given verification
+/// key and template proof, from the ceremony `.zkey` file set with `SP1_GROTH16_ZKEY`.
+///
+/// Without `SP1_GROTH16_ZKEY`, the circuit to run the ceremony on is written to the directory and
+/// an error is returned.
+#[cfg(feature = "ark-groth16")]
+pub fn build_groth16_bn254_ark_artifacts(
+    template_vk: &StarkVerifyingKey<OuterSC>,
+    template_proof: &ShardProof<OuterSC>,
+    security_preset: SecurityPreset,
+    build_dir: impl Into<PathBuf>,
+) -> anyhow::Result<()> {
+    let build_dir = build_dir.into();
+    std::fs::create_dir_all(&build_dir)?;
+    let (constraints, witness) =
+        build_constraints_and_witness(template_vk, template_proof, security_preset);
+    Groth16Bn254ArkProver::build(constraints, witness, build_dir)
+}
+
+/// Writes the groth16 bn254 circuit to the given directory for the given verification key and
+/// template proof, without running a setup, so that its keys can come from a
+/// [`Groth16Bn254Ceremony`].
+pub fn build_groth16_bn254_ceremony_circuit(
+    template_vk: &StarkVerifyingKey<OuterSC>,
+    template_proof: &ShardProof<OuterSC>,
+    security_preset: SecurityPreset,
+    build_dir: impl Into<PathBuf>,
+) {
+    let build_dir = build_dir.into();
+    std::fs::create_dir_all(&build_dir).expect("failed to create build directory");
+    let (constraints, witness) =
+        build_constraints_and_witness(template_vk, template_proof, security_preset);
+    Groth16Bn254Prover::write_circuit(constraints, witness, &build_dir);
+}
+
+/// Build the fflonk bn254 artifacts to the given directory for the given verification key and
+/// template proof.
+///
+/// This fails if `SP1_FFLONK_PTAU` is not set or snarkjs is not installed, see
+/// [`sp1_recursion_gnark_ffi::ark::FflonkBn254Prover`].
+#[cfg(feature = "fflonk")]
+pub fn build_fflonk_bn254_artifacts(
+    template_vk: &StarkVerifyingKey<OuterSC>,
+    template_proof: &ShardProof<OuterSC>,
+    security_preset: SecurityPreset,
+    build_dir: impl Into<PathBuf>,
+) -> anyhow::Result<()> {
+    let build_dir = build_dir.into();
+    std::fs::create_dir_all(&build_dir)?;
+    let (constraints, witness) =
+        build_constraints_and_witness(template_vk, template_proof, security_preset);
+    sp1_recursion_gnark_ffi::ark::FflonkBn254Prover::build(constraints, witness, build_dir)
+}
+
 /// Builds the plonk bn254 artifacts to the given directory.
 ///
 /// This may take a while as it needs to first generate a dummy proof and then it needs to compile
 /// the circuit.
-pub fn build_plonk_bn254_artifacts_with_dummy(build_dir: impl Into<PathBuf>) {
-    let (wrap_vk, wrapped_proof) = dummy_proof();
+pub fn build_plonk_bn254_artifacts_with_dummy(
+    build_dir: impl Into<PathBuf>,
+    security_preset: SecurityPreset,
+) {
+    let (wrap_vk, wrapped_proof) = dummy_proof(security_preset);
     let wrap_vk_bytes = bincode::serialize(&wrap_vk).unwrap();
     let wrapped_proof_bytes = bincode::serialize(&wrapped_proof).unwrap();
     std::fs::write("wrap_vk.bin", wrap_vk_bytes).unwrap();
@@ -98,15 +254,23 @@ pub fn build_plonk_bn254_artifacts_with_dummy(build_dir: impl Into<PathBuf>) {
     let wrapped_proof_bytes = std::fs::read("wrapped_proof.bin").unwrap();
     let wrap_vk = bincode::deserialize(&wrap_vk_bytes).unwrap();
     let wrapped_proof = bincode::deserialize(&wrapped_proof_bytes).unwrap();
-    crate::build::build_plonk_bn254_artifacts(&wrap_vk, &wrapped_proof, build_dir.into());
+    crate::build::build_plonk_bn254_artifacts(
+        &wrap_vk,
+        &wrapped_proof,
+        security_preset,
+        build_dir.into(),
+    );
 }
 
 /// Builds the groth16 bn254 artifacts to the given directory.
 ///
 /// This may take a while as it needs to first generate a dummy proof and then it needs to compile
 /// the circuit.
-pub fn build_groth16_bn254_artifacts_with_dummy(build_dir: impl Into<PathBuf>) {
-    let (wrap_vk, wrapped_proof) = dummy_proof();
+pub fn build_groth16_bn254_artifacts_with_dummy(
+    build_dir: impl Into<PathBuf>,
+    security_preset: SecurityPreset,
+) {
+    let (wrap_vk, wrapped_proof) = dummy_proof(security_preset);
     let wrap_vk_bytes = bincode::serialize(&wrap_vk).unwrap();
     let wrapped_proof_bytes = bincode::serialize(&wrapped_proof).unwrap();
     std::fs::write("wrap_vk.bin", wrap_vk_bytes).unwrap();
@@ -115,21 +279,38 @@ pub fn build_groth16_bn254_artifacts_with_dummy(build_dir: impl Into<PathBuf>) {
     let wrapped_proof_bytes = std::fs::read("wrapped_proof.bin").unwrap();
     let wrap_vk = bincode::deserialize(&wrap_vk_bytes).unwrap();
     let wrapped_proof = bincode::deserialize(&wrapped_proof_bytes).unwrap();
-    crate::build::build_groth16_bn254_artifacts(&wrap_vk, &wrapped_proof, build_dir.into());
+    crate::build::build_groth16_bn254_artifacts(
+        &wrap_vk,
+        &wrapped_proof,
+        security_preset,
+        build_dir.into(),
+    );
+}
+
+/// Writes the groth16 bn254 circuit for a [`Groth16Bn254Ceremony`] to the given directory.
+///
+/// This may take a while as it needs to first generate a dummy proof.
+pub fn build_groth16_bn254_ceremony_circuit_with_dummy(
+    build_dir: impl Into<PathBuf>,
+    security_preset: SecurityPreset,
+) {
+    let (wrap_vk, wrapped_proof) = dummy_proof(security_preset);
+    build_groth16_bn254_ceremony_circuit(&wrap_vk, &wrapped_proof, security_preset, build_dir);
 }
 
 /// Build the verifier constraints and template witness for the circuit.
 pub fn build_constraints_and_witness(
     template_vk: &StarkVerifyingKey<OuterSC>,
     template_proof: &ShardProof<OuterSC>,
+    security_preset: SecurityPreset,
 ) -> (Vec<Constraint>, OuterWitness<OuterConfig>) {
     tracing::info!("building verifier constraints");
     let template_input = SP1CompressWitnessValues {
         vks_and_proofs: vec![(template_vk.clone(), template_proof.clone())],
         is_complete: true,
     };
-    let constraints =
-        tracing::info_span!("wrap circuit").in_scope(|| build_outer_circuit(&template_input));
+    let constraints = tracing::info_span!("wrap circuit")
+        .in_scope(|| build_outer_circuit(&template_input, security_preset));
 
     let pv: &RecursionPublicValues<BabyBear> = template_proof.public_values.as_slice().borrow();
     let vkey_hash = babybears_to_bn254(&pv.sp1_vk_digest);
@@ -148,12 +329,15 @@ pub fn build_constraints_and_witness(
 
 /// Generate a dummy proof that we can use to build the circuit. We need this to know the shape of
 /// the proof.
-pub fn dummy_proof() -> (StarkVerifyingKey<OuterSC>, ShardProof<OuterSC>) {
+pub fn dummy_proof(
+    security_preset: SecurityPreset,
+) -> (StarkVerifyingKey<OuterSC>, ShardProof<OuterSC>) {
     let elf = include_bytes!("../elf/riscv32im-succinct-zkvm-elf");
 
     tracing::info!("initializing prover");
-    let prover: SP1Prover = SP1Prover::new();
-    let opts = SP1ProverOpts::default();
+    let prover: SP1Prover =
+        SP1Prover::with_security_preset(security_preset).expect("failed to initialize the prover");
+    let opts = SP1ProverOpts::default().with_security_preset(security_preset);
     let context = SP1Context::default();
 
     tracing::info!("setup elf");
@@ -176,8 +360,33 @@ pub fn dummy_proof() -> (StarkVerifyingKey<OuterSC>, ShardProof<OuterSC>) {
     (wrapped_proof.vk, wrapped_proof.proof)
 }
 
-fn build_outer_circuit(template_input: &SP1CompressWitnessValues<OuterSC>) -> Vec<Constraint> {
-    let wrap_machine = WrapAir::wrap_machine(OuterSC::default());
+/// Profile the verifier constraints of the circuit, counting the constraints of each opcode
+/// within each cycle tracker span of the verifier.
+pub fn profile_outer_circuit(
+    template_vk: &StarkVerifyingKey<OuterSC>,
+    template_proof: &ShardProof<OuterSC>,
+    security_preset: SecurityPreset,
+) -> Span<String, String> {
+    let template_input = SP1CompressWitnessValues {
+        vks_and_proofs: vec![(template_vk.clone(), template_proof.clone())],
+        is_complete: true,
+    };
+    let (constraints, spans) = build_outer_circuit_with_spans(&template_input, security_preset);
+    spans.profile(&constraints, |constraint| (format!("{:?}", constraint.opcode), 1))
+}
+
+fn build_outer_circuit(
+    template_input: &SP1CompressWitnessValues<OuterSC>,
+    security_preset: SecurityPreset,
+) -> Vec<Constraint> {
+    build_outer_circuit_with_spans(template_input, security_preset).0
+}
+
+fn build_outer_circuit_with_spans(
+    template_input: &SP1CompressWitnessValues<OuterSC>,
+    security_preset: SecurityPreset,
+) -> (Vec<Constraint>, SpanMarkers) {
+    let wrap_machine = WrapAir::wrap_machine(OuterSC::with_fri_parameters(security_preset.wrap()));
 
     let wrap_span = tracing::debug_span!("build wrap circuit").entered();
     let mut builder = Builder::<OuterConfig>::default();
@@ -203,7 +412,7 @@ fn build_outer_circuit(template_input: &SP1CompressWitnessValues<OuterSC>) -> Ve
     SP1WrapVerifier::verify(&mut builder, &wrap_machine, input);
 
     let mut backend = ConstraintCompiler::<OuterConfig>::default();
-    let operations = backend.emit(builder.into_operations());
+    let operations = backend.emit_with_spans(builder.into_operations());
     wrap_span.exit();
 
     operations
@@ -0,0 +1,360 @@
+use std::{
+    collections::VecDeque,
+    io::Write,
+    sync::{Arc, Mutex},
+    thread,
+};
+
+use p3_challenger::{CanObserve, FieldChallenger};
+use sp1_core_executor::{
+    subproof::NoOpSubproofVerifier, ExecutionRecord, ExecutionReport, Executor, SP1Context,
+};
+use sp1_core_machine::{io::SP1Stdin, reduce::SP1ReduceProof};
+use sp1_primitives::io::SP1PublicValues;
+use sp1_recursion_circuit::machine::SP1CompressWitnessValues;
+use sp1_stark::{
+    air::PublicValues, Challenge, MachineProver, MachineProvingKey, SP1ProverOpts, ShardProof,
+    StarkGenericConfig, StarkVerifyingKey,
+};
+
+use super::{
+    protocol::{
+        read_message, write_message, ShardTask, WorkerRequest, WorkerResponse, PROTOCOL_VERSION,
+    },
+    DistributedError, Endpoint,
+};
+use crate::{
+    components::{CpuProverComponents, SP1ProverComponents},
+    CoreSC, InnerSC, SP1CircuitWitness, SP1CoreProof, SP1CoreProofData, SP1Prover, SP1ProvingKey,
+    SP1VerifyingKey, REDUCE_BATCH_SIZE,
+};
+
+/// Executes programs and schedules their shards and recursion nodes on a set of
+/// [`super::ProverWorker`]s.
+///
+/// The coordinator produces the same proofs as [`SP1Prover::prove_core`] and
+/// [`SP1Prover::compress`].
+pub struct Coordinator<'a, C: SP1ProverComponents = CpuProverComponents> {
+    prover: &'a SP1Prover<C>,
+    workers: Vec<Endpoint>,
+}
+
+impl<'a, C: SP1ProverComponents> Coordinator<'a, C> {
+    /// Creates a new [`Coordinator`] scheduling tasks on the given workers.
+    ///
+    /// The prover is used for execution and for preparing the inputs of the recursion tree, so it
+    /// must be configured like the provers of the workers.
+    pub fn new(prover: &'a SP1Prover<C>, workers: Vec<Endpoint>) -> Self {
+        Self { prover, workers }
+    }
+
+    /// The workers tasks are scheduled on.
+    pub fn workers(&self) -> &[Endpoint] {
+        &self.workers
+    }
+
+    /// Generate shard proofs which split up and prove the valid execution of a RISC-V program on
+    /// the workers.
+    #[tracing::instrument(name = "distributed prove_core", level = "info", skip_all)]
+    pub fn prove_core(
+        &self,
+        pk: &SP1ProvingKey,
+        stdin: &SP1Stdin,
+        opts: SP1ProverOpts,
+        mut context: SP1Context<'a>,
+    ) -> Result<SP1CoreProof, DistributedError> {
+        context.subproof_verifier.replace(Arc::new(self.prover));
+        let (tasks, public_values_stream, report) = self.checkpoints(pk, stdin, opts, context)?;
+        tracing::info!("scheduling {} checkpoints on {} workers", tasks.len(), self.workers.len());
+
+        // Phase 1: collect the commitments of every shard.
+        let commitments = tracing::info_span!("phase 1").in_scope(|| {
+            self.dispatch(
+                Some(pk),
+                opts,
+                tasks.clone(),
+                |task| WorkerRequest::Commit(Box::new(task)),
+                |response| match response {
+                    WorkerResponse::Commitments(commitments) => Ok(commitments),
+                    _ => Err(DistributedError::UnexpectedMessage("commitments")),
+                },
+            )
+        })?;
+
+        // Observe the commitments in shard order and sample the global permutation challenges.
+        let core_prover = &self.prover.core_prover;
+        let mut challenger = core_prover.config().challenger();
+        core_prover.pk_to_device(&pk.pk).observe_into(&mut challenger);
+        for (commitment, public_values) in commitments.into_iter().flatten() {
+            core_prover.observe(&mut challenger, commitment, &public_values);
+        }
+        let global_permutation_challenges: Vec<Challenge<CoreSC>> =
+            (0..2).map(|_| challenger.sample_ext_element()).collect();
+
+        // Phase 2: prove every shard.
+        let shard_proofs = tracing::info_span!("phase 2").in_scope(|| {
+            self.dispatch(
+                Some(pk),
+                opts,
+                tasks,
+                |task| WorkerRequest::Prove {
+                    task: Box::new(task),
+                    challenger: Box::new(challenger.clone()),
+                    global_permutation_challenges: global_permutation_challenges.clone(),
+                },
+                |response| match response {
+                    WorkerResponse::ShardProofs(proofs) => Ok(proofs),
+                    _ => Err(DistributedError::UnexpectedMessage("shard proofs")),
+                },
+            )
+        })?;
+
+        Ok(SP1CoreProof {
+            proof: SP1CoreProofData(shard_proofs.into_iter().flatten().collect()),
+            stdin: stdin.clone(),
+            public_values: SP1PublicValues::from(&public_values_stream),
+            cycles: report.total_instruction_count(),
+        })
+    }
+
+    /// Reduce shard proofs to a single shard proof by proving the compress tree on the workers.
+    #[tracing::instrument(name = "distributed compress", level = "info", skip_all)]
+    pub fn compress(
+        &self,
+        vk: &SP1VerifyingKey,
+        proof: SP1CoreProof,
+        deferred_proofs: Vec<SP1ReduceProof<InnerSC>>,
+        opts: SP1ProverOpts,
+    ) -> Result<SP1ReduceProof<InnerSC>, DistributedError> {
+        let shard_proofs = &proof.proof.0;
+        let core_prover = &self.prover.core_prover;
+
+        // Get the leaf challenger.
+        let mut leaf_challenger = core_prover.config().challenger();
+        vk.vk.observe_into(&mut leaf_challenger);
+        for proof in shard_proofs.iter() {
+            leaf_challenger.observe(proof.commitment.global_main_commit);
+            leaf_challenger.observe_slice(&proof.public_values[0..core_prover.num_pv_elts()]);
+        }
+
+        // Prove the first layer.
+        let first_layer_inputs = self.prover.get_first_layer_inputs(
+            vk,
+            &leaf_challenger,
+            shard_proofs,
+            &deferred_proofs,
+            1,
+        );
+        let mut layer = self.prove_layer(first_layer_inputs, opts)?;
+
+        // Reduce the layers until a single proof is left. A proof which does not fill a whole batch
+        // is carried over to the next layer.
+        while layer.len() > 1 {
+            let carried = layer.split_off(layer.len() - layer.len() % REDUCE_BATCH_SIZE);
+            let is_complete = layer.len() == REDUCE_BATCH_SIZE && carried.is_empty();
+            let inputs = layer
+                .chunks(REDUCE_BATCH_SIZE)
+                .map(|batch| {
+                    SP1CircuitWitness::Compress(SP1CompressWitnessValues {
+                        vks_and_proofs: batch.to_vec(),
+                        is_complete,
+                    })
+                })
+                .collect();
+            layer = self.prove_layer(inputs, opts)?;
+            layer.extend(carried);
+        }
+
+        let (vk, proof) = layer.pop().expect("the compress tree has a root");
+        Ok(SP1ReduceProof { vk, proof })
+    }
+
+    #[allow(clippy::type_complexity)]
+    fn prove_layer(
+        &self,
+        inputs: Vec<SP1CircuitWitness>,
+        opts: SP1ProverOpts,
+    ) -> Result<Vec<(StarkVerifyingKey<InnerSC>, ShardProof<InnerSC>)>, DistributedError> {
+        tracing::debug!("proving {} recursion nodes", inputs.len());
+        self.dispatch(
+            None,
+            opts,
+            inputs,
+            |input| WorkerRequest::Recursion(Box::new(input)),
+            |response| match response {
+                WorkerResponse::Recursion(result) => Ok(*result),
+                _ => Err(DistributedError::UnexpectedMessage("recursion proof")),
+            },
+        )
+    }
+
+    /// Executes the program and splits the execution into [`ShardTask`]s.
+    ///
+    /// Every checkpoint is traced once to assign the public values of its shards and to collect
+    /// the deferred events, which mirrors the bookkeeping of
+    /// [`sp1_core_machine::utils::prove_with_context`].
+    fn checkpoints(
+        &self,
+        pk: &SP1ProvingKey,
+        stdin: &SP1Stdin,
+        opts: SP1ProverOpts,
+        context: SP1Context<'a>,
+    ) -> Result<(Vec<ShardTask>, Vec<u8>, ExecutionReport), DistributedError> {
+        let opts = opts.core_opts;
+        let program = self
+            .prover
+            .get_program(&pk.elf)
+            .map_err(|err| DistributedError::Worker(err.to_string()))?;
+        let maximal_shapes = self.prover.core_shape_config.as_ref().map(|config| {
+            config.maximal_core_shapes().into_iter().map(|s| s.inner).collect::<Vec<_>>()
+        });
+
+        let mut runtime = Executor::with_context(program.clone(), opts, context);
+        runtime.maximal_shapes = maximal_shapes.clone();
+        runtime.write_vecs(&stdin.buffer);
+        for (proof, vk) in stdin.proofs.iter() {
+            runtime.write_proof(proof.clone(), vk.clone());
+        }
+
+        let mut tasks = Vec::new();
+        let mut report = ExecutionReport::default();
+        let mut state = PublicValues::<u32, u32>::default().reset();
+        let mut deferred = ExecutionRecord::new(program.clone().into());
+        loop {
+            // Execute the runtime until we reach a checkpoint.
+            let (checkpoint, done) = runtime.execute_state(false)?;
+
+            // Trace the checkpoint and reconstruct the execution records.
+            let mut tracer = Executor::recover(program.clone(), checkpoint.clone(), opts);
+            tracer.maximal_shapes = maximal_shapes.clone();
+            tracer.subproof_verifier = Arc::new(NoOpSubproofVerifier);
+            let (mut records, _) = tracer.execute_record(true)?;
+            report += tracer.report;
+
+            // Update the public values for the shards which contain "cpu events".
+            let mut public_values = Vec::with_capacity(records.len());
+            for record in records.iter_mut() {
+                state.shard += 1;
+                state.execution_shard = record.public_values.execution_shard;
+                state.start_pc = record.public_values.start_pc;
+                state.next_pc = record.public_values.next_pc;
+                state.committed_value_digest = record.public_values.committed_value_digest;
+                state.deferred_proofs_digest = record.public_values.deferred_proofs_digest;
+                public_values.push(state);
+            }
+
+            // Defer events that are too expensive to include in every shard.
+            for record in records.iter_mut() {
+                deferred.append(&mut record.defer());
+            }
+            let mut deferred_records = deferred.split(done, opts.split_opts);
+
+            // Update the public values for the shards which do not contain "cpu events".
+            if !done {
+                state.execution_shard += 1;
+            }
+            for record in deferred_records.iter_mut() {
+                state.shard += 1;
+                state.previous_init_addr_bits = record.public_values.previous_init_addr_bits;
+                state.last_init_addr_bits = record.public_values.last_init_addr_bits;
+                state.previous_finalize_addr_bits =
+                    record.public_values.previous_finalize_addr_bits;
+                state.last_finalize_addr_bits = record.public_values.last_finalize_addr_bits;
+                state.start_pc = state.next_pc;
+                record.public_values = state;
+            }
+
+            tasks.push(ShardTask {
+                index: tasks.len(),
+                checkpoint,
+                done,
+                public_values,
+                deferred: deferred_records,
+            });
+
+            if done {
+                break;
+            }
+        }
+
+        Ok((tasks, runtime.state.public_values_stream, report))
+    }
+
+    /// Runs the tasks on the workers and returns the results in the order of the tasks.
+    ///
+    /// Every worker gets its own session and pulls tasks from a shared queue until it is empty.
+    fn dispatch<T, R>(
+        &self,
+        pk: Option<&SP1ProvingKey>,
+        opts: SP1ProverOpts,
+        tasks: Vec<T>,
+        request: impl Fn(T) -> WorkerRequest + Sync,
+        response: impl Fn(WorkerResponse) -> Result<R, DistributedError> + Sync,
+    ) -> Result<Vec<R>, DistributedError>
+    where
+        T: Send,
+        R: Send,
+    {
+        if self.workers.is_empty() {
+            return Err(DistributedError::NoWorkers);
+        }
+
+        let num_tasks = tasks.len();
+        let queue = Mutex::new(tasks.into_iter().enumerate().collect::<VecDeque<_>>());
+        let results = Mutex::new((0..num_tasks).map(|_| None).collect::<Vec<Option<R>>>());
+        let setup =
+            WorkerRequest::Setup { version: PROTOCOL_VERSION, pk: pk.cloned().map(Box::new), opts };
+        let setup = bincode::serialize(&setup)?;
+
+        let span = tracing::Span::current().clone();
+        thread::scope(|s| {
+            let handles = self
+                .workers
+                .iter()
+                .map(|endpoint| {
+                    let (queue, results, setup) = (&queue, &results, &setup);
+                    let (request, response) = (&request, &response);
+                    let span = span.clone();
+                    s.spawn(move || {
+                        let _span = span.enter();
+                        let mut connection = endpoint.connect()?;
+                        connection.write_all(&(setup.len() as u64).to_le_bytes())?;
+                        connection.write_all(setup)?;
+                        connection.flush()?;
+                        match read_message(&mut connection)? {
+                            WorkerResponse::Ready => {}
+                            WorkerResponse::Error(err) => {
+                                return Err(DistributedError::Worker(err))
+                            }
+                            _ => return Err(DistributedError::UnexpectedMessage("ready")),
+                        }
+
+                        loop {
+                            let next = { queue.lock().unwrap().pop_front() };
+                            let Some((index, task)) = next else { break };
+                            tracing::debug!("sending task {} to {}", index, endpoint);
+                            write_message(&mut connection, &request(task))?;
+                            let result = response(
+                                read_message::<_, WorkerResponse>(&mut connection)?
+                                    .into_result()?,
+                            )?;
+                            results.lock().unwrap()[index] = Some(result);
+                        }
+
+                        write_message(&mut connection, &WorkerRequest::Shutdown)?;
+                        Ok::<_, DistributedError>(())
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            handles.into_iter().try_for_each(|handle| handle.join().unwrap())
+        })?;
+
+        Ok(results
+            .into_inner()
+            .unwrap()
+            .into_iter()
+            .map(|result| result.expect("every task is run by a worker"))
+            .collect())
+    }
+}
@@ -0,0 +1,124 @@
+//! Distributed proving over a simple local worker protocol.
+//!
+//! The end-to-end pipeline of [`crate::SP1Prover::prove_core`] and [`crate::SP1Prover::compress`]
+//! is split into three roles that can run in different processes or on different machines:
+//!
+//! 1. The [`Coordinator`] executes the program, emits [`sp1_core_executor::ExecutionState`]
+//!    checkpoints and schedules them on the workers. It is the only party that observes the
+//!    commitments of every shard, so it also samples the global permutation challenges.
+//! 2. A [`ProverWorker`] traces the checkpoints assigned to it and returns the commitments
+//!    (phase 1) and the [`sp1_stark::ShardProof`]s (phase 2) of the resulting shards. Workers also
+//!    prove the nodes of the recursion tree.
+//! 3. The reducer, [`Coordinator::compress`], drives the compress tree layer by layer by batching
+//!    the proofs of one layer into the inputs of the next.
+//!
+//! Messages are exchanged as length-prefixed bincode frames over TCP or Unix domain sockets, see
+//! [`protocol`] and [`Endpoint`]. The protocol has no authentication, so workers must only listen
+//! on a trusted network or on a Unix domain socket.
+
+mod coordinator;
+pub mod protocol;
+mod transport;
+mod worker;
+
+use std::io;
+
+use sp1_core_executor::ExecutionError;
+use thiserror::Error;
+
+pub use coordinator::Coordinator;
+pub use transport::{Connection, Endpoint, Listener};
+pub use worker::{ProverWorker, DEFAULT_MAX_SESSIONS};
+
+/// An error that occurs while proving with the [`Coordinator`] or serving a [`ProverWorker`].
+#[derive(Error, Debug)]
+pub enum DistributedError {
+    #[error("io error: {0}")]
+    Io(#[from] io::Error),
+    #[error("serialization error: {0}")]
+    Serialization(#[from] bincode::Error),
+    #[error("failed to execute program: {0}")]
+    Execution(#[from] ExecutionError),
+    #[error("invalid endpoint: {0}")]
+    InvalidEndpoint(String),
+    #[error("protocol version mismatch: expected {expected}, got {actual}")]
+    VersionMismatch { expected: u32, actual: u32 },
+    #[error("unexpected message: expected {0}")]
+    UnexpectedMessage(&'static str),
+    #[error("worker error: {0}")]
+    Worker(String),
+    #[error("no workers are configured")]
+    NoWorkers,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{borrow::Borrow, net::TcpListener, thread};
+
+    use serial_test::serial;
+    use sp1_core_executor::SP1Context;
+    use sp1_core_machine::{io::SP1Stdin, utils::setup_logger};
+    use sp1_recursion_core::air::RecursionPublicValues;
+    use sp1_stark::SP1ProverOpts;
+
+    use super::*;
+    use crate::{components::CpuProverComponents, SP1Prover};
+
+    /// Spawns a worker serving the sessions of a loopback TCP listener on a background thread.
+    fn spawn_worker() -> Endpoint {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let endpoint = Endpoint::Tcp(listener.local_addr().unwrap().to_string());
+        thread::spawn(move || {
+            let worker = ProverWorker::new(SP1Prover::<CpuProverComponents>::new());
+            for stream in listener.incoming() {
+                let stream = stream.unwrap();
+                stream.set_nodelay(true).unwrap();
+                worker.handle(Connection::Tcp(stream)).unwrap();
+            }
+        });
+        endpoint
+    }
+
+    /// Proves a program on two in-process workers and checks the proofs against the local prover.
+    #[test]
+    #[serial]
+    fn test_distributed_prove() {
+        setup_logger();
+        let elf = test_artifacts::FIBONACCI_ELF;
+        let prover = SP1Prover::<CpuProverComponents>::new();
+        let opts = SP1ProverOpts::default();
+        let (pk, vk) = prover.setup(elf);
+
+        let coordinator = Coordinator::new(&prover, vec![spawn_worker(), spawn_worker()]);
+        let core_proof =
+            coordinator.prove_core(&pk, &SP1Stdin::new(), opts, SP1Context::default()).unwrap();
+        prover.verify(&core_proof.proof, &vk).unwrap();
+
+        let local_core_proof =
+            prover.prove_core(&pk, &SP1Stdin::new(), opts, SP1Context::default()).unwrap();
+        assert_eq!(core_proof.public_values, local_core_proof.public_values);
+        assert_eq!(core_proof.cycles, local_core_proof.cycles);
+        assert_eq!(core_proof.proof.0.len(), local_core_proof.proof.0.len());
+        for (shard, local_shard) in core_proof.proof.0.iter().zip(&local_core_proof.proof.0) {
+            assert_eq!(shard.public_values, local_shard.public_values);
+            assert_eq!(
+                shard.commitment.global_main_commit,
+                local_shard.commitment.global_main_commit
+            );
+        }
+
+        let compressed = coordinator.compress(&vk, core_proof, vec![], opts).unwrap();
+        prover.verify_compressed(&compressed, &vk).unwrap();
+
+        // The shape of the compress tree may differ, but both roots attest to the same execution.
+        let local_compressed = prover.compress(&vk, local_core_proof, vec![], opts).unwrap();
+        let pv: &RecursionPublicValues<_> = compressed.proof.public_values.as_slice().borrow();
+        let local_pv: &RecursionPublicValues<_> =
+            local_compressed.proof.public_values.as_slice().borrow();
+        assert_eq!(pv.sp1_vk_digest, local_pv.sp1_vk_digest);
+        assert_eq!(pv.committed_value_digest, local_pv.committed_value_digest);
+        assert_eq!(pv.deferred_proofs_digest, local_pv.deferred_proofs_digest);
+        assert_eq!(pv.next_shard, local_pv.next_shard);
+        assert_eq!(pv.is_complete, local_pv.is_complete);
+    }
+}
@@ -0,0 +1,172 @@
+//! The messages exchanged between a [`super::Coordinator`] and a [`super::ProverWorker`].
+//!
+//! Every message is a little-endian `u64` length followed by the bincode encoding of a
+//! [`WorkerRequest`] or a [`WorkerResponse`]. A session starts with [`WorkerRequest::Setup`] and
+//! is answered by [`WorkerResponse::Ready`]; afterwards every request is answered by exactly one
+//! response, in order.
+
+use std::io::{self, Read, Write};
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use sp1_core_executor::{ExecutionRecord, ExecutionState};
+use sp1_stark::{
+    air::PublicValues, Challenge, Challenger, Com, SP1ProverOpts, ShardProof, StarkVerifyingKey,
+    Val,
+};
+
+use super::DistributedError;
+use crate::{CoreSC, InnerSC, SP1CircuitWitness, SP1ProvingKey};
+
+/// The version of the worker protocol, checked during [`WorkerRequest::Setup`].
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// The maximum size of a single frame, in bytes, which bounds the proving keys and checkpoints
+/// sent to the workers.
+pub const MAX_FRAME_SIZE: u64 = 1 << 32;
+
+/// A checkpoint of the execution together with the bookkeeping needed to prove its shards.
+///
+/// The coordinator traces the checkpoints sequentially to assign the public values of every shard
+/// and to collect the deferred events, so that workers can prove checkpoints independently.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ShardTask {
+    /// The index of the checkpoint.
+    pub index: usize,
+    /// The execution state at the start of the checkpoint.
+    pub checkpoint: ExecutionState,
+    /// Whether this is the last checkpoint of the execution.
+    pub done: bool,
+    /// The public values of the shards traced from the checkpoint, in order.
+    pub public_values: Vec<PublicValues<u32, u32>>,
+    /// The shards of deferred events which became ready at this checkpoint.
+    pub deferred: Vec<ExecutionRecord>,
+}
+
+/// A request sent from the coordinator to a worker.
+#[derive(Serialize, Deserialize)]
+#[allow(clippy::large_enum_variant)]
+pub enum WorkerRequest {
+    /// Start a session for the given program. Sessions that only prove recursion nodes do not
+    /// need a proving key.
+    Setup { version: u32, pk: Option<Box<SP1ProvingKey>>, opts: SP1ProverOpts },
+    /// Trace a checkpoint and commit to the global traces of its shards.
+    Commit(Box<ShardTask>),
+    /// Trace a checkpoint and prove its shards.
+    Prove {
+        task: Box<ShardTask>,
+        challenger: Box<Challenger<CoreSC>>,
+        global_permutation_challenges: Vec<Challenge<CoreSC>>,
+    },
+    /// Prove a node of the recursion tree.
+    Recursion(Box<SP1CircuitWitness>),
+    /// End the session.
+    Shutdown,
+}
+
+/// A response sent from a worker to the coordinator.
+#[derive(Serialize, Deserialize)]
+#[allow(clippy::large_enum_variant)]
+pub enum WorkerResponse {
+    /// The session is set up.
+    Ready,
+    /// The global main commitments and public values of the shards of a checkpoint.
+    Commitments(Vec<(Com<CoreSC>, Vec<Val<CoreSC>>)>),
+    /// The proofs of the shards of a checkpoint.
+    ShardProofs(Vec<ShardProof<CoreSC>>),
+    /// The verifying key and proof of a recursion node.
+    Recursion(Box<(StarkVerifyingKey<InnerSC>, ShardProof<InnerSC>)>),
+    /// The request failed.
+    Error(String),
+}
+
+impl WorkerResponse {
+    /// Turns a [`WorkerResponse::Error`] into a [`DistributedError::Worker`].
+    pub fn into_result(self) -> Result<Self, DistributedError> {
+        match self {
+            WorkerResponse::Error(err) => Err(DistributedError::Worker(err)),
+            response => Ok(response),
+        }
+    }
+}
+
+/// Writes a length-prefixed message.
+pub fn write_message<W: Write, T: Serialize>(
+    writer: &mut W,
+    message: &T,
+) -> Result<(), DistributedError> {
+    let bytes = bincode::serialize(message)?;
+    if bytes.len() as u64 > MAX_FRAME_SIZE {
+        return Err(DistributedError::Worker(format!(
+            "frame of {} bytes is too large",
+            bytes.len()
+        )));
+    }
+    writer.write_all(&(bytes.len() as u64).to_le_bytes())?;
+    writer.write_all(&bytes)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Reads a length-prefixed message.
+///
+/// The length prefix is not trusted: the frame is read into a buffer that grows as its bytes
+/// arrive, so a peer cannot make the reader allocate more memory than it actually sends.
+pub fn read_message<R: Read, T: DeserializeOwned>(reader: &mut R) -> Result<T, DistributedError> {
+    let mut len = [0u8; 8];
+    reader.read_exact(&mut len)?;
+    let len = u64::from_le_bytes(len);
+    if len > MAX_FRAME_SIZE {
+        return Err(DistributedError::Worker(format!("frame of {len} bytes is too large")));
+    }
+    let mut bytes = Vec::new();
+    reader.take(len).read_to_end(&mut bytes)?;
+    if bytes.len() as u64 != len {
+        return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+    }
+    Ok(bincode::deserialize(&bytes)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn test_message_roundtrip() {
+        let mut buffer = Vec::new();
+        write_message(&mut buffer, &WorkerResponse::Error("boom".to_string())).unwrap();
+        write_message(&mut buffer, &WorkerResponse::Ready).unwrap();
+
+        let mut reader = Cursor::new(buffer);
+        let response: WorkerResponse = read_message(&mut reader).unwrap();
+        assert!(
+            matches!(response.into_result(), Err(DistributedError::Worker(err)) if err == "boom")
+        );
+        let response: WorkerResponse = read_message(&mut reader).unwrap();
+        assert!(matches!(response, WorkerResponse::Ready));
+    }
+
+    #[test]
+    fn test_truncated_message() {
+        let mut buffer = Vec::new();
+        write_message(&mut buffer, &WorkerResponse::Ready).unwrap();
+        buffer.pop();
+
+        let result: Result<WorkerResponse, _> = read_message(&mut Cursor::new(buffer));
+        assert!(matches!(result, Err(DistributedError::Io(_))));
+    }
+
+    #[test]
+    fn test_oversized_length_prefix() {
+        // A length prefix larger than the frame does not allocate the announced size.
+        let mut buffer = MAX_FRAME_SIZE.to_le_bytes().to_vec();
+        buffer.extend_from_slice(&[0u8; 4]);
+        let result: Result<WorkerResponse, _> = read_message(&mut Cursor::new(buffer));
+        assert!(matches!(result, Err(DistributedError::Io(_))));
+
+        let buffer = (MAX_FRAME_SIZE + 1).to_le_bytes().to_vec();
+        let result: Result<WorkerResponse, _> = read_message(&mut Cursor::new(buffer));
+        assert!(matches!(result, Err(DistributedError::Worker(_))));
+    }
+}
@@ -0,0 +1,336 @@
+use std::sync::{Arc, Condvar, Mutex};
+
+use p3_baby_bear::BabyBear;
+use p3_challenger::CanObserve;
+use p3_field::AbstractField;
+use sp1_core_executor::{subproof::NoOpSubproofVerifier, ExecutionRecord, Executor, Program};
+use sp1_core_machine::riscv::RiscvAir;
+use sp1_recursion_circuit::witness::Witnessable;
+use sp1_recursion_compiler::config::InnerConfig;
+use sp1_recursion_core::Runtime as RecursionRuntime;
+use sp1_stark::{
+    air::InteractionScope, Challenge, Challenger, Com, MachineProver, MachineProvingKey,
+    MachineRecord, SP1ProverOpts, ShardProof, StarkGenericConfig, StarkVerifyingKey, Val,
+};
+
+use super::{
+    protocol::{
+        read_message, write_message, ShardTask, WorkerRequest, WorkerResponse, PROTOCOL_VERSION,
+    },
+    Connection, DistributedError, Endpoint,
+};
+use crate::{
+    components::{CpuProverComponents, SP1ProverComponents},
+    CoreSC, InnerSC, SP1CircuitWitness, SP1Prover, SP1ProvingKey,
+};
+
+/// The number of sessions a [`ProverWorker`] serves concurrently by default.
+pub const DEFAULT_MAX_SESSIONS: usize = 8;
+
+/// A worker which proves the shards and recursion nodes scheduled by a
+/// [`super::Coordinator`].
+///
+/// # Security
+/// Sessions are not authenticated, and every request is trusted to come from the coordinator. A
+/// worker must only listen on a trusted network, or on a Unix domain socket.
+pub struct ProverWorker<C: SP1ProverComponents = CpuProverComponents> {
+    prover: SP1Prover<C>,
+    max_sessions: usize,
+}
+
+/// The program a session is proving, set up by [`WorkerRequest::Setup`].
+struct Session<C: SP1ProverComponents> {
+    program: Option<(
+        Program,
+        <C::CoreProver as MachineProver<CoreSC, RiscvAir<BabyBear>>>::DeviceProvingKey,
+    )>,
+    opts: SP1ProverOpts,
+}
+
+impl<C: SP1ProverComponents> ProverWorker<C> {
+    /// Creates a new [`ProverWorker`] proving with the given [`SP1Prover`].
+    pub fn new(prover: SP1Prover<C>) -> Self {
+        Self { prover, max_sessions: DEFAULT_MAX_SESSIONS }
+    }
+
+    /// Sets the number of sessions served concurrently, [`DEFAULT_MAX_SESSIONS`] by default.
+    ///
+    /// # Panics
+    /// Panics if `max_sessions` is zero.
+    #[must_use]
+    pub fn with_max_sessions(mut self, max_sessions: usize) -> Self {
+        assert!(max_sessions > 0, "a worker must serve at least one session");
+        self.max_sessions = max_sessions;
+        self
+    }
+
+    /// Listens on the endpoint and serves every incoming session on its own thread.
+    ///
+    /// # Details
+    /// At most `max_sessions` sessions are served at once: further connections are only accepted
+    /// once a session ends, and wait in the backlog of the listener until then.
+    pub fn serve(&self, endpoint: &Endpoint) -> Result<(), DistributedError> {
+        let listener = endpoint.bind()?;
+        tracing::info!("worker listening on {}", endpoint);
+        let sessions = (Mutex::new(0usize), Condvar::new());
+        std::thread::scope(|s| loop {
+            {
+                let (active, ended) = &sessions;
+                let mut active = ended
+                    .wait_while(active.lock().unwrap(), |active| *active >= self.max_sessions)
+                    .unwrap();
+                *active += 1;
+            }
+
+            let connection = listener.accept()?;
+            let sessions = &sessions;
+            s.spawn(move || {
+                if let Err(err) = self.handle(connection) {
+                    tracing::error!("worker session failed: {}", err);
+                }
+                let (active, ended) = sessions;
+                *active.lock().unwrap() -= 1;
+                ended.notify_one();
+            });
+        })
+    }
+
+    /// Serves a single session until the coordinator sends [`WorkerRequest::Shutdown`] or closes
+    /// the connection.
+    pub fn handle(&self, mut connection: Connection) -> Result<(), DistributedError> {
+        let session = match read_message(&mut connection)? {
+            WorkerRequest::Setup { version, pk, opts } => {
+                if version != PROTOCOL_VERSION {
+                    let err = DistributedError::VersionMismatch {
+                        expected: PROTOCOL_VERSION,
+                        actual: version,
+                    };
+                    write_message(&mut connection, &WorkerResponse::Error(err.to_string()))?;
+                    return Err(err);
+                }
+                match self.setup(pk.map(|pk| *pk), opts) {
+                    Ok(session) => session,
+                    Err(err) => {
+                        write_message(&mut connection, &WorkerResponse::Error(err.to_string()))?;
+                        return Err(err);
+                    }
+                }
+            }
+            _ => return Err(DistributedError::UnexpectedMessage("setup")),
+        };
+        write_message(&mut connection, &WorkerResponse::Ready)?;
+
+        loop {
+            let request: WorkerRequest = read_message(&mut connection)?;
+            let response = match request {
+                WorkerRequest::Commit(task) => {
+                    self.commit(&session, *task).map(WorkerResponse::Commitments)
+                }
+                WorkerRequest::Prove { task, challenger, global_permutation_challenges } => self
+                    .prove(&session, *task, &challenger, &global_permutation_challenges)
+                    .map(WorkerResponse::ShardProofs),
+                WorkerRequest::Recursion(input) => self
+                    .prove_recursion(*input, session.opts)
+                    .map(|result| WorkerResponse::Recursion(Box::new(result))),
+                WorkerRequest::Shutdown => return Ok(()),
+                WorkerRequest::Setup { .. } => Err(DistributedError::UnexpectedMessage("task")),
+            };
+            let response = response.unwrap_or_else(|err| {
+                tracing::error!("worker task failed: {}", err);
+                WorkerResponse::Error(err.to_string())
+            });
+            write_message(&mut connection, &response)?;
+        }
+    }
+
+    fn setup(
+        &self,
+        pk: Option<SP1ProvingKey>,
+        opts: SP1ProverOpts,
+    ) -> Result<Session<C>, DistributedError> {
+        let program = pk
+            .map(|pk| {
+                let program = self
+                    .prover
+                    .get_program(&pk.elf)
+                    .map_err(|err| DistributedError::Worker(err.to_string()))?;
+                let pk = self.prover.core_prover.pk_to_device(&pk.pk);
+                Ok::<_, DistributedError>((program, pk))
+            })
+            .transpose()?;
+        Ok(Session { program, opts })
+    }
+
+    /// Traces the checkpoint of a task and reconstructs the shards assigned by the coordinator.
+    fn trace(
+        &self,
+        session: &Session<C>,
+        task: ShardTask,
+        generate_dependencies: bool,
+    ) -> Result<Vec<ExecutionRecord>, DistributedError> {
+        let (program, _) =
+            session.program.as_ref().ok_or(DistributedError::UnexpectedMessage("setup with pk"))?;
+        let ShardTask { checkpoint, public_values, deferred, .. } = task;
+
+        let mut runtime = Executor::recover(program.clone(), checkpoint, session.opts.core_opts);
+        runtime.maximal_shapes = self
+            .prover
+            .core_shape_config
+            .as_ref()
+            .map(|config| config.maximal_core_shapes().into_iter().map(|s| s.inner).collect());
+        // The deferred proofs were verified by the coordinator while creating the checkpoints.
+        runtime.subproof_verifier = Arc::new(NoOpSubproofVerifier);
+        let (mut records, _) = runtime.execute_record(true)?;
+
+        if records.len() != public_values.len() {
+            return Err(DistributedError::Worker(format!(
+                "checkpoint traced into {} shards, expected {}",
+                records.len(),
+                public_values.len()
+            )));
+        }
+
+        // The deferred events of these shards are proven in the deferred shards of the task.
+        for (record, public_values) in records.iter_mut().zip(public_values) {
+            record.defer();
+            record.public_values = public_values;
+        }
+        records.extend(deferred);
+
+        if generate_dependencies {
+            self.prover.core_prover.machine().generate_dependencies(
+                &mut records,
+                &session.opts.core_opts,
+                None,
+            );
+        }
+
+        if let Some(shape_config) = &self.prover.core_shape_config {
+            for record in records.iter_mut() {
+                shape_config
+                    .fix_shape(record)
+                    .map_err(|err| DistributedError::Worker(err.to_string()))?;
+            }
+        }
+
+        Ok(records)
+    }
+
+    /// Phase 1: commit to the global traces of the shards of a checkpoint.
+    #[allow(clippy::type_complexity)]
+    fn commit(
+        &self,
+        session: &Session<C>,
+        task: ShardTask,
+    ) -> Result<Vec<(Com<CoreSC>, Vec<Val<CoreSC>>)>, DistributedError> {
+        let records = tracing::debug_span!("trace checkpoint", index = task.index)
+            .in_scope(|| self.trace(session, task, false))?;
+        let core_prover = &self.prover.core_prover;
+        Ok(records
+            .iter()
+            .map(|record| {
+                let public_values =
+                    record.public_values::<Val<CoreSC>>()[0..core_prover.num_pv_elts()].to_vec();
+                let traces = core_prover.generate_traces(record, InteractionScope::Global);
+                let data = core_prover.commit(record, traces);
+                (data.main_commit, public_values)
+            })
+            .collect())
+    }
+
+    /// Phase 2: prove the shards of a checkpoint.
+    fn prove(
+        &self,
+        session: &Session<C>,
+        task: ShardTask,
+        challenger: &Challenger<CoreSC>,
+        global_permutation_challenges: &[Challenge<CoreSC>],
+    ) -> Result<Vec<ShardProof<CoreSC>>, DistributedError> {
+        let records = tracing::debug_span!("trace checkpoint", index = task.index)
+            .in_scope(|| self.trace(session, task, true))?;
+        let (_, pk) = session.program.as_ref().expect("traced without a program");
+        let core_prover = &self.prover.core_prover;
+        records
+            .into_iter()
+            .map(|record| {
+                let global_traces = core_prover.generate_traces(&record, InteractionScope::Global);
+                let local_traces = core_prover.generate_traces(&record, InteractionScope::Local);
+                let global_data = core_prover.commit(&record, global_traces);
+                let local_data = core_prover.commit(&record, local_traces);
+                core_prover
+                    .open(
+                        pk,
+                        Some(global_data),
+                        local_data,
+                        &mut challenger.clone(),
+                        global_permutation_challenges,
+                    )
+                    .map_err(|err| DistributedError::Worker(err.to_string()))
+            })
+            .collect()
+    }
+
+    /// Prove a node of the recursion tree, in the same way as [`SP1Prover::compress`].
+    fn prove_recursion(
+        &self,
+        input: SP1CircuitWitness,
+        opts: SP1ProverOpts,
+    ) -> Result<(StarkVerifyingKey<InnerSC>, ShardProof<InnerSC>), DistributedError> {
+        let mut witness_stream = Vec::new();
+        let program = match input {
+            SP1CircuitWitness::Core(input) => {
+                Witnessable::<InnerConfig>::write(&input, &mut witness_stream);
+                self.prover.recursion_program(&input)
+            }
+            SP1CircuitWitness::Deferred(input) => {
+                Witnessable::<InnerConfig>::write(&input, &mut witness_stream);
+                self.prover.deferred_program(&input)
+            }
+            // Foreign machines are registered on the coordinator's prover only.
+            SP1CircuitWitness::ForeignDeferred(_) => {
+                return Err(DistributedError::Worker(
+                    "proofs of foreign machines cannot be deferred on distributed workers"
+                        .to_string(),
+                ));
+            }
+            SP1CircuitWitness::Compress(input) => {
+                let input_with_merkle = self.prover.make_merkle_proofs(input);
+                Witnessable::<InnerConfig>::write(&input_with_merkle, &mut witness_stream);
+                self.prover.compress_program(&input_with_merkle)
+            }
+        };
+
+        // Execute the runtime.
+        let compress_prover = &self.prover.compress_prover;
+        let mut runtime = RecursionRuntime::<Val<InnerSC>, Challenge<InnerSC>, _>::new(
+            program.clone(),
+            compress_prover.config().perm.clone(),
+        );
+        runtime.witness_stream = witness_stream.into();
+        runtime.run().map_err(|err| DistributedError::Worker(err.to_string()))?;
+
+        // Generate the dependencies and traces.
+        let mut records = vec![runtime.record];
+        compress_prover.machine().generate_dependencies(&mut records, &opts.recursion_opts, None);
+        let record = records.into_iter().next().unwrap();
+        let traces = compress_prover.generate_traces(&record, InteractionScope::Local);
+
+        // Prove the program.
+        let (pk, vk) = compress_prover.setup(&program);
+        let mut challenger = compress_prover.config().challenger();
+        pk.observe_into(&mut challenger);
+        let local_data = compress_prover.commit(&record, traces);
+        challenger.observe_slice(&local_data.public_values[0..compress_prover.num_pv_elts()]);
+        let proof = compress_prover
+            .open(
+                &pk,
+                None,
+                local_data,
+                &mut challenger,
+                &[<InnerSC as StarkGenericConfig>::Challenge::zero(); 2],
+            )
+            .map_err(|err| DistributedError::Worker(err.to_string()))?;
+
+        Ok((vk, proof))
+    }
+}
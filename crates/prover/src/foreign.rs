@@ -0,0 +1,100 @@
+use p3_air::Air;
+use p3_baby_bear::BabyBear;
+use p3_field::PrimeField32;
+use sp1_primitives::{hash_foreign_public_values, hash_foreign_vk};
+use sp1_recursion_circuit::{
+    constraints::RecursiveVerifierConstraintFolder,
+    machine::{SP1DeferredVerifier, SP1ForeignDeferredWitnessValues},
+    witness::Witnessable,
+};
+use sp1_recursion_compiler::{config::InnerConfig, ir::Builder};
+use sp1_stark::{
+    air::MachineAir, MachineProof, MachineVerificationError, ShardProof, StarkGenericConfig,
+    StarkMachine, StarkVerifyingKey, VerifierConstraintFolder,
+};
+
+use crate::{HashableKey, InnerSC};
+
+/// A machine over BabyBear, other than SP1's own, whose proofs can be deferred.
+///
+/// The proofs of a foreign machine are passed to the prover in the same way as SP1 proofs, as an
+/// `SP1ReduceProof` holding the verifying key and the proof, and are checked by a program with
+/// `verify_sp1_proof`. They must be single-shard proofs, and the machine must not have global
+/// interactions.
+///
+/// This is implemented for every [`StarkMachine`] with the [`InnerSC`] config, and machines are
+/// registered with [`crate::SP1Prover::register_foreign_machine`].
+pub trait ForeignMachine: Send + Sync {
+    /// The number of public values of the machine.
+    fn num_pv_elts(&self) -> usize;
+
+    /// Verifies a proof of the machine on the host.
+    fn verify(
+        &self,
+        vk: &StarkVerifyingKey<InnerSC>,
+        proof: &ShardProof<InnerSC>,
+    ) -> Result<(), MachineVerificationError<InnerSC>>;
+
+    /// Adds the operations verifying a batch of deferred proofs of the machine to the builder.
+    fn build_deferred_verifier(
+        &self,
+        builder: &mut Builder<InnerConfig>,
+        input: &SP1ForeignDeferredWitnessValues<InnerSC>,
+    );
+}
+
+impl<A> ForeignMachine for StarkMachine<InnerSC, A>
+where
+    A: MachineAir<BabyBear>
+        + for<'a> Air<VerifierConstraintFolder<'a, InnerSC>>
+        + for<'a> Air<RecursiveVerifierConstraintFolder<'a, InnerConfig>>
+        + Send
+        + Sync,
+{
+    fn num_pv_elts(&self) -> usize {
+        StarkMachine::num_pv_elts(self)
+    }
+
+    fn verify(
+        &self,
+        vk: &StarkVerifyingKey<InnerSC>,
+        proof: &ShardProof<InnerSC>,
+    ) -> Result<(), MachineVerificationError<InnerSC>> {
+        let mut challenger = self.config().challenger();
+        let proof = MachineProof { shard_proofs: vec![proof.clone()] };
+        StarkMachine::verify(self, vk, &proof, &mut challenger)
+    }
+
+    fn build_deferred_verifier(
+        &self,
+        builder: &mut Builder<InnerConfig>,
+        input: &SP1ForeignDeferredWitnessValues<InnerSC>,
+    ) {
+        let input = input.read(builder);
+        SP1DeferredVerifier::verify_foreign(builder, self, input);
+    }
+}
+
+/// Returns the digest of the verifying key of a foreign proof, which a program passes to
+/// `verify_sp1_proof` as [`foreign_vk_hash_u32`].
+///
+/// This is the digest of the key, `vk.hash_babybear()`, hashed behind a domain separation tag, so
+/// that it never collides with the digest of an SP1 program.
+pub fn foreign_vk_digest(vk: &StarkVerifyingKey<InnerSC>) -> [BabyBear; 8] {
+    hash_foreign_vk(&vk.hash_babybear())
+}
+
+/// Returns the digest of the verifying key of a foreign proof as the words a program passes to
+/// `verify_sp1_proof`.
+pub fn foreign_vk_hash_u32(vk: &StarkVerifyingKey<InnerSC>) -> [u32; 8] {
+    foreign_vk_digest(vk).map(|element| element.as_canonical_u32())
+}
+
+/// Returns the public values digest of a proof of a foreign machine, which a program passes to
+/// `verify_sp1_proof` along with [`foreign_vk_hash_u32`].
+pub fn foreign_public_values_digest(
+    machine: &dyn ForeignMachine,
+    proof: &ShardProof<InnerSC>,
+) -> [u8; 32] {
+    hash_foreign_public_values(&proof.public_values[0..machine.num_pv_elts()])
+}
@@ -13,6 +13,8 @@
 
 pub mod build;
 pub mod components;
+pub mod distributed;
+pub mod foreign;
 pub mod shapes;
 pub mod types;
 pub mod utils;
@@ -20,13 +22,13 @@ pub mod verify;
 
 use std::{
     borrow::Borrow,
-    collections::BTreeMap,
+    collections::{BTreeMap, BTreeSet},
     env,
     num::NonZeroUsize,
     path::Path,
     sync::{
         atomic::{AtomicUsize, Ordering},
-        mpsc::sync_channel,
+        mpsc::{sync_channel, RecvTimeoutError},
         Arc, Mutex, OnceLock,
     },
     thread,
@@ -37,12 +39,17 @@ use p3_baby_bear::BabyBear;
 use p3_challenger::CanObserve;
 use p3_field::{AbstractField, PrimeField, PrimeField32};
 use p3_matrix::dense::RowMajorMatrix;
-use sp1_core_executor::{ExecutionError, ExecutionReport, Executor, Program, SP1Context};
+use sp1_core_executor::{
+    ExecutionError, ExecutionReport, Executor, Program, ProveMonitor, SP1Context,
+};
 use sp1_core_machine::{
     io::SP1Stdin,
     reduce::SP1ReduceProof,
     riscv::{CoreShapeConfig, RiscvAir},
-    utils::{concurrency::TurnBasedSync, SP1CoreProverError},
+    utils::{
+        concurrency::{TurnBasedSync, CANCELLATION_POLL_INTERVAL},
+        SP1CoreProverError,
+    },
 };
 use sp1_primitives::{hash_deferred_proof, io::SP1PublicValues};
 use sp1_recursion_circuit::{
@@ -51,8 +58,8 @@ use sp1_recursion_circuit::{
         PublicValuesOutputDigest, SP1CompressRootVerifierWithVKey, SP1CompressShape,
         SP1CompressWithVKeyVerifier, SP1CompressWithVKeyWitnessValues, SP1CompressWithVkeyShape,
         SP1CompressWitnessValues, SP1DeferredVerifier, SP1DeferredWitnessValues,
-        SP1MerkleProofWitnessValues, SP1RecursionShape, SP1RecursionWitnessValues,
-        SP1RecursiveVerifier,
+        SP1ForeignDeferredWitnessValues, SP1MerkleProofWitnessValues, SP1RecursionShape,
+        SP1RecursionWitnessValues, SP1RecursiveVerifier,
     },
     merkle_tree::MerkleTree,
     witness::Witnessable,
@@ -68,13 +75,15 @@ use sp1_recursion_core::{
     shape::RecursionShapeConfig, stark::BabyBearPoseidon2Outer, RecursionProgram,
     Runtime as RecursionRuntime,
 };
-pub use sp1_recursion_gnark_ffi::proof::{Groth16Bn254Proof, PlonkBn254Proof};
+#[cfg(feature = "ark-groth16")]
+use sp1_recursion_gnark_ffi::ark::Groth16Bn254ArkProver;
+pub use sp1_recursion_gnark_ffi::proof::{FflonkBn254Proof, Groth16Bn254Proof, PlonkBn254Proof};
 use sp1_recursion_gnark_ffi::{groth16_bn254::Groth16Bn254Prover, plonk_bn254::PlonkBn254Prover};
 use sp1_stark::{air::InteractionScope, MachineProvingKey, ProofShape};
 use sp1_stark::{
     air::PublicValues, baby_bear_poseidon2::BabyBearPoseidon2, Challenge, Challenger,
-    MachineProver, SP1CoreOpts, SP1ProverOpts, ShardProof, StarkGenericConfig, StarkVerifyingKey,
-    Val, Word, DIGEST_SIZE,
+    MachineProver, SP1CoreOpts, SP1ProverOpts, SecurityPreset, ShardProof, StarkGenericConfig,
+    StarkVerifyingKey, Val, Word, DIGEST_SIZE,
 };
 use tracing::instrument;
 
@@ -82,6 +91,8 @@ pub use types::*;
 use utils::{sp1_committed_values_digest_bn254, sp1_vkey_digest_bn254, words_to_bytes};
 
 use components::{CpuProverComponents, SP1ProverComponents};
+use foreign::ForeignMachine;
+use shapes::vk_map_dir;
 
 pub use sp1_core_machine::SP1_CIRCUIT_VERSION;
 
@@ -164,6 +175,17 @@ pub struct SP1Prover<C: SP1ProverComponents = CpuProverComponents> {
     pub wrap_vk: OnceLock<StarkVerifyingKey<OuterSC>>,
 
     pub vk_verification: bool,
+
+    /// The FRI parameters the machines are configured with.
+    pub security_preset: SecurityPreset,
+
+    /// The foreign machines whose proofs can be deferred, by the digest of the verifying keys
+    /// they accept.
+    pub foreign_machines: BTreeMap<[BabyBear; DIGEST_SIZE], Arc<dyn ForeignMachine>>,
+
+    /// Whether the allowed vk map was extended with [`Self::extend_allowed_vks`], in which case
+    /// the released circuit artifacts do not verify the proofs of this prover.
+    pub vk_map_extended: bool,
 }
 
 impl<C: SP1ProverComponents> SP1Prover<C> {
@@ -175,18 +197,34 @@ impl<C: SP1ProverComponents> SP1Prover<C> {
 
     /// Creates a new [SP1Prover] with lazily initialized components.
     pub fn uninitialized() -> Self {
+        Self::with_security_preset(SecurityPreset::Standard)
+            .expect("the vk map of the standard preset is embedded in the prover")
+    }
+
+    /// Creates a new [SP1Prover] with lazily initialized components, whose machines use the FRI
+    /// parameters of the given preset.
+    ///
+    /// Proofs must be generated with [SP1ProverOpts] of the same preset.
+    ///
+    /// An error is returned when vk verification is enabled and the vk map of a preset other than
+    /// [SecurityPreset::Standard] has not been generated.
+    pub fn with_security_preset(
+        security_preset: SecurityPreset,
+    ) -> Result<Self, SP1RecursionProverError> {
         // Initialize the provers.
-        let core_machine = RiscvAir::machine(CoreSC::default());
+        let core_machine = RiscvAir::machine(CoreSC::with_fri_parameters(security_preset.core()));
         let core_prover = C::CoreProver::new(core_machine);
 
-        let compress_machine = CompressAir::compress_machine(InnerSC::default());
+        let compress_machine =
+            CompressAir::compress_machine(InnerSC::with_fri_parameters(security_preset.compress()));
         let compress_prover = C::CompressProver::new(compress_machine);
 
-        // TODO: Put the correct shrink and wrap machines here.
-        let shrink_machine = ShrinkAir::shrink_machine(InnerSC::compressed());
+        let shrink_machine =
+            ShrinkAir::shrink_machine(InnerSC::with_fri_parameters(security_preset.shrink()));
         let shrink_prover = C::ShrinkProver::new(shrink_machine);
 
-        let wrap_machine = WrapAir::wrap_machine(OuterSC::default());
+        let wrap_machine =
+            WrapAir::wrap_machine(OuterSC::with_fri_parameters(security_preset.wrap()));
         let wrap_prover = C::WrapProver::new(wrap_machine);
 
         let core_cache_size = NonZeroUsize::new(
@@ -220,16 +258,29 @@ impl<C: SP1ProverComponents> SP1Prover<C> {
 
         tracing::debug!("vk verification: {}", vk_verification);
 
-        // Read the shapes from the shapes directory and deserialize them into memory.
-        let allowed_vk_map: BTreeMap<[BabyBear; DIGEST_SIZE], usize> = if vk_verification {
-            bincode::deserialize(include_bytes!("../vk_map.bin")).unwrap()
-        } else {
-            bincode::deserialize(include_bytes!("../dummy_vk_map.bin")).unwrap()
-        };
+        // Read the shapes from the shapes directory and deserialize them into memory. The dummy vk
+        // map only depends on the shapes, so it is shared by all the presets.
+        let allowed_vk_map: BTreeMap<[BabyBear; DIGEST_SIZE], usize> =
+            match (vk_verification, security_preset) {
+                (false, _) => bincode::deserialize(include_bytes!("../dummy_vk_map.bin")).unwrap(),
+                (true, SecurityPreset::Standard) => {
+                    bincode::deserialize(include_bytes!("../vk_map.bin")).unwrap()
+                }
+                (true, _) => {
+                    let path = vk_map_dir(security_preset).join("vk_map.bin");
+                    let missing = |reason: String| SP1RecursionProverError::MissingVkMap {
+                        security_preset,
+                        path: path.clone(),
+                        reason,
+                    };
+                    let bytes = std::fs::read(&path).map_err(|e| missing(e.to_string()))?;
+                    bincode::deserialize(&bytes).map_err(|e| missing(e.to_string()))?
+                }
+            };
 
         let (root, merkle_tree) = MerkleTree::commit(allowed_vk_map.keys().copied().collect());
 
-        Self {
+        Ok(Self {
             core_prover,
             compress_prover,
             shrink_prover,
@@ -246,9 +297,75 @@ impl<C: SP1ProverComponents> SP1Prover<C> {
             vk_verification,
             wrap_program: OnceLock::new(),
             wrap_vk: OnceLock::new(),
+            security_preset,
+            foreign_machines: BTreeMap::new(),
+            vk_map_extended: false,
+        })
+    }
+
+    /// Checks that the options use the FRI parameters of the machines.
+    fn check_security_preset(&self, opts: &SP1ProverOpts) -> Result<(), SP1RecursionProverError> {
+        if opts.security_preset != self.security_preset {
+            return Err(SP1RecursionProverError::SecurityPresetMismatch {
+                expected: self.security_preset,
+                actual: opts.security_preset,
+            });
+        }
+        Ok(())
+    }
+
+    /// Registers a foreign machine, so that its proofs for the given verifying keys can be passed
+    /// as deferred proofs and checked by a program with `verify_sp1_proof`.
+    ///
+    /// The program passes the digest of the verifying key given by
+    /// [`foreign::foreign_vk_hash_u32`], which is tagged so that it differs from the digest of any
+    /// SP1 program, and the public values digest given by
+    /// [`foreign::foreign_public_values_digest`]. With vk verification enabled, the verifying keys
+    /// of the programs that verify the foreign proofs must be added with
+    /// [`Self::extend_allowed_vks`].
+    pub fn register_foreign_machine<'a>(
+        &mut self,
+        machine: impl ForeignMachine + 'static,
+        vks: impl IntoIterator<Item = &'a StarkVerifyingKey<InnerSC>>,
+    ) {
+        let machine: Arc<dyn ForeignMachine> = Arc::new(machine);
+        for vk in vks {
+            self.foreign_machines.insert(vk.hash_babybear(), machine.clone());
         }
     }
 
+    /// Returns the foreign machine accepting the verifying key, if one was registered.
+    pub fn foreign_machine(
+        &self,
+        vk: &StarkVerifyingKey<InnerSC>,
+    ) -> Option<&Arc<dyn ForeignMachine>> {
+        self.foreign_machines.get(&vk.hash_babybear())
+    }
+
+    /// Adds verifying keys of recursion programs to the allowed vk map and recommits to it.
+    ///
+    /// # Details
+    /// This changes the vk root, which every compress proof commits to and the wrap program is
+    /// compiled with, so the wrap program and key are recompiled on their next use. The released
+    /// Groth16 and Plonk artifacts, and the verifier contracts deployed for them, are built for
+    /// the default vk root and do not verify the proofs of this prover: the circuit artifacts must
+    /// be built locally, and proving with the released ones fails. Proofs of the default and the
+    /// extended prover cannot be aggregated together.
+    pub fn extend_allowed_vks(
+        &mut self,
+        vk_digests: impl IntoIterator<Item = <InnerSC as FieldHasher<BabyBear>>::Digest>,
+    ) {
+        // The index of a vk is its position in the sorted set, which is the leaf of the tree.
+        let vk_set = self.allowed_vk_map.keys().copied().chain(vk_digests).collect::<BTreeSet<_>>();
+        self.allowed_vk_map = vk_set.into_iter().enumerate().map(|(i, vk)| (vk, i)).collect();
+        let (root, merkle_tree) = MerkleTree::commit(self.allowed_vk_map.keys().copied().collect());
+        self.vk_root = root;
+        self.vk_merkle_tree = merkle_tree;
+        self.wrap_program = OnceLock::new();
+        self.wrap_vk = OnceLock::new();
+        self.vk_map_extended = true;
+    }
+
     /// Fully initializes the programs, proving keys, and verifying keys that are normally
     /// lazily initialized. TODO: remove this.
     pub fn initialize(&mut self) {}
@@ -306,6 +423,12 @@ impl<C: SP1ProverComponents> SP1Prover<C> {
         opts: SP1ProverOpts,
         mut context: SP1Context<'a>,
     ) -> Result<SP1CoreProof, SP1CoreProverError> {
+        if opts.security_preset != self.security_preset {
+            return Err(SP1CoreProverError::SecurityPresetMismatch {
+                expected: self.security_preset,
+                actual: opts.security_preset,
+            });
+        }
         context.subproof_verifier.replace(Arc::new(self));
         let program = self.get_program(&pk.elf).unwrap();
         let pk = self.core_prover.pk_to_device(&pk.pk);
@@ -513,6 +636,39 @@ impl<C: SP1ProverComponents> SP1Prover<C> {
         program
     }
 
+    /// Compiles the program verifying a batch of deferred proofs of a registered foreign machine.
+    ///
+    /// All the proofs of the batch must be for the same machine, and an error is returned if no
+    /// machine was registered for their verifying keys.
+    pub fn foreign_deferred_program(
+        &self,
+        input: &SP1ForeignDeferredWitnessValues<InnerSC>,
+    ) -> Result<Arc<RecursionProgram<BabyBear>>, SP1RecursionProverError> {
+        let machine = input
+            .vks_and_proofs
+            .first()
+            .and_then(|(vk, _)| self.foreign_machine(vk))
+            .ok_or(SP1RecursionProverError::UnregisteredForeignMachine)?;
+
+        // Get the operations.
+        let operations_span =
+            tracing::debug_span!("get operations for the foreign deferred program").entered();
+        let mut builder = Builder::<InnerConfig>::default();
+        machine.build_deferred_verifier(&mut builder, input);
+        let operations = builder.into_operations();
+        operations_span.exit();
+
+        let compiler_span = tracing::debug_span!("compile foreign deferred program").entered();
+        let mut compiler = AsmCompiler::<InnerConfig>::default();
+        let mut program = compiler.compile(operations);
+        if let Some(recursion_shape_config) = &self.recursion_shape_config {
+            recursion_shape_config.fix_shape(&mut program);
+        }
+        let program = Arc::new(program);
+        compiler_span.exit();
+        Ok(program)
+    }
+
     pub fn get_recursion_core_inputs(
         &self,
         vk: &StarkVerifyingKey<CoreSC>,
@@ -556,6 +712,10 @@ impl<C: SP1ProverComponents> SP1Prover<C> {
         core_inputs
     }
 
+    /// Generate the inputs for the recursive verification of the deferred proofs.
+    ///
+    /// Consecutive proofs of the same kind are batched together, where proofs of a foreign machine
+    /// are batched by verifying key.
     pub fn get_recursion_deferred_inputs<'a>(
         &'a self,
         vk: &'a StarkVerifyingKey<CoreSC>,
@@ -563,36 +723,72 @@ impl<C: SP1ProverComponents> SP1Prover<C> {
         last_proof_pv: &PublicValues<Word<BabyBear>, BabyBear>,
         deferred_proofs: &[SP1ReduceProof<InnerSC>],
         batch_size: usize,
-    ) -> Vec<SP1DeferredWitnessValues<InnerSC>> {
+    ) -> Vec<SP1CircuitWitness> {
         // Prepare the inputs for the deferred proofs recursive verification.
         let mut deferred_digest = [Val::<InnerSC>::zero(); DIGEST_SIZE];
         let mut deferred_inputs = Vec::new();
 
-        for batch in deferred_proofs.chunks(batch_size) {
-            let vks_and_proofs =
-                batch.iter().cloned().map(|proof| (proof.vk, proof.proof)).collect::<Vec<_>>();
-
-            let input = SP1CompressWitnessValues { vks_and_proofs, is_complete: true };
-            let input = self.make_merkle_proofs(input);
-            let SP1CompressWithVKeyWitnessValues { compress_val, merkle_val } = input;
-
-            deferred_inputs.push(SP1DeferredWitnessValues {
-                vks_and_proofs: compress_val.vks_and_proofs,
-                vk_merkle_data: merkle_val,
-                start_reconstruct_deferred_digest: deferred_digest,
-                is_complete: false,
-                sp1_vk_digest: vk.hash_babybear(),
-                end_pc: Val::<InnerSC>::zero(),
-                end_shard: last_proof_pv.shard + BabyBear::one(),
-                end_execution_shard: last_proof_pv.execution_shard,
-                init_addr_bits: last_proof_pv.last_init_addr_bits,
-                finalize_addr_bits: last_proof_pv.last_finalize_addr_bits,
-                leaf_challenger: leaf_challenger.clone(),
-                committed_value_digest: last_proof_pv.committed_value_digest,
-                deferred_proofs_digest: last_proof_pv.deferred_proofs_digest,
-            });
-
-            deferred_digest = Self::hash_deferred_proofs(deferred_digest, batch);
+        let runs = deferred_proofs.chunk_by(|a, b| {
+            match (self.foreign_machine(&a.vk), self.foreign_machine(&b.vk)) {
+                (None, None) => true,
+                (Some(_), Some(_)) => a.vk.hash_babybear() == b.vk.hash_babybear(),
+                _ => false,
+            }
+        });
+        for run in runs {
+            let foreign_machine = self.foreign_machine(&run[0].vk);
+            for batch in run.chunks(batch_size) {
+                let vks_and_proofs =
+                    batch.iter().cloned().map(|proof| (proof.vk, proof.proof)).collect::<Vec<_>>();
+
+                if let Some(machine) = foreign_machine {
+                    deferred_inputs.push(SP1CircuitWitness::ForeignDeferred(
+                        SP1ForeignDeferredWitnessValues {
+                            vks_and_proofs,
+                            vk_root: self.vk_root,
+                            start_reconstruct_deferred_digest: deferred_digest,
+                            is_complete: false,
+                            sp1_vk_digest: vk.hash_babybear(),
+                            end_pc: Val::<InnerSC>::zero(),
+                            end_shard: last_proof_pv.shard + BabyBear::one(),
+                            end_execution_shard: last_proof_pv.execution_shard,
+                            init_addr_bits: last_proof_pv.last_init_addr_bits,
+                            finalize_addr_bits: last_proof_pv.last_finalize_addr_bits,
+                            leaf_challenger: leaf_challenger.clone(),
+                            committed_value_digest: last_proof_pv.committed_value_digest,
+                            deferred_proofs_digest: last_proof_pv.deferred_proofs_digest,
+                        },
+                    ));
+
+                    deferred_digest = Self::hash_foreign_deferred_proofs(
+                        deferred_digest,
+                        machine.as_ref(),
+                        batch,
+                    );
+                } else {
+                    let input = SP1CompressWitnessValues { vks_and_proofs, is_complete: true };
+                    let input = self.make_merkle_proofs(input);
+                    let SP1CompressWithVKeyWitnessValues { compress_val, merkle_val } = input;
+
+                    deferred_inputs.push(SP1CircuitWitness::Deferred(SP1DeferredWitnessValues {
+                        vks_and_proofs: compress_val.vks_and_proofs,
+                        vk_merkle_data: merkle_val,
+                        start_reconstruct_deferred_digest: deferred_digest,
+                        is_complete: false,
+                        sp1_vk_digest: vk.hash_babybear(),
+                        end_pc: Val::<InnerSC>::zero(),
+                        end_shard: last_proof_pv.shard + BabyBear::one(),
+                        end_execution_shard: last_proof_pv.execution_shard,
+                        init_addr_bits: last_proof_pv.last_init_addr_bits,
+                        finalize_addr_bits: last_proof_pv.last_finalize_addr_bits,
+                        leaf_challenger: leaf_challenger.clone(),
+                        committed_value_digest: last_proof_pv.committed_value_digest,
+                        deferred_proofs_digest: last_proof_pv.deferred_proofs_digest,
+                    }));
+
+                    deferred_digest = Self::hash_deferred_proofs(deferred_digest, batch);
+                }
+            }
         }
         deferred_inputs
     }
@@ -626,12 +822,11 @@ impl<C: SP1ProverComponents> SP1Prover<C> {
 
         let mut inputs = Vec::new();
         inputs.extend(core_inputs.into_iter().map(SP1CircuitWitness::Core));
-        inputs.extend(deferred_inputs.into_iter().map(SP1CircuitWitness::Deferred));
+        inputs.extend(deferred_inputs);
         inputs
     }
 
     /// Reduce shards proofs to a single shard proof using the recursion prover.
-    #[instrument(name = "compress", level = "info", skip_all)]
     pub fn compress(
         &self,
         vk: &SP1VerifyingKey,
@@ -639,6 +834,23 @@ impl<C: SP1ProverComponents> SP1Prover<C> {
         deferred_proofs: Vec<SP1ReduceProof<InnerSC>>,
         opts: SP1ProverOpts,
     ) -> Result<SP1ReduceProof<InnerSC>, SP1RecursionProverError> {
+        self.compress_with_monitor(vk, proof, deferred_proofs, opts, &ProveMonitor::default())
+    }
+
+    /// Reduce shards proofs to a single shard proof using the recursion prover, reporting the
+    /// progress of the compress tree to the monitor and stopping if it is cancelled.
+    ///
+    /// If the program of a node cannot be compiled, the tree is stopped and the error is returned.
+    #[instrument(name = "compress", level = "info", skip_all)]
+    pub fn compress_with_monitor(
+        &self,
+        vk: &SP1VerifyingKey,
+        proof: SP1CoreProof,
+        deferred_proofs: Vec<SP1ReduceProof<InnerSC>>,
+        opts: SP1ProverOpts,
+        monitor: &ProveMonitor,
+    ) -> Result<SP1ReduceProof<InnerSC>, SP1RecursionProverError> {
+        self.check_security_preset(&opts)?;
         // The batch size for reducing two layers of recursion.
         let batch_size = REDUCE_BATCH_SIZE;
         // The batch size for reducing the first layer of recursion.
@@ -672,9 +884,23 @@ impl<C: SP1ProverComponents> SP1Prover<C> {
             expected_height += 1;
         }
 
+        // Count the nodes of the tree which are proven. The last input of an odd layer is passed
+        // through to the next layer without being proven again.
+        let mut num_nodes = num_first_layer_inputs;
+        let mut num_layer_inputs = num_first_layer_inputs;
+        while num_layer_inputs > 1 {
+            num_nodes += num_layer_inputs / batch_size;
+            num_layer_inputs = num_layer_inputs.div_ceil(batch_size);
+        }
+        monitor.compress_started(num_nodes);
+
+        // Stop the tree if the proof is cancelled, or once a node fails with the error below.
+        let token = &monitor.token().child();
+        let error = &Mutex::new(None);
+
         // Generate the proofs.
         let span = tracing::Span::current().clone();
-        let (vk, proof) = thread::scope(|s| {
+        let root = thread::scope(|s| {
             let _span = span.enter();
 
             // Spawn a worker that sends the first layer inputs to a bounded channel.
@@ -688,7 +914,9 @@ impl<C: SP1ProverComponents> SP1Prover<C> {
                 let input_sync = Arc::clone(&input_sync);
                 s.spawn(move || {
                     for (index, input) in first_layer_inputs.into_iter().enumerate() {
-                        input_sync.wait_for_turn(index);
+                        if !input_sync.wait_for_turn_or_cancel(index, token) {
+                            break;
+                        }
                         input_tx.lock().unwrap().send((index, 0, input, false)).unwrap();
                         input_sync.advance_turn();
                     }
@@ -713,21 +941,33 @@ impl<C: SP1ProverComponents> SP1Prover<C> {
                     let _span = span.enter();
                     loop {
                         let received = { input_rx.lock().unwrap().recv() };
+
+                        // Drain the remaining inputs if the proof was cancelled.
+                        if received.is_ok() && token.is_cancelled() {
+                            continue;
+                        }
+
                         if let Ok((index, height, input, false)) = received {
                             // Get the program and witness stream.
-                            let (program, witness_stream) = tracing::debug_span!(
+                            let program_and_witness_stream = tracing::debug_span!(
                                 "get program and witness stream"
                             )
                             .in_scope(|| match input {
                                 SP1CircuitWitness::Core(input) => {
                                     let mut witness_stream = Vec::new();
                                     Witnessable::<InnerConfig>::write(&input, &mut witness_stream);
-                                    (self.recursion_program(&input), witness_stream)
+                                    Ok((self.recursion_program(&input), witness_stream))
                                 }
                                 SP1CircuitWitness::Deferred(input) => {
                                     let mut witness_stream = Vec::new();
                                     Witnessable::<InnerConfig>::write(&input, &mut witness_stream);
-                                    (self.deferred_program(&input), witness_stream)
+                                    Ok((self.deferred_program(&input), witness_stream))
+                                }
+                                SP1CircuitWitness::ForeignDeferred(input) => {
+                                    let mut witness_stream = Vec::new();
+                                    Witnessable::<InnerConfig>::write(&input, &mut witness_stream);
+                                    self.foreign_deferred_program(&input)
+                                        .map(|program| (program, witness_stream))
                                 }
                                 SP1CircuitWitness::Compress(input) => {
                                     let mut witness_stream = Vec::new();
@@ -739,9 +979,18 @@ impl<C: SP1ProverComponents> SP1Prover<C> {
                                         &mut witness_stream,
                                     );
 
-                                    (self.compress_program(&input_with_merkle), witness_stream)
+                                    Ok((self.compress_program(&input_with_merkle), witness_stream))
                                 }
                             });
+                            let (program, witness_stream) = match program_and_witness_stream {
+                                Ok(program_and_witness_stream) => program_and_witness_stream,
+                                Err(e) => {
+                                    // Keep the first error and stop the other workers.
+                                    error.lock().unwrap().get_or_insert(e);
+                                    token.cancel();
+                                    continue;
+                                }
+                            };
 
                             // Execute the runtime.
                             let record = tracing::debug_span!("execute runtime").in_scope(|| {
@@ -778,7 +1027,9 @@ impl<C: SP1ProverComponents> SP1Prover<C> {
                             });
 
                             // Wait for our turn to update the state.
-                            record_and_trace_sync.wait_for_turn(index);
+                            if !record_and_trace_sync.wait_for_turn_or_cancel(index, token) {
+                                continue;
+                            }
 
                             // Send the record and traces to the worker.
                             record_and_trace_tx
@@ -796,7 +1047,9 @@ impl<C: SP1ProverComponents> SP1Prover<C> {
                             // Advance the turn.
                             record_and_trace_sync.advance_turn();
                         } else if let Ok((index, height, input, true)) = received {
-                            record_and_trace_sync.wait_for_turn(index);
+                            if !record_and_trace_sync.wait_for_turn_or_cancel(index, token) {
+                                continue;
+                            }
 
                             // Send the record and traces to the worker.
                             record_and_trace_tx
@@ -836,6 +1089,12 @@ impl<C: SP1ProverComponents> SP1Prover<C> {
                     let _span = span.enter();
                     loop {
                         let received = { record_and_trace_rx.lock().unwrap().recv() };
+
+                        // Drain the remaining records if the proof was cancelled.
+                        if received.is_ok() && token.is_cancelled() {
+                            continue;
+                        }
+
                         if let Ok((index, height, TracesOrInput::ProgramRecordTraces(boxed_prt))) = received {
                             let (program, record, traces) = *boxed_prt;
                             tracing::debug_span!("batch").in_scope(|| {
@@ -895,9 +1154,12 @@ impl<C: SP1ProverComponents> SP1Prover<C> {
                                         &mut self.compress_prover.config().challenger(),
                                     )
                                     .unwrap();
+                                monitor.compress_node_proven(height);
 
                                 // Wait for our turn to update the state.
-                                prover_sync.wait_for_turn(index);
+                                if !prover_sync.wait_for_turn_or_cancel(index, token) {
+                                    return;
+                                }
 
                                 // Send the proof.
                                 proofs_tx.lock().unwrap().send((index, height, vk, proof)).unwrap();
@@ -912,7 +1174,9 @@ impl<C: SP1ProverComponents> SP1Prover<C> {
                                 assert!(vks_and_proofs.len()==1);
                                 let (vk, proof) = vks_and_proofs.last().unwrap();
                         // Wait for our turn to update the state.
-                        prover_sync.wait_for_turn(index);
+                        if !prover_sync.wait_for_turn_or_cancel(index, token) {
+                            continue;
+                        }
 
                         // Send the proof.
                         proofs_tx.lock().unwrap().send((index, height, vk.clone(), proof.clone())).unwrap();
@@ -943,7 +1207,14 @@ impl<C: SP1ProverComponents> SP1Prover<C> {
                         ShardProof<InnerSC>,
                     )> = Vec::new();
                     loop {
-                        let received = { proofs_rx.lock().unwrap().recv() };
+                        // Poll for the next proof, so that a cancelled proof does not wait for
+                        // inputs which are never sent.
+                        let received =
+                            { proofs_rx.lock().unwrap().recv_timeout(CANCELLATION_POLL_INTERVAL) };
+                        if token.is_cancelled() {
+                            break;
+                        }
+
                         if let Ok((index, height, vk, proof)) = received {
                             batch.push((index, height, vk, proof));
 
@@ -976,7 +1247,9 @@ impl<C: SP1ProverComponents> SP1Prover<C> {
                                 is_complete,
                             });
 
-                            input_sync.wait_for_turn(count);
+                            if !input_sync.wait_for_turn_or_cancel(count, token) {
+                                break;
+                            }
                             input_tx
                                 .lock()
                                 .unwrap()
@@ -997,6 +1270,8 @@ impl<C: SP1ProverComponents> SP1Prover<C> {
                             } else {
                                 batch = Vec::new();
                             }
+                        } else if let Err(RecvTimeoutError::Timeout) = received {
+                            continue;
                         } else {
                             break;
                         }
@@ -1013,9 +1288,16 @@ impl<C: SP1ProverComponents> SP1Prover<C> {
             }
             handle.join().unwrap();
 
+            if token.is_cancelled() {
+                return None;
+            }
             let (_, _, vk, proof) = proofs_rx.lock().unwrap().recv().unwrap();
-            (vk, proof)
+            Some((vk, proof))
         });
+        if let Some(e) = error.lock().unwrap().take() {
+            return Err(e);
+        }
+        let (vk, proof) = root.ok_or(SP1RecursionProverError::Cancelled)?;
 
         Ok(SP1ReduceProof { vk, proof })
     }
@@ -1027,6 +1309,7 @@ impl<C: SP1ProverComponents> SP1Prover<C> {
         reduced_proof: SP1ReduceProof<InnerSC>,
         opts: SP1ProverOpts,
     ) -> Result<SP1ReduceProof<InnerSC>, SP1RecursionProverError> {
+        self.check_security_preset(&opts)?;
         // Make the compress proof.
         let SP1ReduceProof { vk: compressed_vk, proof: compressed_proof } = reduced_proof;
         let input = SP1CompressWitnessValues {
@@ -1074,6 +1357,7 @@ impl<C: SP1ProverComponents> SP1Prover<C> {
         compressed_proof: SP1ReduceProof<InnerSC>,
         opts: SP1ProverOpts,
     ) -> Result<SP1ReduceProof<OuterSC>, SP1RecursionProverError> {
+        self.check_security_preset(&opts)?;
         let SP1ReduceProof { vk: compressed_vk, proof: compressed_proof } = compressed_proof;
         let input = SP1CompressWitnessValues {
             vks_and_proofs: vec![(compressed_vk, compressed_proof)],
@@ -1189,6 +1473,78 @@ impl<C: SP1ProverComponents> SP1Prover<C> {
         proof
     }
 
+    /// Wrap the STARK proven over a SNARK-friendly field into a Groth16 proof in pure Rust.
+    ///
+    /// The proof is generated from the artifacts of [`build::build_groth16_bn254_ark_artifacts`],
+    /// so it is only accepted by the verifiers built from them, not by the verifier of the released
+    /// gnark artifacts.
+    #[cfg(feature = "ark-groth16")]
+    #[instrument(name = "wrap_groth16_bn254_ark", level = "info", skip_all)]
+    pub fn wrap_groth16_bn254_ark(
+        &self,
+        proof: SP1ReduceProof<OuterSC>,
+        build_dir: &Path,
+    ) -> Groth16Bn254Proof {
+        let input = SP1CompressWitnessValues {
+            vks_and_proofs: vec![(proof.vk.clone(), proof.proof.clone())],
+            is_complete: true,
+        };
+        let vkey_hash = sp1_vkey_digest_bn254(&proof);
+        let committed_values_digest = sp1_committed_values_digest_bn254(&proof);
+
+        let mut witness = Witness::default();
+        input.write(&mut witness);
+        witness.write_committed_values_digest(committed_values_digest);
+        witness.write_vkey_hash(vkey_hash);
+
+        let prover = Groth16Bn254ArkProver::new();
+        let proof = prover.prove(witness, build_dir.to_path_buf());
+
+        // Verify the proof.
+        prover.verify(
+            &proof,
+            &vkey_hash.as_canonical_biguint(),
+            &committed_values_digest.as_canonical_biguint(),
+            build_dir,
+        );
+
+        proof
+    }
+
+    /// Wrap the STARK proven over a SNARK-friendly field into an fflonk proof.
+    #[cfg(feature = "fflonk")]
+    #[instrument(name = "wrap_fflonk_bn254", level = "info", skip_all)]
+    pub fn wrap_fflonk_bn254(
+        &self,
+        proof: SP1ReduceProof<OuterSC>,
+        build_dir: &Path,
+    ) -> anyhow::Result<FflonkBn254Proof> {
+        let input = SP1CompressWitnessValues {
+            vks_and_proofs: vec![(proof.vk.clone(), proof.proof.clone())],
+            is_complete: true,
+        };
+        let vkey_hash = sp1_vkey_digest_bn254(&proof);
+        let committed_values_digest = sp1_committed_values_digest_bn254(&proof);
+
+        let mut witness = Witness::default();
+        input.write(&mut witness);
+        witness.write_committed_values_digest(committed_values_digest);
+        witness.write_vkey_hash(vkey_hash);
+
+        let prover = sp1_recursion_gnark_ffi::ark::FflonkBn254Prover::new();
+        let proof = prover.prove(witness, build_dir.to_path_buf())?;
+
+        // Verify the proof.
+        prover.verify(
+            &proof,
+            &vkey_hash.as_canonical_biguint(),
+            &committed_values_digest.as_canonical_biguint(),
+            build_dir,
+        )?;
+
+        Ok(proof)
+    }
+
     /// Accumulate deferred proofs into a single digest.
     pub fn hash_deferred_proofs(
         prev_digest: [Val<CoreSC>; DIGEST_SIZE],
@@ -1208,6 +1564,22 @@ impl<C: SP1ProverComponents> SP1Prover<C> {
         digest
     }
 
+    /// Accumulate deferred proofs of a foreign machine into a single digest.
+    pub fn hash_foreign_deferred_proofs(
+        prev_digest: [Val<CoreSC>; DIGEST_SIZE],
+        machine: &dyn ForeignMachine,
+        deferred_proofs: &[SP1ReduceProof<InnerSC>],
+    ) -> [Val<CoreSC>; 8] {
+        let mut digest = prev_digest;
+        for proof in deferred_proofs.iter() {
+            let public_values_digest = foreign::foreign_public_values_digest(machine, &proof.proof);
+            let public_values_digest = public_values_digest.map(BabyBear::from_canonical_u8);
+            let vk_digest = foreign::foreign_vk_digest(&proof.vk);
+            digest = hash_deferred_proof(&digest, &vk_digest, &public_values_digest);
+        }
+        digest
+    }
+
     pub fn make_merkle_proofs(
         &self,
         input: SP1CompressWitnessValues<CoreSC>,
@@ -1278,7 +1650,9 @@ pub mod tests {
     use p3_field::PrimeField32;
 
     use shapes::SP1ProofShape;
-    use sp1_recursion_core::air::RecursionPublicValues;
+    use sp1_core_executor::subproof::SubproofVerifier;
+    use sp1_recursion_compiler::{circuit::CircuitV2Builder, ir::Felt};
+    use sp1_recursion_core::air::{RecursionPublicValues, RECURSIVE_PROOF_NUM_PV_ELTS};
 
     #[cfg(test)]
     use serial_test::serial;
@@ -1414,8 +1788,11 @@ pub mod tests {
         assert_eq!(vk_digest_bn254, vk.hash_bn254());
 
         tracing::info!("Test the outer Plonk circuit");
-        let (constraints, witness) =
-            build_constraints_and_witness(&wrapped_bn254_proof.vk, &wrapped_bn254_proof.proof);
+        let (constraints, witness) = build_constraints_and_witness(
+            &wrapped_bn254_proof.vk,
+            &wrapped_bn254_proof.proof,
+            prover.security_preset,
+        );
         PlonkBn254Prover::test(constraints, witness);
         tracing::info!("Circuit test succeeded");
 
@@ -1427,6 +1804,7 @@ pub mod tests {
         let artifacts_dir = try_build_plonk_bn254_artifacts_dev(
             &wrapped_bn254_proof.vk,
             &wrapped_bn254_proof.proof,
+            prover.security_preset,
         );
         let plonk_bn254_proof =
             prover.wrap_plonk_bn254(wrapped_bn254_proof.clone(), &artifacts_dir);
@@ -1438,6 +1816,7 @@ pub mod tests {
         let artifacts_dir = try_build_groth16_bn254_artifacts_dev(
             &wrapped_bn254_proof.vk,
             &wrapped_bn254_proof.proof,
+            prover.security_preset,
         );
         let groth16_bn254_proof = prover.wrap_groth16_bn254(wrapped_bn254_proof, &artifacts_dir);
         println!("{:?}", groth16_bn254_proof);
@@ -1547,6 +1926,92 @@ pub mod tests {
         Ok(())
     }
 
+    /// Proves a recursion program committing to public values derived from `seed` with the
+    /// compress machine, which stands in for a foreign machine.
+    pub fn prove_foreign_proof<C: SP1ProverComponents>(
+        prover: &SP1Prover<C>,
+        seed: usize,
+        opts: SP1ProverOpts,
+    ) -> SP1ReduceProof<InnerSC> {
+        let mut builder = Builder::<InnerConfig>::default();
+        let stream: Vec<Felt<BabyBear>> = (0..RECURSIVE_PROOF_NUM_PV_ELTS)
+            .map(|i| builder.eval(BabyBear::from_canonical_usize(seed + i)))
+            .collect();
+        let public_values: &RecursionPublicValues<_> = stream.as_slice().borrow();
+        builder.commit_public_values_v2(*public_values);
+        let program = AsmCompiler::<InnerConfig>::default().compile(builder.into_operations());
+        let program = Arc::new(program);
+
+        let mut runtime = RecursionRuntime::<Val<InnerSC>, Challenge<InnerSC>, _>::new(
+            program.clone(),
+            prover.compress_prover.config().perm.clone(),
+        );
+        runtime.run().unwrap();
+        let mut records = vec![runtime.record];
+        prover.compress_prover.machine().generate_dependencies(
+            &mut records,
+            &opts.recursion_opts,
+            None,
+        );
+
+        let (pk, vk) = prover.compress_prover.setup(&program);
+        let mut challenger = prover.compress_prover.config().challenger();
+        let proof = prover
+            .compress_prover
+            .prove(&pk, records, &mut challenger, opts.recursion_opts)
+            .unwrap();
+        SP1ReduceProof { vk, proof: proof.shard_proofs.into_iter().next().unwrap() }
+    }
+
+    /// Returns the compress machine of the prover, registered as a foreign machine for `vk`.
+    fn register_compress_machine<C: SP1ProverComponents>(
+        prover: &mut SP1Prover<C>,
+        vk: &StarkVerifyingKey<InnerSC>,
+    ) -> Arc<dyn ForeignMachine> {
+        let config = InnerSC::with_fri_parameters(prover.security_preset.compress());
+        prover.register_foreign_machine(CompressAir::<BabyBear>::compress_machine(config), [vk]);
+        prover.foreign_machine(vk).unwrap().clone()
+    }
+
+    pub fn test_e2e_with_foreign_deferred_proofs_prover<C: SP1ProverComponents>(
+        opts: SP1ProverOpts,
+    ) -> Result<()> {
+        // Test program which verifies foreign proofs of a vkey and a list of public values digests.
+        let verify_elf = test_artifacts::VERIFY_FOREIGN_PROOF_ELF;
+
+        tracing::info!("initializing prover");
+        let mut prover = SP1Prover::<C>::new();
+
+        tracing::info!("setup verify elf");
+        let (verify_pk, verify_vk) = prover.setup(verify_elf);
+
+        tracing::info!("prove foreign proofs");
+        let foreign_1 = prove_foreign_proof(&prover, 1, opts);
+        let foreign_2 = prove_foreign_proof(&prover, 2, opts);
+        let machine = register_compress_machine(&mut prover, &foreign_1.vk);
+        let pv_1 = foreign::foreign_public_values_digest(machine.as_ref(), &foreign_1.proof);
+        let pv_2 = foreign::foreign_public_values_digest(machine.as_ref(), &foreign_2.proof);
+
+        // Run verify program with the tagged foreign vkey, the proofs, and their digests.
+        let mut stdin = SP1Stdin::new();
+        stdin.write(&foreign::foreign_vk_hash_u32(&foreign_1.vk));
+        stdin.write(&vec![pv_1, pv_2]);
+        stdin.write_proof(foreign_1.clone(), foreign_1.vk.clone());
+        stdin.write_proof(foreign_2.clone(), foreign_2.vk.clone());
+
+        tracing::info!("proving verify program (core)");
+        let verify_proof = prover.prove_core(&verify_pk, &stdin, opts, Default::default())?;
+
+        tracing::info!("compress verify program");
+        let verify_reduce =
+            prover.compress(&verify_vk, verify_proof, vec![foreign_1, foreign_2], opts)?;
+
+        tracing::info!("verify verify program");
+        prover.verify_compressed(&verify_reduce, &verify_vk)?;
+
+        Ok(())
+    }
+
     /// Tests an end-to-end workflow of proving a program across the entire proof generation
     /// pipeline.
     ///
@@ -1575,6 +2040,67 @@ pub mod tests {
         test_e2e_with_deferred_proofs_prover::<CpuProverComponents>(SP1ProverOpts::default())
     }
 
+    /// Tests proving a program that verifies deferred proofs of a foreign machine.
+    #[test]
+    #[serial]
+    fn test_e2e_with_foreign_deferred_proofs() -> Result<()> {
+        setup_logger();
+        test_e2e_with_foreign_deferred_proofs_prover::<CpuProverComponents>(SP1ProverOpts::default())
+    }
+
+    #[test]
+    fn test_foreign_deferred_proof_verification() {
+        setup_logger();
+        let opts = SP1ProverOpts::default();
+        let mut prover = SP1Prover::<CpuProverComponents>::new();
+        let foreign_1 = prove_foreign_proof(&prover, 1, opts);
+        let foreign_2 = prove_foreign_proof(&prover, 2, opts);
+        let machine = register_compress_machine(&mut prover, &foreign_1.vk);
+
+        let vk = &foreign_1.vk;
+        let vk_hash = foreign::foreign_vk_hash_u32(vk);
+        let digest = |proof: &SP1ReduceProof<InnerSC>| {
+            let bytes = foreign::foreign_public_values_digest(machine.as_ref(), &proof.proof);
+            std::array::from_fn::<u32, 8, _>(|i| {
+                u32::from_le_bytes(bytes[4 * i..4 * i + 4].try_into().unwrap())
+            })
+        };
+        let verifier = &prover;
+        verifier.verify_deferred_proof(&foreign_1, vk, vk_hash, digest(&foreign_1)).unwrap();
+
+        // The public values of another proof are rejected.
+        assert!(verifier
+            .verify_deferred_proof(&foreign_1, vk, vk_hash, digest(&foreign_2))
+            .is_err());
+
+        // The untagged digest of the key is rejected.
+        assert_ne!(vk.hash_u32(), vk_hash);
+        assert!(verifier
+            .verify_deferred_proof(&foreign_1, vk, vk.hash_u32(), digest(&foreign_1))
+            .is_err());
+
+        // A proof for another key is rejected.
+        let mut tampered = foreign_1.clone();
+        tampered.vk.pc_start += BabyBear::one();
+        assert!(verifier
+            .verify_deferred_proof(&tampered, vk, vk_hash, digest(&foreign_1))
+            .is_err());
+
+        // The deferred digest accumulates the tagged digest of the key.
+        let zero = [BabyBear::zero(); DIGEST_SIZE];
+        let pv_digest = foreign::foreign_public_values_digest(machine.as_ref(), &foreign_1.proof)
+            .map(BabyBear::from_canonical_u8);
+        assert_eq!(
+            SP1Prover::<CpuProverComponents>::hash_foreign_deferred_proofs(
+                zero,
+                machine.as_ref(),
+                &[foreign_1.clone()],
+            ),
+            hash_deferred_proof(&zero, &foreign::foreign_vk_digest(vk), &pv_digest)
+        );
+        assert_ne!(foreign::foreign_vk_digest(vk), vk.hash_babybear());
+    }
+
     #[test]
     fn test_deterministic_setup() {
         setup_logger();
@@ -1584,4 +2110,19 @@ pub mod tests {
         let pk2 = prover.setup(program).0;
         assert_eq!(pk.pk.commit, pk2.pk.commit);
     }
+
+    #[test]
+    fn test_security_preset_mismatch() {
+        let prover = SP1Prover::<CpuProverComponents>::new();
+        let (pk, _) = prover.setup(test_artifacts::FIBONACCI_ELF);
+        let opts = SP1ProverOpts::default().with_security_preset(SecurityPreset::Conjectured100);
+        let result = prover.prove_core(&pk, &SP1Stdin::new(), opts, SP1Context::default());
+        assert!(matches!(
+            result,
+            Err(SP1CoreProverError::SecurityPresetMismatch {
+                expected: SecurityPreset::Standard,
+                actual: SecurityPreset::Conjectured100,
+            })
+        ));
+    }
 }
@@ -19,7 +19,7 @@ use sp1_recursion_circuit::machine::{
     SP1DeferredWitnessValues, SP1RecursionShape, SP1RecursionWitnessValues,
 };
 use sp1_recursion_core::{shape::RecursionShapeConfig, RecursionProgram};
-use sp1_stark::{MachineProver, ProofShape, DIGEST_SIZE};
+use sp1_stark::{MachineProver, ProofShape, SecurityPreset, DIGEST_SIZE};
 
 use crate::{components::SP1ProverComponents, CompressAir, HashableKey, SP1Prover};
 
@@ -55,14 +55,27 @@ pub enum VkBuildError {
     Bincode(#[from] bincode::Error),
 }
 
+/// The directory of the vk map of a security preset other than [SecurityPreset::Standard], whose
+/// vk map is embedded in the prover.
+pub fn vk_map_dir(security_preset: SecurityPreset) -> PathBuf {
+    dirs::home_dir()
+        .unwrap()
+        .join(".sp1")
+        .join("circuits")
+        .join("vk-maps")
+        .join(security_preset.name())
+}
+
 pub fn build_vk_map<C: SP1ProverComponents>(
     reduce_batch_size: usize,
     dummy: bool,
     num_compiler_workers: usize,
     num_setup_workers: usize,
     indices: Option<Vec<usize>>,
+    security_preset: SecurityPreset,
 ) -> (BTreeSet<[BabyBear; DIGEST_SIZE]>, Vec<usize>, usize) {
-    let mut prover = SP1Prover::<C>::new();
+    let mut prover = SP1Prover::<C>::with_security_preset(security_preset)
+        .expect("failed to initialize the prover");
     prover.vk_verification = !dummy;
     let core_shape_config = prover.core_shape_config.as_ref().expect("core shape config not found");
     let recursion_shape_config =
@@ -193,6 +206,7 @@ pub fn build_vk_map_to_file<C: SP1ProverComponents>(
     num_setup_workers: usize,
     range_start: Option<usize>,
     range_end: Option<usize>,
+    security_preset: SecurityPreset,
 ) -> Result<(), VkBuildError> {
     std::fs::create_dir_all(&build_dir)?;
 
@@ -204,6 +218,7 @@ pub fn build_vk_map_to_file<C: SP1ProverComponents>(
         num_compiler_workers,
         num_setup_workers,
         range_start.and_then(|start| range_end.map(|end| (start..end).collect())),
+        security_preset,
     );
 
     let vk_map = vk_set.into_iter().enumerate().map(|(i, vk)| (vk, i)).collect::<BTreeMap<_, _>>();
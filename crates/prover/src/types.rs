@@ -1,4 +1,7 @@
-use std::{fs::File, path::Path};
+use std::{
+    fs::File,
+    path::{Path, PathBuf},
+};
 
 use anyhow::Result;
 use clap::ValueEnum;
@@ -11,12 +14,15 @@ use sp1_core_machine::{io::SP1Stdin, reduce::SP1ReduceProof};
 use sp1_primitives::{io::SP1PublicValues, poseidon2_hash};
 
 use sp1_recursion_circuit::machine::{
-    SP1CompressWitnessValues, SP1DeferredWitnessValues, SP1RecursionWitnessValues,
+    SP1CompressWitnessValues, SP1DeferredWitnessValues, SP1ForeignDeferredWitnessValues,
+    SP1RecursionWitnessValues,
 };
 
 use sp1_recursion_gnark_ffi::proof::{Groth16Bn254Proof, PlonkBn254Proof};
 
-use sp1_stark::{ShardProof, StarkGenericConfig, StarkProvingKey, StarkVerifyingKey, DIGEST_SIZE};
+use sp1_stark::{
+    SecurityPreset, ShardProof, StarkGenericConfig, StarkProvingKey, StarkVerifyingKey, DIGEST_SIZE,
+};
 use thiserror::Error;
 
 use crate::{
@@ -235,11 +241,25 @@ pub enum SP1ReduceProofWrapper {
 pub enum SP1RecursionProverError {
     #[error("Runtime error: {0}")]
     RuntimeError(String),
+    #[error("proof generation was cancelled")]
+    Cancelled,
+    #[error("the prover was initialized with the {expected} security preset, not {actual}")]
+    SecurityPresetMismatch { expected: SecurityPreset, actual: SecurityPreset },
+    #[error(
+        "failed to read the vk map of the {security_preset} preset at {}: {reason}, generate it \
+         with `build_compress_vks --security-preset {security_preset}`",
+        path.display()
+    )]
+    MissingVkMap { security_preset: SecurityPreset, path: PathBuf, reason: String },
+    #[error("no foreign machine is registered for the verifying keys of the deferred batch")]
+    UnregisteredForeignMachine,
 }
 
+#[derive(Serialize, Deserialize)]
 #[allow(clippy::large_enum_variant)]
 pub enum SP1CircuitWitness {
     Core(SP1RecursionWitnessValues<CoreSC>),
     Deferred(SP1DeferredWitnessValues<InnerSC>),
+    ForeignDeferred(SP1ForeignDeferredWitnessValues<InnerSC>),
     Compress(SP1CompressWitnessValues<InnerSC>),
 }
@@ -10,6 +10,10 @@ use sp1_primitives::{consts::WORD_SIZE, io::SP1PublicValues};
 
 use sp1_recursion_circuit::machine::RootPublicValues;
 use sp1_recursion_core::{air::RecursionPublicValues, stark::BabyBearPoseidon2Outer};
+#[cfg(feature = "ark-groth16")]
+use sp1_recursion_gnark_ffi::ark::Groth16Bn254ArkProver;
+#[cfg(feature = "fflonk")]
+use sp1_recursion_gnark_ffi::{ark::FflonkBn254Prover, FflonkBn254Proof};
 use sp1_recursion_gnark_ffi::{
     Groth16Bn254Proof, Groth16Bn254Prover, PlonkBn254Proof, PlonkBn254Prover,
 };
@@ -22,6 +26,7 @@ use thiserror::Error;
 
 use crate::{
     components::SP1ProverComponents,
+    foreign::{foreign_public_values_digest, foreign_vk_hash_u32},
     utils::{assert_recursion_public_values_valid, assert_root_public_values_valid},
     CoreSC, HashableKey, OuterSC, SP1CoreProofData, SP1Prover, SP1VerifyingKey,
 };
@@ -50,6 +55,18 @@ pub enum Groth16VerificationError {
     InvalidPublicValues,
 }
 
+#[derive(Error, Debug)]
+pub enum FflonkVerificationError {
+    #[error(
+        "the verifying key does not match the inner fflonk bn254 proof's committed verifying key"
+    )]
+    InvalidVerificationKey,
+    #[error(
+        "the public values in the sp1 proof do not match the public values in the inner fflonk bn254 proof"
+    )]
+    InvalidPublicValues,
+}
+
 impl<C: SP1ProverComponents> SP1Prover<C> {
     /// Verify a core proof by verifying the shards, verifying lookup bus, verifying that the
     /// shards are contiguous and complete.
@@ -422,6 +439,51 @@ impl<C: SP1ProverComponents> SP1Prover<C> {
 
         Ok(())
     }
+
+    /// Verifies a Groth16 proof of [`SP1Prover::wrap_groth16_bn254_ark`] using the arkworks
+    /// artifacts in the build directory.
+    #[cfg(feature = "ark-groth16")]
+    pub fn verify_groth16_bn254_ark(
+        &self,
+        proof: &Groth16Bn254Proof,
+        vk: &SP1VerifyingKey,
+        public_values: &SP1PublicValues,
+        build_dir: &Path,
+    ) -> Result<()> {
+        let prover = Groth16Bn254ArkProver::new();
+
+        let vkey_hash = BigUint::from_str(&proof.public_inputs[0])?;
+        let committed_values_digest = BigUint::from_str(&proof.public_inputs[1])?;
+
+        // Verify the proof with the corresponding public inputs.
+        prover.verify(proof, &vkey_hash, &committed_values_digest, build_dir);
+
+        verify_groth16_bn254_public_inputs(vk, public_values, &proof.public_inputs)?;
+
+        Ok(())
+    }
+
+    /// Verifies an fflonk proof using the circuit artifacts in the build directory.
+    #[cfg(feature = "fflonk")]
+    pub fn verify_fflonk_bn254(
+        &self,
+        proof: &FflonkBn254Proof,
+        vk: &SP1VerifyingKey,
+        public_values: &SP1PublicValues,
+        build_dir: &Path,
+    ) -> Result<()> {
+        let prover = FflonkBn254Prover::new();
+
+        let vkey_hash = BigUint::from_str(&proof.public_inputs[0])?;
+        let committed_values_digest = BigUint::from_str(&proof.public_inputs[1])?;
+
+        // Verify the proof with the corresponding public inputs.
+        prover.verify(proof, &vkey_hash, &committed_values_digest, build_dir)?;
+
+        verify_fflonk_bn254_public_inputs(vk, public_values, &proof.public_inputs)?;
+
+        Ok(())
+    }
 }
 
 /// Verify the vk_hash and public_values_hash in the public inputs of the PlonkBn254Proof match the
@@ -470,6 +532,29 @@ pub fn verify_groth16_bn254_public_inputs(
     Ok(())
 }
 
+/// Verify the vk_hash and public_values_hash in the public inputs of the FflonkBn254Proof match
+/// the expected values.
+pub fn verify_fflonk_bn254_public_inputs(
+    vk: &SP1VerifyingKey,
+    public_values: &SP1PublicValues,
+    fflonk_bn254_public_inputs: &[String],
+) -> Result<()> {
+    let expected_vk_hash = BigUint::from_str(&fflonk_bn254_public_inputs[0])?;
+    let expected_public_values_hash = BigUint::from_str(&fflonk_bn254_public_inputs[1])?;
+
+    let vk_hash = vk.hash_bn254().as_canonical_biguint();
+    if vk_hash != expected_vk_hash {
+        return Err(FflonkVerificationError::InvalidVerificationKey.into());
+    }
+
+    let public_values_hash = public_values.hash_bn254();
+    if public_values_hash != expected_public_values_hash {
+        return Err(FflonkVerificationError::InvalidPublicValues.into());
+    }
+
+    Ok(())
+}
+
 impl<C: SP1ProverComponents> SubproofVerifier for &SP1Prover<C> {
     fn verify_deferred_proof(
         &self,
@@ -478,6 +563,27 @@ impl<C: SP1ProverComponents> SubproofVerifier for &SP1Prover<C> {
         vk_hash: [u32; 8],
         committed_value_digest: [u32; 8],
     ) -> Result<(), MachineVerificationError<BabyBearPoseidon2>> {
+        // Check proofs of foreign machines against the machine, under the tagged vk digest.
+        if let Some(machine) = self.foreign_machine(vk) {
+            if foreign_vk_hash_u32(vk) != vk_hash {
+                return Err(MachineVerificationError::InvalidPublicValues(
+                    "vk hash from syscall does not match vkey from input",
+                ));
+            }
+            if proof.vk.hash_babybear() != vk.hash_babybear() {
+                return Err(MachineVerificationError::InvalidVerificationKey);
+            }
+            machine.verify(&proof.vk, &proof.proof)?;
+            let public_values_digest = foreign_public_values_digest(machine.as_ref(), &proof.proof);
+            for (i, word) in public_values_digest.chunks_exact(WORD_SIZE).enumerate() {
+                if u32::from_le_bytes(word.try_into().unwrap()) != committed_value_digest[i] {
+                    return Err(MachineVerificationError::InvalidPublicValues(
+                        "public values digest does not match",
+                    ));
+                }
+            }
+            return Ok(());
+        }
         // Check that the vk hash matches the vk hash from the input.
         if vk.hash_u32() != vk_hash {
             return Err(MachineVerificationError::InvalidPublicValues(
@@ -7,7 +7,10 @@ use p3_fri::{
 };
 use p3_symmetric::Hash;
 use p3_util::log2_strict_usize;
-use sp1_recursion_compiler::ir::{Builder, DslIr, Felt, SymbolicExt};
+use sp1_recursion_compiler::{
+    circuit::CircuitV2Builder,
+    ir::{Builder, DslIr, Felt, SymbolicExt},
+};
 use sp1_recursion_core::DIGEST_SIZE;
 use sp1_stark::{InnerChallenge, InnerChallengeMmcs, InnerPcsProof, InnerVal};
 use std::{
@@ -76,10 +79,14 @@ pub fn verify_two_adic_pcs<C: CircuitConfig<F = SC::Val>, SC: BabyBearFriConfigV
     challenger: &mut SC::FriChallengerVariable,
     rounds: Vec<TwoAdicPcsRoundVariable<C, SC>>,
 ) {
+    builder.cycle_tracker_v2_enter("fri-sample-challenges".to_string());
     let alpha = challenger.sample_ext(builder);
 
     let fri_challenges =
         verify_shape_and_sample_challenges::<C, SC>(builder, config, &proof.fri_proof, challenger);
+    builder.cycle_tracker_v2_exit();
+
+    builder.cycle_tracker_v2_enter("fri-reduced-openings".to_string());
 
     let log_global_max_height = proof.fri_proof.commit_phase_commits.len() + config.log_blowup;
 
@@ -205,7 +212,9 @@ pub fn verify_two_adic_pcs<C: CircuitConfig<F = SC::Val>, SC: BabyBearFriConfigV
             ro
         })
         .collect::<Vec<_>>();
+    builder.cycle_tracker_v2_exit();
 
+    builder.cycle_tracker_v2_enter("fri-verify-queries".to_string());
     verify_challenges::<C, SC>(
         builder,
         config,
@@ -213,6 +222,7 @@ pub fn verify_two_adic_pcs<C: CircuitConfig<F = SC::Val>, SC: BabyBearFriConfigV
         &fri_challenges,
         reduced_openings,
     );
+    builder.cycle_tracker_v2_exit();
 }
 
 pub fn verify_challenges<C: CircuitConfig<F = SC::Val>, SC: BabyBearFriConfigVariable<C>>(
@@ -11,7 +11,7 @@ use p3_commit::Mmcs;
 use p3_field::AbstractField;
 use p3_matrix::dense::RowMajorMatrix;
 
-use sp1_primitives::consts::WORD_SIZE;
+use sp1_primitives::{consts::WORD_SIZE, FOREIGN_VK_DIGEST_TAG};
 use sp1_recursion_compiler::ir::{Builder, Ext, Felt};
 
 use sp1_stark::{
@@ -91,6 +91,52 @@ pub struct SP1DeferredWitnessVariable<
     pub is_complete: Felt<C::F>,
 }
 
+/// The witness of a batch of deferred proofs of a foreign machine.
+///
+/// This has the same hinted values as [`SP1DeferredWitnessValues`], but the verifying keys are
+/// those of the foreign machine, so they are not opened against the vk root.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "SC::Challenger: Serialize, ShardProof<SC>: Serialize, Dom<SC>: Serialize, [SC::Val; DIGEST_SIZE]: Serialize, SC::Digest: Serialize"
+))]
+#[serde(bound(
+    deserialize = "SC::Challenger: Deserialize<'de>, ShardProof<SC>: Deserialize<'de>, Dom<SC>: DeserializeOwned, [SC::Val; DIGEST_SIZE]: Deserialize<'de>, SC::Digest: Deserialize<'de>"
+))]
+pub struct SP1ForeignDeferredWitnessValues<SC: BabyBearFriConfig + FieldHasher<BabyBear>> {
+    pub vks_and_proofs: Vec<(StarkVerifyingKey<SC>, ShardProof<SC>)>,
+    pub vk_root: SC::Digest,
+    pub start_reconstruct_deferred_digest: [SC::Val; POSEIDON_NUM_WORDS],
+    pub sp1_vk_digest: [SC::Val; DIGEST_SIZE],
+    pub leaf_challenger: SC::Challenger,
+    pub committed_value_digest: [Word<SC::Val>; PV_DIGEST_NUM_WORDS],
+    pub deferred_proofs_digest: [SC::Val; POSEIDON_NUM_WORDS],
+    pub end_pc: SC::Val,
+    pub end_shard: SC::Val,
+    pub end_execution_shard: SC::Val,
+    pub init_addr_bits: [SC::Val; 32],
+    pub finalize_addr_bits: [SC::Val; 32],
+    pub is_complete: bool,
+}
+
+pub struct SP1ForeignDeferredWitnessVariable<
+    C: CircuitConfig<F = BabyBear>,
+    SC: FieldHasherVariable<C> + BabyBearFriConfigVariable<C>,
+> {
+    pub vks_and_proofs: Vec<(VerifyingKeyVariable<C, SC>, ShardProofVariable<C, SC>)>,
+    pub vk_root: SC::DigestVariable,
+    pub start_reconstruct_deferred_digest: [Felt<C::F>; POSEIDON_NUM_WORDS],
+    pub sp1_vk_digest: [Felt<C::F>; DIGEST_SIZE],
+    pub leaf_challenger: SC::FriChallengerVariable,
+    pub committed_value_digest: [Word<Felt<C::F>>; PV_DIGEST_NUM_WORDS],
+    pub deferred_proofs_digest: [Felt<C::F>; POSEIDON_NUM_WORDS],
+    pub end_pc: Felt<C::F>,
+    pub end_shard: Felt<C::F>,
+    pub end_execution_shard: Felt<C::F>,
+    pub init_addr_bits: [Felt<C::F>; 32],
+    pub finalize_addr_bits: [Felt<C::F>; 32],
+    pub is_complete: Felt<C::F>,
+}
+
 impl<C, SC, A> SP1DeferredVerifier<C, SC, A>
 where
     SC: BabyBearFriConfigVariable<
@@ -209,54 +255,236 @@ where
             reconstruct_deferred_digest = SC::hash(builder, &inputs);
         }
 
-        // Set the public values.
-
-        // Set initial_pc, end_pc, initial_shard, and end_shard to be the hitned values.
-        deferred_public_values.start_pc = end_pc;
-        deferred_public_values.next_pc = end_pc;
-        deferred_public_values.start_shard = end_shard;
-        deferred_public_values.next_shard = end_shard;
-        deferred_public_values.start_execution_shard = end_execution_shard;
-        deferred_public_values.next_execution_shard = end_execution_shard;
-        // Set the init and finalize address bits to be the hinted values.
-        deferred_public_values.previous_init_addr_bits = init_addr_bits;
-        deferred_public_values.last_init_addr_bits = init_addr_bits;
-        deferred_public_values.previous_finalize_addr_bits = finalize_addr_bits;
-        deferred_public_values.last_finalize_addr_bits = finalize_addr_bits;
-
-        // Set the sp1_vk_digest to be the hitned value.
-        deferred_public_values.sp1_vk_digest = sp1_vk_digest;
-
-        // Set the committed value digest to be the hitned value.
-        deferred_public_values.committed_value_digest = committed_value_digest;
-        // Set the deferred proof digest to be the hitned value.
-        deferred_public_values.deferred_proofs_digest = deferred_proofs_digest;
-
-        // Set the initial, end, and leaf challenger to be the hitned values.
-        let values = leaf_challenger.public_values(builder);
-        deferred_public_values.leaf_challenger = values;
-        deferred_public_values.start_reconstruct_challenger = values;
-        deferred_public_values.end_reconstruct_challenger = values;
-        // Set the exit code to be zero for now.
-        deferred_public_values.exit_code = builder.eval(C::F::zero());
-        // Assign the deferred proof digests.
-        deferred_public_values.end_reconstruct_deferred_digest = reconstruct_deferred_digest;
-        // Set the is_complete flag.
-        deferred_public_values.is_complete = is_complete;
-        // Set the `contains_execution_shard` flag.
-        deferred_public_values.contains_execution_shard = builder.eval(C::F::zero());
-        // Set the cumulative sum to zero.
-        deferred_public_values.cumulative_sum = array::from_fn(|_| builder.eval(C::F::zero()));
-        // Set the vk root from the witness.
-        deferred_public_values.vk_root = vk_root;
-        // Set the digest according to the previous values.
-        deferred_public_values.digest =
-            recursion_public_values_digest::<C, SC>(builder, deferred_public_values);
-
-        SC::commit_recursion_public_values(builder, *deferred_public_values);
+        commit_deferred_public_values::<C, SC>(
+            builder,
+            deferred_public_values,
+            reconstruct_deferred_digest,
+            vk_root,
+            DeferredHints {
+                sp1_vk_digest,
+                leaf_challenger,
+                committed_value_digest,
+                deferred_proofs_digest,
+                end_pc,
+                end_shard,
+                end_execution_shard,
+                init_addr_bits,
+                finalize_addr_bits,
+                is_complete,
+            },
+        );
+    }
+
+    /// Verify a batch of deferred proofs of a foreign machine.
+    ///
+    /// Unlike the proofs of [`Self::verify`], these are not recursive SP1 proofs but single-shard
+    /// proofs of an arbitrary machine over BabyBear, such as a STARK of another Plonky3-based
+    /// system. The verifier:
+    /// - Asserts that each of these proofs is valid for the machine.
+    /// - Aggregates the digest of the verifying key and the digest of the public values of each
+    ///   proof into the accumulated deferred digest, in the same way as for SP1 proofs.
+    ///
+    /// The vk digest is the hash of the digest of the key behind [`FOREIGN_VK_DIGEST_TAG`], and
+    /// the public values digest is the hash of the public values, with each of its elements
+    /// written as four little-endian bytes. A program can then check a foreign proof with
+    /// `verify_sp1_proof`, passing the tagged digest of the key it expects. The verifying keys
+    /// themselves are not checked against the vk root, since the program commits to the key it
+    /// accepts.
+    pub fn verify_foreign(
+        builder: &mut Builder<C>,
+        machine: &StarkMachine<SC, A>,
+        input: SP1ForeignDeferredWitnessVariable<C, SC>,
+    ) {
+        assert!(
+            !machine.contains_global_bus(),
+            "foreign machines with global interactions are not supported"
+        );
+
+        let SP1ForeignDeferredWitnessVariable {
+            vks_and_proofs,
+            vk_root,
+            start_reconstruct_deferred_digest,
+            sp1_vk_digest,
+            leaf_challenger,
+            committed_value_digest,
+            deferred_proofs_digest,
+            end_pc,
+            end_shard,
+            end_execution_shard,
+            init_addr_bits,
+            finalize_addr_bits,
+            is_complete,
+        } = input;
+
+        let mut deferred_public_values_stream: Vec<Felt<C::F>> =
+            (0..RECURSIVE_PROOF_NUM_PV_ELTS).map(|_| builder.uninit()).collect();
+        let deferred_public_values: &mut RecursionPublicValues<_> =
+            deferred_public_values_stream.as_mut_slice().borrow_mut();
+
+        // Initialize the start of deferred digests.
+        deferred_public_values.start_reconstruct_deferred_digest =
+            start_reconstruct_deferred_digest;
+
+        // Initialize the consistency check variable.
+        let mut reconstruct_deferred_digest: [Felt<C::F>; POSEIDON_NUM_WORDS] =
+            start_reconstruct_deferred_digest;
+
+        for (vk, shard_proof) in vks_and_proofs {
+            // Initialize a challenger and observe the vk.
+            let mut challenger = machine.config().challenger_variable(builder);
+            vk.observe_into(builder, &mut challenger);
+
+            // Observe the public values.
+            let public_values = &shard_proof.public_values[0..machine.num_pv_elts()];
+            challenger.observe_slice(builder, public_values.iter().copied());
+
+            let zero_ext: Ext<C::F, C::EF> = builder.eval(C::F::zero());
+            StarkVerifier::verify_shard(
+                builder,
+                &vk,
+                machine,
+                &mut challenger,
+                &shard_proof,
+                &[zero_ext, zero_ext],
+            );
+
+            // Update deferred proof digest
+            // poseidon2( current_digest[..8] || vk_digest[..8] || pv_digest_bytes[..32] )
+            let mut inputs: [Felt<C::F>; 48] = array::from_fn(|_| builder.uninit());
+            inputs[0..DIGEST_SIZE].copy_from_slice(&reconstruct_deferred_digest);
+
+            // The vk digest is hashed behind a tag, so that foreign proofs are accumulated under
+            // digests that differ from the digest of any SP1 program.
+            let mut tagged_vk: Vec<Felt<C::F>> = FOREIGN_VK_DIGEST_TAG
+                .iter()
+                .map(|byte| builder.eval(C::F::from_canonical_u8(*byte)))
+                .collect();
+            tagged_vk.extend(vk.hash(builder));
+            let vk_digest = SC::hash(builder, &tagged_vk);
+            inputs[DIGEST_SIZE..DIGEST_SIZE + DIGEST_SIZE].copy_from_slice(&vk_digest);
+
+            let public_values_digest = SC::hash(builder, public_values);
+            for (j, element) in public_values_digest.into_iter().enumerate() {
+                let bits = C::num2bits(builder, element, 8 * WORD_SIZE);
+                for (k, byte_bits) in bits.chunks(8).enumerate() {
+                    let byte = C::bits2num(builder, byte_bits.iter().copied());
+                    inputs[j * WORD_SIZE + k + 16] = byte;
+                }
+            }
+            reconstruct_deferred_digest = SC::hash(builder, &inputs);
+        }
+
+        commit_deferred_public_values::<C, SC>(
+            builder,
+            deferred_public_values,
+            reconstruct_deferred_digest,
+            vk_root,
+            DeferredHints {
+                sp1_vk_digest,
+                leaf_challenger,
+                committed_value_digest,
+                deferred_proofs_digest,
+                end_pc,
+                end_shard,
+                end_execution_shard,
+                init_addr_bits,
+                finalize_addr_bits,
+                is_complete,
+            },
+        );
     }
 }
 
+/// The values of a deferred batch that are taken from the witness, since they continue the
+/// execution of the program that verifies the deferred proofs.
+struct DeferredHints<C: CircuitConfig<F = BabyBear>> {
+    sp1_vk_digest: [Felt<C::F>; DIGEST_SIZE],
+    leaf_challenger: DuplexChallengerVariable<C>,
+    committed_value_digest: [Word<Felt<C::F>>; PV_DIGEST_NUM_WORDS],
+    deferred_proofs_digest: [Felt<C::F>; POSEIDON_NUM_WORDS],
+    end_pc: Felt<C::F>,
+    end_shard: Felt<C::F>,
+    end_execution_shard: Felt<C::F>,
+    init_addr_bits: [Felt<C::F>; 32],
+    finalize_addr_bits: [Felt<C::F>; 32],
+    is_complete: Felt<C::F>,
+}
+
+/// Sets the public values of a deferred batch and commits to them.
+fn commit_deferred_public_values<C, SC>(
+    builder: &mut Builder<C>,
+    deferred_public_values: &mut RecursionPublicValues<Felt<C::F>>,
+    reconstruct_deferred_digest: [Felt<C::F>; POSEIDON_NUM_WORDS],
+    vk_root: [Felt<C::F>; DIGEST_SIZE],
+    hints: DeferredHints<C>,
+) where
+    SC: BabyBearFriConfigVariable<
+        C,
+        FriChallengerVariable = DuplexChallengerVariable<C>,
+        DigestVariable = [Felt<BabyBear>; DIGEST_SIZE],
+    >,
+    C: CircuitConfig<F = SC::Val, EF = SC::Challenge, Bit = Felt<BabyBear>>,
+{
+    let DeferredHints {
+        sp1_vk_digest,
+        leaf_challenger,
+        committed_value_digest,
+        deferred_proofs_digest,
+        end_pc,
+        end_shard,
+        end_execution_shard,
+        init_addr_bits,
+        finalize_addr_bits,
+        is_complete,
+    } = hints;
+
+    // Set the public values.
+
+    // Set initial_pc, end_pc, initial_shard, and end_shard to be the hitned values.
+    deferred_public_values.start_pc = end_pc;
+    deferred_public_values.next_pc = end_pc;
+    deferred_public_values.start_shard = end_shard;
+    deferred_public_values.next_shard = end_shard;
+    deferred_public_values.start_execution_shard = end_execution_shard;
+    deferred_public_values.next_execution_shard = end_execution_shard;
+    // Set the init and finalize address bits to be the hinted values.
+    deferred_public_values.previous_init_addr_bits = init_addr_bits;
+    deferred_public_values.last_init_addr_bits = init_addr_bits;
+    deferred_public_values.previous_finalize_addr_bits = finalize_addr_bits;
+    deferred_public_values.last_finalize_addr_bits = finalize_addr_bits;
+
+    // Set the sp1_vk_digest to be the hitned value.
+    deferred_public_values.sp1_vk_digest = sp1_vk_digest;
+
+    // Set the committed value digest to be the hitned value.
+    deferred_public_values.committed_value_digest = committed_value_digest;
+    // Set the deferred proof digest to be the hitned value.
+    deferred_public_values.deferred_proofs_digest = deferred_proofs_digest;
+
+    // Set the initial, end, and leaf challenger to be the hitned values.
+    let values = leaf_challenger.public_values(builder);
+    deferred_public_values.leaf_challenger = values;
+    deferred_public_values.start_reconstruct_challenger = values;
+    deferred_public_values.end_reconstruct_challenger = values;
+    // Set the exit code to be zero for now.
+    deferred_public_values.exit_code = builder.eval(C::F::zero());
+    // Assign the deferred proof digests.
+    deferred_public_values.end_reconstruct_deferred_digest = reconstruct_deferred_digest;
+    // Set the is_complete flag.
+    deferred_public_values.is_complete = is_complete;
+    // Set the `contains_execution_shard` flag.
+    deferred_public_values.contains_execution_shard = builder.eval(C::F::zero());
+    // Set the cumulative sum to zero.
+    deferred_public_values.cumulative_sum = array::from_fn(|_| builder.eval(C::F::zero()));
+    // Set the vk root from the witness.
+    deferred_public_values.vk_root = vk_root;
+    // Set the digest according to the previous values.
+    deferred_public_values.digest =
+        recursion_public_values_digest::<C, SC>(builder, deferred_public_values);
+
+    SC::commit_recursion_public_values(builder, *deferred_public_values);
+}
+
 impl SP1DeferredWitnessValues<BabyBearPoseidon2> {
     pub fn dummy<A: MachineAir<BabyBear>>(
         machine: &StarkMachine<BabyBearPoseidon2, A>,
@@ -286,6 +514,31 @@ impl SP1DeferredWitnessValues<BabyBearPoseidon2> {
     }
 }
 
+impl SP1ForeignDeferredWitnessValues<BabyBearPoseidon2> {
+    pub fn dummy<A: MachineAir<BabyBear>>(
+        machine: &StarkMachine<BabyBearPoseidon2, A>,
+        shape: &SP1CompressShape,
+    ) -> Self {
+        let vks_and_proofs = SP1CompressWitnessValues::dummy(machine, shape).vks_and_proofs;
+
+        Self {
+            vks_and_proofs,
+            vk_root: [BabyBear::zero(); DIGEST_SIZE],
+            leaf_challenger: dummy_challenger(machine.config()),
+            is_complete: true,
+            sp1_vk_digest: [BabyBear::zero(); DIGEST_SIZE],
+            start_reconstruct_deferred_digest: [BabyBear::zero(); POSEIDON_NUM_WORDS],
+            committed_value_digest: [Word::default(); PV_DIGEST_NUM_WORDS],
+            deferred_proofs_digest: [BabyBear::zero(); POSEIDON_NUM_WORDS],
+            end_pc: BabyBear::zero(),
+            end_shard: BabyBear::zero(),
+            end_execution_shard: BabyBear::zero(),
+            init_addr_bits: [BabyBear::zero(); 32],
+            finalize_addr_bits: [BabyBear::zero(); 32],
+        }
+    }
+}
+
 impl SP1DeferredShape {
     pub const fn new(inner: SP1CompressShape, height: usize) -> Self {
         Self { inner, height }
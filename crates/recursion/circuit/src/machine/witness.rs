@@ -24,8 +24,9 @@ use crate::{
 
 use super::{
     SP1CompressWitnessValues, SP1CompressWitnessVariable, SP1DeferredWitnessValues,
-    SP1DeferredWitnessVariable, SP1MerkleProofWitnessValues, SP1MerkleProofWitnessVariable,
-    SP1RecursionWitnessValues, SP1RecursionWitnessVariable,
+    SP1DeferredWitnessVariable, SP1ForeignDeferredWitnessValues, SP1ForeignDeferredWitnessVariable,
+    SP1MerkleProofWitnessValues, SP1MerkleProofWitnessVariable, SP1RecursionWitnessValues,
+    SP1RecursionWitnessVariable,
 };
 
 impl<C: CircuitConfig, T: Witnessable<C>> Witnessable<C> for Word<T> {
@@ -213,6 +214,62 @@ where
     }
 }
 
+impl<C> Witnessable<C> for SP1ForeignDeferredWitnessValues<BabyBearPoseidon2>
+where
+    C: CircuitConfig<F = InnerVal, EF = InnerChallenge, Bit = Felt<InnerVal>>,
+{
+    type WitnessVariable = SP1ForeignDeferredWitnessVariable<C, BabyBearPoseidon2>;
+
+    fn read(&self, builder: &mut Builder<C>) -> Self::WitnessVariable {
+        let vks_and_proofs = self.vks_and_proofs.read(builder);
+        let vk_root = self.vk_root.read(builder);
+        let start_reconstruct_deferred_digest =
+            self.start_reconstruct_deferred_digest.read(builder);
+        let sp1_vk_digest = self.sp1_vk_digest.read(builder);
+        let leaf_challenger = self.leaf_challenger.read(builder);
+        let committed_value_digest = self.committed_value_digest.read(builder);
+        let deferred_proofs_digest = self.deferred_proofs_digest.read(builder);
+        let end_pc = self.end_pc.read(builder);
+        let end_shard = self.end_shard.read(builder);
+        let end_execution_shard = self.end_execution_shard.read(builder);
+        let init_addr_bits = self.init_addr_bits.read(builder);
+        let finalize_addr_bits = self.finalize_addr_bits.read(builder);
+        let is_complete = InnerVal::from_bool(self.is_complete).read(builder);
+
+        SP1ForeignDeferredWitnessVariable {
+            vks_and_proofs,
+            vk_root,
+            start_reconstruct_deferred_digest,
+            sp1_vk_digest,
+            leaf_challenger,
+            committed_value_digest,
+            deferred_proofs_digest,
+            end_pc,
+            end_shard,
+            end_execution_shard,
+            init_addr_bits,
+            finalize_addr_bits,
+            is_complete,
+        }
+    }
+
+    fn write(&self, witness: &mut impl WitnessWriter<C>) {
+        self.vks_and_proofs.write(witness);
+        self.vk_root.write(witness);
+        self.start_reconstruct_deferred_digest.write(witness);
+        self.sp1_vk_digest.write(witness);
+        self.leaf_challenger.write(witness);
+        self.committed_value_digest.write(witness);
+        self.deferred_proofs_digest.write(witness);
+        self.end_pc.write(witness);
+        self.end_shard.write(witness);
+        self.end_execution_shard.write(witness);
+        self.init_addr_bits.write(witness);
+        self.finalize_addr_bits.write(witness);
+        self.is_complete.write(witness);
+    }
+}
+
 impl<C: CircuitConfig, HV: FieldHasherVariable<C>> Witnessable<C> for MerkleProof<C::F, HV>
 where
     HV::Digest: Witnessable<C, WitnessVariable = HV::DigestVariable>,
@@ -316,6 +316,7 @@ where
             quotient_commit,
         } = *commitment;
 
+        builder.cycle_tracker_v2_enter("stage-c-sample-challenges".to_string());
         challenger.observe(builder, local_main_commit);
 
         let local_permutation_challenges =
@@ -351,6 +352,7 @@ where
         challenger.observe(builder, quotient_commit);
 
         let zeta = challenger.sample_ext(builder);
+        builder.cycle_tracker_v2_exit();
 
         let preprocessed_domains_points_and_opens = vk
             .chip_information
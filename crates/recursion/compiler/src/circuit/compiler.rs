@@ -5,7 +5,7 @@ use itertools::Itertools;
 use p3_field::{
     AbstractExtensionField, AbstractField, Field, PrimeField, PrimeField64, TwoAdicField,
 };
-use sp1_core_machine::utils::{sp1_debug_mode, SpanBuilder};
+use sp1_core_machine::utils::{sp1_debug_mode, Span, SpanBuilder};
 use sp1_recursion_core::{
     air::{Block, RecursionPublicValues, RECURSIVE_PROOF_NUM_PV_ELTS},
     BaseAluInstr, BaseAluOpcode,
@@ -15,7 +15,7 @@ use vec_map::VecMap;
 
 use sp1_recursion_core::*;
 
-use crate::prelude::*;
+use crate::{prelude::*, profile::ROOT_SPAN};
 
 /// The number of instructions to preallocate in a recursion program
 const PREALLOC_INSTRUCTIONS: usize = 10000000;
@@ -525,6 +525,32 @@ where
 
     /// Emit the instructions from a list of operations in the DSL.
     pub fn compile<F>(&mut self, operations: TracedVec<DslIr<C>>) -> RecursionProgram<C::F>
+    where
+        F: PrimeField + TwoAdicField,
+        C: Config<N = F, F = F> + Debug,
+    {
+        self.compile_inner(operations, false).0
+    }
+
+    /// Emit the instructions from a list of operations in the DSL, along with the number of
+    /// instructions of each kind emitted within each cycle tracker span.
+    pub fn compile_with_profile<F>(
+        &mut self,
+        operations: TracedVec<DslIr<C>>,
+    ) -> (RecursionProgram<C::F>, Span<String, &'static str>)
+    where
+        F: PrimeField + TwoAdicField,
+        C: Config<N = F, F = F> + Debug,
+    {
+        let (program, profile) = self.compile_inner(operations, true);
+        (program, profile.unwrap())
+    }
+
+    fn compile_inner<F>(
+        &mut self,
+        operations: TracedVec<DslIr<C>>,
+        profile: bool,
+    ) -> (RecursionProgram<C::F>, Option<Span<String, &'static str>>)
     where
         F: PrimeField + TwoAdicField,
         C: Config<N = F, F = F> + Debug,
@@ -534,18 +560,20 @@ where
         let debug_mode = sp1_debug_mode();
         // Compile each IR instruction into a list of ASM instructions, then combine them.
         // This step also counts the number of times each address is read from.
+        let mut span = None;
         let (mut instrs, traces) = tracing::debug_span!("compile_one loop").in_scope(|| {
             let mut instrs = Vec::with_capacity(PREALLOC_INSTRUCTIONS);
             let mut traces = vec![];
-            if debug_mode {
-                let mut span_builder =
-                    SpanBuilder::<_, &'static str>::new("cycle_tracker".to_string());
+            if debug_mode || profile {
+                let mut span_builder = SpanBuilder::<_, &'static str>::new(ROOT_SPAN.to_string());
                 for (ir_instr, trace) in operations {
                     self.compile_one(ir_instr, &mut |item| match item {
                         Ok(instr) => {
                             span_builder.item(instr_name(&instr));
                             instrs.push(instr);
-                            traces.push(trace.clone());
+                            if debug_mode {
+                                traces.push(trace.clone());
+                            }
                         }
                         Err(CompileOneErr::CycleTrackerEnter(name)) => {
                             span_builder.enter(name);
@@ -559,9 +587,12 @@ where
                     });
                 }
                 let cycle_tracker_root_span = span_builder.finish().unwrap();
-                for line in cycle_tracker_root_span.lines() {
-                    tracing::info!("{}", line);
+                if debug_mode {
+                    for line in cycle_tracker_root_span.lines() {
+                        tracing::info!("{}", line);
+                    }
                 }
+                span = Some(cycle_tracker_root_span);
             } else {
                 for (ir_instr, trace) in operations {
                     self.compile_one(ir_instr, &mut |item| match item {
@@ -675,6 +706,10 @@ where
                 })
             });
         tracing::debug!("number of consts to initialize: {}", instrs_consts.len());
+        // The constant-initializing instructions are outside of any span.
+        if let Some(span) = span.as_mut() {
+            *span.cts.entry("Mem").or_default() += total_consts;
+        }
         // Reset the other fields.
         self.next_addr = Default::default();
         self.virtual_to_physical.clear();
@@ -688,7 +723,7 @@ where
                 (instrs_consts.chain(instrs).collect(), traces)
             }
         });
-        RecursionProgram { instructions, total_memory, traces, shape: None }
+        (RecursionProgram { instructions, total_memory, traces, shape: None }, span)
     }
 }
 
@@ -1135,6 +1170,24 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_compile_with_profile() {
+        let mut builder = AsmBuilder::<F, EF>::default();
+        let a: Felt<_> = builder.eval(F::from_canonical_u32(3));
+        builder.cycle_tracker_v2_enter("squares".to_string());
+        for _ in 0..4 {
+            let _: Felt<_> = builder.eval(a * a);
+        }
+        builder.cycle_tracker_v2_exit();
+
+        let mut compiler = AsmCompiler::<AsmConfig<F, EF>>::default();
+        let (program, span) = compiler.compile_with_profile(builder.into_operations());
+        assert_eq!(span.total(), program.instructions.len());
+        assert_eq!(span.children.len(), 1);
+        assert_eq!(span.children[0].name, "squares");
+        assert_eq!(span.children[0].cts["BaseAlu"], 4);
+    }
+
     #[test]
     fn test_ext2felts() {
         setup_logger();
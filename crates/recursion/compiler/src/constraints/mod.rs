@@ -9,6 +9,7 @@ use self::opcodes::ConstraintOpcode;
 use crate::{
     ir::{Config, DslIr},
     prelude::TracedVec,
+    profile::SpanMarkers,
 };
 
 /// A constraint is an operation and a list of nested arguments.
@@ -72,7 +73,17 @@ impl<C: Config + Debug> ConstraintCompiler<C> {
 
     /// Emit the constraints from a list of operations in the DSL.
     pub fn emit(&mut self, operations: TracedVec<DslIr<C>>) -> Vec<Constraint> {
+        self.emit_with_spans(operations).0
+    }
+
+    /// Emit the constraints from a list of operations in the DSL, along with the positions of the
+    /// cycle tracker spans in the constraints.
+    pub fn emit_with_spans(
+        &mut self,
+        operations: TracedVec<DslIr<C>>,
+    ) -> (Vec<Constraint>, SpanMarkers) {
         let mut constraints: Vec<Constraint> = Vec::new();
+        let mut spans = SpanMarkers::default();
         for (instruction, _) in operations {
             match instruction {
                 DslIr::ImmV(a, b) => constraints.push(Constraint {
@@ -394,8 +405,8 @@ impl<C: Config + Debug> ConstraintCompiler<C> {
                 // Ignore cycle tracker instruction.
                 // It currently serves as a marker for calculation at compile time.
                 DslIr::CycleTracker(_) => (),
-                DslIr::CycleTrackerV2Enter(_) => (),
-                DslIr::CycleTrackerV2Exit => (),
+                DslIr::CycleTrackerV2Enter(name) => spans.enter(constraints.len(), name),
+                DslIr::CycleTrackerV2Exit => spans.exit(constraints.len()),
                 DslIr::ReduceE(a) => constraints.push(Constraint {
                     opcode: ConstraintOpcode::ReduceE,
                     args: vec![vec![a.id()]],
@@ -410,6 +421,6 @@ impl<C: Config + Debug> ConstraintCompiler<C> {
                 _ => panic!("unsupported {:?}", instruction),
             };
         }
-        constraints
+        (constraints, spans)
     }
 }
@@ -7,6 +7,7 @@ pub mod circuit;
 pub mod config;
 pub mod constraints;
 pub mod ir;
+pub mod profile;
 
 pub mod prelude {
     pub use crate::ir::*;
@@ -0,0 +1,123 @@
+//! Attribution of the cost of compiled programs and circuits to cycle tracker spans.
+//!
+//! Spans are delimited in the DSL with `cycle_tracker_v2_enter` and `cycle_tracker_v2_exit`. The
+//! [crate::circuit::AsmCompiler] counts the recursion instructions emitted within each span, while
+//! the [crate::constraints::ConstraintCompiler] records [SpanMarkers] alongside the constraints, so
+//! that a backend can attribute its own cost (e.g. R1CS constraints) to each span.
+
+use serde::{Deserialize, Serialize};
+use sp1_core_machine::utils::{Span, SpanBuilder};
+
+/// The name of the root span of a profile.
+pub const ROOT_SPAN: &str = "cycle_tracker";
+
+/// A boundary of a cycle tracker span.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SpanMarker {
+    Enter(String),
+    Exit,
+}
+
+/// The span boundaries of a list of compiled items, as positions in that list.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SpanMarkers {
+    pub markers: Vec<(usize, SpanMarker)>,
+}
+
+impl SpanMarkers {
+    /// Records that a span named `name` starts before the item at `position`.
+    pub fn enter(&mut self, position: usize, name: String) {
+        self.markers.push((position, SpanMarker::Enter(name)));
+    }
+
+    /// Records that the innermost open span ends before the item at `position`.
+    pub fn exit(&mut self, position: usize) {
+        self.markers.push((position, SpanMarker::Exit));
+    }
+
+    /// Builds the span tree of `items`, where `cost` gives the name and cost of each item.
+    ///
+    /// Panics if the markers are unbalanced.
+    pub fn profile<I, T>(
+        &self,
+        items: impl IntoIterator<Item = I>,
+        mut cost: impl FnMut(I) -> (T, usize),
+    ) -> Span<String, T>
+    where
+        T: Ord + Clone + std::fmt::Display + std::hash::Hash,
+    {
+        let mut builder = SpanBuilder::<String, T>::new(ROOT_SPAN.to_string());
+        let mut markers = self.markers.iter().peekable();
+        let mut apply_markers = |builder: &mut SpanBuilder<String, T>, position: usize| {
+            while let Some((_, marker)) = markers.next_if(|(p, _)| *p <= position) {
+                match marker {
+                    SpanMarker::Enter(name) => {
+                        builder.enter(name.clone());
+                    }
+                    SpanMarker::Exit => {
+                        builder.exit().expect("unbalanced cycle tracker spans");
+                    }
+                }
+            }
+        };
+
+        for (position, item) in items.into_iter().enumerate() {
+            apply_markers(&mut builder, position);
+            let (name, ct) = cost(item);
+            builder.items(name, ct);
+        }
+        apply_markers(&mut builder, usize::MAX);
+        builder.finish().expect("unbalanced cycle tracker spans")
+    }
+}
+
+/// Formats a span tree as a report, with the share of the total cost of each span.
+pub fn report<T>(span: &Span<String, T>) -> String
+where
+    T: Ord + std::fmt::Display + std::hash::Hash,
+{
+    fn summarize<T>(span: &Span<String, T>, total: usize, depth: usize, out: &mut String)
+    where
+        T: Ord + std::fmt::Display + std::hash::Hash,
+    {
+        let ct = span.total();
+        let share = if total == 0 { 0.0 } else { 100.0 * ct as f64 / total as f64 };
+        out.push_str(&format!("{}{} {} ({:.2}%)\n", "  ".repeat(depth), span.name, ct, share));
+        for child in &span.children {
+            summarize(child, total, depth + 1, out);
+        }
+    }
+
+    let mut out = String::new();
+    summarize(span, span.total(), 0, &mut out);
+    out.push('\n');
+    for line in span.lines() {
+        out.push_str(&line);
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_span_markers_profile() {
+        let mut markers = SpanMarkers::default();
+        markers.enter(1, "outer".to_string());
+        markers.enter(2, "inner".to_string());
+        markers.exit(3);
+        markers.exit(4);
+
+        let items = ["a", "b", "c", "d"];
+        let span = markers.profile(items, |item| (item.to_string(), 2));
+        assert_eq!(span.total(), 8);
+        assert_eq!(span.children.len(), 1);
+        let outer = &span.children[0];
+        assert_eq!(outer.total(), 6);
+        assert_eq!(outer.children[0].name, "inner");
+        assert_eq!(outer.children[0].cts["c"], 2);
+        assert!(report(&span).starts_with("cycle_tracker 8 (100.00%)\n  outer 6 (75.00%)\n"));
+    }
+}
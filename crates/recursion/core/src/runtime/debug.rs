@@ -0,0 +1,362 @@
+//! Debugging aids for recursion programs: a disassembler, and the faults reported by
+//! [super::Runtime::run_checked].
+
+use std::fmt::{self, Display, Formatter};
+
+use backtrace::Backtrace;
+use p3_field::{Field, PrimeField32, PrimeField64};
+
+use crate::{air::Block, *};
+
+/// Symbol prefixes of frames that are skipped when looking for the call site of an instruction.
+const SKIPPED_FRAMES: &[&str] =
+    &["backtrace::", "sp1_recursion_compiler::", "core::", "std::", "alloc::"];
+
+/// The reason an instruction cannot be executed.
+#[derive(Debug, Clone)]
+pub enum FaultKind<F> {
+    /// The instruction reads an address that has not been written.
+    UnassignedRead(Address<F>),
+    /// The instruction writes an address that has already been written.
+    DoubleWrite(Address<F>),
+    /// A memory read expects a different value than the one stored, e.g. a failed assertion.
+    MemoryMismatch { addr: Address<F>, expected: Block<F>, actual: Block<F> },
+}
+
+/// A faulty instruction, along with the memory state of its operands.
+#[derive(Debug)]
+pub struct Fault<F> {
+    pub pc: usize,
+    pub instruction: Instruction<F>,
+    pub kind: FaultKind<F>,
+    /// The operands of the instruction, reads followed by writes, with their memory entries.
+    pub operands: Vec<(Address<F>, Option<MemoryEntry<F>>)>,
+    /// The nearest pc at or before the fault with a backtrace, with that backtrace.
+    pub trace: Option<(usize, Backtrace)>,
+}
+
+impl<F: PrimeField64> Display for Address<F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "%{}", self.as_usize())
+    }
+}
+
+/// Formats a list of addresses.
+struct Addrs<'a, F>(&'a [Address<F>]);
+
+impl<'a, F: PrimeField64> Display for Addrs<'a, F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "[")?;
+        for (i, addr) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{addr}")?;
+        }
+        write!(f, "]")
+    }
+}
+
+/// Formats a block as a base field element if its higher limbs are zero.
+struct Val<'a, F>(&'a Block<F>);
+
+impl<'a, F: PrimeField32> Display for Val<'a, F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let [a, b, c, d] = self.0 .0;
+        if b.is_zero() && c.is_zero() && d.is_zero() {
+            write!(f, "{a}")
+        } else {
+            write!(f, "({a}, {b}, {c}, {d})")
+        }
+    }
+}
+
+impl<F: PrimeField32> Display for Instruction<F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Instruction::BaseAlu(BaseAluInstr { opcode, mult, addrs }) => {
+                write!(f, "{opcode:?} {} <- {}, {} (mult {mult})", addrs.out, addrs.in1, addrs.in2)
+            }
+            Instruction::ExtAlu(ExtAluInstr { opcode, mult, addrs }) => {
+                write!(f, "{opcode:?} {} <- {}, {} (mult {mult})", addrs.out, addrs.in1, addrs.in2)
+            }
+            Instruction::Mem(MemInstr { addrs, vals, mult, kind }) => match kind {
+                MemAccessKind::Read => {
+                    write!(f, "MemRead {} == {} (mult {mult})", addrs.inner, Val(&vals.inner))
+                }
+                MemAccessKind::Write => {
+                    write!(f, "MemWrite {} <- {} (mult {mult})", addrs.inner, Val(&vals.inner))
+                }
+            },
+            Instruction::Poseidon2(instr) => write!(
+                f,
+                "Poseidon2 {} <- {}",
+                Addrs(&instr.addrs.output),
+                Addrs(&instr.addrs.input)
+            ),
+            Instruction::Select(SelectInstr { addrs, mult1, mult2 }) => write!(
+                f,
+                "Select {}, {} <- {} ? ({}, {}) (mults {mult1}, {mult2})",
+                addrs.out1, addrs.out2, addrs.bit, addrs.in1, addrs.in2
+            ),
+            Instruction::ExpReverseBitsLen(ExpReverseBitsInstr { addrs, mult }) => write!(
+                f,
+                "ExpReverseBitsLen {} <- {} ^ rev({}) (mult {mult})",
+                addrs.result,
+                addrs.base,
+                Addrs(&addrs.exp)
+            ),
+            Instruction::HintBits(HintBitsInstr { output_addrs_mults, input_addr }) => {
+                let outputs = output_addrs_mults.iter().map(|(addr, _)| *addr).collect::<Vec<_>>();
+                write!(f, "HintBits {} <- bits({input_addr})", Addrs(&outputs))
+            }
+            Instruction::FriFold(instr) => {
+                let FriFoldInstr { base_single_addrs, ext_single_addrs, ext_vec_addrs, .. } =
+                    instr.as_ref();
+                write!(
+                    f,
+                    "FriFold ro {}, alpha_pow {} <- x {}, z {}, alpha {}, mat_opening {}, \
+                    ps_at_z {}, alpha_pow {}, ro {}",
+                    Addrs(&ext_vec_addrs.ro_output),
+                    Addrs(&ext_vec_addrs.alpha_pow_output),
+                    base_single_addrs.x,
+                    ext_single_addrs.z,
+                    ext_single_addrs.alpha,
+                    Addrs(&ext_vec_addrs.mat_opening),
+                    Addrs(&ext_vec_addrs.ps_at_z),
+                    Addrs(&ext_vec_addrs.alpha_pow_input),
+                    Addrs(&ext_vec_addrs.ro_input)
+                )
+            }
+            Instruction::BatchFRI(instr) => write!(
+                f,
+                "BatchFRI {} <- p_at_x {}, p_at_z {}, alpha_pow {} (mult {})",
+                instr.ext_single_addrs.acc,
+                Addrs(&instr.base_vec_addrs.p_at_x),
+                Addrs(&instr.ext_vec_addrs.p_at_z),
+                Addrs(&instr.ext_vec_addrs.alpha_pow),
+                instr.acc_mult
+            ),
+            Instruction::Print(PrintInstr { field_elt_type, addr }) => match field_elt_type {
+                FieldEltType::Base => write!(f, "PrintF {addr}"),
+                FieldEltType::Extension => write!(f, "PrintE {addr}"),
+            },
+            Instruction::HintExt2Felts(HintExt2FeltsInstr { output_addrs_mults, input_addr }) => {
+                let outputs = output_addrs_mults.iter().map(|(addr, _)| *addr).collect::<Vec<_>>();
+                write!(f, "HintExt2Felts {} <- {input_addr}", Addrs(&outputs))
+            }
+            Instruction::CommitPublicValues(instr) => {
+                write!(f, "CommitPublicValues {}", Addrs(&instr.pv_addrs.as_array()))
+            }
+            Instruction::Hint(HintInstr { output_addrs_mults }) => {
+                let outputs = output_addrs_mults.iter().map(|(addr, _)| *addr).collect::<Vec<_>>();
+                write!(f, "Hint {}", Addrs(&outputs))
+            }
+        }
+    }
+}
+
+impl<F: Copy> Instruction<F> {
+    /// The addresses read and written by the instruction, in that order.
+    pub fn operands(&self) -> (Vec<Address<F>>, Vec<Address<F>>) {
+        match self {
+            Instruction::BaseAlu(BaseAluInstr { addrs, .. }) => {
+                (vec![addrs.in1, addrs.in2], vec![addrs.out])
+            }
+            Instruction::ExtAlu(ExtAluInstr { addrs, .. }) => {
+                (vec![addrs.in1, addrs.in2], vec![addrs.out])
+            }
+            Instruction::Mem(MemInstr { addrs, kind, .. }) => match kind {
+                MemAccessKind::Read => (vec![addrs.inner], vec![]),
+                MemAccessKind::Write => (vec![], vec![addrs.inner]),
+            },
+            Instruction::Poseidon2(instr) => {
+                (instr.addrs.input.to_vec(), instr.addrs.output.to_vec())
+            }
+            Instruction::Select(SelectInstr { addrs, .. }) => {
+                (vec![addrs.bit, addrs.in1, addrs.in2], vec![addrs.out1, addrs.out2])
+            }
+            Instruction::ExpReverseBitsLen(ExpReverseBitsInstr { addrs, .. }) => {
+                let reads = std::iter::once(addrs.base).chain(addrs.exp.iter().copied()).collect();
+                (reads, vec![addrs.result])
+            }
+            Instruction::HintBits(HintBitsInstr { output_addrs_mults, input_addr }) => {
+                (vec![*input_addr], output_addrs_mults.iter().map(|(addr, _)| *addr).collect())
+            }
+            Instruction::FriFold(instr) => {
+                let FriFoldInstr { base_single_addrs, ext_single_addrs, ext_vec_addrs, .. } =
+                    instr.as_ref();
+                let reads = [base_single_addrs.x, ext_single_addrs.z, ext_single_addrs.alpha]
+                    .into_iter()
+                    .chain(ext_vec_addrs.mat_opening.iter().copied())
+                    .chain(ext_vec_addrs.ps_at_z.iter().copied())
+                    .chain(ext_vec_addrs.alpha_pow_input.iter().copied())
+                    .chain(ext_vec_addrs.ro_input.iter().copied())
+                    .collect();
+                let writes = ext_vec_addrs
+                    .ro_output
+                    .iter()
+                    .chain(&ext_vec_addrs.alpha_pow_output)
+                    .copied()
+                    .collect();
+                (reads, writes)
+            }
+            Instruction::BatchFRI(instr) => {
+                let reads = instr
+                    .base_vec_addrs
+                    .p_at_x
+                    .iter()
+                    .chain(&instr.ext_vec_addrs.p_at_z)
+                    .chain(&instr.ext_vec_addrs.alpha_pow)
+                    .copied()
+                    .collect();
+                (reads, vec![instr.ext_single_addrs.acc])
+            }
+            Instruction::Print(PrintInstr { addr, .. }) => (vec![*addr], vec![]),
+            Instruction::HintExt2Felts(HintExt2FeltsInstr { output_addrs_mults, input_addr }) => {
+                (vec![*input_addr], output_addrs_mults.iter().map(|(addr, _)| *addr).collect())
+            }
+            Instruction::CommitPublicValues(instr) => (instr.pv_addrs.as_array().to_vec(), vec![]),
+            Instruction::Hint(HintInstr { output_addrs_mults }) => {
+                (vec![], output_addrs_mults.iter().map(|(addr, _)| *addr).collect())
+            }
+        }
+    }
+}
+
+impl<F: PrimeField32> RecursionProgram<F> {
+    /// Disassembles the program, one instruction per line.
+    ///
+    /// Instructions are annotated with the DSL call site that emitted them when the program was
+    /// compiled with backtraces, i.e. with `SP1_DEBUG` set.
+    pub fn disassemble(&self) -> String {
+        let mut out = String::new();
+        for (pc, instruction) in self.instructions.iter().enumerate() {
+            out.push_str(&format!("{pc:>6}: {instruction}"));
+            if let Some(site) = self.traces.get(pc).and_then(Option::as_ref).and_then(call_site) {
+                out.push_str(&format!("  @ {site}"));
+            }
+            out.push('\n');
+        }
+        out
+    }
+}
+
+/// The first frame of a backtrace outside of the compiler and the standard library, formatted as
+/// `function (file:line)`.
+pub fn call_site(trace: &Backtrace) -> Option<String> {
+    let mut trace = trace.clone();
+    trace.resolve();
+    trace.frames().iter().flat_map(|frame| frame.symbols()).find_map(|symbol| {
+        let name = format!("{:#}", symbol.name()?);
+        let path = name.trim_start_matches('<');
+        if SKIPPED_FRAMES.iter().any(|prefix| path.starts_with(prefix)) {
+            return None;
+        }
+        let file = symbol.filename()?;
+        Some(format!("{name} ({}:{})", file.display(), symbol.lineno()?))
+    })
+}
+
+impl<F: PrimeField32> Display for FaultKind<F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            FaultKind::UnassignedRead(addr) => write!(f, "read from unassigned address {addr}"),
+            FaultKind::DoubleWrite(addr) => write!(f, "write to assigned address {addr}"),
+            FaultKind::MemoryMismatch { addr, expected, actual } => write!(
+                f,
+                "memory at {addr} holds {}, but {} was expected",
+                Val(actual),
+                Val(expected)
+            ),
+        }
+    }
+}
+
+impl<F: PrimeField32> Display for Fault<F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "fault at pc {}: {}", self.pc, self.kind)?;
+        writeln!(f, "  instruction: {}", self.instruction)?;
+        writeln!(f, "  operands:")?;
+        for (addr, entry) in &self.operands {
+            match entry {
+                Some(entry) => {
+                    writeln!(f, "    {addr} = {} (mult {})", Val(&entry.val), entry.mult)?
+                }
+                None => writeln!(f, "    {addr} unassigned")?,
+            }
+        }
+        match &self.trace {
+            Some((pc, trace)) => {
+                let site = call_site(trace).unwrap_or_else(|| "unknown call site".to_string());
+                write!(f, "nearest pc with backtrace: {pc} @ {site}\n{trace:?}")
+            }
+            None => write!(f, "no backtrace available, compile with SP1_DEBUG=1 to record one"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use p3_baby_bear::DiffusionMatrixBabyBear;
+    use p3_field::AbstractField;
+    use sp1_stark::{baby_bear_poseidon2::BabyBearPoseidon2, StarkGenericConfig};
+
+    use super::*;
+    use crate::runtime::instruction as instr;
+
+    type SC = BabyBearPoseidon2;
+    type F = <SC as StarkGenericConfig>::Val;
+    type EF = <SC as StarkGenericConfig>::Challenge;
+
+    fn run_checked(
+        instructions: Vec<Instruction<F>>,
+    ) -> (Runtime<'static, F, EF, DiffusionMatrixBabyBear>, Box<Fault<F>>) {
+        let program = Arc::new(RecursionProgram { instructions, ..Default::default() });
+        let mut runtime = Runtime::new(program, SC::new().perm);
+        match runtime.run_checked() {
+            Err(RuntimeError::Fault(fault)) => (runtime, fault),
+            result => panic!("expected a fault, got {result:?}"),
+        }
+    }
+
+    #[test]
+    fn test_run_checked_faults() {
+        let three = F::from_canonical_u32(3);
+        let four = F::from_canonical_u32(4);
+        let instructions = vec![
+            instr::mem_single(MemAccessKind::Write, 1, 0, three),
+            instr::mem_single(MemAccessKind::Write, 1, 1, four),
+            instr::base_alu(BaseAluOpcode::MulF, 1, 2, 0, 1),
+            instr::mem_single(MemAccessKind::Read, 1, 2, three + four),
+        ];
+        let program = RecursionProgram { instructions: instructions.clone(), ..Default::default() };
+        let disassembly = program.disassemble();
+        let lines = disassembly.lines().collect::<Vec<_>>();
+        assert_eq!(lines[0], "     0: MemWrite %0 <- 3 (mult 1)");
+        assert_eq!(lines[2], "     2: MulF %2 <- %0, %1 (mult 1)");
+        assert_eq!(lines[3], "     3: MemRead %2 == 7 (mult 1)");
+
+        // The failed assertion is reported without modifying the memory.
+        let (runtime, fault) = run_checked(instructions);
+        assert_eq!(fault.pc, 3);
+        assert!(matches!(fault.kind, FaultKind::MemoryMismatch { .. }));
+        assert!(fault.to_string().starts_with(
+            "fault at pc 3: memory at %2 holds 12, but 7 was expected\n  \
+            instruction: MemRead %2 == 7 (mult 1)\n  operands:\n    %2 = 12 (mult 1)\n"
+        ));
+        assert_eq!(runtime.memory.0[2].mult, F::one());
+
+        let (_, fault) = run_checked(vec![
+            instr::mem_single(MemAccessKind::Write, 1, 0, three),
+            instr::base_alu(BaseAluOpcode::AddF, 1, 0, 0, 0),
+        ]);
+        assert_eq!(fault.pc, 1);
+        assert!(matches!(fault.kind, FaultKind::DoubleWrite(Address(addr)) if addr.is_zero()));
+
+        let (_, fault) = run_checked(vec![instr::base_alu(BaseAluOpcode::AddF, 1, 2, 0, 1)]);
+        assert!(matches!(fault.kind, FaultKind::UnassignedRead(Address(addr)) if addr.is_zero()));
+    }
+}
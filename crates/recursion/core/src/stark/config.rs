@@ -12,7 +12,7 @@ use p3_merkle_tree::FieldMerkleTreeMmcs;
 use p3_poseidon2::{Poseidon2, Poseidon2ExternalMatrixGeneral};
 use p3_symmetric::{Hash, MultiField32PaddingFreeSponge, TruncatedPermutation};
 use serde::{Deserialize, Serialize};
-use sp1_stark::{Com, StarkGenericConfig, ZeroCommitment};
+use sp1_stark::{Com, FriParameters, SecurityPreset, StarkGenericConfig, ZeroCommitment};
 
 use super::{poseidon2::bn254_poseidon2_rc3, sp1_dev_mode};
 
@@ -72,23 +72,25 @@ pub fn outer_perm() -> OuterPerm {
 
 /// The FRI config for outer recursion.
 pub fn outer_fri_config() -> FriConfig<OuterChallengeMmcs> {
-    let perm = outer_perm();
-    let hash = OuterHash::new(perm.clone()).unwrap();
-    let compress = OuterCompress::new(perm.clone());
-    let challenge_mmcs = OuterChallengeMmcs::new(OuterValMmcs::new(hash, compress));
-    let num_queries = if sp1_dev_mode() {
-        1
-    } else {
-        match std::env::var("FRI_QUERIES") {
-            Ok(value) => value.parse().unwrap(),
-            Err(_) => 25,
-        }
-    };
-    FriConfig { log_blowup: 4, num_queries, proof_of_work_bits: 16, mmcs: challenge_mmcs }
+    outer_fri_config_with_parameters(SecurityPreset::Standard.wrap())
 }
 
 /// The FRI config for outer recursion.
 pub fn outer_fri_config_with_blowup(log_blowup: usize) -> FriConfig<OuterChallengeMmcs> {
+    outer_fri_config_with_parameters(FriParameters {
+        log_blowup,
+        num_queries: 100 / log_blowup,
+        proof_of_work_bits: 16,
+    })
+}
+
+/// The FRI config for outer recursion with the given parameters.
+///
+/// The number of queries can be overridden with the `FRI_QUERIES` environment variable, and is
+/// set to one in development mode.
+pub fn outer_fri_config_with_parameters(
+    parameters: FriParameters,
+) -> FriConfig<OuterChallengeMmcs> {
     let perm = outer_perm();
     let hash = OuterHash::new(perm.clone()).unwrap();
     let compress = OuterCompress::new(perm.clone());
@@ -98,10 +100,15 @@ pub fn outer_fri_config_with_blowup(log_blowup: usize) -> FriConfig<OuterChallen
     } else {
         match std::env::var("FRI_QUERIES") {
             Ok(value) => value.parse().unwrap(),
-            Err(_) => 100 / log_blowup,
+            Err(_) => parameters.num_queries,
         }
     };
-    FriConfig { log_blowup, num_queries, proof_of_work_bits: 16, mmcs: challenge_mmcs }
+    FriConfig {
+        log_blowup: parameters.log_blowup,
+        num_queries,
+        proof_of_work_bits: parameters.proof_of_work_bits,
+        mmcs: challenge_mmcs,
+    }
 }
 
 #[derive(Deserialize)]
@@ -109,11 +116,12 @@ pub fn outer_fri_config_with_blowup(log_blowup: usize) -> FriConfig<OuterChallen
 pub struct BabyBearPoseidon2Outer {
     pub perm: OuterPerm,
     pub pcs: OuterPcs,
+    fri_parameters: FriParameters,
 }
 
 impl Clone for BabyBearPoseidon2Outer {
     fn clone(&self) -> Self {
-        Self::new()
+        Self::with_fri_parameters(self.fri_parameters)
     }
 }
 
@@ -134,24 +142,32 @@ impl From<std::marker::PhantomData<BabyBearPoseidon2Outer>> for BabyBearPoseidon
 
 impl BabyBearPoseidon2Outer {
     pub fn new() -> Self {
-        let perm = outer_perm();
-        let hash = OuterHash::new(perm.clone()).unwrap();
-        let compress = OuterCompress::new(perm.clone());
-        let val_mmcs = OuterValMmcs::new(hash, compress);
-        let dft = OuterDft {};
-        let fri_config = outer_fri_config();
-        let pcs = OuterPcs::new(27, dft, val_mmcs, fri_config);
-        Self { pcs, perm }
+        Self::with_fri_parameters(SecurityPreset::Standard.wrap())
     }
+
     pub fn new_with_log_blowup(log_blowup: usize) -> Self {
+        Self::with_fri_parameters(FriParameters {
+            log_blowup,
+            num_queries: 100 / log_blowup,
+            proof_of_work_bits: 16,
+        })
+    }
+
+    /// A config with the given FRI parameters.
+    pub fn with_fri_parameters(fri_parameters: FriParameters) -> Self {
         let perm = outer_perm();
         let hash = OuterHash::new(perm.clone()).unwrap();
         let compress = OuterCompress::new(perm.clone());
         let val_mmcs = OuterValMmcs::new(hash, compress);
         let dft = OuterDft {};
-        let fri_config = outer_fri_config_with_blowup(log_blowup);
+        let fri_config = outer_fri_config_with_parameters(fri_parameters);
         let pcs = OuterPcs::new(27, dft, val_mmcs, fri_config);
-        Self { pcs, perm }
+        Self { pcs, perm, fri_parameters }
+    }
+
+    /// The FRI parameters of the config.
+    pub fn fri_parameters(&self) -> FriParameters {
+        self.fri_parameters
     }
 }
 
@@ -3,8 +3,10 @@
 
 use sp1_recursion_gnark_ffi::{
     ffi::{
-        build_groth16_bn254, build_plonk_bn254, test_groth16_bn254, test_plonk_bn254,
-        verify_groth16_bn254, verify_plonk_bn254,
+        build_groth16_bn254, build_plonk_bn254, contribute_phase1_groth16_bn254,
+        contribute_phase2_groth16_bn254, finalize_groth16_bn254, init_phase1_groth16_bn254,
+        init_phase2_groth16_bn254, test_groth16_bn254, test_plonk_bn254, verify_groth16_bn254,
+        verify_phase1_groth16_bn254, verify_phase2_groth16_bn254, verify_plonk_bn254,
     },
     ProofBn254,
 };
@@ -27,6 +29,13 @@ enum Command {
     Prove(ProveArgs),
     Verify(VerifyArgs),
     Test(TestArgs),
+    InitPhase1(InitPhase1Args),
+    ContributePhase1(ContributeArgs),
+    VerifyPhase1(VerifyPhase1Args),
+    InitPhase2(InitPhase2Args),
+    ContributePhase2(ContributeArgs),
+    VerifyPhase2(VerifyPhase2Args),
+    Finalize(FinalizeArgs),
 }
 
 #[derive(Debug, Args)]
@@ -64,6 +73,46 @@ struct TestArgs {
     system: String,
 }
 
+#[derive(Debug, Args)]
+struct InitPhase1Args {
+    power: u32,
+    output_path: String,
+}
+
+#[derive(Debug, Args)]
+struct ContributeArgs {
+    input_path: String,
+    output_path: String,
+}
+
+#[derive(Debug, Args)]
+struct VerifyPhase1Args {
+    output_path: String,
+    contribution_paths: Vec<String>,
+}
+
+#[derive(Debug, Args)]
+struct InitPhase2Args {
+    data_dir: String,
+    phase1_path: String,
+    output_path: String,
+}
+
+#[derive(Debug, Args)]
+struct VerifyPhase2Args {
+    data_dir: String,
+    phase1_path: String,
+    output_path: String,
+    contribution_paths: Vec<String>,
+}
+
+#[derive(Debug, Args)]
+struct FinalizeArgs {
+    data_dir: String,
+    phase1_path: String,
+    contribution_paths: Vec<String>,
+}
+
 fn run_build(args: BuildArgs) {
     match args.system.as_str() {
         "plonk" => build_plonk_bn254(&args.data_dir),
@@ -124,6 +173,41 @@ fn run_test(args: TestArgs) {
     }
 }
 
+fn write_verification_result(result: Result<(), String>, output_path: &str) {
+    let output = match result {
+        Ok(_) => "OK".to_string(),
+        Err(e) => e,
+    };
+    let mut file = File::create(output_path).unwrap();
+    file.write_all(output.as_bytes()).unwrap();
+}
+
+fn run_verify_phase1(args: VerifyPhase1Args) {
+    let paths = args.contribution_paths.iter().map(String::as_str).collect::<Vec<_>>();
+    write_verification_result(verify_phase1_groth16_bn254(&paths), &args.output_path);
+}
+
+fn run_verify_phase2(args: VerifyPhase2Args) {
+    let paths = args.contribution_paths.iter().map(String::as_str).collect::<Vec<_>>();
+    write_verification_result(
+        verify_phase2_groth16_bn254(&args.data_dir, &args.phase1_path, &paths),
+        &args.output_path,
+    );
+}
+
+fn run_finalize(args: FinalizeArgs) {
+    let paths = args.contribution_paths.iter().map(String::as_str).collect::<Vec<_>>();
+    exit_on_error(finalize_groth16_bn254(&args.data_dir, &args.phase1_path, &paths));
+}
+
+/// Exits with the error of a ceremony step, which fails the docker command.
+fn exit_on_error(result: Result<(), String>) {
+    if let Err(e) = result {
+        eprintln!("{e}");
+        std::process::exit(1);
+    }
+}
+
 fn main() {
     let cli = Cli::parse();
 
@@ -132,5 +216,22 @@ fn main() {
         Command::Prove(args) => run_prove(args),
         Command::Verify(args) => run_verify(args),
         Command::Test(args) => run_test(args),
+        Command::InitPhase1(args) => {
+            exit_on_error(init_phase1_groth16_bn254(args.power, &args.output_path))
+        }
+        Command::ContributePhase1(args) => {
+            exit_on_error(contribute_phase1_groth16_bn254(&args.input_path, &args.output_path))
+        }
+        Command::VerifyPhase1(args) => run_verify_phase1(args),
+        Command::InitPhase2(args) => exit_on_error(init_phase2_groth16_bn254(
+            &args.data_dir,
+            &args.phase1_path,
+            &args.output_path,
+        )),
+        Command::ContributePhase2(args) => {
+            exit_on_error(contribute_phase2_groth16_bn254(&args.input_path, &args.output_path))
+        }
+        Command::VerifyPhase2(args) => run_verify_phase2(args),
+        Command::Finalize(args) => run_finalize(args),
     }
 }
@@ -34,6 +34,7 @@ import (
 	"github.com/succinctlabs/sp1-recursion-gnark/sp1"
 	"github.com/succinctlabs/sp1-recursion-gnark/sp1/babybear"
 	"github.com/succinctlabs/sp1-recursion-gnark/sp1/poseidon2"
+	"github.com/succinctlabs/sp1-recursion-gnark/sp1/trusted_setup"
 )
 
 func main() {}
@@ -175,6 +176,62 @@ func TestGroth16Bn254(witnessJson *C.char, constraintsJson *C.char) *C.char {
 	return nil
 }
 
+//export InitPhase1Groth16Bn254
+func InitPhase1Groth16Bn254(power C.int, outputPath *C.char) *C.char {
+	return errorToCString(trusted_setup.InitPhase1(int(power), C.GoString(outputPath)))
+}
+
+//export ContributePhase1Groth16Bn254
+func ContributePhase1Groth16Bn254(inputPath *C.char, outputPath *C.char) *C.char {
+	return errorToCString(trusted_setup.ContributePhase1(C.GoString(inputPath), C.GoString(outputPath)))
+}
+
+//export VerifyPhase1Groth16Bn254
+func VerifyPhase1Groth16Bn254(contributionPaths *C.char) *C.char {
+	var paths []string
+	if err := json.Unmarshal([]byte(C.GoString(contributionPaths)), &paths); err != nil {
+		return C.CString(err.Error())
+	}
+	return errorToCString(trusted_setup.VerifyPhase1(paths))
+}
+
+//export InitPhase2Groth16Bn254
+func InitPhase2Groth16Bn254(dataDir *C.char, phase1Path *C.char, outputPath *C.char) *C.char {
+	return errorToCString(sp1.InitPhase2Groth16(C.GoString(dataDir), C.GoString(phase1Path), C.GoString(outputPath)))
+}
+
+//export ContributePhase2Groth16Bn254
+func ContributePhase2Groth16Bn254(inputPath *C.char, outputPath *C.char) *C.char {
+	return errorToCString(trusted_setup.ContributePhase2(C.GoString(inputPath), C.GoString(outputPath)))
+}
+
+//export VerifyPhase2Groth16Bn254
+func VerifyPhase2Groth16Bn254(dataDir *C.char, phase1Path *C.char, contributionPaths *C.char) *C.char {
+	var paths []string
+	if err := json.Unmarshal([]byte(C.GoString(contributionPaths)), &paths); err != nil {
+		return C.CString(err.Error())
+	}
+	return errorToCString(sp1.VerifyPhase2Groth16(C.GoString(dataDir), C.GoString(phase1Path), paths))
+}
+
+//export FinalizeGroth16Bn254
+func FinalizeGroth16Bn254(dataDir *C.char, phase1Path *C.char, contributionPaths *C.char) *C.char {
+	var paths []string
+	if err := json.Unmarshal([]byte(C.GoString(contributionPaths)), &paths); err != nil {
+		return C.CString(err.Error())
+	}
+	return errorToCString(sp1.FinalizeGroth16(C.GoString(dataDir), C.GoString(phase1Path), paths))
+}
+
+// errorToCString returns the message of an error as a C string to be freed by the caller, or nil
+// if there is no error.
+func errorToCString(err error) *C.char {
+	if err != nil {
+		return C.CString(err.Error())
+	}
+	return nil
+}
+
 func TestMain() error {
 	// Get the file name from an environment variable.
 	fileName := os.Getenv("WITNESS_JSON")
@@ -11,6 +11,7 @@ import (
 	"github.com/consensys/gnark-crypto/kzg"
 	groth16 "github.com/consensys/gnark/backend/groth16"
 	"github.com/consensys/gnark/backend/plonk"
+	"github.com/consensys/gnark/constraint"
 	"github.com/consensys/gnark/frontend"
 	"github.com/consensys/gnark/frontend/cs/r1cs"
 	"github.com/consensys/gnark/frontend/cs/scs"
@@ -253,47 +254,55 @@ func BuildGroth16(dataDir string) {
 		panic(err)
 	}
 
+	if err := writeGroth16Artifacts(dataDir, r1cs, pk, vk); err != nil {
+		panic(err)
+	}
+}
+
+// writeGroth16Artifacts writes the solidity verifier, the R1CS and the keys to the build directory.
+func writeGroth16Artifacts(dataDir string, r1cs constraint.ConstraintSystem, pk groth16.ProvingKey, vk groth16.VerifyingKey) error {
 	// Create the build directory.
-	os.MkdirAll(dataDir, 0755)
+	if err := os.MkdirAll(dataDir, 0755); err != nil {
+		return err
+	}
 
 	// Write the solidity verifier.
 	solidityVerifierFile, err := os.Create(dataDir + "/" + groth16VerifierContractPath)
 	if err != nil {
-		panic(err)
+		return err
 	}
-	vk.ExportSolidity(solidityVerifierFile)
 	defer solidityVerifierFile.Close()
+	if err := vk.ExportSolidity(solidityVerifierFile); err != nil {
+		return err
+	}
 
 	// Write the R1CS.
 	r1csFile, err := os.Create(dataDir + "/" + groth16CircuitPath)
 	if err != nil {
-		panic(err)
+		return err
 	}
 	defer r1csFile.Close()
 	_, err = r1cs.WriteTo(r1csFile)
 	if err != nil {
-		panic(err)
+		return err
 	}
 
 	// Write the verifier key.
 	vkFile, err := os.Create(dataDir + "/" + groth16VkPath)
 	if err != nil {
-		panic(err)
+		return err
 	}
 	defer vkFile.Close()
 	_, err = vk.WriteTo(vkFile)
 	if err != nil {
-		panic(err)
+		return err
 	}
 
 	// Write the proving key.
 	pkFile, err := os.Create(dataDir + "/" + groth16PkPath)
 	if err != nil {
-		panic(err)
+		return err
 	}
 	defer pkFile.Close()
-	err = pk.WriteDump(pkFile)
-	if err != nil {
-		panic(err)
-	}
+	return pk.WriteDump(pkFile)
 }
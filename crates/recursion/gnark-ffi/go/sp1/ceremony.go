@@ -0,0 +1,90 @@
+package sp1
+
+import (
+	"encoding/json"
+	"fmt"
+	"os"
+
+	"github.com/consensys/gnark-crypto/ecc"
+	groth16 "github.com/consensys/gnark/backend/groth16"
+	cs_bn254 "github.com/consensys/gnark/constraint/bn254"
+	"github.com/consensys/gnark/frontend"
+	"github.com/consensys/gnark/frontend/cs/r1cs"
+	"github.com/succinctlabs/sp1-recursion-gnark/sp1/trusted_setup"
+)
+
+// InitPhase2Groth16 writes the initial phase-2 parameters of the Groth16 circuit in the data
+// directory.
+func InitPhase2Groth16(dataDir string, phase1Path string, outputPath string) error {
+	r1cs, _, err := compileGroth16(dataDir)
+	if err != nil {
+		return err
+	}
+	return trusted_setup.InitPhase2(r1cs, phase1Path, outputPath)
+}
+
+// VerifyPhase2Groth16 checks the phase-2 contributions to the Groth16 circuit in the data
+// directory.
+func VerifyPhase2Groth16(dataDir string, phase1Path string, contributionPaths []string) error {
+	r1cs, _, err := compileGroth16(dataDir)
+	if err != nil {
+		return err
+	}
+	return trusted_setup.VerifyPhase2(r1cs, phase1Path, contributionPaths)
+}
+
+// FinalizeGroth16 extracts the keys of the Groth16 circuit in the data directory from the
+// ceremony, and writes the same artifacts as BuildGroth16.
+func FinalizeGroth16(dataDir string, phase1Path string, contributionPaths []string) error {
+	r1cs, witnessInput, err := compileGroth16(dataDir)
+	if err != nil {
+		return err
+	}
+	pk, vk, err := trusted_setup.ExtractKeys(r1cs, phase1Path, contributionPaths)
+	if err != nil {
+		return err
+	}
+
+	// Check the keys against the template witness.
+	assignment := NewCircuit(witnessInput)
+	witness, err := frontend.NewWitness(&assignment, ecc.BN254.ScalarField())
+	if err != nil {
+		return err
+	}
+	proof, err := groth16.Prove(r1cs, pk, witness)
+	if err != nil {
+		return fmt.Errorf("failed to prove the template witness with the ceremony keys: %w", err)
+	}
+	publicWitness, err := witness.Public()
+	if err != nil {
+		return err
+	}
+	if err := groth16.Verify(proof, vk, publicWitness); err != nil {
+		return fmt.Errorf("failed to verify the template proof with the ceremony keys: %w", err)
+	}
+
+	return writeGroth16Artifacts(dataDir, r1cs, pk, vk)
+}
+
+// compileGroth16 compiles the Groth16 circuit from the constraints and the template witness in
+// the data directory.
+func compileGroth16(dataDir string) (*cs_bn254.R1CS, WitnessInput, error) {
+	os.Setenv("CONSTRAINTS_JSON", dataDir+"/"+constraintsJsonFile)
+	os.Setenv("GROTH16", "1")
+
+	var witnessInput WitnessInput
+	data, err := os.ReadFile(dataDir + "/" + groth16WitnessPath)
+	if err != nil {
+		return nil, witnessInput, err
+	}
+	if err := json.Unmarshal(data, &witnessInput); err != nil {
+		return nil, witnessInput, fmt.Errorf("failed to read the template witness: %w", err)
+	}
+
+	circuit := NewCircuit(witnessInput)
+	ccs, err := frontend.Compile(ecc.BN254.ScalarField(), r1cs.NewBuilder, &circuit)
+	if err != nil {
+		return nil, witnessInput, err
+	}
+	return ccs.(*cs_bn254.R1CS), witnessInput, nil
+}
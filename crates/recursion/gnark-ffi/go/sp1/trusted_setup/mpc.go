@@ -0,0 +1,179 @@
+package trusted_setup
+
+import (
+	"bufio"
+	"errors"
+	"fmt"
+	"io"
+	"os"
+
+	curve "github.com/consensys/gnark-crypto/ecc/bn254"
+	groth16_bn254 "github.com/consensys/gnark/backend/groth16/bn254"
+	"github.com/consensys/gnark/backend/groth16/bn254/mpcsetup"
+	"github.com/consensys/gnark/constraint"
+	cs_bn254 "github.com/consensys/gnark/constraint/bn254"
+)
+
+// InitPhase1 writes the initial phase-1 parameters for circuits of up to 2^power constraints.
+func InitPhase1(power int, outputPath string) error {
+	phase1 := mpcsetup.InitPhase1(power)
+	return writeParameters(outputPath, &phase1)
+}
+
+// ContributePhase1 adds a random contribution to the phase-1 parameters.
+func ContributePhase1(inputPath string, outputPath string) error {
+	var phase1 mpcsetup.Phase1
+	if err := readParameters(inputPath, &phase1); err != nil {
+		return err
+	}
+	phase1.Contribute()
+	return writeParameters(outputPath, &phase1)
+}
+
+// VerifyPhase1 checks that each phase-1 file is a valid contribution to the previous one.
+func VerifyPhase1(contributionPaths []string) error {
+	if len(contributionPaths) < 2 {
+		return fmt.Errorf("expected the initial parameters and at least one contribution, got %d files", len(contributionPaths))
+	}
+	contributions := make([]*mpcsetup.Phase1, len(contributionPaths))
+	for i, path := range contributionPaths {
+		contributions[i] = new(mpcsetup.Phase1)
+		if err := readParameters(path, contributions[i]); err != nil {
+			return err
+		}
+	}
+	return mpcsetup.VerifyPhase1(contributions[0], contributions[1], contributions[2:]...)
+}
+
+// InitPhase2 writes the initial phase-2 parameters of the circuit.
+func InitPhase2(r1cs *cs_bn254.R1CS, phase1Path string, outputPath string) error {
+	if err := checkNoCommitments(r1cs); err != nil {
+		return err
+	}
+	var phase1 mpcsetup.Phase1
+	if err := readParameters(phase1Path, &phase1); err != nil {
+		return err
+	}
+	phase2, _ := mpcsetup.InitPhase2(r1cs, &phase1)
+	return writeParameters(outputPath, &phase2)
+}
+
+// ContributePhase2 adds a random contribution to the phase-2 parameters.
+func ContributePhase2(inputPath string, outputPath string) error {
+	var phase2 mpcsetup.Phase2
+	if err := readParameters(inputPath, &phase2); err != nil {
+		return err
+	}
+	phase2.Contribute()
+	return writeParameters(outputPath, &phase2)
+}
+
+// VerifyPhase2 checks that the initial phase-2 parameters were derived from the circuit and the
+// phase-1 parameters, and that each following file is a valid contribution to the previous one.
+func VerifyPhase2(r1cs *cs_bn254.R1CS, phase1Path string, contributionPaths []string) error {
+	var phase1 mpcsetup.Phase1
+	if err := readParameters(phase1Path, &phase1); err != nil {
+		return err
+	}
+	_, _, err := loadPhase2(r1cs, &phase1, contributionPaths)
+	return err
+}
+
+// ExtractKeys verifies the phase-2 contributions and builds the Groth16 keys of the circuit.
+//
+// Every point of the keys comes from the contributions, so the circuit must not use commitments,
+// whose Pedersen keys mpcsetup has no phase for.
+func ExtractKeys(r1cs *cs_bn254.R1CS, phase1Path string, contributionPaths []string) (*groth16_bn254.ProvingKey, *groth16_bn254.VerifyingKey, error) {
+	if err := checkNoCommitments(r1cs); err != nil {
+		return nil, nil, err
+	}
+	var phase1 mpcsetup.Phase1
+	if err := readParameters(phase1Path, &phase1); err != nil {
+		return nil, nil, err
+	}
+	last, evals, err := loadPhase2(r1cs, &phase1, contributionPaths)
+	if err != nil {
+		return nil, nil, err
+	}
+
+	pk, vk := mpcsetup.ExtractKeys(&phase1, last, &evals, r1cs.GetNbConstraints())
+	return &pk, &vk, nil
+}
+
+// checkNoCommitments returns an error if the circuit uses commitments. The Groth16 wrap circuit
+// does not, as its range checks are only committed to outside of Groth16.
+func checkNoCommitments(r1cs *cs_bn254.R1CS) error {
+	if commitments, _ := r1cs.CommitmentInfo.(constraint.Groth16Commitments); len(commitments) != 0 {
+		return fmt.Errorf("the circuit has %d commitments, which the ceremony does not support", len(commitments))
+	}
+	return nil
+}
+
+// loadPhase2 reads and verifies the phase-2 contributions, returning the last parameters along
+// with the evaluations of the circuit.
+func loadPhase2(r1cs *cs_bn254.R1CS, phase1 *mpcsetup.Phase1, contributionPaths []string) (*mpcsetup.Phase2, mpcsetup.Phase2Evaluations, error) {
+	var evals mpcsetup.Phase2Evaluations
+	if len(contributionPaths) < 2 {
+		return nil, evals, fmt.Errorf("expected the initial parameters and at least one contribution, got %d files", len(contributionPaths))
+	}
+	contributions := make([]*mpcsetup.Phase2, len(contributionPaths))
+	for i, path := range contributionPaths {
+		contributions[i] = new(mpcsetup.Phase2)
+		if err := readParameters(path, contributions[i]); err != nil {
+			return nil, evals, err
+		}
+	}
+
+	// The initial parameters carry no contribution, so they are recomputed to check that the
+	// contributions apply to this circuit and these phase-1 parameters.
+	expected, evals := mpcsetup.InitPhase2(r1cs, phase1)
+	initial := contributions[0]
+	if !initial.Parameters.G1.Delta.Equal(&expected.Parameters.G1.Delta) ||
+		!initial.Parameters.G2.Delta.Equal(&expected.Parameters.G2.Delta) ||
+		!equalG1(initial.Parameters.G1.L, expected.Parameters.G1.L) ||
+		!equalG1(initial.Parameters.G1.Z, expected.Parameters.G1.Z) {
+		return nil, evals, errors.New("the initial phase-2 parameters do not match the circuit and the phase-1 parameters")
+	}
+
+	if err := mpcsetup.VerifyPhase2(contributions[0], contributions[1], contributions[2:]...); err != nil {
+		return nil, evals, err
+	}
+	return contributions[len(contributions)-1], evals, nil
+}
+
+func equalG1(a []curve.G1Affine, b []curve.G1Affine) bool {
+	if len(a) != len(b) {
+		return false
+	}
+	for i := range a {
+		if !a[i].Equal(&b[i]) {
+			return false
+		}
+	}
+	return true
+}
+
+func readParameters(path string, parameters io.ReaderFrom) error {
+	file, err := os.Open(path)
+	if err != nil {
+		return err
+	}
+	defer file.Close()
+	if _, err := parameters.ReadFrom(bufio.NewReaderSize(file, 1024*1024)); err != nil {
+		return fmt.Errorf("failed to read %s: %w", path, err)
+	}
+	return nil
+}
+
+func writeParameters(path string, parameters io.WriterTo) error {
+	file, err := os.Create(path)
+	if err != nil {
+		return err
+	}
+	defer file.Close()
+	writer := bufio.NewWriterSize(file, 1024*1024)
+	if _, err := parameters.WriteTo(writer); err != nil {
+		return fmt.Errorf("failed to write %s: %w", path, err)
+	}
+	return writer.Flush()
+}
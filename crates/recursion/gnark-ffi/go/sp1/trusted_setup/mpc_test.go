@@ -0,0 +1,134 @@
+package trusted_setup
+
+import (
+	"fmt"
+	"path/filepath"
+	"testing"
+
+	"github.com/consensys/gnark-crypto/ecc"
+	groth16 "github.com/consensys/gnark/backend/groth16"
+	cs_bn254 "github.com/consensys/gnark/constraint/bn254"
+	"github.com/consensys/gnark/frontend"
+	"github.com/consensys/gnark/frontend/cs/r1cs"
+)
+
+type squareCircuit struct {
+	X frontend.Variable
+	Y frontend.Variable `gnark:",public"`
+}
+
+func (circuit *squareCircuit) Define(api frontend.API) error {
+	api.AssertIsEqual(api.Mul(circuit.X, circuit.X), circuit.Y)
+	return nil
+}
+
+type committedSquareCircuit struct {
+	X frontend.Variable
+	Y frontend.Variable `gnark:",public"`
+}
+
+func (circuit *committedSquareCircuit) Define(api frontend.API) error {
+	commitment, err := api.(frontend.Committer).Commit(circuit.X)
+	if err != nil {
+		return err
+	}
+	api.AssertIsDifferent(commitment, 0)
+	api.AssertIsEqual(api.Mul(circuit.X, circuit.X), circuit.Y)
+	return nil
+}
+
+func compile(t *testing.T, circuit frontend.Circuit) *cs_bn254.R1CS {
+	ccs, err := frontend.Compile(ecc.BN254.ScalarField(), r1cs.NewBuilder, circuit)
+	if err != nil {
+		t.Fatal(err)
+	}
+	return ccs.(*cs_bn254.R1CS)
+}
+
+func TestCeremony(t *testing.T) {
+	dir := t.TempDir()
+	path := func(name string, i int) string {
+		return filepath.Join(dir, fmt.Sprintf("%s_%d", name, i))
+	}
+	ccs := compile(t, &squareCircuit{})
+
+	// Phase 1, with two contributions.
+	if err := InitPhase1(4, path("phase1", 0)); err != nil {
+		t.Fatal(err)
+	}
+	phase1 := []string{path("phase1", 0)}
+	for i := 1; i <= 2; i++ {
+		if err := ContributePhase1(path("phase1", i-1), path("phase1", i)); err != nil {
+			t.Fatal(err)
+		}
+		phase1 = append(phase1, path("phase1", i))
+	}
+	if err := VerifyPhase1(phase1); err != nil {
+		t.Fatal(err)
+	}
+	if err := VerifyPhase1(phase1[:1]); err == nil {
+		t.Fatal("phase 1 verified without contributions")
+	}
+	if err := VerifyPhase1([]string{phase1[0], phase1[2]}); err == nil {
+		t.Fatal("phase 1 verified with a missing contribution")
+	}
+	finalPhase1 := phase1[len(phase1)-1]
+
+	// Phase 2, with two contributions.
+	if err := InitPhase2(ccs, finalPhase1, path("phase2", 0)); err != nil {
+		t.Fatal(err)
+	}
+	phase2 := []string{path("phase2", 0)}
+	for i := 1; i <= 2; i++ {
+		if err := ContributePhase2(path("phase2", i-1), path("phase2", i)); err != nil {
+			t.Fatal(err)
+		}
+		phase2 = append(phase2, path("phase2", i))
+	}
+	if err := VerifyPhase2(ccs, finalPhase1, phase2); err != nil {
+		t.Fatal(err)
+	}
+	if err := VerifyPhase2(ccs, phase1[1], phase2); err == nil {
+		t.Fatal("phase 2 verified against other phase-1 parameters")
+	}
+
+	// The extracted keys prove and verify the circuit.
+	pk, vk, err := ExtractKeys(ccs, finalPhase1, phase2)
+	if err != nil {
+		t.Fatal(err)
+	}
+	witness, err := frontend.NewWitness(&squareCircuit{X: 3, Y: 9}, ecc.BN254.ScalarField())
+	if err != nil {
+		t.Fatal(err)
+	}
+	proof, err := groth16.Prove(ccs, pk, witness)
+	if err != nil {
+		t.Fatal(err)
+	}
+	publicWitness, err := witness.Public()
+	if err != nil {
+		t.Fatal(err)
+	}
+	if err := groth16.Verify(proof, vk, publicWitness); err != nil {
+		t.Fatal(err)
+	}
+	wrongWitness, err := frontend.NewWitness(&squareCircuit{Y: 10}, ecc.BN254.ScalarField(), frontend.PublicOnly())
+	if err != nil {
+		t.Fatal(err)
+	}
+	if err := groth16.Verify(proof, vk, wrongWitness); err == nil {
+		t.Fatal("the proof verified with other public inputs")
+	}
+}
+
+func TestCeremonyRejectsCommitments(t *testing.T) {
+	dir := t.TempDir()
+	phase1 := filepath.Join(dir, "phase1")
+	if err := InitPhase1(4, phase1); err != nil {
+		t.Fatal(err)
+	}
+	ccs := compile(t, &committedSquareCircuit{})
+	if err := InitPhase2(ccs, phase1, filepath.Join(dir, "phase2")); err == nil {
+		t.Fatal("phase 2 was initialized for a circuit with commitments")
+	}
+}
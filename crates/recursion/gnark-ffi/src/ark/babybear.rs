@@ -0,0 +1,332 @@
+use ark_bn254::Fr;
+use ark_ff::{BigInteger, PrimeField};
+use ark_r1cs_std::{
+    alloc::AllocVar,
+    boolean::Boolean,
+    eq::EqGadget,
+    fields::{fp::FpVar, FieldVar},
+    select::CondSelectGadget,
+    R1CSVar,
+};
+use ark_relations::r1cs::{ConstraintSystemRef, SynthesisError};
+use num_bigint::BigUint;
+use p3_baby_bear::BabyBear;
+use p3_field::{
+    extension::BinomialExtensionField, AbstractExtensionField, AbstractField, Field, PrimeField32,
+};
+
+/// The BabyBear modulus.
+pub const MODULUS: u64 = 2013265921;
+
+/// A BabyBear element emulated in BN254, along with an upper bound of its unreduced value.
+#[derive(Clone)]
+pub struct Felt {
+    pub value: FpVar<Fr>,
+    pub upper_bound: BigUint,
+}
+
+/// A BabyBear quartic extension element emulated in BN254.
+pub type Ext = [Felt; 4];
+
+impl Felt {
+    /// A felt whose value is range checked to 32 bits.
+    pub fn new(value: FpVar<Fr>) -> Self {
+        Self { value, upper_bound: BigUint::from(1u64 << 32) }
+    }
+
+    /// A constant felt.
+    pub fn constant(value: u64) -> Self {
+        Self { value: FpVar::constant(Fr::from(value)), upper_bound: BigUint::from(value) }
+    }
+}
+
+/// Decomposes a variable into `num_bits` little-endian bits, which constrains it to be smaller than
+/// `2^num_bits`.
+pub fn to_bits(
+    cs: &ConstraintSystemRef<Fr>,
+    value: &FpVar<Fr>,
+    num_bits: usize,
+) -> Result<Vec<Boolean<Fr>>, SynthesisError> {
+    if let FpVar::Constant(constant) = value {
+        let bits = constant.into_bigint();
+        assert!(bits.num_bits() as usize <= num_bits, "constant does not fit in {num_bits} bits");
+        return Ok((0..num_bits).map(|i| Boolean::constant(bits.get_bit(i))).collect());
+    }
+    let bits = (0..num_bits)
+        .map(|i| Boolean::new_witness(cs.clone(), || Ok(value.value()?.into_bigint().get_bit(i))))
+        .collect::<Result<Vec<_>, _>>()?;
+    Boolean::le_bits_to_fp_var(&bits)?.enforce_equal(value)?;
+    Ok(bits)
+}
+
+/// Allocates a witness computed from the values of other variables.
+pub fn hint(
+    cs: &ConstraintSystemRef<Fr>,
+    f: impl FnOnce() -> Result<BigUint, SynthesisError>,
+) -> Result<FpVar<Fr>, SynthesisError> {
+    FpVar::new_witness(cs.clone(), || f().map(Fr::from))
+}
+
+fn biguint(value: &FpVar<Fr>) -> Result<BigUint, SynthesisError> {
+    Ok(value.value()?.into())
+}
+
+fn babybear(value: &FpVar<Fr>) -> Result<BabyBear, SynthesisError> {
+    let value = biguint(value)? % MODULUS;
+    Ok(BabyBear::from_canonical_u64(value.try_into().unwrap()))
+}
+
+/// Emulates BabyBear arithmetic over BN254, mirroring the gnark `babybear` chip.
+pub struct BabyBearChip {
+    cs: ConstraintSystemRef<Fr>,
+}
+
+impl BabyBearChip {
+    pub fn new(cs: ConstraintSystemRef<Fr>) -> Self {
+        Self { cs }
+    }
+
+    /// Range checks a felt allocated from the witness.
+    pub fn range_check(&self, a: &Felt) -> Result<(), SynthesisError> {
+        to_bits(&self.cs, &a.value, 31).map(drop)
+    }
+
+    pub fn add_f(&self, a: &Felt, b: &Felt, reduce: bool) -> Result<Felt, SynthesisError> {
+        let result =
+            Felt { value: &a.value + &b.value, upper_bound: &a.upper_bound + &b.upper_bound };
+        if reduce {
+            self.reduce_fast(result)
+        } else {
+            Ok(result)
+        }
+    }
+
+    pub fn sub_f(&self, a: &Felt, b: &Felt) -> Result<Felt, SynthesisError> {
+        let neg_b = self.neg_f(b)?;
+        self.add_f(a, &neg_b, true)
+    }
+
+    pub fn mul_f(&self, a: &Felt, b: &Felt, reduce: bool) -> Result<Felt, SynthesisError> {
+        let result =
+            Felt { value: &a.value * &b.value, upper_bound: &a.upper_bound * &b.upper_bound };
+        if reduce {
+            self.reduce_fast(result)
+        } else {
+            Ok(result)
+        }
+    }
+
+    pub fn mul_f_const(&self, a: &Felt, b: u64, reduce: bool) -> Result<Felt, SynthesisError> {
+        let result = Felt { value: &a.value * Fr::from(b), upper_bound: &a.upper_bound * b };
+        if reduce {
+            self.reduce_fast(result)
+        } else {
+            Ok(result)
+        }
+    }
+
+    pub fn neg_f(&self, a: &Felt) -> Result<Felt, SynthesisError> {
+        let lifted_modulus = (&a.upper_bound / MODULUS + 1u64) * MODULUS;
+        self.reduce_fast(Felt {
+            value: FpVar::constant(Fr::from(lifted_modulus.clone())) - &a.value,
+            upper_bound: lifted_modulus,
+        })
+    }
+
+    fn inv_f(&self, a: &Felt) -> Result<Felt, SynthesisError> {
+        let inv = hint(&self.cs, || Ok(babybear(&a.value)?.inverse().as_canonical_u32().into()))?;
+        to_bits(&self.cs, &inv, 31)?;
+        let inv = Felt { value: inv, upper_bound: BigUint::from(1u64 << 31) };
+
+        let product = self.mul_f(a, &inv, true)?;
+        self.assert_eq_f(&product, &Felt::constant(1))?;
+        Ok(inv)
+    }
+
+    pub fn div_f(&self, a: &Felt, b: &Felt) -> Result<Felt, SynthesisError> {
+        let b_inv = self.inv_f(b)?;
+        self.mul_f(a, &b_inv, true)
+    }
+
+    pub fn assert_eq_f(&self, a: &Felt, b: &Felt) -> Result<(), SynthesisError> {
+        let a = self.reduce_slow(a.clone())?;
+        let b = self.reduce_slow(b.clone())?;
+        a.value.enforce_equal(&b.value)
+    }
+
+    pub fn assert_ne_f(&self, a: &Felt, b: &Felt) -> Result<(), SynthesisError> {
+        let a = self.reduce_slow(a.clone())?;
+        let b = self.reduce_slow(b.clone())?;
+        a.value.enforce_not_equal(&b.value)
+    }
+
+    pub fn assert_eq_e(&self, a: &Ext, b: &Ext) -> Result<(), SynthesisError> {
+        for (a, b) in a.iter().zip(b.iter()) {
+            self.assert_eq_f(a, b)?;
+        }
+        Ok(())
+    }
+
+    pub fn select_f(&self, cond: &Boolean<Fr>, a: &Felt, b: &Felt) -> Result<Felt, SynthesisError> {
+        Ok(Felt {
+            value: FpVar::conditionally_select(cond, &a.value, &b.value)?,
+            upper_bound: a.upper_bound.clone().max(b.upper_bound.clone()),
+        })
+    }
+
+    pub fn select_e(&self, cond: &Boolean<Fr>, a: &Ext, b: &Ext) -> Result<Ext, SynthesisError> {
+        Ok([
+            self.select_f(cond, &a[0], &b[0])?,
+            self.select_f(cond, &a[1], &b[1])?,
+            self.select_f(cond, &a[2], &b[2])?,
+            self.select_f(cond, &a[3], &b[3])?,
+        ])
+    }
+
+    pub fn add_ef(&self, a: &Ext, b: &Felt) -> Result<Ext, SynthesisError> {
+        let mut result = a.clone();
+        result[0] = self.add_f(&a[0], b, true)?;
+        Ok(result)
+    }
+
+    pub fn add_e(&self, a: &Ext, b: &Ext) -> Result<Ext, SynthesisError> {
+        Ok([
+            self.add_f(&a[0], &b[0], true)?,
+            self.add_f(&a[1], &b[1], true)?,
+            self.add_f(&a[2], &b[2], true)?,
+            self.add_f(&a[3], &b[3], true)?,
+        ])
+    }
+
+    pub fn sub_e(&self, a: &Ext, b: &Ext) -> Result<Ext, SynthesisError> {
+        Ok([
+            self.sub_f(&a[0], &b[0])?,
+            self.sub_f(&a[1], &b[1])?,
+            self.sub_f(&a[2], &b[2])?,
+            self.sub_f(&a[3], &b[3])?,
+        ])
+    }
+
+    pub fn sub_ef(&self, a: &Ext, b: &Felt) -> Result<Ext, SynthesisError> {
+        let mut result = a.clone();
+        result[0] = self.sub_f(&a[0], b)?;
+        Ok(result)
+    }
+
+    pub fn mul_e(&self, a: &Ext, b: &Ext) -> Result<Ext, SynthesisError> {
+        let mut result =
+            [Felt::constant(0), Felt::constant(0), Felt::constant(0), Felt::constant(0)];
+        for i in 0..4 {
+            for j in 0..4 {
+                let product = self.mul_f(&a[i], &b[j], false)?;
+                if i + j >= 4 {
+                    let product = self.mul_f_const(&product, 11, false)?;
+                    result[i + j - 4] = self.add_f(&result[i + j - 4], &product, false)?;
+                } else {
+                    result[i + j] = self.add_f(&result[i + j], &product, false)?;
+                }
+            }
+        }
+        let [a, b, c, d] = result;
+        Ok([self.reduce_fast(a)?, self.reduce_fast(b)?, self.reduce_fast(c)?, self.reduce_fast(d)?])
+    }
+
+    pub fn mul_ef(&self, a: &Ext, b: &Felt) -> Result<Ext, SynthesisError> {
+        Ok([
+            self.mul_f(&a[0], b, true)?,
+            self.mul_f(&a[1], b, true)?,
+            self.mul_f(&a[2], b, true)?,
+            self.mul_f(&a[3], b, true)?,
+        ])
+    }
+
+    pub fn inv_e(&self, a: &Ext) -> Result<Ext, SynthesisError> {
+        let inverse = || -> Result<BinomialExtensionField<BabyBear, 4>, SynthesisError> {
+            let limbs = a.iter().map(|x| babybear(&x.value)).collect::<Result<Vec<_>, _>>()?;
+            Ok(BinomialExtensionField::<BabyBear, 4>::from_base_slice(&limbs).inverse())
+        };
+        let mut result = Vec::with_capacity(4);
+        for i in 0..4 {
+            let inv =
+                hint(&self.cs, || Ok(inverse()?.as_base_slice()[i].as_canonical_u32().into()))?;
+            to_bits(&self.cs, &inv, 31)?;
+            result.push(Felt { value: inv, upper_bound: BigUint::from(1u64 << 31) });
+        }
+        let result: Ext = result.try_into().unwrap_or_else(|_| unreachable!());
+
+        let product = self.mul_e(a, &result)?;
+        let one = [Felt::constant(1), Felt::constant(0), Felt::constant(0), Felt::constant(0)];
+        self.assert_eq_e(&product, &one)?;
+        Ok(result)
+    }
+
+    pub fn div_e(&self, a: &Ext, b: &Ext) -> Result<Ext, SynthesisError> {
+        let b_inv = self.inv_e(b)?;
+        self.mul_e(a, &b_inv)
+    }
+
+    pub fn div_ef(&self, a: &Ext, b: &Felt) -> Result<Ext, SynthesisError> {
+        let b_inv = self.inv_f(b)?;
+        self.mul_ef(a, &b_inv)
+    }
+
+    pub fn neg_e(&self, a: &Ext) -> Result<Ext, SynthesisError> {
+        Ok([self.neg_f(&a[0])?, self.neg_f(&a[1])?, self.neg_f(&a[2])?, self.neg_f(&a[3])?])
+    }
+
+    pub fn to_binary(&self, a: &Felt) -> Result<Vec<Boolean<Fr>>, SynthesisError> {
+        let a = self.reduce_slow(a.clone())?;
+        to_bits(&self.cs, &a.value, 31)
+    }
+
+    pub fn reduce_fast(&self, a: Felt) -> Result<Felt, SynthesisError> {
+        if a.upper_bound.bits() >= 120 {
+            let value = self.reduce_with_max_bits(&a.value, a.upper_bound.bits())?;
+            return Ok(Felt { value, upper_bound: BigUint::from(MODULUS - 1) });
+        }
+        Ok(a)
+    }
+
+    pub fn reduce_slow(&self, a: Felt) -> Result<Felt, SynthesisError> {
+        if a.upper_bound < BigUint::from(MODULUS) {
+            return Ok(a);
+        }
+        let value = self.reduce_with_max_bits(&a.value, a.upper_bound.bits())?;
+        Ok(Felt { value, upper_bound: BigUint::from(MODULUS - 1) })
+    }
+
+    pub fn reduce_e(&self, a: Ext) -> Result<Ext, SynthesisError> {
+        let [a, b, c, d] = a;
+        Ok([self.reduce_slow(a)?, self.reduce_slow(b)?, self.reduce_slow(c)?, self.reduce_slow(d)?])
+    }
+
+    fn reduce_with_max_bits(
+        &self,
+        x: &FpVar<Fr>,
+        max_bits: u64,
+    ) -> Result<FpVar<Fr>, SynthesisError> {
+        if max_bits <= 30 {
+            return Ok(x.clone());
+        }
+
+        let quotient = hint(&self.cs, || Ok(biguint(x)? / MODULUS))?;
+        let remainder = hint(&self.cs, || Ok(biguint(x)? % MODULUS))?;
+        to_bits(&self.cs, &quotient, (max_bits - 30) as usize)?;
+
+        // Check that the remainder is smaller than the BabyBear modulus, by decomposing it into a
+        // 27 bit limb and a 4 bit limb.
+        let low_limb = hint(&self.cs, || Ok(biguint(&remainder)? % (1u64 << 27)))?;
+        let high_limb = hint(&self.cs, || Ok(biguint(&remainder)? >> 27))?;
+        (&high_limb * Fr::from(1u64 << 27) + &low_limb).enforce_equal(&remainder)?;
+        to_bits(&self.cs, &high_limb, 4)?;
+        to_bits(&self.cs, &low_limb, 27)?;
+
+        // If the most significant bits are all 1, then the least significant bits must be zero
+        // for the element to be smaller than the BabyBear modulus.
+        let should_check = high_limb.is_eq(&FpVar::constant(Fr::from(15u64)))?;
+        (FpVar::from(should_check) * &low_limb).enforce_equal(&FpVar::zero())?;
+
+        (quotient * Fr::from(MODULUS) + &remainder).enforce_equal(x)?;
+        Ok(remainder)
+    }
+}
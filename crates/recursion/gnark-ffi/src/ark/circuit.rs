@@ -0,0 +1,336 @@
+use std::collections::HashMap;
+
+use ark_bn254::Fr;
+use ark_ff::{One, Zero};
+use ark_r1cs_std::{
+    alloc::AllocVar,
+    boolean::Boolean,
+    eq::EqGadget,
+    fields::{fp::FpVar, FieldVar},
+    select::CondSelectGadget,
+    R1CSVar,
+};
+use ark_relations::r1cs::{
+    ConstraintSynthesizer, ConstraintSystem, ConstraintSystemRef, SynthesisError, SynthesisMode,
+};
+use num_bigint::BigUint;
+use sp1_recursion_compiler::constraints::{Constraint, ConstraintOpcode};
+
+use super::{
+    babybear::{to_bits, BabyBearChip, Ext, Felt},
+    poseidon2::{Poseidon2BabyBearChip, Poseidon2Chip},
+};
+use crate::GnarkWitness;
+
+/// The wrap circuit described by a list of [Constraint]s, mirroring the gnark `Circuit`.
+///
+/// The witness fixes the shape of the circuit, so it must be provided even during setup, where
+/// only its lengths are used.
+#[derive(Debug, Clone)]
+pub struct ConstraintsCircuit {
+    pub constraints: Vec<Constraint>,
+    pub witness: GnarkWitness,
+}
+
+impl ConstraintsCircuit {
+    pub fn new(constraints: Vec<Constraint>, witness: GnarkWitness) -> Self {
+        Self { constraints, witness }
+    }
+}
+
+fn parse(value: &str) -> Fr {
+    let value: BigUint = value.parse().expect("invalid witness value");
+    Fr::from(value)
+}
+
+fn parse_index(value: &str) -> usize {
+    value.parse().expect("invalid witness index")
+}
+
+/// Allocates a boolean constrained to be equal to `value`.
+fn to_boolean(
+    cs: &ConstraintSystemRef<Fr>,
+    value: &FpVar<Fr>,
+) -> Result<Boolean<Fr>, SynthesisError> {
+    if let FpVar::Constant(constant) = value {
+        assert!(constant.is_zero() || constant.is_one(), "select condition is not a boolean");
+        return Ok(Boolean::constant(constant.is_one()));
+    }
+    let bit = Boolean::new_witness(cs.clone(), || Ok(value.value()?.is_one()))?;
+    FpVar::from(bit.clone()).enforce_equal(value)?;
+    Ok(bit)
+}
+
+impl ConstraintsCircuit {
+    /// The number of R1CS constraints of the witness range checks, and of each [Constraint].
+    ///
+    /// The circuit is synthesized in setup mode, so the witness values are not used.
+    pub fn r1cs_costs(self) -> (usize, Vec<usize>) {
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        cs.set_mode(SynthesisMode::Setup);
+        let mut totals = Vec::with_capacity(self.constraints.len() + 1);
+        self.synthesize(cs, &mut |total| totals.push(total)).expect("failed to synthesize circuit");
+        let costs = totals.windows(2).map(|w| w[1] - w[0]).collect();
+        (totals[0], costs)
+    }
+
+    /// Synthesizes the circuit, calling `checkpoint` with the total number of R1CS constraints
+    /// after the witness is allocated and after each [Constraint].
+    fn synthesize(
+        self,
+        cs: ConstraintSystemRef<Fr>,
+        checkpoint: &mut dyn FnMut(usize),
+    ) -> Result<(), SynthesisError> {
+        let witness = &self.witness;
+
+        // The public inputs, in the order expected by the verifier.
+        let vkey_hash = FpVar::new_input(cs.clone(), || Ok(parse(&witness.vkey_hash)))?;
+        let committed_values_digest =
+            FpVar::new_input(cs.clone(), || Ok(parse(&witness.committed_values_digest)))?;
+
+        // The private inputs, with felts and exts range checked to 31 bits.
+        let field = BabyBearChip::new(cs.clone());
+        let witness_vars = witness
+            .vars
+            .iter()
+            .map(|v| FpVar::new_witness(cs.clone(), || Ok(parse(v))))
+            .collect::<Result<Vec<_>, _>>()?;
+        let witness_felts = witness
+            .felts
+            .iter()
+            .map(|v| {
+                let felt = Felt::new(FpVar::new_witness(cs.clone(), || Ok(parse(v)))?);
+                field.range_check(&felt)?;
+                Ok(felt)
+            })
+            .collect::<Result<Vec<_>, SynthesisError>>()?;
+        let witness_exts = witness
+            .exts
+            .iter()
+            .map(|ext| {
+                let limbs = ext
+                    .iter()
+                    .map(|v| {
+                        let felt = Felt::new(FpVar::new_witness(cs.clone(), || Ok(parse(v)))?);
+                        field.range_check(&felt)?;
+                        Ok(felt)
+                    })
+                    .collect::<Result<Vec<_>, SynthesisError>>()?;
+                Ok(limbs.try_into().expect("ext must have four limbs"))
+            })
+            .collect::<Result<Vec<Ext>, SynthesisError>>()?;
+
+        let hash = Poseidon2Chip::new();
+        let hash_babybear = Poseidon2BabyBearChip::new(&field);
+        let mut vars: HashMap<String, FpVar<Fr>> = HashMap::new();
+        let mut felts: HashMap<String, Felt> = HashMap::new();
+        let mut exts: HashMap<String, Ext> = HashMap::new();
+        checkpoint(cs.num_constraints());
+
+        for constraint in self.constraints {
+            let args = &constraint.args;
+            match constraint.opcode {
+                ConstraintOpcode::ImmV => {
+                    vars.insert(args[0][0].clone(), FpVar::constant(parse(&args[1][0])));
+                }
+                ConstraintOpcode::ImmF => {
+                    felts
+                        .insert(args[0][0].clone(), Felt::new(FpVar::constant(parse(&args[1][0]))));
+                }
+                ConstraintOpcode::ImmE => {
+                    let ext = [0, 1, 2, 3].map(|i| Felt::new(FpVar::constant(parse(&args[1][i]))));
+                    exts.insert(args[0][0].clone(), ext);
+                }
+                ConstraintOpcode::AddV => {
+                    let value = &vars[&args[1][0]] + &vars[&args[2][0]];
+                    vars.insert(args[0][0].clone(), value);
+                }
+                ConstraintOpcode::AddF => {
+                    let value = field.add_f(&felts[&args[1][0]], &felts[&args[2][0]], true)?;
+                    felts.insert(args[0][0].clone(), value);
+                }
+                ConstraintOpcode::AddE => {
+                    let value = field.add_e(&exts[&args[1][0]], &exts[&args[2][0]])?;
+                    exts.insert(args[0][0].clone(), value);
+                }
+                ConstraintOpcode::AddEF => {
+                    let value = field.add_ef(&exts[&args[1][0]], &felts[&args[2][0]])?;
+                    exts.insert(args[0][0].clone(), value);
+                }
+                ConstraintOpcode::SubV => {
+                    let value = &vars[&args[1][0]] - &vars[&args[2][0]];
+                    vars.insert(args[0][0].clone(), value);
+                }
+                ConstraintOpcode::SubF => {
+                    let value = field.sub_f(&felts[&args[1][0]], &felts[&args[2][0]])?;
+                    felts.insert(args[0][0].clone(), value);
+                }
+                ConstraintOpcode::SubE => {
+                    let value = field.sub_e(&exts[&args[1][0]], &exts[&args[2][0]])?;
+                    exts.insert(args[0][0].clone(), value);
+                }
+                ConstraintOpcode::SubEF => {
+                    let value = field.sub_ef(&exts[&args[1][0]], &felts[&args[2][0]])?;
+                    exts.insert(args[0][0].clone(), value);
+                }
+                ConstraintOpcode::MulV => {
+                    let value = &vars[&args[1][0]] * &vars[&args[2][0]];
+                    vars.insert(args[0][0].clone(), value);
+                }
+                ConstraintOpcode::MulF => {
+                    let value = field.mul_f(&felts[&args[1][0]], &felts[&args[2][0]], true)?;
+                    felts.insert(args[0][0].clone(), value);
+                }
+                ConstraintOpcode::MulE => {
+                    let value = field.mul_e(&exts[&args[1][0]], &exts[&args[2][0]])?;
+                    exts.insert(args[0][0].clone(), value);
+                }
+                ConstraintOpcode::MulEF => {
+                    let value = field.mul_ef(&exts[&args[1][0]], &felts[&args[2][0]])?;
+                    exts.insert(args[0][0].clone(), value);
+                }
+                ConstraintOpcode::DivF => {
+                    let value = field.div_f(&felts[&args[1][0]], &felts[&args[2][0]])?;
+                    felts.insert(args[0][0].clone(), value);
+                }
+                ConstraintOpcode::DivE => {
+                    let value = field.div_e(&exts[&args[1][0]], &exts[&args[2][0]])?;
+                    exts.insert(args[0][0].clone(), value);
+                }
+                ConstraintOpcode::DivEF => {
+                    let value = field.div_ef(&exts[&args[1][0]], &felts[&args[2][0]])?;
+                    exts.insert(args[0][0].clone(), value);
+                }
+                ConstraintOpcode::NegE => {
+                    let value = field.neg_e(&exts[&args[1][0]])?;
+                    exts.insert(args[0][0].clone(), value);
+                }
+                ConstraintOpcode::InvE => {
+                    let value = field.inv_e(&exts[&args[1][0]])?;
+                    exts.insert(args[0][0].clone(), value);
+                }
+                ConstraintOpcode::Num2BitsV => {
+                    let num_bits = parse_index(&args[2][0]);
+                    let bits = to_bits(&cs, &vars[&args[1][0]], num_bits)?;
+                    for (name, bit) in args[0].iter().zip(bits) {
+                        vars.insert(name.clone(), bit.into());
+                    }
+                }
+                ConstraintOpcode::Num2BitsF => {
+                    let bits = field.to_binary(&felts[&args[1][0]])?;
+                    for (name, bit) in args[0].iter().zip(bits) {
+                        vars.insert(name.clone(), bit.into());
+                    }
+                }
+                ConstraintOpcode::Permute => {
+                    let mut state = [0, 1, 2].map(|i| vars[&args[i][0]].clone());
+                    hash.permute_mut(&mut state);
+                    for (i, value) in state.into_iter().enumerate() {
+                        vars.insert(args[i][0].clone(), value);
+                    }
+                }
+                ConstraintOpcode::PermuteBabyBear => {
+                    let mut state: [Felt; 16] =
+                        core::array::from_fn(|i| felts[&args[i][0]].clone());
+                    hash_babybear.permute_mut(&mut state)?;
+                    for (i, value) in state.into_iter().enumerate() {
+                        felts.insert(args[i][0].clone(), value);
+                    }
+                }
+                ConstraintOpcode::SelectV => {
+                    let cond = to_boolean(&cs, &vars[&args[1][0]])?;
+                    let value =
+                        FpVar::conditionally_select(&cond, &vars[&args[2][0]], &vars[&args[3][0]])?;
+                    vars.insert(args[0][0].clone(), value);
+                }
+                ConstraintOpcode::SelectF => {
+                    let cond = to_boolean(&cs, &vars[&args[1][0]])?;
+                    let value = field.select_f(&cond, &felts[&args[2][0]], &felts[&args[3][0]])?;
+                    felts.insert(args[0][0].clone(), value);
+                }
+                ConstraintOpcode::SelectE => {
+                    let cond = to_boolean(&cs, &vars[&args[1][0]])?;
+                    let value = field.select_e(&cond, &exts[&args[2][0]], &exts[&args[3][0]])?;
+                    exts.insert(args[0][0].clone(), value);
+                }
+                ConstraintOpcode::Ext2Felt => {
+                    let ext = exts[&args[4][0]].clone();
+                    for (i, value) in ext.into_iter().enumerate() {
+                        felts.insert(args[i][0].clone(), value);
+                    }
+                }
+                ConstraintOpcode::AssertEqV => {
+                    vars[&args[0][0]].enforce_equal(&vars[&args[1][0]])?;
+                }
+                ConstraintOpcode::AssertEqF => {
+                    field.assert_eq_f(&felts[&args[0][0]], &felts[&args[1][0]])?;
+                }
+                ConstraintOpcode::AssertNeF => {
+                    field.assert_ne_f(&felts[&args[0][0]], &felts[&args[1][0]])?;
+                }
+                ConstraintOpcode::AssertEqE => {
+                    field.assert_eq_e(&exts[&args[0][0]], &exts[&args[1][0]])?;
+                }
+                ConstraintOpcode::PrintV => {
+                    if let Ok(value) = vars[&args[0][0]].value() {
+                        log::info!("{}", value);
+                    }
+                }
+                ConstraintOpcode::PrintF => {
+                    let felt = field.reduce_slow(felts[&args[0][0]].clone())?;
+                    if let Ok(value) = felt.value.value() {
+                        log::info!("{}", value);
+                    }
+                }
+                ConstraintOpcode::PrintE => {
+                    let ext = field.reduce_e(exts[&args[0][0]].clone())?;
+                    for felt in ext {
+                        if let Ok(value) = felt.value.value() {
+                            log::info!("{}", value);
+                        }
+                    }
+                }
+                ConstraintOpcode::WitnessV => {
+                    let value = witness_vars[parse_index(&args[1][0])].clone();
+                    vars.insert(args[0][0].clone(), value);
+                }
+                ConstraintOpcode::WitnessF => {
+                    let value = witness_felts[parse_index(&args[1][0])].clone();
+                    felts.insert(args[0][0].clone(), value);
+                }
+                ConstraintOpcode::WitnessE => {
+                    let value = witness_exts[parse_index(&args[1][0])].clone();
+                    exts.insert(args[0][0].clone(), value);
+                }
+                ConstraintOpcode::CommitVkeyHash => {
+                    vkey_hash.enforce_equal(&vars[&args[0][0]])?;
+                }
+                ConstraintOpcode::CommitCommitedValuesDigest => {
+                    committed_values_digest.enforce_equal(&vars[&args[0][0]])?;
+                }
+                ConstraintOpcode::CircuitFelts2Ext => {
+                    let ext = [1, 2, 3, 4].map(|i| felts[&args[i][0]].clone());
+                    exts.insert(args[0][0].clone(), ext);
+                }
+                ConstraintOpcode::CircuitFelt2Var => {
+                    let felt = field.reduce_slow(felts[&args[1][0]].clone())?;
+                    vars.insert(args[0][0].clone(), felt.value);
+                }
+                ConstraintOpcode::ReduceE => {
+                    let ext = field.reduce_e(exts[&args[0][0]].clone())?;
+                    exts.insert(args[0][0].clone(), ext);
+                }
+                opcode => panic!("unhandled opcode: {:?}", opcode),
+            }
+            checkpoint(cs.num_constraints());
+        }
+
+        Ok(())
+    }
+}
+
+impl ConstraintSynthesizer<Fr> for ConstraintsCircuit {
+    fn generate_constraints(self, cs: ConstraintSystemRef<Fr>) -> Result<(), SynthesisError> {
+        self.synthesize(cs, &mut |_| ())
+    }
+}
@@ -0,0 +1,401 @@
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{BufReader, BufWriter, Write},
+    path::{Path, PathBuf},
+    process::Command,
+    str::FromStr,
+};
+
+use anyhow::{ensure, Context, Result};
+use num_bigint::BigUint;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use sp1_core_machine::SP1_CIRCUIT_VERSION;
+use sp1_recursion_compiler::{
+    constraints::Constraint,
+    ir::{Config, Witness},
+};
+
+use super::r1cs::R1csCircuit;
+use crate::{witness::GnarkWitness, FflonkBn254Proof};
+
+const R1CS_FILE: &str = "fflonk_circuit.r1cs";
+const ZKEY_FILE: &str = "fflonk.zkey";
+const VK_JSON_FILE: &str = "fflonk_vk.json";
+const VK_FILE: &str = "fflonk_vk.bin";
+
+/// The commitments of a proof, in the order of the Solidity calldata.
+const POLYNOMIALS: [&str; 4] = ["C1", "C2", "W1", "W2"];
+
+/// The evaluations of a proof, in the order of the Solidity calldata. The last one is the inverse
+/// the verifier contract uses to batch its inversions.
+const EVALUATIONS: [&str; 16] =
+    ["ql", "qr", "qm", "qo", "qc", "s1", "s2", "s3", "a", "b", "c", "z", "zw", "t1w", "t2w", "inv"];
+
+/// A prover that can generate fflonk proofs with bindings to snarkjs.
+///
+/// fflonk is a KZG based protocol with a universal setup, so the keys are derived from a powers
+/// of tau file set with `SP1_FFLONK_PTAU` instead of a circuit specific ceremony. The circuit is
+/// the R1CS export of the wrap circuit.
+///
+/// # Runtime dependencies
+/// The setup, the prover and the verifier are run by snarkjs, which needs Node.js and is
+/// installed with `npm install -g snarkjs`. It is run with the command in `SP1_SNARKJS`, which
+/// defaults to `snarkjs`, and every method returns an error if it is missing.
+#[derive(Debug, Clone)]
+pub struct FflonkBn254Prover;
+
+impl FflonkBn254Prover {
+    /// Creates a new [FflonkBn254Prover].
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn get_vkey_hash(build_dir: &Path) -> [u8; 32] {
+        let vk_bin_bytes = std::fs::read(build_dir.join(VK_FILE)).unwrap();
+        Sha256::digest(vk_bin_bytes).into()
+    }
+
+    /// Executes the prover in testing mode with a circuit definition and witness.
+    pub fn test<C: Config>(constraints: Vec<Constraint>, witness: Witness<C>) {
+        let circuit = R1csCircuit::new(constraints, witness);
+        assert!(circuit.is_satisfied(), "the witness does not satisfy the circuit");
+    }
+
+    /// Runs the setup from the powers of tau and writes the keys and contracts to the build dir.
+    pub fn build<C: Config>(
+        constraints: Vec<Constraint>,
+        witness: Witness<C>,
+        build_dir: PathBuf,
+    ) -> Result<()> {
+        let ptau = std::env::var("SP1_FFLONK_PTAU")
+            .context("SP1_FFLONK_PTAU must point to a powers of tau file for the fflonk setup")?;
+        ensure!(Path::new(&ptau).is_file(), "the powers of tau file {ptau} does not exist");
+
+        // Write constraints.
+        let serialized = serde_json::to_string(&constraints)?;
+        std::fs::write(build_dir.join("constraints.json"), serialized)?;
+
+        // Write the circuit as R1CS.
+        let circuit = R1csCircuit::new(constraints, witness);
+        let r1cs_path = build_dir.join(R1CS_FILE);
+        circuit.write_r1cs(BufWriter::new(File::create(&r1cs_path)?))?;
+
+        // Run the setup.
+        let zkey_path = build_dir.join(ZKEY_FILE);
+        let vk_json_path = build_dir.join(VK_JSON_FILE);
+        snarkjs(&["fflonk", "setup", path_str(&r1cs_path)?, &ptau, path_str(&zkey_path)?])
+            .context("failed to run the fflonk setup")?;
+        snarkjs(&[
+            "zkey",
+            "export",
+            "verificationkey",
+            path_str(&zkey_path)?,
+            path_str(&vk_json_path)?,
+        ])
+        .context("failed to export the fflonk verifying key")?;
+
+        let vk_json = std::fs::read_to_string(&vk_json_path)?;
+        let vk = encode_vk(&vk_json).context("failed to encode the fflonk verifying key")?;
+        std::fs::write(build_dir.join(VK_FILE), vk)?;
+
+        // Build the contracts.
+        Self::build_contracts(build_dir)
+    }
+
+    pub fn build_contracts(build_dir: PathBuf) -> Result<()> {
+        let sp1_verifier_path = build_dir.join("SP1VerifierFflonk.sol");
+        let vkey_hash = Self::get_vkey_hash(&build_dir);
+        let sp1_verifier_str = include_str!("../../assets/SP1VerifierFflonk.txt")
+            .replace("{SP1_CIRCUIT_VERSION}", SP1_CIRCUIT_VERSION)
+            .replace("{VERIFIER_HASH}", format!("0x{}", hex::encode(vkey_hash)).as_str())
+            .replace("{PROOF_SYSTEM}", "Fflonk");
+        std::fs::write(sp1_verifier_path, sp1_verifier_str)?;
+
+        let fflonk_verifier_path = build_dir.join("FflonkVerifier.sol");
+        snarkjs(&[
+            "zkey",
+            "export",
+            "solidityverifier",
+            path_str(&build_dir.join(ZKEY_FILE))?,
+            path_str(&fflonk_verifier_path)?,
+        ])
+        .context("failed to export the fflonk verifier contract")
+    }
+
+    /// Generates an fflonk proof given a witness.
+    pub fn prove<C: Config>(
+        &self,
+        witness: Witness<C>,
+        build_dir: PathBuf,
+    ) -> Result<FflonkBn254Proof> {
+        let constraints_file = File::open(build_dir.join("constraints.json"))
+            .context("the fflonk artifacts are missing their constraints")?;
+        let constraints: Vec<Constraint> =
+            serde_json::from_reader(BufReader::new(constraints_file))?;
+
+        let gnark_witness = GnarkWitness::new(witness.clone());
+        let public_inputs = [gnark_witness.vkey_hash, gnark_witness.committed_values_digest];
+
+        // Write the witness in the wtns format.
+        let circuit = R1csCircuit::new(constraints, witness);
+        let witness_file = tempfile::NamedTempFile::new()?;
+        circuit.write_wtns(BufWriter::new(witness_file.as_file()))?;
+
+        let proof_file = tempfile::NamedTempFile::new()?;
+        let public_file = tempfile::NamedTempFile::new()?;
+        snarkjs(&[
+            "fflonk",
+            "prove",
+            path_str(&build_dir.join(ZKEY_FILE))?,
+            path_str(witness_file.path())?,
+            path_str(proof_file.path())?,
+            path_str(public_file.path())?,
+        ])
+        .context("failed to prove with snarkjs")?;
+
+        let raw_proof = std::fs::read_to_string(proof_file.path())?;
+        let encoded_proof =
+            encode_proof(&raw_proof).context("failed to encode the fflonk proof")?;
+        Ok(FflonkBn254Proof {
+            public_inputs,
+            encoded_proof: hex::encode(encoded_proof),
+            raw_proof,
+            fflonk_vkey_hash: Self::get_vkey_hash(&build_dir),
+        })
+    }
+
+    /// Verify an fflonk proof and verify that the supplied vkey_hash and committed_values_digest
+    /// match.
+    pub fn verify(
+        &self,
+        proof: &FflonkBn254Proof,
+        vkey_hash: &BigUint,
+        committed_values_digest: &BigUint,
+        build_dir: &Path,
+    ) -> Result<()> {
+        ensure!(
+            proof.fflonk_vkey_hash == Self::get_vkey_hash(build_dir),
+            "Proof vkey hash does not match circuit vkey hash, it was generated with a different circuit."
+        );
+
+        let mut proof_file = tempfile::NamedTempFile::new()?;
+        proof_file.write_all(proof.raw_proof.as_bytes())?;
+        let mut public_file = tempfile::NamedTempFile::new()?;
+        let public_inputs = [vkey_hash.to_string(), committed_values_digest.to_string()];
+        public_file.write_all(serde_json::to_string(&public_inputs)?.as_bytes())?;
+
+        snarkjs(&[
+            "fflonk",
+            "verify",
+            path_str(&build_dir.join(VK_JSON_FILE))?,
+            path_str(public_file.path())?,
+            path_str(proof_file.path())?,
+        ])
+        .context("failed to verify the fflonk proof")
+    }
+}
+
+impl Default for FflonkBn254Prover {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Runs snarkjs with the given arguments.
+fn snarkjs(args: &[&str]) -> Result<()> {
+    let command = std::env::var("SP1_SNARKJS").unwrap_or_else(|_| "snarkjs".to_string());
+    let mut parts = command.split_whitespace();
+    let mut cmd = Command::new(parts.next().context("SP1_SNARKJS is empty")?);
+    cmd.args(parts).args(args);
+    log::info!("Running snarkjs {}", args[..2].join(" "));
+    let status = cmd.status().with_context(|| {
+        format!("failed to run `{command}`, install snarkjs with `npm install -g snarkjs`")
+    })?;
+    ensure!(status.success(), "`{command} {}` failed with {status}", args.join(" "));
+    Ok(())
+}
+
+fn path_str(path: &Path) -> Result<&str> {
+    path.to_str().with_context(|| format!("{} is not valid UTF-8", path.display()))
+}
+
+/// Encodes a decimal field element as a 32 byte big-endian word.
+fn word(decimal: &str) -> Result<[u8; 32]> {
+    let bytes = BigUint::from_str(decimal)?.to_bytes_be();
+    ensure!(bytes.len() <= 32, "{decimal} does not fit in 32 bytes");
+    let mut word = [0u8; 32];
+    word[32 - bytes.len()..].copy_from_slice(&bytes);
+    Ok(word)
+}
+
+#[derive(Deserialize)]
+struct ProofJson {
+    polynomials: HashMap<String, [String; 3]>,
+    evaluations: HashMap<String, String>,
+}
+
+/// Encodes a snarkjs fflonk proof as the `bytes32[24]` calldata of the verifier contract: the
+/// commitments uncompressed, followed by the evaluations, all big-endian.
+fn encode_proof(raw_proof: &str) -> Result<Vec<u8>> {
+    let proof: ProofJson = serde_json::from_str(raw_proof)?;
+    let mut encoded = Vec::with_capacity(24 * 32);
+    for name in POLYNOMIALS {
+        let point = proof.polynomials.get(name).with_context(|| format!("missing {name}"))?;
+        ensure!(point[2] == "1", "commitment {name} is not in affine form");
+        encoded.extend(word(&point[0])?);
+        encoded.extend(word(&point[1])?);
+    }
+    for name in EVALUATIONS {
+        let evaluation = proof.evaluations.get(name).with_context(|| format!("missing {name}"))?;
+        encoded.extend(word(evaluation)?);
+    }
+    Ok(encoded)
+}
+
+#[derive(Deserialize)]
+struct VerifyingKeyJson {
+    power: u32,
+    #[serde(rename = "nPublic")]
+    n_public: u32,
+    k1: String,
+    k2: String,
+    w: String,
+    w3: String,
+    w4: String,
+    w8: String,
+    wr: String,
+    #[serde(rename = "X_2")]
+    x_2: [[String; 2]; 3],
+    #[serde(rename = "C0")]
+    c0: [String; 3],
+}
+
+/// Encodes a snarkjs fflonk verifying key in the layout read by `sp1-verifier`: the power and the
+/// number of public inputs as big-endian u32s, then `k1`, `k2`, `w`, `w3`, `w4`, `w8`, `wr`, `[x]₂`
+/// with the coordinates in the order x1, x0, y1, y0, and `C0`, all as 32 byte big-endian words.
+fn encode_vk(vk_json: &str) -> Result<Vec<u8>> {
+    let vk: VerifyingKeyJson = serde_json::from_str(vk_json)?;
+    let mut encoded = Vec::with_capacity(424);
+    encoded.extend(vk.power.to_be_bytes());
+    encoded.extend(vk.n_public.to_be_bytes());
+    for scalar in [&vk.k1, &vk.k2, &vk.w, &vk.w3, &vk.w4, &vk.w8, &vk.wr] {
+        encoded.extend(word(scalar)?);
+    }
+    let [x, y, _] = &vk.x_2;
+    for coordinate in [&x[1], &x[0], &y[1], &y[0], &vk.c0[0], &vk.c0[1]] {
+        encoded.extend(word(coordinate)?);
+    }
+    Ok(encoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use p3_baby_bear::BabyBear;
+    use p3_field::AbstractField;
+    use sp1_recursion_compiler::{config::OuterConfig, constraints::ConstraintOpcode};
+
+    use super::*;
+
+    #[test]
+    fn test_encode_fflonk_proof_and_vk() {
+        let point = |i: usize| [i.to_string(), (i + 1).to_string(), "1".to_string()];
+        let polynomials = POLYNOMIALS
+            .iter()
+            .enumerate()
+            .map(|(i, name)| (name.to_string(), point(2 * i + 1)))
+            .collect::<HashMap<_, _>>();
+        let evaluations = EVALUATIONS
+            .iter()
+            .enumerate()
+            .map(|(i, name)| (name.to_string(), (9 + i).to_string()))
+            .collect::<HashMap<_, _>>();
+        let raw_proof = serde_json::json!({
+            "polynomials": polynomials,
+            "evaluations": evaluations,
+            "protocol": "fflonk",
+            "curve": "bn128",
+        })
+        .to_string();
+
+        // The words of the proof count up from 1 in calldata order.
+        let encoded = encode_proof(&raw_proof).unwrap();
+        assert_eq!(encoded.len(), 24 * 32);
+        for (i, chunk) in encoded.chunks(32).enumerate() {
+            assert_eq!(chunk, word(&(i + 1).to_string()).unwrap());
+        }
+
+        let vk_json = serde_json::json!({
+            "protocol": "fflonk",
+            "curve": "bn128",
+            "nPublic": 2,
+            "power": 3,
+            "k1": "2",
+            "k2": "3",
+            "w": "4",
+            "w3": "5",
+            "w4": "6",
+            "w8": "7",
+            "wr": "8",
+            "X_2": [["10", "9"], ["12", "11"], ["1", "0"]],
+            "C0": ["13", "14", "1"],
+        })
+        .to_string();
+        let encoded = encode_vk(&vk_json).unwrap();
+        assert_eq!(encoded.len(), 424);
+        assert_eq!(encoded[..8], [0, 0, 0, 3, 0, 0, 0, 2]);
+        for (i, chunk) in encoded[8..].chunks(32).enumerate() {
+            assert_eq!(chunk, word(&(i + 2).to_string()).unwrap());
+        }
+    }
+
+    /// Generates the snarkjs test vectors of `sp1-verifier` in its `test_binaries` directory, for
+    /// a circuit checking `7 * 3 = 21` with the public inputs `1` and `2`.
+    ///
+    /// This needs snarkjs and a powers of tau file of at least 2^8 constraints, as in
+    /// `SP1_FFLONK_PTAU=powersOfTau28_hez_final_08.ptau cargo test -p sp1-recursion-gnark-ffi
+    /// --features ark -- --ignored generate_fflonk_test_vectors`.
+    #[test]
+    #[ignore]
+    fn generate_fflonk_test_vectors() {
+        let constraint = |opcode: ConstraintOpcode, args: &[&[&str]]| Constraint {
+            opcode,
+            args: args.iter().map(|arg| arg.iter().map(|s| s.to_string()).collect()).collect(),
+        };
+        let constraints = vec![
+            constraint(ConstraintOpcode::WitnessF, &[&["a"], &["0"]]),
+            constraint(ConstraintOpcode::ImmF, &[&["b"], &["3"]]),
+            constraint(ConstraintOpcode::MulF, &[&["c"], &["a"], &["b"]]),
+            constraint(ConstraintOpcode::ImmF, &[&["d"], &["21"]]),
+            constraint(ConstraintOpcode::AssertEqF, &[&["c"], &["d"]]),
+            constraint(ConstraintOpcode::WitnessV, &[&["v0"], &["0"]]),
+            constraint(ConstraintOpcode::WitnessV, &[&["v1"], &["1"]]),
+            constraint(ConstraintOpcode::CommitVkeyHash, &[&["v0"]]),
+            constraint(ConstraintOpcode::CommitCommitedValuesDigest, &[&["v1"]]),
+        ];
+        let vkey_hash = <OuterConfig as Config>::N::from_canonical_u32(1);
+        let committed_values_digest = <OuterConfig as Config>::N::from_canonical_u32(2);
+        let witness = Witness::<OuterConfig> {
+            vars: vec![vkey_hash, committed_values_digest],
+            felts: vec![BabyBear::from_canonical_u32(7)],
+            exts: vec![],
+            vkey_hash,
+            committed_values_digest,
+        };
+
+        let build_dir = tempfile::tempdir().unwrap();
+        FflonkBn254Prover::build(constraints, witness.clone(), build_dir.path().to_path_buf())
+            .unwrap();
+        let prover = FflonkBn254Prover::new();
+        let proof = prover.prove(witness, build_dir.path().to_path_buf()).unwrap();
+        prover
+            .verify(&proof, &BigUint::from(1u32), &BigUint::from(2u32), build_dir.path())
+            .unwrap();
+
+        let out_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("../../verifier/test_binaries");
+        std::fs::copy(build_dir.path().join(VK_FILE), out_dir.join("fflonk-vk.bin")).unwrap();
+        std::fs::write(out_dir.join("fflonk-proof.bin"), hex::decode(proof.encoded_proof).unwrap())
+            .unwrap();
+    }
+}
@@ -0,0 +1,553 @@
+use std::{
+    fs::File,
+    io::{BufReader, BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, bail, ensure, Context, Result};
+use ark_bn254::{Bn254, Fq, Fq2, Fr, G1Affine, G2Affine};
+use ark_ff::{BigInteger, PrimeField, UniformRand, Zero};
+use ark_groth16::{Groth16, Proof, ProvingKey, VerifyingKey};
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_snark::SNARK;
+use num_bigint::BigUint;
+use rand::rngs::OsRng;
+use sha2::{Digest, Sha256};
+use sp1_core_machine::SP1_CIRCUIT_VERSION;
+use sp1_recursion_compiler::{
+    constraints::Constraint,
+    ir::{Config, Witness},
+};
+
+use super::{
+    circuit::ConstraintsCircuit, qap::CircomReduction, r1cs::R1csCircuit, zkey::read_zkey,
+};
+use crate::{witness::GnarkWitness, Groth16Bn254Proof};
+
+const R1CS_FILE: &str = "groth16_circuit.r1cs";
+const PK_FILE: &str = "groth16_ark_pk.bin";
+const ARK_VK_FILE: &str = "groth16_ark_vk.bin";
+const VK_FILE: &str = "groth16_vk.bin";
+
+/// The tail of a gnark raw proof without commitments: an empty list of commitments and the proof
+/// of knowledge of the commitments, which is the point at infinity.
+const NO_COMMITMENTS: [u8; 68] = {
+    let mut tail = [0u8; 68];
+    tail[4] = INFINITY;
+    tail
+};
+
+/// The flags gnark sets on the most significant byte of an encoded point.
+const COMPRESSED_SMALLEST: u8 = 0b10 << 6;
+const COMPRESSED_LARGEST: u8 = 0b11 << 6;
+const INFINITY: u8 = 0b01 << 6;
+
+/// A prover that can generate proofs with the Groth16 protocol in pure Rust using arkworks.
+///
+/// The keys are imported from the `.zkey` output of a snarkjs phase 2 ceremony on the R1CS export
+/// of the circuit (see [Groth16Bn254ArkProver::build]), and the proofs are generated with the
+/// snarkjs QAP reduction those keys are computed for. The proofs and the verifying key are written
+/// in the gnark layouts, so they are checked by `sp1-verifier` with the `groth16_vk.bin` of the
+/// build dir and by the `SP1VerifierGroth16` contract built alongside the keys. They are not
+/// accepted by the verifier of the released gnark artifacts, whose keys come from a ceremony over
+/// the gnark circuit.
+#[derive(Debug, Clone)]
+pub struct Groth16Bn254ArkProver;
+
+impl Groth16Bn254ArkProver {
+    /// Creates a new [Groth16Bn254ArkProver].
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn get_vkey_hash(build_dir: &Path) -> [u8; 32] {
+        let vk_bin_bytes = std::fs::read(build_dir.join(VK_FILE)).unwrap();
+        Sha256::digest(vk_bin_bytes).into()
+    }
+
+    /// Executes the prover in testing mode with a circuit definition and witness.
+    pub fn test<C: Config>(constraints: Vec<Constraint>, witness: Witness<C>) {
+        let circuit = ConstraintsCircuit::new(constraints, GnarkWitness::new(witness));
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        circuit.generate_constraints(cs.clone()).expect("failed to synthesize circuit");
+        if let Some(unsatisfied) = cs.which_is_unsatisfied().unwrap() {
+            panic!("constraint {} is not satisfied", unsatisfied);
+        }
+    }
+
+    pub fn build_contracts(build_dir: PathBuf) {
+        let vk = Self::load_vk(&build_dir);
+
+        let sp1_verifier_path = build_dir.join("SP1VerifierGroth16.sol");
+        let vkey_hash = Self::get_vkey_hash(&build_dir);
+        let sp1_verifier_str = include_str!("../../assets/SP1VerifierGroth16.txt")
+            .replace("{SP1_CIRCUIT_VERSION}", SP1_CIRCUIT_VERSION)
+            .replace("{VERIFIER_HASH}", format!("0x{}", hex::encode(vkey_hash)).as_str())
+            .replace("{PROOF_SYSTEM}", "Groth16");
+        let mut sp1_verifier_file = File::create(sp1_verifier_path).unwrap();
+        sp1_verifier_file.write_all(sp1_verifier_str.as_bytes()).unwrap();
+
+        let groth16_verifier_path = build_dir.join("Groth16Verifier.sol");
+        let mut groth16_verifier_file = File::create(groth16_verifier_path).unwrap();
+        groth16_verifier_file.write_all(Self::groth16_verifier(&vk).as_bytes()).unwrap();
+    }
+
+    /// Imports the keys from the ceremony `.zkey` file set with `SP1_GROTH16_ZKEY` and writes the
+    /// keys and contracts to the build dir.
+    ///
+    /// Without `SP1_GROTH16_ZKEY`, the circuit is written to `groth16_circuit.r1cs` in the build
+    /// dir and an error is returned. The ceremony is run on that file with snarkjs and a powers of
+    /// tau file large enough for the circuit:
+    ///
+    /// ```sh
+    /// snarkjs groth16 setup groth16_circuit.r1cs pot.ptau circuit_0000.zkey
+    /// snarkjs zkey contribute circuit_0000.zkey circuit_0001.zkey   # once per contributor
+    /// snarkjs zkey beacon circuit_0001.zkey circuit_final.zkey <beacon> 10
+    /// snarkjs zkey verify groth16_circuit.r1cs pot.ptau circuit_final.zkey
+    /// ```
+    pub fn build<C: Config>(
+        constraints: Vec<Constraint>,
+        witness: Witness<C>,
+        build_dir: PathBuf,
+    ) -> Result<()> {
+        match std::env::var_os("SP1_GROTH16_ZKEY") {
+            Some(zkey_path) => {
+                Self::build_from_zkey(constraints, witness, Path::new(&zkey_path), build_dir)
+            }
+            None => {
+                let circuit = R1csCircuit::new(constraints, witness);
+                let r1cs_path = build_dir.join(R1CS_FILE);
+                circuit.write_r1cs(BufWriter::new(File::create(&r1cs_path)?))?;
+                bail!(
+                    "SP1_GROTH16_ZKEY must point to the .zkey file of a Groth16 ceremony on {}",
+                    r1cs_path.display()
+                )
+            }
+        }
+    }
+
+    /// Imports the keys from a ceremony `.zkey` file and writes the keys and contracts to the
+    /// build dir.
+    ///
+    /// The keys are checked against the circuit by proving and verifying the template witness.
+    pub fn build_from_zkey<C: Config>(
+        constraints: Vec<Constraint>,
+        witness: Witness<C>,
+        zkey_path: &Path,
+        build_dir: PathBuf,
+    ) -> Result<()> {
+        // Write constraints.
+        let serialized = serde_json::to_string(&constraints)?;
+        std::fs::write(build_dir.join("constraints.json"), serialized)?;
+
+        // Write witness.
+        let witness_path = build_dir.join("groth16_witness.json");
+        GnarkWitness::new(witness.clone()).save(witness_path.to_str().unwrap());
+
+        // Write the circuit as R1CS, which the ceremony was run on.
+        let circuit = R1csCircuit::new(constraints, witness);
+        circuit.write_r1cs(BufWriter::new(File::create(build_dir.join(R1CS_FILE))?))?;
+
+        // Read the keys and check that they are for a circuit of the same shape.
+        let zkey_file = File::open(zkey_path)
+            .with_context(|| format!("failed to open {}", zkey_path.display()))?;
+        let zkey = read_zkey(BufReader::new(zkey_file))
+            .with_context(|| format!("failed to read {}", zkey_path.display()))?;
+        let domain_size =
+            (circuit.num_constraints() + circuit.num_public_inputs() + 1).next_power_of_two();
+        ensure!(
+            zkey.num_wires == circuit.num_wires()
+                && zkey.num_public_inputs == circuit.num_public_inputs()
+                && zkey.domain_size == domain_size,
+            "the zkey is for a circuit with {} wires, {} public inputs and a domain of size {}, \
+             but the circuit has {} wires, {} public inputs and a domain of size {domain_size}",
+            zkey.num_wires,
+            zkey.num_public_inputs,
+            zkey.domain_size,
+            circuit.num_wires(),
+            circuit.num_public_inputs(),
+        );
+        let pk = zkey.pk;
+        let proof = prove_circuit(&pk, &circuit)?;
+        let inputs = &circuit.assignment()[1..=circuit.num_public_inputs()];
+        ensure!(
+            Groth16::<Bn254>::verify(&pk.vk, inputs, &proof)?,
+            "the zkey is not a key for the circuit"
+        );
+
+        let pk_file = BufWriter::new(File::create(build_dir.join(PK_FILE))?);
+        pk.serialize_uncompressed(pk_file)?;
+        let vk_file = BufWriter::new(File::create(build_dir.join(ARK_VK_FILE))?);
+        pk.vk.serialize_uncompressed(vk_file)?;
+        std::fs::write(build_dir.join(VK_FILE), encode_vk(&pk))?;
+
+        // Build the contracts.
+        Self::build_contracts(build_dir);
+        Ok(())
+    }
+
+    /// Generates a Groth16 proof given a witness.
+    pub fn prove<C: Config>(&self, witness: Witness<C>, build_dir: PathBuf) -> Groth16Bn254Proof {
+        let constraints_file = File::open(build_dir.join("constraints.json")).unwrap();
+        let constraints: Vec<Constraint> =
+            serde_json::from_reader(BufReader::new(constraints_file)).unwrap();
+        let pk_file = BufReader::new(File::open(build_dir.join(PK_FILE)).unwrap());
+        let pk = ProvingKey::<Bn254>::deserialize_uncompressed_unchecked(pk_file).unwrap();
+
+        let gnark_witness = GnarkWitness::new(witness.clone());
+        let public_inputs = [gnark_witness.vkey_hash, gnark_witness.committed_values_digest];
+        let circuit = R1csCircuit::new(constraints, witness);
+        let proof = prove_circuit(&pk, &circuit).expect("failed to prove");
+
+        let encoded_proof = encode_proof(&proof);
+        let raw_proof = [encoded_proof.as_slice(), &NO_COMMITMENTS].concat();
+        Groth16Bn254Proof {
+            public_inputs,
+            encoded_proof: hex::encode(encoded_proof),
+            raw_proof: hex::encode(raw_proof),
+            groth16_vkey_hash: Self::get_vkey_hash(&build_dir),
+        }
+    }
+
+    /// Verify a Groth16 proof and verify that the supplied vkey_hash and committed_values_digest
+    /// match.
+    pub fn verify(
+        &self,
+        proof: &Groth16Bn254Proof,
+        vkey_hash: &BigUint,
+        committed_values_digest: &BigUint,
+        build_dir: &Path,
+    ) {
+        if proof.groth16_vkey_hash != Self::get_vkey_hash(build_dir) {
+            panic!(
+                "Proof vkey hash does not match circuit vkey hash, it was generated with a different circuit."
+            );
+        }
+        let vk = Self::load_vk(build_dir);
+        let raw_proof = hex::decode(&proof.raw_proof).expect("invalid proof hex");
+        let proof = decode_raw_proof(&raw_proof).expect("failed to decode proof");
+        let inputs = [Fr::from(vkey_hash.clone()), Fr::from(committed_values_digest.clone())];
+        let valid = Groth16::<Bn254>::verify(&vk, &inputs, &proof).expect("failed to verify proof");
+        assert!(valid, "failed to verify proof");
+    }
+
+    fn load_vk(build_dir: &Path) -> VerifyingKey<Bn254> {
+        let vk_file = BufReader::new(File::open(build_dir.join(ARK_VK_FILE)).unwrap());
+        VerifyingKey::deserialize_uncompressed(vk_file).unwrap()
+    }
+
+    /// Renders a Solidity verifier for the verifying key.
+    fn groth16_verifier(vk: &VerifyingKey<Bn254>) -> String {
+        let beta_neg = -vk.beta_g2;
+        let gamma_neg = -vk.gamma_g2;
+        let delta_neg = -vk.delta_g2;
+        let mut replacements =
+            vec![("ALPHA_X".to_string(), vk.alpha_g1.x), ("ALPHA_Y".to_string(), vk.alpha_g1.y)];
+        for (name, point) in [("BETA", beta_neg), ("GAMMA", gamma_neg), ("DELTA", delta_neg)] {
+            replacements.push((format!("{name}_NEG_X_0"), point.x.c0));
+            replacements.push((format!("{name}_NEG_X_1"), point.x.c1));
+            replacements.push((format!("{name}_NEG_Y_0"), point.y.c0));
+            replacements.push((format!("{name}_NEG_Y_1"), point.y.c1));
+        }
+        assert_eq!(vk.gamma_abc_g1.len(), 3, "the wrap circuit has two public inputs");
+        for (name, point) in ["CONSTANT", "PUB_0", "PUB_1"].into_iter().zip(&vk.gamma_abc_g1) {
+            replacements.push((format!("{name}_X"), point.x));
+            replacements.push((format!("{name}_Y"), point.y));
+        }
+
+        let mut verifier = include_str!("../../assets/Groth16VerifierArk.txt").to_string();
+        for (name, value) in replacements {
+            let value = BigUint::from(value.into_bigint()).to_string();
+            verifier = verifier.replace(&format!("{{{name}}}"), &value);
+        }
+        verifier
+    }
+}
+
+impl Default for Groth16Bn254ArkProver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Proves the circuit with the snarkjs QAP reduction.
+fn prove_circuit(pk: &ProvingKey<Bn254>, circuit: &R1csCircuit) -> Result<Proof<Bn254>> {
+    let matrices = circuit.matrices();
+    let (r, s) = (Fr::rand(&mut OsRng), Fr::rand(&mut OsRng));
+    Groth16::<Bn254, CircomReduction>::create_proof_with_reduction_and_matrices(
+        pk,
+        r,
+        s,
+        matrices,
+        matrices.num_instance_variables,
+        matrices.num_constraints,
+        circuit.assignment(),
+    )
+    .map_err(|err| anyhow!("failed to prove: {err}"))
+}
+
+/// Encodes the verifying key in the layout gnark writes for a key without commitments, which is
+/// read by `sp1-verifier`: `[α]₁`, `[β]₁`, `[β]₂`, `[γ]₂`, `[δ]₁`, `[δ]₂` and the length prefixed
+/// `[K]₁` compressed, followed by the empty lists of committed wires and of commitment keys.
+pub fn encode_vk(pk: &ProvingKey<Bn254>) -> Vec<u8> {
+    let vk = &pk.vk;
+    let mut encoded = Vec::new();
+    encoded.extend(compress_g1(&vk.alpha_g1));
+    encoded.extend(compress_g1(&pk.beta_g1));
+    encoded.extend(compress_g2(&vk.beta_g2));
+    encoded.extend(compress_g2(&vk.gamma_g2));
+    encoded.extend(compress_g1(&pk.delta_g1));
+    encoded.extend(compress_g2(&vk.delta_g2));
+    encoded.extend((vk.gamma_abc_g1.len() as u32).to_be_bytes());
+    for point in &vk.gamma_abc_g1 {
+        encoded.extend(compress_g1(point));
+    }
+    encoded.extend(0u32.to_be_bytes());
+    encoded.extend(0u32.to_be_bytes());
+    encoded
+}
+
+/// Whether `y` is larger than `-y`, which gnark records in the flags of a compressed point.
+fn is_largest(y: Fq) -> bool {
+    y.into_bigint() > (-y).into_bigint()
+}
+
+/// Compresses a G1 point as its big-endian `x` coordinate, flagged with the sign of `y`.
+fn compress_g1(point: &G1Affine) -> [u8; 32] {
+    let mut bytes = [0u8; 32];
+    if point.infinity {
+        bytes[0] = INFINITY;
+        return bytes;
+    }
+    bytes.copy_from_slice(&point.x.into_bigint().to_bytes_be());
+    bytes[0] |= if is_largest(point.y) { COMPRESSED_LARGEST } else { COMPRESSED_SMALLEST };
+    bytes
+}
+
+/// Compresses a G2 point as the big-endian coordinates `x1`, `x0`, flagged with the sign of `y`.
+fn compress_g2(point: &G2Affine) -> [u8; 64] {
+    let mut bytes = [0u8; 64];
+    if point.infinity {
+        bytes[0] = INFINITY;
+        return bytes;
+    }
+    bytes[..32].copy_from_slice(&point.x.c1.into_bigint().to_bytes_be());
+    bytes[32..].copy_from_slice(&point.x.c0.into_bigint().to_bytes_be());
+    let largest =
+        if point.y.c1.is_zero() { is_largest(point.y.c0) } else { is_largest(point.y.c1) };
+    bytes[0] |= if largest { COMPRESSED_LARGEST } else { COMPRESSED_SMALLEST };
+    bytes
+}
+
+/// Encodes a proof in the gnark layout: A, B and C uncompressed and big-endian, with the
+/// coordinates of B in the order x1, x0, y1, y0.
+pub fn encode_proof(proof: &Proof<Bn254>) -> Vec<u8> {
+    [
+        proof.a.x,
+        proof.a.y,
+        proof.b.x.c1,
+        proof.b.x.c0,
+        proof.b.y.c1,
+        proof.b.y.c0,
+        proof.c.x,
+        proof.c.y,
+    ]
+    .iter()
+    .flat_map(|x| x.into_bigint().to_bytes_be())
+    .collect()
+}
+
+/// Decodes a proof in the gnark raw layout, which must not have commitments.
+pub fn decode_raw_proof(bytes: &[u8]) -> Result<Proof<Bn254>> {
+    ensure!(bytes.len() == 256 + NO_COMMITMENTS.len(), "invalid proof length {}", bytes.len());
+    ensure!(bytes[256..] == NO_COMMITMENTS, "the proof has commitments");
+    decode_proof(&bytes[..256])
+}
+
+/// Decodes a proof encoded with [encode_proof], checking that the points are valid.
+pub fn decode_proof(bytes: &[u8]) -> Result<Proof<Bn254>> {
+    ensure!(bytes.len() == 256, "invalid proof length {}", bytes.len());
+    let coordinates = bytes
+        .chunks(32)
+        .map(|chunk| {
+            let x = Fq::from_be_bytes_mod_order(chunk);
+            ensure!(x.into_bigint().to_bytes_be() == chunk, "coordinate is not in the field");
+            Ok(x)
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let a = G1Affine::new_unchecked(coordinates[0], coordinates[1]);
+    let b = G2Affine::new_unchecked(
+        Fq2::new(coordinates[3], coordinates[2]),
+        Fq2::new(coordinates[5], coordinates[4]),
+    );
+    let c = G1Affine::new_unchecked(coordinates[6], coordinates[7]);
+    for point in [a, c] {
+        ensure!(point.is_on_curve() && point.is_in_correct_subgroup_assuming_on_curve());
+    }
+    ensure!(b.is_on_curve() && b.is_in_correct_subgroup_assuming_on_curve());
+    Ok(Proof { a, b, c })
+}
+
+#[cfg(test)]
+mod tests {
+    use p3_baby_bear::BabyBear;
+    use p3_field::{AbstractField, PrimeField32};
+    use p3_symmetric::Permutation;
+    use sp1_recursion_compiler::{config::OuterConfig, constraints::ConstraintOpcode};
+    use sp1_stark::inner_perm;
+    use sp1_verifier::{hash_public_inputs, Groth16Verifier};
+
+    use super::*;
+    use crate::ark::r1cs::write_section;
+
+    fn constraint(opcode: ConstraintOpcode, args: &[&[&str]]) -> Constraint {
+        let args = args.iter().map(|arg| arg.iter().map(|s| s.to_string()).collect()).collect();
+        Constraint { opcode, args }
+    }
+
+    #[test]
+    fn test_ark_groth16_prove_verify() {
+        let input: [BabyBear; 16] = core::array::from_fn(BabyBear::from_canonical_usize);
+        let mut output = input;
+        inner_perm().permute_mut(&mut output);
+
+        // Permute the witness felts and check the result against the native permutation.
+        let names = (0..16).map(|i| format!("f{i}")).collect::<Vec<_>>();
+        let mut constraints = Vec::new();
+        for (i, name) in names.iter().enumerate() {
+            constraints.push(constraint(ConstraintOpcode::WitnessF, &[&[name], &[&i.to_string()]]));
+        }
+        let state = names.iter().map(|name| [name.as_str()]).collect::<Vec<_>>();
+        let state = state.iter().map(|name| name.as_slice()).collect::<Vec<_>>();
+        constraints.push(constraint(ConstraintOpcode::PermuteBabyBear, &state));
+        for (name, value) in names.iter().zip(output) {
+            let expected = format!("e{name}");
+            let value = value.as_canonical_u32().to_string();
+            constraints.push(constraint(ConstraintOpcode::ImmF, &[&[&expected], &[&value]]));
+            constraints.push(constraint(ConstraintOpcode::AssertEqF, &[&[name], &[&expected]]));
+        }
+        constraints.push(constraint(ConstraintOpcode::WitnessV, &[&["v0"], &["0"]]));
+        constraints.push(constraint(ConstraintOpcode::WitnessV, &[&["v1"], &["1"]]));
+        constraints.push(constraint(ConstraintOpcode::CommitVkeyHash, &[&["v0"]]));
+        constraints.push(constraint(ConstraintOpcode::CommitCommitedValuesDigest, &[&["v1"]]));
+
+        // The public inputs of an SP1 proof, to check the proof with `sp1-verifier` too.
+        type N = <OuterConfig as Config>::N;
+        let sp1_vkey_hash = format!("0x{:064x}", 1234);
+        let public_values = b"public values";
+        let digest = hash_public_inputs(public_values);
+        let vkey_hash = N::from_canonical_u32(1234);
+        let committed_values_digest = digest.iter().fold(N::zero(), |acc, byte| {
+            acc * N::from_canonical_u32(256) + N::from_canonical_u8(*byte)
+        });
+        let witness = Witness::<OuterConfig> {
+            vars: vec![vkey_hash, committed_values_digest],
+            felts: input.to_vec(),
+            exts: vec![],
+            vkey_hash,
+            committed_values_digest,
+        };
+        Groth16Bn254ArkProver::test(constraints.clone(), witness.clone());
+
+        // Import the keys of a setup standing in for the ceremony.
+        let build_dir = tempfile::tempdir().unwrap();
+        let zkey_path = build_dir.path().join("circuit_final.zkey");
+        write_zkey(&constraints, &witness, &zkey_path);
+        Groth16Bn254ArkProver::build_from_zkey(
+            constraints.clone(),
+            witness.clone(),
+            &zkey_path,
+            build_dir.path().to_path_buf(),
+        )
+        .unwrap();
+
+        let prover = Groth16Bn254ArkProver::new();
+        let proof = prover.prove(witness.clone(), build_dir.path().to_path_buf());
+        let digest_int = BigUint::from_bytes_be(&digest);
+        prover.verify(&proof, &BigUint::from(1234u32), &digest_int, build_dir.path());
+
+        // The proof and the verifying key are read by `sp1-verifier` in the gnark layouts.
+        let vk = std::fs::read(build_dir.path().join(VK_FILE)).unwrap();
+        let encoded_proof = hex::decode(&proof.encoded_proof).unwrap();
+        let proof_bytes = [&proof.groth16_vkey_hash[..4], &encoded_proof].concat();
+        Groth16Verifier::verify(&proof_bytes, public_values, &sp1_vkey_hash, &vk).unwrap();
+        Groth16Verifier::verify(&proof_bytes, b"other values", &sp1_vkey_hash, &vk).unwrap_err();
+
+        let raw_proof = hex::decode(&proof.raw_proof).unwrap();
+        assert_eq!(raw_proof[..256], encoded_proof);
+        assert_eq!(encode_proof(&decode_raw_proof(&raw_proof).unwrap()), encoded_proof);
+        let verifier =
+            std::fs::read_to_string(build_dir.path().join("Groth16Verifier.sol")).unwrap();
+        assert!(!verifier.contains("= {"), "verifier has unreplaced constants");
+
+        // The keys of another circuit are rejected.
+        let other_constraints = constraints[constraints.len() - 4..].to_vec();
+        write_zkey(&other_constraints, &witness, &zkey_path);
+        let other_dir = tempfile::tempdir().unwrap();
+        Groth16Bn254ArkProver::build_from_zkey(
+            constraints,
+            witness,
+            &zkey_path,
+            other_dir.path().to_path_buf(),
+        )
+        .unwrap_err();
+    }
+
+    /// Runs a setup for the circuit and writes its keys in the snarkjs `.zkey` layout.
+    fn write_zkey(constraints: &[Constraint], witness: &Witness<OuterConfig>, path: &Path) {
+        let setup_circuit =
+            ConstraintsCircuit::new(constraints.to_vec(), GnarkWitness::new(witness.clone()));
+        let (pk, _) =
+            Groth16::<Bn254, CircomReduction>::circuit_specific_setup(setup_circuit, &mut OsRng)
+                .unwrap();
+        let circuit = R1csCircuit::new(constraints.to_vec(), witness.clone());
+
+        let fq = |x: Fq| x.0 .0.iter().flat_map(|limb| limb.to_le_bytes()).collect::<Vec<_>>();
+        let g1 = |point: &G1Affine| match point.infinity {
+            true => vec![0u8; 64],
+            false => [fq(point.x), fq(point.y)].concat(),
+        };
+        let g2 = |point: &G2Affine| match point.infinity {
+            true => vec![0u8; 128],
+            false => [fq(point.x.c0), fq(point.x.c1), fq(point.y.c0), fq(point.y.c1)].concat(),
+        };
+        let g1s = |points: &[G1Affine]| points.iter().flat_map(g1).collect::<Vec<_>>();
+
+        let mut header = Vec::new();
+        for modulus in [Fq::MODULUS.to_bytes_le(), Fr::MODULUS.to_bytes_le()] {
+            header.extend(32u32.to_le_bytes());
+            header.extend(modulus);
+        }
+        let domain_size =
+            (circuit.num_constraints() + circuit.num_public_inputs() + 1).next_power_of_two();
+        for n in [circuit.num_wires(), circuit.num_public_inputs(), domain_size] {
+            header.extend((n as u32).to_le_bytes());
+        }
+        header.extend(g1(&pk.vk.alpha_g1));
+        header.extend(g1(&pk.beta_g1));
+        header.extend(g2(&pk.vk.beta_g2));
+        header.extend(g2(&pk.vk.gamma_g2));
+        header.extend(g1(&pk.delta_g1));
+        header.extend(g2(&pk.vk.delta_g2));
+
+        let sections = [
+            (1, 1u32.to_le_bytes().to_vec()),
+            (2, header),
+            (3, g1s(&pk.vk.gamma_abc_g1)),
+            (5, g1s(&pk.a_query)),
+            (6, g1s(&pk.b_g1_query)),
+            (7, pk.b_g2_query.iter().flat_map(g2).collect()),
+            (8, g1s(&pk.l_query)),
+            (9, g1s(&pk.h_query)),
+        ];
+        let mut writer = BufWriter::new(File::create(path).unwrap());
+        writer.write_all(b"zkey").unwrap();
+        writer.write_all(&1u32.to_le_bytes()).unwrap();
+        writer.write_all(&(sections.len() as u32).to_le_bytes()).unwrap();
+        for (section_type, data) in sections {
+            write_section(&mut writer, section_type, &data).unwrap();
+        }
+    }
+}
@@ -0,0 +1,25 @@
+//! A pure-Rust Groth16 backend for the wrap circuit, built on arkworks.
+//!
+//! The circuit is synthesized from the same [sp1_recursion_compiler::constraints::Constraint]s
+//! as the gnark circuit, so it does not require Go or Docker. The keys are imported from a snarkjs
+//! ceremony on the R1CS export of the circuit, and the proofs and verifying key share the gnark
+//! encodings. As the keys differ from those of the released gnark artifacts, the proofs are only
+//! accepted by the verifiers generated from them.
+//!
+//! The circuit can also be exported as R1CS in the iden3 formats, to use other SNARK toolchains
+//! or to audit its constraint count, which [r1cs_profile] breaks down by cycle tracker span.
+//! [FflonkBn254Prover] uses this export to prove the circuit with fflonk through snarkjs.
+
+mod babybear;
+mod circuit;
+mod fflonk;
+mod groth16;
+mod poseidon2;
+mod qap;
+mod r1cs;
+mod zkey;
+
+pub use circuit::ConstraintsCircuit;
+pub use fflonk::FflonkBn254Prover;
+pub use groth16::*;
+pub use r1cs::{r1cs_profile, R1csCircuit};
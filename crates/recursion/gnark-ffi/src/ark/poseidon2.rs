@@ -0,0 +1,235 @@
+use ark_bn254::Fr;
+use ark_ff::PrimeField;
+use ark_r1cs_std::fields::fp::FpVar;
+use ark_relations::r1cs::SynthesisError;
+use num_bigint::BigUint;
+use p3_field::PrimeField32;
+use sp1_primitives::RC_16_30;
+use zkhash::poseidon2::poseidon2_instance_bn256::RC3;
+
+use super::babybear::{BabyBearChip, Felt, MODULUS};
+
+const WIDTH: usize = 3;
+const NUM_EXTERNAL_ROUNDS: usize = 8;
+const NUM_INTERNAL_ROUNDS: usize = 56;
+
+const BABYBEAR_WIDTH: usize = 16;
+const BABYBEAR_NUM_EXTERNAL_ROUNDS: usize = 8;
+const BABYBEAR_NUM_INTERNAL_ROUNDS: usize = 13;
+
+/// The Poseidon2 permutation over a state of three BN254 elements.
+pub struct Poseidon2Chip {
+    rc3: Vec<[Fr; WIDTH]>,
+}
+
+impl Poseidon2Chip {
+    pub fn new() -> Self {
+        let rc3 = RC3
+            .iter()
+            .map(|round| {
+                let round = round
+                    .iter()
+                    .map(|rc| Fr::from_bigint(rc.into_bigint()).unwrap())
+                    .collect::<Vec<_>>();
+                round.try_into().unwrap()
+            })
+            .collect();
+        Self { rc3 }
+    }
+
+    pub fn permute_mut(&self, state: &mut [FpVar<Fr>; WIDTH]) {
+        // The initial linear layer.
+        Self::matrix_permute_mut(state);
+
+        // The first half of the external rounds.
+        let rounds = NUM_EXTERNAL_ROUNDS + NUM_INTERNAL_ROUNDS;
+        let rounds_f_beginning = NUM_EXTERNAL_ROUNDS / 2;
+        for r in 0..rounds_f_beginning {
+            self.add_rc(state, r);
+            Self::sbox(state);
+            Self::matrix_permute_mut(state);
+        }
+
+        // The internal rounds.
+        let p_end = rounds_f_beginning + NUM_INTERNAL_ROUNDS;
+        for r in rounds_f_beginning..p_end {
+            state[0] = &state[0] + self.rc3[r][0];
+            state[0] = Self::sbox_p(&state[0]);
+            Self::diffusion_permute_mut(state);
+        }
+
+        // The second half of the external rounds.
+        for r in p_end..rounds {
+            self.add_rc(state, r);
+            Self::sbox(state);
+            Self::matrix_permute_mut(state);
+        }
+    }
+
+    fn add_rc(&self, state: &mut [FpVar<Fr>; WIDTH], round: usize) {
+        for (x, rc) in state.iter_mut().zip(self.rc3[round]) {
+            *x = &*x + rc;
+        }
+    }
+
+    fn sbox_p(input: &FpVar<Fr>) -> FpVar<Fr> {
+        let squared = input * input;
+        let input_4 = &squared * &squared;
+        input_4 * input
+    }
+
+    fn sbox(state: &mut [FpVar<Fr>; WIDTH]) {
+        for x in state.iter_mut() {
+            *x = Self::sbox_p(x);
+        }
+    }
+
+    fn diffusion_permute_mut(state: &mut [FpVar<Fr>; WIDTH]) {
+        let sum = &state[0] + &state[1] + &state[2];
+        state[2] = &state[2] + &state[2];
+        for x in state.iter_mut() {
+            *x = &*x + &sum;
+        }
+    }
+
+    fn matrix_permute_mut(state: &mut [FpVar<Fr>; WIDTH]) {
+        let sum = &state[0] + &state[1] + &state[2];
+        for x in state.iter_mut() {
+            *x = &*x + &sum;
+        }
+    }
+}
+
+impl Default for Poseidon2Chip {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The Poseidon2 permutation over a state of sixteen emulated BabyBear elements.
+pub struct Poseidon2BabyBearChip<'a> {
+    field: &'a BabyBearChip,
+}
+
+impl<'a> Poseidon2BabyBearChip<'a> {
+    pub fn new(field: &'a BabyBearChip) -> Self {
+        Self { field }
+    }
+
+    pub fn permute_mut(&self, state: &mut [Felt; BABYBEAR_WIDTH]) -> Result<(), SynthesisError> {
+        // The initial linear layer.
+        self.external_linear_layer(state)?;
+
+        // The first half of the external rounds.
+        let rounds = BABYBEAR_NUM_EXTERNAL_ROUNDS + BABYBEAR_NUM_INTERNAL_ROUNDS;
+        let rounds_f_beginning = BABYBEAR_NUM_EXTERNAL_ROUNDS / 2;
+        for r in 0..rounds_f_beginning {
+            self.add_rc(state, r)?;
+            self.sbox(state)?;
+            self.external_linear_layer(state)?;
+        }
+
+        // The internal rounds.
+        let p_end = rounds_f_beginning + BABYBEAR_NUM_INTERNAL_ROUNDS;
+        for r in rounds_f_beginning..p_end {
+            let rc = Felt::constant(RC_16_30[r][0].as_canonical_u32().into());
+            state[0] = self.field.add_f(&state[0], &rc, true)?;
+            state[0] = self.sbox_p(&state[0])?;
+            self.diffusion_permute_mut(state)?;
+        }
+
+        // The second half of the external rounds.
+        for r in p_end..rounds {
+            self.add_rc(state, r)?;
+            self.sbox(state)?;
+            self.external_linear_layer(state)?;
+        }
+        Ok(())
+    }
+
+    fn add_rc(
+        &self,
+        state: &mut [Felt; BABYBEAR_WIDTH],
+        round: usize,
+    ) -> Result<(), SynthesisError> {
+        for (x, rc) in state.iter_mut().zip(RC_16_30[round]) {
+            *x = self.field.add_f(x, &Felt::constant(rc.as_canonical_u32().into()), true)?;
+        }
+        Ok(())
+    }
+
+    fn sbox_p(&self, input: &Felt) -> Result<Felt, SynthesisError> {
+        let input = self.field.add_f(input, &Felt::constant(0), true)?;
+        let input = self.field.reduce_slow(input)?.value;
+        let i2 = &input * &input;
+        let i4 = &i2 * &i2;
+        let i6 = &i4 * &i2;
+        let i7 = i6 * &input;
+        self.field.reduce_slow(Felt { value: i7, upper_bound: BigUint::from(MODULUS).pow(7) })
+    }
+
+    fn sbox(&self, state: &mut [Felt; BABYBEAR_WIDTH]) -> Result<(), SynthesisError> {
+        for x in state.iter_mut() {
+            *x = self.sbox_p(x)?;
+        }
+        Ok(())
+    }
+
+    fn mds_light_permutation_4x4(&self, state: &mut [Felt]) -> Result<(), SynthesisError> {
+        let f = self.field;
+        let t01 = f.add_f(&state[0], &state[1], true)?;
+        let t23 = f.add_f(&state[2], &state[3], true)?;
+        let t0123 = f.add_f(&t01, &t23, true)?;
+        let t01123 = f.add_f(&t0123, &state[1], true)?;
+        let t01233 = f.add_f(&t0123, &state[3], true)?;
+        state[3] = f.add_f(&t01233, &f.mul_f_const(&state[0], 2, true)?, true)?;
+        state[1] = f.add_f(&t01123, &f.mul_f_const(&state[2], 2, true)?, true)?;
+        state[0] = f.add_f(&t01123, &t01, true)?;
+        state[2] = f.add_f(&t01233, &t23, true)?;
+        Ok(())
+    }
+
+    fn external_linear_layer(
+        &self,
+        state: &mut [Felt; BABYBEAR_WIDTH],
+    ) -> Result<(), SynthesisError> {
+        for chunk in state.chunks_mut(4) {
+            self.mds_light_permutation_4x4(chunk)?;
+        }
+
+        let mut sums = [state[0].clone(), state[1].clone(), state[2].clone(), state[3].clone()];
+        for chunk in state[4..].chunks(4) {
+            for (sum, x) in sums.iter_mut().zip(chunk) {
+                *sum = self.field.add_f(sum, x, true)?;
+            }
+        }
+
+        for (i, x) in state.iter_mut().enumerate() {
+            *x = self.field.add_f(x, &sums[i % 4], true)?;
+        }
+        Ok(())
+    }
+
+    fn diffusion_permute_mut(
+        &self,
+        state: &mut [Felt; BABYBEAR_WIDTH],
+    ) -> Result<(), SynthesisError> {
+        const MAT_INTERNAL_DIAG_M1: [u64; BABYBEAR_WIDTH] =
+            [2013265919, 1, 2, 4, 8, 16, 32, 64, 128, 256, 512, 1024, 2048, 4096, 8192, 32768];
+        const MONTY_INVERSE: u64 = 943718400;
+
+        let mut sum = Felt::constant(0);
+        for x in state.iter() {
+            sum = self.field.add_f(&sum, x, true)?;
+        }
+
+        for (x, diag) in state.iter_mut().zip(MAT_INTERNAL_DIAG_M1) {
+            *x = self.field.mul_f(x, &Felt::constant(diag), true)?;
+            *x = self.field.add_f(x, &sum, true)?;
+        }
+        for x in state.iter_mut() {
+            *x = self.field.mul_f(x, &Felt::constant(MONTY_INVERSE), true)?;
+        }
+        Ok(())
+    }
+}
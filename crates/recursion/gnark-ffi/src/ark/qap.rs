@@ -0,0 +1,77 @@
+use ark_ff::PrimeField;
+use ark_groth16::r1cs_to_qap::{LibsnarkReduction, R1CSToQAP};
+use ark_poly::EvaluationDomain;
+use ark_relations::r1cs::{ConstraintMatrices, ConstraintSystemRef, SynthesisError};
+
+/// The R1CS to QAP reduction of snarkjs, which the proving keys of a snarkjs ceremony are
+/// computed for.
+///
+/// It differs from [LibsnarkReduction] in the evaluation of the quotient `h`: snarkjs evaluates
+/// it at the odd powers of the `2n`-th root of unity instead of on a multiplicative coset, so
+/// the `h` query of a `.zkey` file holds the corresponding Lagrange coefficients.
+pub struct CircomReduction;
+
+impl R1CSToQAP for CircomReduction {
+    #[allow(clippy::type_complexity)]
+    fn instance_map_with_evaluation<F: PrimeField, D: EvaluationDomain<F>>(
+        cs: ConstraintSystemRef<F>,
+        t: &F,
+    ) -> Result<(Vec<F>, Vec<F>, Vec<F>, F, usize, usize), SynthesisError> {
+        LibsnarkReduction::instance_map_with_evaluation::<F, D>(cs, t)
+    }
+
+    fn witness_map_from_matrices<F: PrimeField, D: EvaluationDomain<F>>(
+        matrices: &ConstraintMatrices<F>,
+        num_inputs: usize,
+        num_constraints: usize,
+        full_assignment: &[F],
+    ) -> Result<Vec<F>, SynthesisError> {
+        let domain =
+            D::new(num_constraints + num_inputs).ok_or(SynthesisError::PolynomialDegreeTooLarge)?;
+        let domain_size = domain.size();
+        let eval = |row: &[(F, usize)]| -> F {
+            row.iter().map(|(coeff, wire)| *coeff * full_assignment[*wire]).sum()
+        };
+
+        // The evaluations of A, B and C on the domain, where each public input `i` has the extra
+        // constraint `input_i * 0 = 0`.
+        let mut a = vec![F::zero(); domain_size];
+        let mut b = vec![F::zero(); domain_size];
+        let mut c = vec![F::zero(); domain_size];
+        for i in 0..num_constraints {
+            a[i] = eval(&matrices.a[i]);
+            b[i] = eval(&matrices.b[i]);
+            c[i] = a[i] * b[i];
+        }
+        a[num_constraints..num_constraints + num_inputs]
+            .copy_from_slice(&full_assignment[..num_inputs]);
+
+        // Evaluate them at the odd powers of the 2n-th root of unity.
+        let root = D::new(2 * domain_size).ok_or(SynthesisError::PolynomialDegreeTooLarge)?;
+        let root = root.element(1);
+        for evals in [&mut a, &mut b, &mut c] {
+            domain.ifft_in_place(evals);
+            D::distribute_powers_and_mul_by_const(evals, root, F::one());
+            domain.fft_in_place(evals);
+        }
+
+        let mut h = domain.mul_polynomials_in_evaluation_domain(&a, &b);
+        h.iter_mut().zip(c).for_each(|(h, c)| *h -= c);
+        Ok(h)
+    }
+
+    fn h_query_scalars<F: PrimeField, D: EvaluationDomain<F>>(
+        max_power: usize,
+        t: F,
+        _zt: F,
+        delta_inverse: F,
+    ) -> Result<Vec<F>, SynthesisError> {
+        // The powers of `t` up to the degree of `h * z`, interpolated on the 2n domain, of which
+        // the odd Lagrange coefficients are the ones `h` is evaluated against.
+        let mut scalars =
+            (0..2 * max_power + 1).map(|i| delta_inverse * t.pow([i as u64])).collect::<Vec<_>>();
+        let domain = D::new(scalars.len()).ok_or(SynthesisError::PolynomialDegreeTooLarge)?;
+        domain.ifft_in_place(&mut scalars);
+        Ok(scalars.into_iter().skip(1).step_by(2).collect())
+    }
+}
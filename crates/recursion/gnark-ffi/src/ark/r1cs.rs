@@ -0,0 +1,231 @@
+use std::io::{self, Write};
+
+use ark_bn254::Fr;
+use ark_ff::{BigInteger, PrimeField};
+use ark_relations::r1cs::{
+    ConstraintMatrices, ConstraintSynthesizer, ConstraintSystem, ConstraintSystemRef,
+    OptimizationGoal,
+};
+use sp1_core_machine::utils::Span;
+use sp1_recursion_compiler::{
+    constraints::Constraint,
+    ir::{Config, Witness},
+    profile::SpanMarkers,
+};
+
+use super::circuit::ConstraintsCircuit;
+use crate::GnarkWitness;
+
+const R1CS_VERSION: u32 = 1;
+const WTNS_VERSION: u32 = 2;
+const FIELD_SIZE: u32 = 32;
+
+/// The wrap circuit flattened to R1CS, which can be exported in the iden3 `.r1cs` and `.wtns`
+/// formats used by circom and snarkjs.
+///
+/// Wire 0 is the constant one, wires 1 and 2 are the public inputs (the vkey hash and the
+/// committed values digest), and the remaining wires are private.
+pub struct R1csCircuit {
+    matrices: ConstraintMatrices<Fr>,
+    assignment: Vec<Fr>,
+}
+
+impl R1csCircuit {
+    /// Synthesizes the circuit from its constraints and assigns the witness.
+    pub fn new<C: Config>(constraints: Vec<Constraint>, witness: Witness<C>) -> Self {
+        let circuit = ConstraintsCircuit::new(constraints, GnarkWitness::new(witness));
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        cs.set_optimization_goal(OptimizationGoal::Constraints);
+        circuit.generate_constraints(cs.clone()).expect("failed to synthesize circuit");
+        cs.finalize();
+        Self::from_constraint_system(cs)
+    }
+
+    fn from_constraint_system(cs: ConstraintSystemRef<Fr>) -> Self {
+        let matrices = cs.to_matrices().expect("constraint matrices were not constructed");
+        let cs = cs.borrow().unwrap();
+        let assignment =
+            cs.instance_assignment.iter().chain(&cs.witness_assignment).copied().collect();
+        Self { matrices, assignment }
+    }
+
+    /// The number of constraints.
+    pub fn num_constraints(&self) -> usize {
+        self.matrices.num_constraints
+    }
+
+    /// The number of wires, including the constant one.
+    pub fn num_wires(&self) -> usize {
+        self.matrices.num_instance_variables + self.matrices.num_witness_variables
+    }
+
+    /// The number of public inputs, excluding the constant one.
+    pub fn num_public_inputs(&self) -> usize {
+        self.matrices.num_instance_variables - 1
+    }
+
+    /// The constraint matrices.
+    pub(super) fn matrices(&self) -> &ConstraintMatrices<Fr> {
+        &self.matrices
+    }
+
+    /// The values of the wires, starting with the constant one and the public inputs.
+    pub(super) fn assignment(&self) -> &[Fr] {
+        &self.assignment
+    }
+
+    /// Whether the witness satisfies every constraint.
+    pub fn is_satisfied(&self) -> bool {
+        let eval = |row: &[(Fr, usize)]| -> Fr {
+            row.iter().map(|(coeff, wire)| *coeff * self.assignment[*wire]).sum()
+        };
+        (0..self.num_constraints()).all(|i| {
+            eval(&self.matrices.a[i]) * eval(&self.matrices.b[i]) == eval(&self.matrices.c[i])
+        })
+    }
+
+    /// Writes the constraints in the iden3 `.r1cs` binary format.
+    pub fn write_r1cs<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_all(b"r1cs")?;
+        writer.write_all(&R1CS_VERSION.to_le_bytes())?;
+        writer.write_all(&3u32.to_le_bytes())?;
+
+        // The header section.
+        let mut header = Vec::new();
+        header.extend(FIELD_SIZE.to_le_bytes());
+        header.extend(Fr::MODULUS.to_bytes_le());
+        header.extend((self.num_wires() as u32).to_le_bytes());
+        header.extend(0u32.to_le_bytes());
+        header.extend((self.num_public_inputs() as u32).to_le_bytes());
+        header.extend((self.matrices.num_witness_variables as u32).to_le_bytes());
+        header.extend((self.num_wires() as u64).to_le_bytes());
+        header.extend((self.num_constraints() as u32).to_le_bytes());
+        write_section(&mut writer, 1, &header)?;
+
+        // The constraints section, as the linear combinations A, B and C of each constraint.
+        let mut constraints = Vec::new();
+        for i in 0..self.num_constraints() {
+            for row in [&self.matrices.a[i], &self.matrices.b[i], &self.matrices.c[i]] {
+                constraints.extend((row.len() as u32).to_le_bytes());
+                for (coeff, wire) in row {
+                    constraints.extend((*wire as u32).to_le_bytes());
+                    constraints.extend(coeff.into_bigint().to_bytes_le());
+                }
+            }
+        }
+        write_section(&mut writer, 2, &constraints)?;
+
+        // The wire to label map, which is the identity.
+        let labels =
+            (0..self.num_wires() as u64).flat_map(|label| label.to_le_bytes()).collect::<Vec<_>>();
+        write_section(&mut writer, 3, &labels)
+    }
+
+    /// Writes the witness in the iden3 `.wtns` binary format.
+    pub fn write_wtns<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_all(b"wtns")?;
+        writer.write_all(&WTNS_VERSION.to_le_bytes())?;
+        writer.write_all(&2u32.to_le_bytes())?;
+
+        let mut header = Vec::new();
+        header.extend(FIELD_SIZE.to_le_bytes());
+        header.extend(Fr::MODULUS.to_bytes_le());
+        header.extend((self.assignment.len() as u32).to_le_bytes());
+        write_section(&mut writer, 1, &header)?;
+
+        let values =
+            self.assignment.iter().flat_map(|x| x.into_bigint().to_bytes_le()).collect::<Vec<_>>();
+        write_section(&mut writer, 2, &values)
+    }
+}
+
+/// Attributes the R1CS constraints of the circuit to the cycle tracker spans of its
+/// [Constraint]s, as recorded by [sp1_recursion_compiler::constraints::ConstraintCompiler].
+///
+/// The range checks of the witness are attributed to the root span.
+pub fn r1cs_profile<C: Config>(
+    constraints: Vec<Constraint>,
+    spans: &SpanMarkers,
+    witness: Witness<C>,
+) -> Span<String, String> {
+    let opcodes = constraints.iter().map(|c| format!("{:?}", c.opcode)).collect::<Vec<_>>();
+    let circuit = ConstraintsCircuit::new(constraints, GnarkWitness::new(witness));
+    let (range_checks, costs) = circuit.r1cs_costs();
+    let mut span = spans.profile(opcodes.into_iter().zip(costs), |item| item);
+    span.cts.insert("WitnessRangeCheck".to_string(), range_checks);
+    span
+}
+
+pub(super) fn write_section<W: Write>(
+    writer: &mut W,
+    section_type: u32,
+    data: &[u8],
+) -> io::Result<()> {
+    writer.write_all(&section_type.to_le_bytes())?;
+    writer.write_all(&(data.len() as u64).to_le_bytes())?;
+    writer.write_all(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use p3_baby_bear::BabyBear;
+    use p3_field::AbstractField;
+    use sp1_recursion_compiler::{config::OuterConfig, constraints::ConstraintOpcode};
+
+    use super::*;
+
+    fn constraint(opcode: ConstraintOpcode, args: &[&[&str]]) -> Constraint {
+        let args = args.iter().map(|arg| arg.iter().map(|s| s.to_string()).collect()).collect();
+        Constraint { opcode, args }
+    }
+
+    #[test]
+    fn test_r1cs_export() {
+        let constraints = vec![
+            constraint(ConstraintOpcode::WitnessF, &[&["a"], &["0"]]),
+            constraint(ConstraintOpcode::ImmF, &[&["b"], &["3"]]),
+            constraint(ConstraintOpcode::MulF, &[&["c"], &["a"], &["b"]]),
+            constraint(ConstraintOpcode::ImmF, &[&["d"], &["21"]]),
+            constraint(ConstraintOpcode::AssertEqF, &[&["c"], &["d"]]),
+            constraint(ConstraintOpcode::WitnessV, &[&["v0"], &["0"]]),
+            constraint(ConstraintOpcode::WitnessV, &[&["v1"], &["1"]]),
+            constraint(ConstraintOpcode::CommitVkeyHash, &[&["v0"]]),
+            constraint(ConstraintOpcode::CommitCommitedValuesDigest, &[&["v1"]]),
+        ];
+        let vkey_hash = <OuterConfig as Config>::N::from_canonical_u32(1);
+        let committed_values_digest = <OuterConfig as Config>::N::from_canonical_u32(2);
+        let witness = Witness::<OuterConfig> {
+            vars: vec![vkey_hash, committed_values_digest],
+            felts: vec![BabyBear::from_canonical_u32(7)],
+            exts: vec![],
+            vkey_hash,
+            committed_values_digest,
+        };
+
+        let mut spans = SpanMarkers::default();
+        spans.enter(2, "mul".to_string());
+        spans.exit(5);
+        let profile = r1cs_profile(constraints.clone(), &spans, witness.clone());
+
+        let circuit = R1csCircuit::new(constraints, witness);
+        assert!(circuit.is_satisfied());
+        assert_eq!(circuit.num_public_inputs(), 2);
+        assert_eq!(profile.total(), circuit.num_constraints());
+        assert_eq!(profile.children[0].name, "mul");
+
+        let mut r1cs = Vec::new();
+        circuit.write_r1cs(&mut r1cs).unwrap();
+        assert_eq!(&r1cs[..4], b"r1cs");
+        // The header follows the magic, version, section count, section type and section size.
+        let header = &r1cs[24..];
+        let num_wires = u32::from_le_bytes(header[36..40].try_into().unwrap());
+        let num_constraints = u32::from_le_bytes(header[60..64].try_into().unwrap());
+        assert_eq!(num_wires as usize, circuit.num_wires());
+        assert_eq!(num_constraints as usize, circuit.num_constraints());
+
+        let mut wtns = Vec::new();
+        circuit.write_wtns(&mut wtns).unwrap();
+        assert_eq!(&wtns[..4], b"wtns");
+        assert_eq!(wtns.len(), 12 + 12 + 40 + 12 + 32 * circuit.num_wires());
+    }
+}
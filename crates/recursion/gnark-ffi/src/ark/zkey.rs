@@ -0,0 +1,162 @@
+use std::{
+    collections::HashMap,
+    io::{Read, Seek, SeekFrom},
+};
+
+use anyhow::{ensure, Context, Result};
+use ark_bn254::{Bn254, Fq, Fq2, Fr, G1Affine, G2Affine};
+use ark_ff::{BigInt, BigInteger, PrimeField, Zero};
+use ark_groth16::{ProvingKey, VerifyingKey};
+
+const ZKEY_VERSION: u32 = 1;
+const GROTH16_PROTOCOL: u32 = 1;
+const FIELD_SIZE: u32 = 32;
+const G1_SIZE: u64 = 2 * FIELD_SIZE as u64;
+const G2_SIZE: u64 = 4 * FIELD_SIZE as u64;
+
+/// A Groth16 proving key read from a snarkjs `.zkey` file, with the shape of the circuit it was
+/// set up for.
+///
+/// A `.zkey` file is the output of a phase 2 ceremony on a circuit (`snarkjs groth16 setup`,
+/// `snarkjs zkey contribute` and `snarkjs zkey beacon`), so its toxic waste is safe as long as
+/// one of the contributors was honest.
+pub struct Zkey {
+    pub pk: ProvingKey<Bn254>,
+    /// The number of wires, including the constant one.
+    pub num_wires: usize,
+    /// The number of public inputs, excluding the constant one.
+    pub num_public_inputs: usize,
+    /// The size of the evaluation domain of the QAP.
+    pub domain_size: usize,
+}
+
+/// Reads a Groth16 `.zkey` file.
+///
+/// The points are checked to be on the curve, but only the points of the verifying key are
+/// checked to be in the prime order subgroup, as `snarkjs zkey verify` checks the rest of the
+/// ceremony transcript.
+pub fn read_zkey<R: Read + Seek>(mut reader: R) -> Result<Zkey> {
+    let mut magic = [0u8; 4];
+    reader.read_exact(&mut magic)?;
+    ensure!(&magic == b"zkey", "not a zkey file");
+    let version = read_u32(&mut reader)?;
+    ensure!(version == ZKEY_VERSION, "unsupported zkey version {version}");
+
+    // Index the sections, which may come in any order.
+    let num_sections = read_u32(&mut reader)?;
+    let mut sections = HashMap::new();
+    for _ in 0..num_sections {
+        let section_type = read_u32(&mut reader)?;
+        let size = read_u64(&mut reader)?;
+        sections.insert(section_type, (reader.stream_position()?, size));
+        reader.seek(SeekFrom::Current(size.try_into()?))?;
+    }
+
+    seek_section(&mut reader, &sections, 1, Some(4))?;
+    let protocol = read_u32(&mut reader)?;
+    ensure!(protocol == GROTH16_PROTOCOL, "the zkey is not a Groth16 key");
+
+    // The header, with the moduli, the shape of the circuit and the verifying key.
+    seek_section(&mut reader, &sections, 2, None)?;
+    for modulus in [Fq::MODULUS.to_bytes_le(), Fr::MODULUS.to_bytes_le()] {
+        ensure!(read_u32(&mut reader)? == FIELD_SIZE, "the zkey is not for BN254");
+        let mut bytes = [0u8; FIELD_SIZE as usize];
+        reader.read_exact(&mut bytes)?;
+        ensure!(bytes[..] == modulus[..], "the zkey is not for BN254");
+    }
+    let num_wires = read_u32(&mut reader)? as usize;
+    let num_public_inputs = read_u32(&mut reader)? as usize;
+    let domain_size = read_u32(&mut reader)? as usize;
+    ensure!(num_public_inputs < num_wires, "the zkey has more public inputs than wires");
+    let alpha_g1 = read_g1(&mut reader)?;
+    let beta_g1 = read_g1(&mut reader)?;
+    let beta_g2 = read_g2(&mut reader)?;
+    let gamma_g2 = read_g2(&mut reader)?;
+    let delta_g1 = read_g1(&mut reader)?;
+    let delta_g2 = read_g2(&mut reader)?;
+    for point in [beta_g2, gamma_g2, delta_g2] {
+        ensure!(point.is_in_correct_subgroup_assuming_on_curve(), "invalid verifying key");
+    }
+
+    // The queries of the proving key, where the `C` section holds the private wires only.
+    let num_private = num_wires - num_public_inputs - 1;
+    let mut read_g1s = |section_type: u32, len: usize| -> Result<Vec<G1Affine>> {
+        seek_section(&mut reader, &sections, section_type, Some(G1_SIZE * len as u64))?;
+        (0..len).map(|_| read_g1(&mut reader)).collect()
+    };
+    let gamma_abc_g1 = read_g1s(3, num_public_inputs + 1)?;
+    let a_query = read_g1s(5, num_wires)?;
+    let b_g1_query = read_g1s(6, num_wires)?;
+    let l_query = read_g1s(8, num_private)?;
+    let h_query = read_g1s(9, domain_size)?;
+    seek_section(&mut reader, &sections, 7, Some(G2_SIZE * num_wires as u64))?;
+    let b_g2_query = (0..num_wires).map(|_| read_g2(&mut reader)).collect::<Result<Vec<_>>>()?;
+
+    let vk = VerifyingKey { alpha_g1, beta_g2, gamma_g2, delta_g2, gamma_abc_g1 };
+    let pk =
+        ProvingKey { vk, beta_g1, delta_g1, a_query, b_g1_query, b_g2_query, h_query, l_query };
+    Ok(Zkey { pk, num_wires, num_public_inputs, domain_size })
+}
+
+fn read_u32<R: Read>(reader: &mut R) -> Result<u32> {
+    let mut bytes = [0u8; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn read_u64<R: Read>(reader: &mut R) -> Result<u64> {
+    let mut bytes = [0u8; 8];
+    reader.read_exact(&mut bytes)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+/// Seeks to the start of a section, checking its size if it is known.
+fn seek_section<R: Seek>(
+    reader: &mut R,
+    sections: &HashMap<u32, (u64, u64)>,
+    section_type: u32,
+    len: Option<u64>,
+) -> Result<()> {
+    let (position, size) =
+        *sections.get(&section_type).with_context(|| format!("missing section {section_type}"))?;
+    if let Some(len) = len {
+        ensure!(size == len, "section {section_type} has size {size}, expected {len}");
+    }
+    reader.seek(SeekFrom::Start(position))?;
+    Ok(())
+}
+
+/// Reads a base field element, which snarkjs stores little-endian in Montgomery form.
+fn read_fq<R: Read>(reader: &mut R) -> Result<Fq> {
+    let mut limbs = [0u64; 4];
+    for limb in &mut limbs {
+        *limb = read_u64(reader)?;
+    }
+    let montgomery = BigInt(limbs);
+    ensure!(montgomery < Fq::MODULUS, "coordinate is not in the field");
+    Ok(Fq::new_unchecked(montgomery))
+}
+
+/// Reads a G1 point as its coordinates, where `(0, 0)` is the point at infinity.
+fn read_g1<R: Read>(reader: &mut R) -> Result<G1Affine> {
+    let (x, y) = (read_fq(reader)?, read_fq(reader)?);
+    if x.is_zero() && y.is_zero() {
+        return Ok(G1Affine::identity());
+    }
+    let point = G1Affine::new_unchecked(x, y);
+    ensure!(point.is_on_curve(), "point is not on the curve");
+    Ok(point)
+}
+
+/// Reads a G2 point as the coordinates `x.c0`, `x.c1`, `y.c0`, `y.c1`, where zero is the point at
+/// infinity.
+fn read_g2<R: Read>(reader: &mut R) -> Result<G2Affine> {
+    let x = Fq2::new(read_fq(reader)?, read_fq(reader)?);
+    let y = Fq2::new(read_fq(reader)?, read_fq(reader)?);
+    if x.is_zero() && y.is_zero() {
+        return Ok(G2Affine::identity());
+    }
+    let point = G2Affine::new_unchecked(x, y);
+    ensure!(point.is_on_curve(), "point is not on the curve");
+    Ok(point)
+}
@@ -1,6 +1,6 @@
 use crate::ProofBn254;
 use crate::{Groth16Bn254Proof, PlonkBn254Proof};
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, Context, Result};
 use sp1_core_machine::SP1_CIRCUIT_VERSION;
 use std::{io::Write, process::Command};
 
@@ -177,6 +177,121 @@ pub fn test_groth16_bn254(witness_json: &str, constraints_json: &str) {
     test(ProofSystem::Groth16, witness_json, constraints_json).expect("failed to test with docker");
 }
 
+/// Runs a ceremony command in docker with the given input files mounted, and copies its output
+/// file to `output_path`.
+fn ceremony_with_output(args: &[&str], mounts: &[(&str, &str)], output_path: &str) -> Result<()> {
+    let output_file = tempfile::NamedTempFile::new()?;
+    let mut mounts = mounts.to_vec();
+    mounts.push((output_file.path().to_str().context("invalid temporary path")?, "/output"));
+    assert_docker();
+    call_docker(args, &mounts)?;
+    std::fs::copy(output_file.path(), output_path)?;
+    Ok(())
+}
+
+/// Mounts each contribution at `/contributions/{i}` and returns the mounted paths.
+fn contribution_mounts<'a>(
+    contribution_paths: &[&'a str],
+    mounts: &mut Vec<(&'a str, String)>,
+) -> Vec<String> {
+    contribution_paths
+        .iter()
+        .enumerate()
+        .map(|(i, &path)| {
+            let dest = format!("/contributions/{i}");
+            mounts.push((path, dest.clone()));
+            dest
+        })
+        .collect()
+}
+
+/// Runs a ceremony verification in docker, which reports the result in an output file.
+fn ceremony_verify(args: &[&str], mounts: &[(&str, &str)]) -> Result<()> {
+    let output_file = tempfile::NamedTempFile::new()?;
+    let mut mounts = mounts.to_vec();
+    mounts.push((output_file.path().to_str().unwrap(), "/output"));
+    assert_docker();
+    call_docker(args, &mounts)?;
+    let result = std::fs::read_to_string(output_file.path())?;
+    if result == "OK" {
+        Ok(())
+    } else {
+        Err(anyhow!(result))
+    }
+}
+
+pub fn init_phase1_groth16_bn254(power: u32, output_path: &str) -> Result<()> {
+    ceremony_with_output(&["init-phase1", &power.to_string(), "/output"], &[], output_path)
+        .context("failed to initialize phase 1 with docker")
+}
+
+pub fn contribute_phase1_groth16_bn254(input_path: &str, output_path: &str) -> Result<()> {
+    ceremony_with_output(
+        &["contribute-phase1", "/input", "/output"],
+        &[(input_path, "/input")],
+        output_path,
+    )
+    .context("failed to contribute to phase 1 with docker")
+}
+
+pub fn verify_phase1_groth16_bn254(contribution_paths: &[&str]) -> Result<()> {
+    let mut mounts = Vec::new();
+    let contributions = contribution_mounts(contribution_paths, &mut mounts);
+    let mut args = vec!["verify-phase1", "/output"];
+    args.extend(contributions.iter().map(String::as_str));
+    let mounts = mounts.iter().map(|(src, dest)| (*src, dest.as_str())).collect::<Vec<_>>();
+    ceremony_verify(&args, &mounts)
+}
+
+pub fn init_phase2_groth16_bn254(
+    data_dir: &str,
+    phase1_path: &str,
+    output_path: &str,
+) -> Result<()> {
+    ceremony_with_output(
+        &["init-phase2", "/circuit", "/phase1", "/output"],
+        &[(data_dir, "/circuit"), (phase1_path, "/phase1")],
+        output_path,
+    )
+    .context("failed to initialize phase 2 with docker")
+}
+
+pub fn contribute_phase2_groth16_bn254(input_path: &str, output_path: &str) -> Result<()> {
+    ceremony_with_output(
+        &["contribute-phase2", "/input", "/output"],
+        &[(input_path, "/input")],
+        output_path,
+    )
+    .context("failed to contribute to phase 2 with docker")
+}
+
+pub fn verify_phase2_groth16_bn254(
+    data_dir: &str,
+    phase1_path: &str,
+    contribution_paths: &[&str],
+) -> Result<()> {
+    let mut mounts = vec![(data_dir, "/circuit".to_string()), (phase1_path, "/phase1".to_string())];
+    let contributions = contribution_mounts(contribution_paths, &mut mounts);
+    let mut args = vec!["verify-phase2", "/circuit", "/phase1", "/output"];
+    args.extend(contributions.iter().map(String::as_str));
+    let mounts = mounts.iter().map(|(src, dest)| (*src, dest.as_str())).collect::<Vec<_>>();
+    ceremony_verify(&args, &mounts)
+}
+
+pub fn finalize_groth16_bn254(
+    data_dir: &str,
+    phase1_path: &str,
+    contribution_paths: &[&str],
+) -> Result<()> {
+    let mut mounts = vec![(data_dir, "/circuit".to_string()), (phase1_path, "/phase1".to_string())];
+    let contributions = contribution_mounts(contribution_paths, &mut mounts);
+    let mut args = vec!["finalize", "/circuit", "/phase1"];
+    args.extend(contributions.iter().map(String::as_str));
+    let mounts = mounts.iter().map(|(src, dest)| (*src, dest.as_str())).collect::<Vec<_>>();
+    assert_docker();
+    call_docker(&args, &mounts).context("failed to finalize the ceremony with docker")
+}
+
 pub fn test_babybear_poseidon2() {
     unimplemented!()
 }
@@ -190,6 +190,113 @@ pub fn test_groth16_bn254(witness_json: &str, constraints_json: &str) {
     test(ProofSystem::Groth16, witness_json, constraints_json)
 }
 
+pub fn init_phase1_groth16_bn254(power: u32, output_path: &str) -> Result<(), String> {
+    let output_path = CString::new(output_path).expect("CString::new failed");
+    let err_ptr =
+        unsafe { bind::InitPhase1Groth16Bn254(power as i32, output_path.as_ptr() as *mut c_char) };
+    result_from_ptr(err_ptr)
+}
+
+pub fn contribute_phase1_groth16_bn254(input_path: &str, output_path: &str) -> Result<(), String> {
+    let input_path = CString::new(input_path).expect("CString::new failed");
+    let output_path = CString::new(output_path).expect("CString::new failed");
+    let err_ptr = unsafe {
+        bind::ContributePhase1Groth16Bn254(
+            input_path.as_ptr() as *mut c_char,
+            output_path.as_ptr() as *mut c_char,
+        )
+    };
+    result_from_ptr(err_ptr)
+}
+
+pub fn verify_phase1_groth16_bn254(contribution_paths: &[&str]) -> Result<(), String> {
+    let contribution_paths = paths_to_cstring(contribution_paths);
+    let err_ptr =
+        unsafe { bind::VerifyPhase1Groth16Bn254(contribution_paths.as_ptr() as *mut c_char) };
+    result_from_ptr(err_ptr)
+}
+
+pub fn init_phase2_groth16_bn254(
+    data_dir: &str,
+    phase1_path: &str,
+    output_path: &str,
+) -> Result<(), String> {
+    let data_dir = CString::new(data_dir).expect("CString::new failed");
+    let phase1_path = CString::new(phase1_path).expect("CString::new failed");
+    let output_path = CString::new(output_path).expect("CString::new failed");
+    let err_ptr = unsafe {
+        bind::InitPhase2Groth16Bn254(
+            data_dir.as_ptr() as *mut c_char,
+            phase1_path.as_ptr() as *mut c_char,
+            output_path.as_ptr() as *mut c_char,
+        )
+    };
+    result_from_ptr(err_ptr)
+}
+
+pub fn contribute_phase2_groth16_bn254(input_path: &str, output_path: &str) -> Result<(), String> {
+    let input_path = CString::new(input_path).expect("CString::new failed");
+    let output_path = CString::new(output_path).expect("CString::new failed");
+    let err_ptr = unsafe {
+        bind::ContributePhase2Groth16Bn254(
+            input_path.as_ptr() as *mut c_char,
+            output_path.as_ptr() as *mut c_char,
+        )
+    };
+    result_from_ptr(err_ptr)
+}
+
+pub fn verify_phase2_groth16_bn254(
+    data_dir: &str,
+    phase1_path: &str,
+    contribution_paths: &[&str],
+) -> Result<(), String> {
+    let data_dir = CString::new(data_dir).expect("CString::new failed");
+    let phase1_path = CString::new(phase1_path).expect("CString::new failed");
+    let contribution_paths = paths_to_cstring(contribution_paths);
+    let err_ptr = unsafe {
+        bind::VerifyPhase2Groth16Bn254(
+            data_dir.as_ptr() as *mut c_char,
+            phase1_path.as_ptr() as *mut c_char,
+            contribution_paths.as_ptr() as *mut c_char,
+        )
+    };
+    result_from_ptr(err_ptr)
+}
+
+pub fn finalize_groth16_bn254(
+    data_dir: &str,
+    phase1_path: &str,
+    contribution_paths: &[&str],
+) -> Result<(), String> {
+    let data_dir = CString::new(data_dir).expect("CString::new failed");
+    let phase1_path = CString::new(phase1_path).expect("CString::new failed");
+    let contribution_paths = paths_to_cstring(contribution_paths);
+    let err_ptr = unsafe {
+        bind::FinalizeGroth16Bn254(
+            data_dir.as_ptr() as *mut c_char,
+            phase1_path.as_ptr() as *mut c_char,
+            contribution_paths.as_ptr() as *mut c_char,
+        )
+    };
+    result_from_ptr(err_ptr)
+}
+
+/// Encodes a list of paths as a JSON array, which is how lists are passed to the Go code.
+fn paths_to_cstring(paths: &[&str]) -> CString {
+    CString::new(serde_json::to_string(paths).unwrap()).expect("CString::new failed")
+}
+
+/// Converts an error returned by the Go code, which is null on success, into a result.
+fn result_from_ptr(err_ptr: *mut c_char) -> Result<(), String> {
+    if err_ptr.is_null() {
+        Ok(())
+    } else {
+        // Safety: The error message is returned from the go code and is guaranteed to be valid.
+        unsafe { Err(ptr_to_string_freed(err_ptr)) }
+    }
+}
+
 pub fn test_babybear_poseidon2() {
     unsafe {
         let err_ptr = bind::TestPoseidonBabyBear2();
@@ -70,8 +70,13 @@ impl Groth16Bn254Prover {
         Self::modify_groth16_verifier(&groth16_verifier_path);
     }
 
-    /// Builds the Groth16 circuit locally.
-    pub fn build<C: Config>(constraints: Vec<Constraint>, witness: Witness<C>, build_dir: PathBuf) {
+    /// Writes the constraints and the template witness of the circuit to the build directory,
+    /// which is what the Go code compiles the circuit from.
+    pub fn write_circuit<C: Config>(
+        constraints: Vec<Constraint>,
+        witness: Witness<C>,
+        build_dir: &Path,
+    ) {
         let serialized = serde_json::to_string(&constraints).unwrap();
 
         // Write constraints.
@@ -85,6 +90,11 @@ impl Groth16Bn254Prover {
         let mut file = File::create(witness_path).unwrap();
         let serialized = serde_json::to_string(&gnark_witness).unwrap();
         file.write_all(serialized.as_bytes()).unwrap();
+    }
+
+    /// Builds the Groth16 circuit locally.
+    pub fn build<C: Config>(constraints: Vec<Constraint>, witness: Witness<C>, build_dir: PathBuf) {
+        Self::write_circuit(constraints, witness, &build_dir);
 
         // Build the circuit.
         build_groth16_bn254(build_dir.to_str().unwrap());
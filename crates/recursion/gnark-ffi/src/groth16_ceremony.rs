@@ -0,0 +1,153 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context, Result};
+
+use crate::{
+    ffi::{
+        contribute_phase1_groth16_bn254, contribute_phase2_groth16_bn254, finalize_groth16_bn254,
+        init_phase1_groth16_bn254, init_phase2_groth16_bn254, verify_phase1_groth16_bn254,
+        verify_phase2_groth16_bn254,
+    },
+    Groth16Bn254Prover,
+};
+
+/// A multi-party trusted-setup ceremony for the Groth16 circuit, using gnark's `mpcsetup`.
+///
+/// Phase 1 is a powers of tau ceremony independent of the circuit, and phase 2 is specific to the
+/// circuit written to the build directory with [`Groth16Bn254Prover::write_circuit`]. Each phase
+/// starts from initial parameters that every participant then contributes to in turn, writing a
+/// new file. Verification takes the initial parameters followed by every contribution, in order.
+///
+/// The keys are sound as long as one participant of each phase discarded their randomness. Every
+/// point of the keys comes from the contributions, which is possible because the Groth16 circuit
+/// has no commitments, so a circuit with commitments is rejected when initializing phase 2.
+#[derive(Debug, Clone)]
+pub struct Groth16Bn254Ceremony;
+
+impl Groth16Bn254Ceremony {
+    /// Writes the initial phase-1 parameters for circuits of up to `2^power` constraints.
+    pub fn init_phase1(power: u32, output: &Path) -> Result<()> {
+        init_phase1_groth16_bn254(power, path_str(output)?).map_err(|e| anyhow!("{e}"))
+    }
+
+    /// Adds a random contribution to the phase-1 parameters in `input`.
+    pub fn contribute_phase1(input: &Path, output: &Path) -> Result<()> {
+        contribute_phase1_groth16_bn254(path_str(input)?, path_str(output)?)
+            .map_err(|e| anyhow!("{e}"))
+    }
+
+    /// Verifies the phase-1 contributions, starting from the initial parameters.
+    pub fn verify_phase1(contributions: &[PathBuf]) -> Result<()> {
+        let contributions = paths_to_str(contributions)?;
+        verify_phase1_groth16_bn254(&contributions).map_err(|e| anyhow!("{e}"))
+    }
+
+    /// Writes the initial phase-2 parameters for the circuit in the build directory.
+    pub fn init_phase2(build_dir: &Path, phase1: &Path, output: &Path) -> Result<()> {
+        init_phase2_groth16_bn254(path_str(build_dir)?, path_str(phase1)?, path_str(output)?)
+            .map_err(|e| anyhow!("{e}"))
+    }
+
+    /// Adds a random contribution to the phase-2 parameters in `input`.
+    pub fn contribute_phase2(input: &Path, output: &Path) -> Result<()> {
+        contribute_phase2_groth16_bn254(path_str(input)?, path_str(output)?)
+            .map_err(|e| anyhow!("{e}"))
+    }
+
+    /// Verifies the phase-2 contributions, starting from the initial parameters, and checks that
+    /// they were derived from the circuit in the build directory and the final phase-1 parameters.
+    pub fn verify_phase2(build_dir: &Path, phase1: &Path, contributions: &[PathBuf]) -> Result<()> {
+        let contributions = paths_to_str(contributions)?;
+        verify_phase2_groth16_bn254(path_str(build_dir)?, path_str(phase1)?, &contributions)
+            .map_err(|e| anyhow!("{e}"))
+    }
+
+    /// Verifies the phase-2 contributions and writes the keys of the circuit to the build
+    /// directory, in the layout that [`Groth16Bn254Prover`] expects, along with the contracts.
+    pub fn finalize(build_dir: PathBuf, phase1: &Path, contributions: &[PathBuf]) -> Result<()> {
+        let paths = paths_to_str(contributions)?;
+        finalize_groth16_bn254(path_str(&build_dir)?, path_str(phase1)?, &paths)
+            .map_err(|e| anyhow!("{e}"))?;
+        Groth16Bn254Prover::build_contracts(build_dir);
+        Ok(())
+    }
+}
+
+fn path_str(path: &Path) -> Result<&str> {
+    path.to_str().with_context(|| format!("{} is not valid UTF-8", path.display()))
+}
+
+fn paths_to_str(paths: &[PathBuf]) -> Result<Vec<&str>> {
+    paths.iter().map(|path| path_str(path)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigUint;
+    use p3_baby_bear::BabyBear;
+    use p3_field::AbstractField;
+    use sp1_recursion_compiler::{
+        config::OuterConfig,
+        constraints::{Constraint, ConstraintOpcode},
+        ir::{Config, Witness},
+    };
+
+    use super::*;
+
+    /// Runs a ceremony with one contribution to each phase for a circuit checking `7 * 3 = 21`,
+    /// then proves and verifies the circuit with its keys.
+    ///
+    /// This runs the Go code, natively or in docker.
+    #[test]
+    #[ignore]
+    fn test_ceremony_round_trip() {
+        let constraint = |opcode: ConstraintOpcode, args: &[&[&str]]| Constraint {
+            opcode,
+            args: args.iter().map(|arg| arg.iter().map(|s| s.to_string()).collect()).collect(),
+        };
+        let constraints = vec![
+            constraint(ConstraintOpcode::WitnessF, &[&["a"], &["0"]]),
+            constraint(ConstraintOpcode::ImmF, &[&["b"], &["3"]]),
+            constraint(ConstraintOpcode::MulF, &[&["c"], &["a"], &["b"]]),
+            constraint(ConstraintOpcode::ImmF, &[&["d"], &["21"]]),
+            constraint(ConstraintOpcode::AssertEqF, &[&["c"], &["d"]]),
+            constraint(ConstraintOpcode::WitnessV, &[&["v0"], &["0"]]),
+            constraint(ConstraintOpcode::WitnessV, &[&["v1"], &["1"]]),
+            constraint(ConstraintOpcode::CommitVkeyHash, &[&["v0"]]),
+            constraint(ConstraintOpcode::CommitCommitedValuesDigest, &[&["v1"]]),
+        ];
+        let vkey_hash = <OuterConfig as Config>::N::from_canonical_u32(1);
+        let committed_values_digest = <OuterConfig as Config>::N::from_canonical_u32(2);
+        let witness = Witness::<OuterConfig> {
+            vars: vec![vkey_hash, committed_values_digest],
+            felts: vec![BabyBear::from_canonical_u32(7)],
+            exts: vec![],
+            vkey_hash,
+            committed_values_digest,
+        };
+
+        let dir = tempfile::tempdir().unwrap();
+        let build_dir = dir.path().join("circuit");
+        std::fs::create_dir(&build_dir).unwrap();
+        Groth16Bn254Prover::write_circuit(constraints, witness.clone(), &build_dir);
+        let path = |name: &str| dir.path().join(name);
+
+        let phase1 = [path("phase1_0"), path("phase1_1")];
+        Groth16Bn254Ceremony::init_phase1(10, &phase1[0]).unwrap();
+        Groth16Bn254Ceremony::contribute_phase1(&phase1[0], &phase1[1]).unwrap();
+        Groth16Bn254Ceremony::verify_phase1(&phase1).unwrap();
+
+        let phase2 = [path("phase2_0"), path("phase2_1")];
+        Groth16Bn254Ceremony::init_phase2(&build_dir, &phase1[1], &phase2[0]).unwrap();
+        Groth16Bn254Ceremony::contribute_phase2(&phase2[0], &phase2[1]).unwrap();
+        Groth16Bn254Ceremony::verify_phase2(&build_dir, &phase1[1], &phase2).unwrap();
+
+        // The contributions must apply to the final phase-1 parameters.
+        assert!(Groth16Bn254Ceremony::finalize(build_dir.clone(), &phase1[0], &phase2).is_err());
+        Groth16Bn254Ceremony::finalize(build_dir.clone(), &phase1[1], &phase2).unwrap();
+
+        let prover = Groth16Bn254Prover::new();
+        let proof = prover.prove(witness, build_dir.clone());
+        prover.verify(&proof, &BigUint::from(1u32), &BigUint::from(2u32), &build_dir);
+    }
+}
@@ -1,12 +1,16 @@
 mod babybear;
 
+#[cfg(feature = "ark")]
+pub mod ark;
 pub mod ffi;
 pub mod groth16_bn254;
+pub mod groth16_ceremony;
 pub mod plonk_bn254;
 pub mod proof;
 pub mod witness;
 
 pub use groth16_bn254::*;
+pub use groth16_ceremony::*;
 pub use plonk_bn254::*;
 pub use proof::*;
 pub use witness::*;
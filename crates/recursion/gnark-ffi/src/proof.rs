@@ -21,3 +21,11 @@ pub struct Groth16Bn254Proof {
     pub raw_proof: String,
     pub groth16_vkey_hash: [u8; 32],
 }
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct FflonkBn254Proof {
+    pub public_inputs: [String; 2],
+    pub encoded_proof: String,
+    pub raw_proof: String,
+    pub fflonk_vkey_hash: [u8; 32],
+}
@@ -5,9 +5,13 @@
 use std::path::PathBuf;
 
 use anyhow::{Context, Result};
+use sp1_stark::SecurityPreset;
 
 use crate::install::try_install_circuit_artifacts;
-pub use sp1_prover::build::build_plonk_bn254_artifacts_with_dummy;
+pub use sp1_prover::build::{
+    build_groth16_bn254_ceremony_circuit_with_dummy, build_plonk_bn254_artifacts_with_dummy,
+    Groth16Bn254Ceremony,
+};
 
 /// Exports the solidity verifier for PLONK proofs to the specified output directory.
 ///
@@ -16,7 +20,7 @@ pub use sp1_prover::build::build_plonk_bn254_artifacts_with_dummy;
 pub fn export_solidity_plonk_bn254_verifier(output_dir: impl Into<PathBuf>) -> Result<()> {
     let output_dir: PathBuf = output_dir.into();
     let artifacts_dir = if sp1_prover::build::sp1_dev_mode() {
-        sp1_prover::build::plonk_bn254_artifacts_dev_dir()
+        sp1_prover::build::plonk_bn254_artifacts_dev_dir(SecurityPreset::Standard)
     } else {
         try_install_circuit_artifacts("plonk")
     };
@@ -45,7 +49,7 @@ pub fn export_solidity_plonk_bn254_verifier(output_dir: impl Into<PathBuf>) -> R
 pub fn export_solidity_groth16_bn254_verifier(output_dir: impl Into<PathBuf>) -> Result<()> {
     let output_dir: PathBuf = output_dir.into();
     let artifacts_dir = if sp1_prover::build::sp1_dev_mode() {
-        sp1_prover::build::groth16_bn254_artifacts_dev_dir()
+        sp1_prover::build::groth16_bn254_artifacts_dev_dir(SecurityPreset::Standard)
     } else {
         try_install_circuit_artifacts("groth16")
     };
@@ -66,3 +70,33 @@ pub fn export_solidity_groth16_bn254_verifier(output_dir: impl Into<PathBuf>) ->
 
     Ok(())
 }
+
+/// Exports the solidity verifier for fflonk proofs to the specified output directory.
+///
+/// The verifier consists of `SP1VerifierFflonk.sol` and the `FflonkVerifier.sol` contract it
+/// inherits from, both generated when the fflonk artifacts are built.
+///
+/// WARNING: This function assumes that the fflonk artifacts have already been built, as there are
+/// no released fflonk artifacts.
+pub fn export_solidity_fflonk_bn254_verifier(output_dir: impl Into<PathBuf>) -> Result<()> {
+    let output_dir: PathBuf = output_dir.into();
+    let artifacts_dir = sp1_prover::build::fflonk_bn254_artifacts_dir(SecurityPreset::Standard);
+
+    std::fs::create_dir_all(&output_dir).context("Failed to create output directory.")?;
+    for file in ["SP1VerifierFflonk.sol", "FflonkVerifier.sol"] {
+        let verifier_path = artifacts_dir.join(file);
+        if !verifier_path.exists() {
+            return Err(anyhow::anyhow!("verifier file not found at {:?}", verifier_path));
+        }
+
+        let output_path = output_dir.join(file);
+        std::fs::copy(&verifier_path, &output_path).context("Failed to copy verifier file.")?;
+        tracing::info!(
+            "exported verifier from {} to {}",
+            verifier_path.display(),
+            output_path.display()
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,187 @@
+//! # SP1 Key Cache
+//!
+//! An on-disk cache of the proving and verifying keys of programs, keyed by the hash of the ELF,
+//! the circuit version, the security preset and whether the core shapes are fixed.
+//!
+//! Entries are never evicted. The cache can be disabled with
+//! [`CpuProverBuilder::key_cache`](crate::cpu::builder::CpuProverBuilder::key_cache), or for all
+//! the provers with the `SP1_DISABLE_KEY_CACHE` environment variable.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use anyhow::{ensure, Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sp1_prover::{SP1ProvingKey, SP1VerifyingKey};
+use sp1_stark::SecurityPreset;
+
+use crate::SP1_CIRCUIT_VERSION;
+
+/// The directory where the proving and verifying keys are cached.
+///
+/// # Details
+/// Defaults to `~/.sp1/keys/<circuit version>`. The parent of the versioned directory can be set
+/// with the `SP1_KEY_CACHE_DIR` environment variable.
+#[must_use]
+pub fn key_cache_dir() -> PathBuf {
+    let base = match std::env::var("SP1_KEY_CACHE_DIR") {
+        Ok(dir) => PathBuf::from(dir),
+        Err(_) => dirs::home_dir().unwrap().join(".sp1").join("keys"),
+    };
+    base.join(SP1_CIRCUIT_VERSION)
+}
+
+/// An entry of the key cache.
+#[derive(Serialize, Deserialize)]
+struct CachedKeys {
+    version: String,
+    elf_hash: [u8; 32],
+    fixed_core_shapes: bool,
+    keys_hash: [u8; 32],
+    keys: Vec<u8>,
+}
+
+/// Returns the keys of the program, generating them with `setup` if they are not cached.
+///
+/// # Details
+/// The preprocessed traces of the keys are padded to the core shapes when they are fixed, which
+/// `FIX_CORE_SHAPES=false` disables, so `fixed_core_shapes` must tell whether `setup` fixes them.
+///
+/// Caching is skipped if `SP1_DISABLE_KEY_CACHE` is set. The cache is best-effort: if an entry
+/// cannot be read, fails its integrity check, or cannot be written, the keys are regenerated.
+pub(crate) fn cached_setup(
+    elf: &[u8],
+    security_preset: SecurityPreset,
+    fixed_core_shapes: bool,
+    setup: impl FnOnce() -> (SP1ProvingKey, SP1VerifyingKey),
+) -> (SP1ProvingKey, SP1VerifyingKey) {
+    let disabled = std::env::var("SP1_DISABLE_KEY_CACHE")
+        .map(|v| v == "1" || v.to_lowercase() == "true")
+        .unwrap_or(false);
+    if disabled {
+        return setup();
+    }
+    cached_setup_in(&key_cache_dir(), elf, security_preset, fixed_core_shapes, setup)
+}
+
+fn cached_setup_in(
+    dir: &Path,
+    elf: &[u8],
+    security_preset: SecurityPreset,
+    fixed_core_shapes: bool,
+    setup: impl FnOnce() -> (SP1ProvingKey, SP1VerifyingKey),
+) -> (SP1ProvingKey, SP1VerifyingKey) {
+    let elf_hash: [u8; 32] = Sha256::digest(elf).into();
+    // The keys depend on the FRI parameters and on the core shapes, so each preset other than the
+    // standard one, and keys without fixed shapes, have their own entries.
+    let mut name = hex::encode(elf_hash);
+    if security_preset != SecurityPreset::Standard {
+        name.push_str(&format!("-{security_preset}"));
+    }
+    if !fixed_core_shapes {
+        name.push_str("-unfixed-shapes");
+    }
+    let path = dir.join(format!("{name}.bin"));
+
+    if path.exists() {
+        match load(&path, elf, elf_hash, fixed_core_shapes) {
+            Ok(keys) => {
+                tracing::debug!("loaded keys from {}", path.display());
+                return keys;
+            }
+            Err(e) => tracing::warn!("ignoring cached keys at {}: {e}", path.display()),
+        }
+    }
+
+    let keys = setup();
+    if let Err(e) = store(dir, &path, elf_hash, fixed_core_shapes, &keys) {
+        tracing::warn!("failed to cache keys at {}: {e}", path.display());
+    }
+    keys
+}
+
+fn load(
+    path: &Path,
+    elf: &[u8],
+    elf_hash: [u8; 32],
+    fixed_core_shapes: bool,
+) -> Result<(SP1ProvingKey, SP1VerifyingKey)> {
+    let bytes = std::fs::read(path)?;
+    let entry: CachedKeys = bincode::deserialize(&bytes).context("invalid cache entry")?;
+    ensure!(entry.version == SP1_CIRCUIT_VERSION, "circuit version {} mismatch", entry.version);
+    ensure!(entry.elf_hash == elf_hash, "program hash mismatch");
+    ensure!(entry.fixed_core_shapes == fixed_core_shapes, "core shapes mismatch");
+    let keys_hash: [u8; 32] = Sha256::digest(&entry.keys).into();
+    ensure!(entry.keys_hash == keys_hash, "keys hash mismatch");
+
+    let (pk, vk): (SP1ProvingKey, SP1VerifyingKey) = bincode::deserialize(&entry.keys)?;
+    ensure!(pk.elf == elf, "program mismatch");
+    Ok((pk, vk))
+}
+
+fn store(
+    dir: &Path,
+    path: &Path,
+    elf_hash: [u8; 32],
+    fixed_core_shapes: bool,
+    keys: &(SP1ProvingKey, SP1VerifyingKey),
+) -> Result<()> {
+    let keys = bincode::serialize(keys)?;
+    let entry = CachedKeys {
+        version: SP1_CIRCUIT_VERSION.to_string(),
+        elf_hash,
+        fixed_core_shapes,
+        keys_hash: Sha256::digest(&keys).into(),
+        keys,
+    };
+
+    // Write to a temporary file first, so that concurrent readers never see a partial entry.
+    std::fs::create_dir_all(dir)?;
+    let mut file = tempfile::NamedTempFile::new_in(dir)?;
+    file.write_all(&bincode::serialize(&entry)?)?;
+    file.persist(path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use sp1_prover::{components::CpuProverComponents, HashableKey, SP1Prover};
+
+    use super::*;
+
+    #[test]
+    fn test_cached_setup() {
+        let dir = tempfile::tempdir().unwrap();
+        let elf = test_artifacts::FIBONACCI_ELF;
+        let prover = SP1Prover::<CpuProverComponents>::new();
+
+        // The first setup generates the keys, the second one loads them.
+        let preset = SecurityPreset::Standard;
+        let (_, vk) = cached_setup_in(dir.path(), elf, preset, true, || prover.setup(elf));
+        let (pk, cached_vk) = cached_setup_in(dir.path(), elf, preset, true, || {
+            panic!("keys should have been cached")
+        });
+        assert_eq!(pk.elf, elf);
+        assert_eq!(cached_vk.bytes32(), vk.bytes32());
+
+        // A corrupted entry is regenerated.
+        let path = std::fs::read_dir(dir.path()).unwrap().next().unwrap().unwrap().path();
+        let mut bytes = std::fs::read(&path).unwrap();
+        let last = bytes.len() - 1;
+        bytes[last] ^= 1;
+        std::fs::write(&path, bytes).unwrap();
+        let (_, regenerated_vk) =
+            cached_setup_in(dir.path(), elf, preset, true, || prover.setup(elf));
+        assert_eq!(regenerated_vk.bytes32(), vk.bytes32());
+
+        // Keys without fixed core shapes do not share the entry of the keys with fixed shapes.
+        let mut generated = false;
+        cached_setup_in(dir.path(), elf, preset, false, || {
+            generated = true;
+            prover.setup(elf)
+        });
+        assert!(generated, "keys without fixed core shapes should not have been cached");
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 2);
+    }
+}
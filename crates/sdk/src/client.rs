@@ -6,6 +6,7 @@ use crate::cpu::builder::CpuProverBuilder;
 use crate::cuda::builder::CudaProverBuilder;
 use crate::env::EnvProver;
 use crate::network::builder::NetworkProverBuilder;
+use crate::SecurityPreset;
 
 /// An entrypoint for interacting with the prover for the SP1 RISC-V zkVM.
 pub struct ProverClient;
@@ -76,7 +77,7 @@ impl ProverClientBuilder {
     /// ```
     #[must_use]
     pub fn mock(&self) -> CpuProverBuilder {
-        CpuProverBuilder { mock: true }
+        CpuProverBuilder { mock: true, security_preset: SecurityPreset::default(), key_cache: true }
     }
 
     /// Builds a [`CpuProver`] specifically for local CPU proving.
@@ -94,7 +95,11 @@ impl ProverClientBuilder {
     /// ```
     #[must_use]
     pub fn cpu(&self) -> CpuProverBuilder {
-        CpuProverBuilder { mock: false }
+        CpuProverBuilder {
+            mock: false,
+            security_preset: SecurityPreset::default(),
+            key_cache: true,
+        }
     }
 
     /// Builds a [`CudaProver`] specifically for local proving on NVIDIA GPUs.
@@ -2,6 +2,9 @@
 //!
 //! This module provides a builder for the [`CpuProver`].
 
+use sp1_prover::{components::CpuProverComponents, SP1Prover, SP1RecursionProverError};
+use sp1_stark::SecurityPreset;
+
 use super::CpuProver;
 
 /// A builder for the [`CpuProver`].
@@ -9,15 +12,59 @@ use super::CpuProver;
 /// The builder is used to configure the [`CpuProver`] before it is built.
 pub struct CpuProverBuilder {
     pub(crate) mock: bool,
+    pub(crate) security_preset: SecurityPreset,
+    pub(crate) key_cache: bool,
 }
 
 impl CpuProverBuilder {
+    /// Sets the FRI parameters of the prover.
+    ///
+    /// # Details
+    /// Presets other than [`SecurityPreset::Standard`] trade proof size against prover time. Their
+    /// circuit artifacts are built locally, and verifying their proofs requires a prover with the
+    /// same preset.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use sp1_sdk::{ProverClient, SecurityPreset};
+    ///
+    /// let prover =
+    ///     ProverClient::builder().cpu().security_preset(SecurityPreset::Conjectured100).build();
+    /// ```
+    #[must_use]
+    pub fn security_preset(mut self, security_preset: SecurityPreset) -> Self {
+        self.security_preset = security_preset;
+        self
+    }
+
+    /// Sets whether the keys generated by [`Prover::setup`](crate::Prover::setup) are cached on
+    /// disk, which they are by default.
+    ///
+    /// # Details
+    /// The cache lives in [`key_cache_dir`](crate::cache::key_cache_dir) and is never evicted. It can also
+    /// be disabled for all the provers with `SP1_DISABLE_KEY_CACHE=true`.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use sp1_sdk::ProverClient;
+    ///
+    /// let prover = ProverClient::builder().cpu().key_cache(false).build();
+    /// ```
+    #[must_use]
+    pub fn key_cache(mut self, enabled: bool) -> Self {
+        self.key_cache = enabled;
+        self
+    }
+
     /// Builds a [`CpuProver`].
     ///
     /// # Details
     /// This method will build a [`CpuProver`] with the given parameters. In particular, it will
     /// build a mock prover if the `mock` flag is set.
     ///
+    /// # Panics
+    /// Panics if the prover cannot be initialized, see [`CpuProverBuilder::try_build`].
+    ///
     /// # Example
     /// ```rust,no_run
     /// use sp1_sdk::ProverClient;
@@ -26,10 +73,27 @@ impl CpuProverBuilder {
     /// ```
     #[must_use]
     pub fn build(self) -> CpuProver {
-        if self.mock {
-            CpuProver::mock()
-        } else {
-            CpuProver::new()
-        }
+        self.try_build().expect("failed to initialize the prover")
+    }
+
+    /// Builds a [`CpuProver`], returning an error if it cannot be initialized.
+    ///
+    /// # Details
+    /// With `VERIFY_VK=true`, a preset other than [`SecurityPreset::Standard`] requires its vk map,
+    /// which is generated with `build_compress_vks --security-preset <preset>`.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use sp1_sdk::{ProverClient, SecurityPreset};
+    ///
+    /// let prover = ProverClient::builder()
+    ///     .cpu()
+    ///     .security_preset(SecurityPreset::Conjectured100)
+    ///     .try_build()
+    ///     .unwrap();
+    /// ```
+    pub fn try_build(self) -> Result<CpuProver, SP1RecursionProverError> {
+        let prover = SP1Prover::<CpuProverComponents>::with_security_preset(self.security_preset)?;
+        Ok(CpuProver { prover, mock: self.mock, key_cache: self.key_cache })
     }
 }
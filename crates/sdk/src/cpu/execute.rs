@@ -7,6 +7,12 @@ use sp1_core_executor::{ExecutionReport, HookEnv, SP1ContextBuilder};
 use sp1_core_machine::io::SP1Stdin;
 use sp1_primitives::io::SP1PublicValues;
 use sp1_prover::{components::CpuProverComponents, SP1Prover};
+#[cfg(feature = "profiling")]
+use {
+    sp1_core_executor::{Executor, Profile},
+    sp1_stark::SP1CoreOpts,
+    std::sync::Arc,
+};
 
 /// A builder for simulating the execution of a program on the CPU.
 ///
@@ -135,4 +141,74 @@ impl<'a> CpuExecuteBuilder<'a> {
         let context = context_builder.build();
         Ok(prover.execute(elf, &stdin, context)?)
     }
+
+    /// Executes the program on the input with the built arguments under the profiler, sampling the
+    /// call stack every `sample_rate` cycles.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use sp1_sdk::{ProverClient, SP1Stdin, Prover};
+    ///
+    /// let elf = &[1, 2, 3];
+    /// let stdin = SP1Stdin::new();
+    ///
+    /// let client = ProverClient::builder().cpu().build();
+    /// let (_, _, profile) = client.execute(elf, &stdin).run_profiled(1).unwrap();
+    /// println!("{}", profile.folded());
+    /// ```
+    #[cfg(feature = "profiling")]
+    pub fn run_profiled(
+        self,
+        sample_rate: u64,
+    ) -> Result<(SP1PublicValues, ExecutionReport, Profile)> {
+        let Self { prover, elf, stdin, mut context_builder } = self;
+        let mut context = context_builder.build();
+        context.subproof_verifier.replace(Arc::new(prover));
+
+        let mut runtime = Executor::with_context_and_elf(SP1CoreOpts::default(), context, elf);
+        runtime.enable_profiler(elf, sample_rate)?;
+        runtime.write_vecs(&stdin.buffer);
+        for (proof, vkey) in stdin.proofs.iter() {
+            runtime.write_proof(proof.clone(), vkey.clone());
+        }
+        runtime.run_fast()?;
+
+        let profile = runtime.take_profile().expect("the profiler is enabled");
+        Ok((SP1PublicValues::from(&runtime.state.public_values_stream), runtime.report, profile))
+    }
+}
+
+#[cfg(feature = "async")]
+impl CpuExecuteBuilder<'static> {
+    /// Executes the program on the input with the built arguments asynchronously.
+    ///
+    /// # Details
+    /// The program is executed on the blocking thread pool of the tokio runtime, so the other tasks
+    /// of the runtime keep making progress while executing. See [`Self::run`] for more details.
+    ///
+    /// The builder is moved to the blocking thread, so the prover, the ELF and the hooks must live
+    /// for `'static`, for example in a [`std::sync::OnceLock`]. A prover shared through an
+    /// [`std::sync::Arc`] can use [`crate::AsyncProver`] instead.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use std::sync::OnceLock;
+    /// use sp1_sdk::{CpuProver, ProverClient, SP1Stdin, Prover};
+    ///
+    /// static CLIENT: OnceLock<CpuProver> = OnceLock::new();
+    ///
+    /// let client = CLIENT.get_or_init(|| ProverClient::builder().cpu().build());
+    ///
+    /// tokio_test::block_on(async {
+    ///     let elf = &[1, 2, 3];
+    ///     let stdin = SP1Stdin::new();
+    ///     let (public_values, execution_report) = client.execute(elf, &stdin)
+    ///         .run_async()
+    ///         .await
+    ///         .unwrap();
+    /// })
+    /// ```
+    pub async fn run_async(self) -> Result<(SP1PublicValues, ExecutionReport)> {
+        crate::utils::spawn_blocking(move || self.run()).await
+    }
 }
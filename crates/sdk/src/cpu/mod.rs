@@ -5,6 +5,7 @@
 pub mod builder;
 pub mod execute;
 pub mod prove;
+pub mod test;
 
 use anyhow::Result;
 use execute::CpuExecuteBuilder;
@@ -13,18 +14,24 @@ use p3_baby_bear::BabyBear;
 use p3_field::{extension::BinomialExtensionField, AbstractField, PrimeField};
 use p3_fri::{FriProof, TwoAdicFriPcsProof};
 use prove::CpuProveBuilder;
-use sp1_core_executor::{SP1Context, SP1ContextBuilder, SP1ReduceProof};
+use sp1_core_executor::{ProofStage, ProveMonitor, SP1Context, SP1ContextBuilder, SP1ReduceProof};
 use sp1_core_machine::io::SP1Stdin;
 use sp1_prover::{
+    build::use_dev_artifacts,
     components::CpuProverComponents,
-    verify::{verify_groth16_bn254_public_inputs, verify_plonk_bn254_public_inputs},
-    Groth16Bn254Proof, HashableKey, PlonkBn254Proof, SP1CoreProofData, SP1ProofWithMetadata,
-    SP1Prover,
+    foreign::ForeignMachine,
+    verify::{
+        verify_fflonk_bn254_public_inputs, verify_groth16_bn254_public_inputs,
+        verify_plonk_bn254_public_inputs,
+    },
+    FflonkBn254Proof, Groth16Bn254Proof, HashableKey, InnerSC, PlonkBn254Proof, SP1CoreProofData,
+    SP1ProofWithMetadata, SP1Prover, SP1RecursionProverError,
 };
 use sp1_stark::{
     SP1CoreOpts, SP1ProverOpts, ShardCommitment, ShardOpenedValues, ShardProof, StarkVerifyingKey,
 };
 
+use crate::cache::cached_setup;
 use crate::install::try_install_circuit_artifacts;
 use crate::prover::verify_proof;
 use crate::SP1VerificationError;
@@ -36,6 +43,7 @@ use crate::{
 pub struct CpuProver {
     pub(crate) prover: SP1Prover<CpuProverComponents>,
     pub(crate) mock: bool,
+    pub(crate) key_cache: bool,
 }
 
 impl CpuProver {
@@ -48,7 +56,7 @@ impl CpuProver {
     /// Creates a new [`CpuProver`] in mock mode.
     #[must_use]
     pub fn mock() -> Self {
-        Self { prover: SP1Prover::new(), mock: true }
+        Self { prover: SP1Prover::new(), mock: true, key_cache: true }
     }
 
     /// Creates a new [`CpuExecuteBuilder`] for simulating the execution of a program on the CPU.
@@ -109,6 +117,38 @@ impl CpuProver {
         }
     }
 
+    /// Registers a foreign machine, so that its proofs for the given verifying keys can be
+    /// written to the stdin as deferred proofs and checked by the program with `verify_sp1_proof`.
+    ///
+    /// # Details
+    /// A proof of a foreign machine is written with [`SP1Stdin::write_proof`], as an
+    /// [`SP1ReduceProof`] holding its verifying key and shard proof, along with the same
+    /// verifying key. See [`SP1Prover::register_foreign_machine`] for the digests the program
+    /// passes to `verify_sp1_proof`.
+    pub fn register_foreign_machine<'a>(
+        &mut self,
+        machine: impl ForeignMachine + 'static,
+        vks: impl IntoIterator<Item = &'a StarkVerifyingKey<InnerSC>>,
+    ) {
+        self.prover.register_foreign_machine(machine, vks);
+    }
+
+    /// Adds verifying keys of recursion programs, such as the programs verifying foreign proofs,
+    /// to the allowed vk map.
+    ///
+    /// # Details
+    /// This changes the vk root, so Groth16 and Plonk proofs require circuit artifacts built
+    /// locally with `SP1_DEV=true`, and proving with the released artifacts fails. See
+    /// [`SP1Prover::extend_allowed_vks`].
+    pub fn extend_allowed_vks(&mut self, vk_digests: impl IntoIterator<Item = [BabyBear; 8]>) {
+        self.prover.extend_allowed_vks(vk_digests);
+    }
+
+    /// The default [`SP1ProverOpts`] for the security preset of the prover.
+    pub(crate) fn default_opts(&self) -> SP1ProverOpts {
+        SP1ProverOpts::default().with_security_preset(self.prover.security_preset)
+    }
+
     pub(crate) fn prove_impl<'a>(
         &'a self,
         pk: &SP1ProvingKey,
@@ -122,7 +162,26 @@ impl CpuProver {
             return self.mock_prove_impl(pk, stdin.clone(), mode);
         }
 
+        // Fail before proving if the fflonk backend is not compiled in.
+        #[cfg(not(feature = "fflonk"))]
+        if mode == SP1ProofMode::Fflonk {
+            anyhow::bail!("fflonk proofs require the `fflonk` feature of `sp1-sdk`");
+        }
+
+        // Fail before proving if the released circuit artifacts would be used with an extended
+        // vk map, since they are built for the default vk root.
+        if matches!(mode, SP1ProofMode::Groth16 | SP1ProofMode::Plonk)
+            && self.prover.vk_map_extended
+            && !use_dev_artifacts(self.prover.security_preset)
+        {
+            anyhow::bail!(
+                "the released {mode:?} artifacts do not verify proofs with an extended vk map, \
+                 build the circuit artifacts locally with `SP1_DEV=true`"
+            );
+        }
+
         // Generate the core proof.
+        let monitor = context.monitor.clone();
         let proof: SP1ProofWithMetadata<SP1CoreProofData> =
             self.prover.prove_core(pk, stdin, opts, context)?;
         if mode == SP1ProofMode::Core {
@@ -137,7 +196,8 @@ impl CpuProver {
         let deferred_proofs =
             stdin.proofs.iter().map(|(reduce_proof, _)| reduce_proof.clone()).collect();
         let public_values = proof.public_values.clone();
-        let reduce_proof = self.prover.compress(&pk.vk, proof, deferred_proofs, opts)?;
+        let reduce_proof =
+            self.prover.compress_with_monitor(&pk.vk, proof, deferred_proofs, opts, &monitor)?;
         if mode == SP1ProofMode::Compressed {
             return Ok(SP1ProofWithPublicValues {
                 proof: SP1Proof::Compressed(Box::new(reduce_proof)),
@@ -147,18 +207,22 @@ impl CpuProver {
         }
 
         // Generate the shrink proof.
+        Self::enter_stage(&monitor, ProofStage::Shrink)?;
         let compress_proof = self.prover.shrink(reduce_proof, opts)?;
 
         // Generate the wrap proof.
+        Self::enter_stage(&monitor, ProofStage::Wrap)?;
         let outer_proof = self.prover.wrap_bn254(compress_proof, opts)?;
 
         // Generate the gnark proof.
+        Self::enter_stage(&monitor, ProofStage::Snark)?;
         match mode {
             SP1ProofMode::Groth16 => {
-                let groth16_bn254_artifacts = if sp1_prover::build::sp1_dev_mode() {
+                let groth16_bn254_artifacts = if use_dev_artifacts(self.prover.security_preset) {
                     sp1_prover::build::try_build_groth16_bn254_artifacts_dev(
                         &outer_proof.vk,
                         &outer_proof.proof,
+                        self.prover.security_preset,
                     )
                 } else {
                     try_install_circuit_artifacts("groth16")
@@ -172,10 +236,11 @@ impl CpuProver {
                 });
             }
             SP1ProofMode::Plonk => {
-                let plonk_bn254_artifacts = if sp1_prover::build::sp1_dev_mode() {
+                let plonk_bn254_artifacts = if use_dev_artifacts(self.prover.security_preset) {
                     sp1_prover::build::try_build_plonk_bn254_artifacts_dev(
                         &outer_proof.vk,
                         &outer_proof.proof,
+                        self.prover.security_preset,
                     )
                 } else {
                     try_install_circuit_artifacts("plonk")
@@ -187,10 +252,36 @@ impl CpuProver {
                     sp1_version: self.version().to_string(),
                 });
             }
+            #[cfg(feature = "fflonk")]
+            SP1ProofMode::Fflonk => {
+                let fflonk_bn254_artifacts = sp1_prover::build::try_build_fflonk_bn254_artifacts(
+                    &outer_proof.vk,
+                    &outer_proof.proof,
+                    self.prover.security_preset,
+                )?;
+                let proof = self.prover.wrap_fflonk_bn254(outer_proof, &fflonk_bn254_artifacts)?;
+                return Ok(SP1ProofWithPublicValues {
+                    proof: SP1Proof::Fflonk(proof),
+                    public_values,
+                    sp1_version: self.version().to_string(),
+                });
+            }
             _ => unreachable!(),
         }
     }
 
+    /// Reports a new stage to the monitor, unless the proof was cancelled.
+    pub(crate) fn enter_stage(
+        monitor: &ProveMonitor,
+        stage: ProofStage,
+    ) -> Result<(), SP1RecursionProverError> {
+        if monitor.is_cancelled() {
+            return Err(SP1RecursionProverError::Cancelled);
+        }
+        monitor.stage(stage);
+        Ok(())
+    }
+
     #[allow(clippy::needless_pass_by_value)]
     pub(crate) fn mock_prove_impl(
         &self,
@@ -282,6 +373,22 @@ impl CpuProver {
                     sp1_version: self.version().to_string(),
                 })
             }
+            SP1ProofMode::Fflonk => {
+                let (public_values, _) = self.prover.execute(&pk.elf, &stdin, context)?;
+                Ok(SP1ProofWithPublicValues {
+                    proof: SP1Proof::Fflonk(FflonkBn254Proof {
+                        public_inputs: [
+                            pk.vk.hash_bn254().as_canonical_biguint().to_string(),
+                            public_values.hash_bn254().to_string(),
+                        ],
+                        encoded_proof: String::new(),
+                        raw_proof: String::new(),
+                        fflonk_vkey_hash: [0; 32],
+                    }),
+                    public_values,
+                    sp1_version: self.version().to_string(),
+                })
+            }
         }
     }
 
@@ -298,6 +405,10 @@ impl CpuProver {
                 verify_groth16_bn254_public_inputs(vkey, &bundle.public_values, public_inputs)
                     .map_err(SP1VerificationError::Groth16)
             }
+            SP1Proof::Fflonk(FflonkBn254Proof { public_inputs, .. }) => {
+                verify_fflonk_bn254_public_inputs(vkey, &bundle.public_values, public_inputs)
+                    .map_err(SP1VerificationError::Fflonk)
+            }
             _ => Ok(()),
         }
     }
@@ -305,7 +416,11 @@ impl CpuProver {
 
 impl Prover<CpuProverComponents> for CpuProver {
     fn setup(&self, elf: &[u8]) -> (SP1ProvingKey, SP1VerifyingKey) {
-        self.prover.setup(elf)
+        if !self.key_cache {
+            return self.prover.setup(elf);
+        }
+        let fixed_core_shapes = self.prover.core_shape_config.is_some();
+        cached_setup(elf, self.prover.security_preset, fixed_core_shapes, || self.prover.setup(elf))
     }
 
     fn inner(&self) -> &SP1Prover<CpuProverComponents> {
@@ -318,7 +433,18 @@ impl Prover<CpuProverComponents> for CpuProver {
         stdin: &SP1Stdin,
         mode: SP1ProofMode,
     ) -> Result<SP1ProofWithPublicValues> {
-        self.prove_impl(pk, stdin, SP1ProverOpts::default(), SP1Context::default(), mode)
+        self.prove_impl(pk, stdin, self.default_opts(), SP1Context::default(), mode)
+    }
+
+    fn prove_with_monitor(
+        &self,
+        pk: &SP1ProvingKey,
+        stdin: &SP1Stdin,
+        mode: SP1ProofMode,
+        monitor: &ProveMonitor,
+    ) -> Result<SP1ProofWithPublicValues> {
+        let context = SP1Context { monitor: monitor.clone(), ..SP1Context::default() };
+        self.prove_impl(pk, stdin, self.default_opts(), context, mode)
     }
 
     fn verify(
@@ -337,6 +463,6 @@ impl Prover<CpuProverComponents> for CpuProver {
 impl Default for CpuProver {
     fn default() -> Self {
         let prover = SP1Prover::new();
-        Self { prover, mock: false }
+        Self { prover, mock: false, key_cache: true }
     }
 }
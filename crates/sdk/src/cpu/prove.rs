@@ -3,7 +3,7 @@
 //! This module provides a builder for proving a program on the CPU.
 
 use anyhow::Result;
-use sp1_core_executor::SP1ContextBuilder;
+use sp1_core_executor::{CancellationToken, ProveProgress, SP1ContextBuilder};
 use sp1_core_machine::io::SP1Stdin;
 use sp1_prover::SP1ProvingKey;
 use sp1_stark::{SP1CoreOpts, SP1ProverOpts};
@@ -130,6 +130,32 @@ impl<'a> CpuProveBuilder<'a> {
         self
     }
 
+    /// Set the proof mode to [`SP1ProofKind::Fflonk`] mode.
+    ///
+    /// # Details
+    /// This mode produces an fflonk proof that can be verified on chain for less gas than a PLONK
+    /// proof, without a circuit-specific trusted setup. It requires the `fflonk` feature, `snarkjs`
+    /// and a powers of tau file set with `SP1_FFLONK_PTAU`.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use sp1_sdk::{ProverClient, SP1Stdin, include_elf, Prover};
+    ///
+    /// let elf = &[1, 2, 3];
+    /// let stdin = SP1Stdin::new();
+    ///
+    /// let client = ProverClient::builder().cpu().build();
+    /// let (pk, vk) = client.setup(elf);
+    /// let builder = client.prove(&pk, &stdin)
+    ///     .fflonk()
+    ///     .run();
+    /// ```
+    #[must_use]
+    pub fn fflonk(mut self) -> Self {
+        self.mode = SP1ProofMode::Fflonk;
+        self
+    }
+
     /// Set the proof mode to the given [`SP1ProofKind`].
     ///
     /// # Details
@@ -257,6 +283,68 @@ impl<'a> CpuProveBuilder<'a> {
         self
     }
 
+    /// Set a callback invoked with the progress of the proof.
+    ///
+    /// # Details
+    /// The callback receives a [`ProveProgress`] snapshot every time a shard is executed or
+    /// proven, a node of the compress tree is proven, or the proof enters a new [`sp1_core_executor::ProofStage`].
+    /// The callback is invoked from the worker threads of the prover, so it should return quickly.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use sp1_sdk::{ProverClient, SP1Stdin, Prover};
+    ///
+    /// let elf = &[1, 2, 3];
+    /// let stdin = SP1Stdin::new();
+    ///
+    /// let client = ProverClient::builder().cpu().build();
+    /// let (pk, vk) = client.setup(elf);
+    /// let proof = client.prove(&pk, &stdin)
+    ///     .on_progress(|progress| println!("{progress:?}"))
+    ///     .run();
+    /// ```
+    #[must_use]
+    pub fn on_progress(
+        mut self,
+        observer: impl Fn(&ProveProgress) + Send + Sync + 'static,
+    ) -> Self {
+        self.context_builder.progress_observer(observer);
+        self
+    }
+
+    /// Set the token used to cancel the proof.
+    ///
+    /// # Details
+    /// Once the token is cancelled, the prover stops its worker threads and [`Self::run`] returns
+    /// an error.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use sp1_sdk::{ProverClient, SP1Stdin, Prover, CancellationToken};
+    ///
+    /// let elf = &[1, 2, 3];
+    /// let stdin = SP1Stdin::new();
+    ///
+    /// let client = ProverClient::builder().cpu().build();
+    /// let (pk, vk) = client.setup(elf);
+    /// let token = CancellationToken::new();
+    /// std::thread::spawn({
+    ///     let token = token.clone();
+    ///     move || {
+    ///         std::thread::sleep(std::time::Duration::from_secs(60));
+    ///         token.cancel();
+    ///     }
+    /// });
+    /// let proof = client.prove(&pk, &stdin)
+    ///     .cancellation_token(token)
+    ///     .run();
+    /// ```
+    #[must_use]
+    pub fn cancellation_token(mut self, token: CancellationToken) -> Self {
+        self.context_builder.cancellation_token(token);
+        self
+    }
+
     /// Run the prover with the built arguments.
     ///
     /// # Details
@@ -280,7 +368,8 @@ impl<'a> CpuProveBuilder<'a> {
         // Get the arguments.
         let Self { prover, mode, pk, stdin, mut context_builder, core_opts, recursion_opts, mock } =
             self;
-        let opts = SP1ProverOpts { core_opts, recursion_opts };
+        let opts = SP1ProverOpts::new(core_opts, recursion_opts)
+            .with_security_preset(prover.prover.security_preset);
         let context = context_builder.build();
 
         // Dump the program and stdin to files for debugging if `SP1_DUMP` is set.
@@ -294,3 +383,39 @@ impl<'a> CpuProveBuilder<'a> {
         }
     }
 }
+
+#[cfg(feature = "async")]
+impl CpuProveBuilder<'static> {
+    /// Run the prover with the built arguments asynchronously.
+    ///
+    /// # Details
+    /// The proof is generated on the blocking thread pool of the tokio runtime, so the other tasks
+    /// of the runtime keep making progress while proving. See [`Self::run`] for more details.
+    ///
+    /// The builder is moved to the blocking thread, so the prover and the proving key must live
+    /// for `'static`, for example in a [`std::sync::OnceLock`]. A prover shared through an
+    /// [`std::sync::Arc`] can use [`crate::AsyncProver`] instead.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use std::sync::OnceLock;
+    /// use sp1_sdk::{CpuProver, ProverClient, SP1ProvingKey, SP1Stdin, Prover};
+    ///
+    /// static CLIENT: OnceLock<CpuProver> = OnceLock::new();
+    /// static PK: OnceLock<SP1ProvingKey> = OnceLock::new();
+    ///
+    /// let client = CLIENT.get_or_init(|| ProverClient::builder().cpu().build());
+    /// let pk = PK.get_or_init(|| client.setup(&[1, 2, 3]).0);
+    ///
+    /// tokio_test::block_on(async {
+    ///     let stdin = SP1Stdin::new();
+    ///     let proof = client.prove(pk, &stdin)
+    ///         .run_async()
+    ///         .await
+    ///         .unwrap();
+    /// })
+    /// ```
+    pub async fn run_async(self) -> Result<SP1ProofWithPublicValues> {
+        crate::utils::spawn_blocking(move || self.run()).await
+    }
+}
@@ -0,0 +1,98 @@
+//! # CPU Guest Tests
+//!
+//! This module runs the tests of a guest crate, declared with `sp1_zkvm::test_harness!`, in the
+//! executor.
+
+use std::sync::Mutex;
+
+use anyhow::{Context, Result};
+use sp1_core_executor::{ExecutionError, FD_TEST_PANIC};
+use sp1_core_machine::io::SP1Stdin;
+
+use super::CpuProver;
+
+/// The result of a guest test.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GuestTestResult {
+    /// The name of the test function.
+    pub name: String,
+    /// The number of cycles the test ran for, until it passed or failed, if it is known.
+    pub cycles: Option<u64>,
+    /// Whether the test passed.
+    pub outcome: GuestTestOutcome,
+}
+
+/// The outcome of a guest test.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GuestTestOutcome {
+    /// The test returned.
+    Passed,
+    /// The test panicked, with the given message.
+    Panicked(String),
+    /// The test failed without panicking, for example by exceeding the cycle limit.
+    Failed(String),
+}
+
+impl CpuProver {
+    /// Lists the tests of a test binary built with `sp1_zkvm::test_harness!`.
+    pub fn list_guest_tests(&self, elf: &[u8]) -> Result<Vec<String>> {
+        let mut stdin = SP1Stdin::new();
+        stdin.write(&None::<String>);
+        let (mut public_values, _) =
+            self.execute(elf, &stdin).run().context("failed to list the guest tests")?;
+        Ok(public_values.read())
+    }
+
+    /// Runs a test of a test binary built with `sp1_zkvm::test_harness!`.
+    ///
+    /// The test reads the buffers of `stdin` as if it was a program.
+    ///
+    /// # Details
+    /// An error is only returned when the test could not be run, a failing test is reported in the
+    /// [`GuestTestResult`].
+    pub fn run_guest_test(
+        &self,
+        elf: &[u8],
+        name: &str,
+        stdin: &SP1Stdin,
+        cycle_limit: Option<u64>,
+    ) -> Result<GuestTestResult> {
+        let mut test_stdin = SP1Stdin::new();
+        test_stdin.write(&Some(name));
+        for buffer in &stdin.buffer {
+            test_stdin.write_vec(buffer.clone());
+        }
+        test_stdin.proofs.clone_from(&stdin.proofs);
+
+        let panic = Mutex::new(None);
+        let mut execute = self.execute(elf, &test_stdin).with_hook(FD_TEST_PANIC, |env, buf| {
+            let message = String::from_utf8_lossy(buf).into_owned();
+            *panic.lock().unwrap() = Some((message, env.runtime.state.global_clk));
+            vec![]
+        });
+        if let Some(cycle_limit) = cycle_limit {
+            execute = execute.cycle_limit(cycle_limit);
+        }
+        let result = execute.run();
+
+        let (cycles, outcome) = match (result, panic.into_inner().unwrap()) {
+            (Ok((_, report)), _) => {
+                (Some(report.total_instruction_count()), GuestTestOutcome::Passed)
+            }
+            (Err(_), Some((message, cycles))) => {
+                (Some(cycles), GuestTestOutcome::Panicked(message))
+            }
+            (Err(err), None) => match err.downcast_ref::<ExecutionError>() {
+                Some(err @ ExecutionError::ExceededCycleLimit(cycles)) => {
+                    (Some(*cycles), GuestTestOutcome::Failed(err.to_string()))
+                }
+                Some(err @ ExecutionError::HaltWithNonZeroExitCode(_)) => {
+                    (None, GuestTestOutcome::Failed(err.to_string()))
+                }
+                _ => return Err(err),
+            },
+        };
+
+        Ok(GuestTestResult { name: name.to_string(), cycles, outcome })
+    }
+}
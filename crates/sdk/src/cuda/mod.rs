@@ -7,12 +7,14 @@ pub mod prove;
 
 use anyhow::Result;
 use prove::CudaProveBuilder;
-use sp1_core_executor::SP1ContextBuilder;
-use sp1_core_machine::io::SP1Stdin;
+use sp1_core_executor::{CancellationToken, ProofStage, ProveMonitor, SP1ContextBuilder};
+use sp1_core_machine::{io::SP1Stdin, utils::SP1CoreProverError};
 use sp1_cuda::SP1CudaProver;
 use sp1_prover::{components::CpuProverComponents, SP1Prover};
+use sp1_stark::SecurityPreset;
 
-use crate::cpu::execute::CpuExecuteBuilder;
+use crate::cache::cached_setup;
+use crate::cpu::{execute::CpuExecuteBuilder, CpuProver};
 use crate::install::try_install_circuit_artifacts;
 use crate::{
     Prover, SP1Proof, SP1ProofMode, SP1ProofWithPublicValues, SP1ProvingKey, SP1VerifyingKey,
@@ -79,14 +81,24 @@ impl CudaProver {
     ///     .run()
     ///     .unwrap();
     /// ```
-    pub fn prove<'a>(&'a self, pk: &'a SP1ProvingKey, stdin: &'a SP1Stdin) -> CudaProveBuilder<'a> {
-        CudaProveBuilder { prover: self, mode: SP1ProofMode::Core, pk, stdin: stdin.clone() }
+    pub fn prove<'a>(&'a self, pk: &'a SP1ProvingKey, stdin: &SP1Stdin) -> CudaProveBuilder<'a> {
+        CudaProveBuilder {
+            prover: self,
+            mode: SP1ProofMode::Core,
+            pk,
+            stdin: stdin.clone(),
+            progress_observer: None,
+            cancellation_token: CancellationToken::new(),
+        }
     }
 }
 
 impl Prover<CpuProverComponents> for CudaProver {
     fn setup(&self, elf: &[u8]) -> (SP1ProvingKey, SP1VerifyingKey) {
-        self.cpu_prover.setup(elf)
+        let fixed_core_shapes = self.cpu_prover.core_shape_config.is_some();
+        cached_setup(elf, SecurityPreset::Standard, fixed_core_shapes, || {
+            self.cpu_prover.setup(elf)
+        })
     }
 
     fn inner(&self) -> &SP1Prover<CpuProverComponents> {
@@ -99,7 +111,24 @@ impl Prover<CpuProverComponents> for CudaProver {
         stdin: &SP1Stdin,
         kind: SP1ProofMode,
     ) -> Result<SP1ProofWithPublicValues> {
+        self.prove_with_monitor(pk, stdin, kind, &ProveMonitor::default())
+    }
+
+    fn prove_with_monitor(
+        &self,
+        pk: &SP1ProvingKey,
+        stdin: &SP1Stdin,
+        kind: SP1ProofMode,
+        monitor: &ProveMonitor,
+    ) -> Result<SP1ProofWithPublicValues> {
+        if kind == SP1ProofMode::Fflonk {
+            anyhow::bail!("fflonk proofs are not supported by the CUDA prover");
+        }
+
         // Generate the core proof.
+        if monitor.is_cancelled() {
+            return Err(SP1CoreProverError::Cancelled.into());
+        }
         let proof = self.cuda_prover.prove_core(pk, stdin)?;
         if kind == SP1ProofMode::Core {
             return Ok(SP1ProofWithPublicValues {
@@ -113,6 +142,7 @@ impl Prover<CpuProverComponents> for CudaProver {
         let deferred_proofs =
             stdin.proofs.iter().map(|(reduce_proof, _)| reduce_proof.clone()).collect();
         let public_values = proof.public_values.clone();
+        CpuProver::enter_stage(monitor, ProofStage::Compress)?;
         let reduce_proof = self.cuda_prover.compress(&pk.vk, proof, deferred_proofs)?;
         if kind == SP1ProofMode::Compressed {
             return Ok(SP1ProofWithPublicValues {
@@ -123,16 +153,21 @@ impl Prover<CpuProverComponents> for CudaProver {
         }
 
         // Generate the shrink proof.
+        CpuProver::enter_stage(monitor, ProofStage::Shrink)?;
         let compress_proof = self.cuda_prover.shrink(reduce_proof)?;
 
         // Genenerate the wrap proof.
+        CpuProver::enter_stage(monitor, ProofStage::Wrap)?;
         let outer_proof = self.cuda_prover.wrap_bn254(compress_proof)?;
 
+        // Generate the gnark proof.
+        CpuProver::enter_stage(monitor, ProofStage::Snark)?;
         if kind == SP1ProofMode::Plonk {
             let plonk_bn254_artifacts = if sp1_prover::build::sp1_dev_mode() {
                 sp1_prover::build::try_build_plonk_bn254_artifacts_dev(
                     &outer_proof.vk,
                     &outer_proof.proof,
+                    SecurityPreset::Standard,
                 )
             } else {
                 try_install_circuit_artifacts("plonk")
@@ -148,6 +183,7 @@ impl Prover<CpuProverComponents> for CudaProver {
                 sp1_prover::build::try_build_groth16_bn254_artifacts_dev(
                     &outer_proof.vk,
                     &outer_proof.proof,
+                    SecurityPreset::Standard,
                 )
             } else {
                 try_install_circuit_artifacts("groth16")
@@ -2,7 +2,10 @@
 //!
 //! This module provides a builder for proving a program on the CUDA.
 
+use std::sync::Arc;
+
 use anyhow::Result;
+use sp1_core_executor::{CancellationToken, ProgressObserver, ProveMonitor, ProveProgress};
 use sp1_core_machine::io::SP1Stdin;
 use sp1_prover::{components::CpuProverComponents, SP1ProvingKey};
 
@@ -18,6 +21,8 @@ pub struct CudaProveBuilder<'a> {
     pub(crate) mode: SP1ProofMode,
     pub(crate) pk: &'a SP1ProvingKey,
     pub(crate) stdin: SP1Stdin,
+    pub(crate) progress_observer: Option<ProgressObserver>,
+    pub(crate) cancellation_token: CancellationToken,
 }
 
 impl<'a> CudaProveBuilder<'a> {
@@ -148,6 +153,68 @@ impl<'a> CudaProveBuilder<'a> {
         self
     }
 
+    /// Set a callback invoked with the progress of the proof.
+    ///
+    /// # Details
+    /// The callback receives a [`ProveProgress`] snapshot every time the proof makes progress.
+    /// The CUDA prover reports the stage of the proof, but not the progress of the shards and the
+    /// compress tree, and only checks for cancellation between stages.
+    ///
+    /// The callback is invoked from the worker threads of the prover, so it should return quickly.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use sp1_sdk::{ProverClient, SP1Stdin, Prover};
+    ///
+    /// let elf = &[1, 2, 3];
+    /// let stdin = SP1Stdin::new();
+    ///
+    /// let client = ProverClient::builder().cuda().build();
+    /// let (pk, vk) = client.setup(elf);
+    /// let proof = client.prove(&pk, &stdin)
+    ///     .on_progress(|progress| println!("{progress:?}"))
+    ///     .run();
+    /// ```
+    #[must_use]
+    pub fn on_progress(
+        mut self,
+        observer: impl Fn(&ProveProgress) + Send + Sync + 'static,
+    ) -> Self {
+        self.progress_observer = Some(Arc::new(observer));
+        self
+    }
+
+    /// Set the token used to cancel the proof.
+    ///
+    /// # Details
+    /// Once the token is cancelled, the prover stops as soon as possible and [`Self::run`] returns
+    /// an error.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use sp1_sdk::{CancellationToken, ProverClient, SP1Stdin, Prover};
+    ///
+    /// let elf = &[1, 2, 3];
+    /// let stdin = SP1Stdin::new();
+    ///
+    /// let client = ProverClient::builder().cuda().build();
+    /// let (pk, vk) = client.setup(elf);
+    /// let token = CancellationToken::new();
+    /// let canceller = token.clone();
+    /// std::thread::spawn(move || {
+    ///     std::thread::sleep(std::time::Duration::from_secs(60));
+    ///     canceller.cancel();
+    /// });
+    /// let proof = client.prove(&pk, &stdin)
+    ///     .cancellation_token(token)
+    ///     .run();
+    /// ```
+    #[must_use]
+    pub fn cancellation_token(mut self, token: CancellationToken) -> Self {
+        self.cancellation_token = token;
+        self
+    }
+
     /// Run the prover with the built arguments.
     ///
     /// # Details
@@ -168,11 +235,48 @@ impl<'a> CudaProveBuilder<'a> {
     ///     .unwrap();
     /// ```
     pub fn run(self) -> Result<SP1ProofWithPublicValues> {
-        let Self { prover, mode: kind, pk, stdin } = self;
+        let Self { prover, mode: kind, pk, stdin, progress_observer, cancellation_token } = self;
+        let monitor = ProveMonitor::new(progress_observer, cancellation_token);
 
         // Dump the program and stdin to files for debugging if `SP1_DUMP` is set.
         crate::utils::sp1_dump(&pk.elf, &stdin);
 
-        Prover::<CpuProverComponents>::prove(prover, pk, &stdin, kind)
+        Prover::<CpuProverComponents>::prove_with_monitor(prover, pk, &stdin, kind, &monitor)
+    }
+}
+
+#[cfg(feature = "async")]
+impl CudaProveBuilder<'static> {
+    /// Run the prover with the built arguments asynchronously.
+    ///
+    /// # Details
+    /// The proof is generated on the blocking thread pool of the tokio runtime, so the other tasks
+    /// of the runtime keep making progress while proving. See [`Self::run`] for more details.
+    ///
+    /// The builder is moved to the blocking thread, so the prover and the proving key must live
+    /// for `'static`, for example in a [`std::sync::OnceLock`]. A prover shared through an
+    /// [`std::sync::Arc`] can use [`crate::AsyncProver`] instead.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use std::sync::OnceLock;
+    /// use sp1_sdk::{CudaProver, ProverClient, SP1ProvingKey, SP1Stdin, Prover};
+    ///
+    /// static CLIENT: OnceLock<CudaProver> = OnceLock::new();
+    /// static PK: OnceLock<SP1ProvingKey> = OnceLock::new();
+    ///
+    /// let client = CLIENT.get_or_init(|| ProverClient::builder().cuda().build());
+    /// let pk = PK.get_or_init(|| client.setup(&[1, 2, 3]).0);
+    ///
+    /// tokio_test::block_on(async {
+    ///     let stdin = SP1Stdin::new();
+    ///     let proof = client.prove(pk, &stdin)
+    ///         .run_async()
+    ///         .await
+    ///         .unwrap();
+    /// })
+    /// ```
+    pub async fn run_async(self) -> Result<SP1ProofWithPublicValues> {
+        crate::utils::spawn_blocking(move || self.run()).await
     }
 }
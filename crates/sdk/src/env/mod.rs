@@ -9,8 +9,9 @@ use std::env;
 
 use anyhow::Result;
 use prove::EnvProveBuilder;
-use sp1_core_executor::SP1ContextBuilder;
+use sp1_core_executor::{CancellationToken, ProveMonitor, SP1ContextBuilder};
 use sp1_core_machine::io::SP1Stdin;
+use sp1_primitives::abi::PublicValuesSchema;
 use sp1_prover::{components::CpuProverComponents, SP1Prover, SP1ProvingKey, SP1VerifyingKey};
 
 use super::{Prover, SP1VerificationError};
@@ -126,12 +127,14 @@ impl EnvProver {
     ///     .run();
     /// ```
     #[must_use]
-    pub fn prove<'a>(&'a self, pk: &'a SP1ProvingKey, stdin: &'a SP1Stdin) -> EnvProveBuilder<'a> {
+    pub fn prove<'a>(&'a self, pk: &'a SP1ProvingKey, stdin: &SP1Stdin) -> EnvProveBuilder<'a> {
         EnvProveBuilder {
             prover: self.prover.as_ref(),
             mode: SP1ProofMode::Core,
             pk,
             stdin: stdin.clone(),
+            progress_observer: None,
+            cancellation_token: CancellationToken::new(),
         }
     }
 
@@ -158,8 +161,24 @@ impl EnvProver {
         self.prover.verify(proof, vk)
     }
 
+    /// Verifies that the given proof is valid and matches the given verification key, and decodes
+    /// its public values with an ABI schema defined with [`crate::public_values!`].
+    ///
+    /// Fails if the program committed its public values with another schema than `T`.
+    pub fn verify_abi<T: PublicValuesSchema>(
+        &self,
+        proof: &SP1ProofWithPublicValues,
+        vk: &SP1VerifyingKey,
+    ) -> Result<T, SP1VerificationError> {
+        self.prover.verify(proof, vk)?;
+        proof.public_values.read_abi().map_err(SP1VerificationError::PublicValuesSchema)
+    }
+
     /// Setup a program to be proven and verified by the SP1 RISC-V zkVM by computing the proving
     /// and verifying keys.
+    ///
+    /// # Details
+    /// The keys are cached on disk by the hash of the program, see [`crate::cache::key_cache_dir`].
     #[must_use]
     pub fn setup(&self, elf: &[u8]) -> (SP1ProvingKey, SP1VerifyingKey) {
         self.prover.setup(elf)
@@ -189,4 +208,14 @@ impl Prover<CpuProverComponents> for EnvProver {
     ) -> Result<SP1ProofWithPublicValues> {
         self.prover.prove(pk, stdin, mode)
     }
+
+    fn prove_with_monitor(
+        &self,
+        pk: &SP1ProvingKey,
+        stdin: &SP1Stdin,
+        mode: SP1ProofMode,
+        monitor: &ProveMonitor,
+    ) -> Result<SP1ProofWithPublicValues> {
+        self.prover.prove_with_monitor(pk, stdin, mode, monitor)
+    }
 }
@@ -25,6 +25,7 @@
 #![warn(missing_docs)]
 
 pub mod artifacts;
+pub mod cache;
 pub mod client;
 pub mod cpu;
 pub mod cuda;
@@ -34,13 +35,17 @@ pub mod install;
 pub mod network;
 pub mod proof;
 pub mod prover;
+pub mod solidity;
 pub mod utils;
 
 // Re-export the client.
 pub use crate::client::ProverClient;
 
 // Re-export the provers.
-pub use crate::cpu::CpuProver;
+pub use crate::cpu::{
+    test::{GuestTestOutcome, GuestTestResult},
+    CpuProver,
+};
 pub use crate::cuda::CudaProver;
 pub use crate::env::EnvProver;
 #[cfg(feature = "network")]
@@ -48,31 +53,47 @@ pub use crate::network::prover::NetworkProver;
 
 // Re-export the proof and prover traits.
 pub use proof::*;
+#[cfg(feature = "async")]
+pub use prover::AsyncProver;
 #[cfg(feature = "network")]
 pub use prover::Prover;
 pub use prover::SP1VerificationError;
 
 // Re-export the build utilities and executor primitives.
-pub use sp1_build::include_elf;
-pub use sp1_core_executor::{ExecutionReport, Executor, HookEnv, SP1Context, SP1ContextBuilder};
+pub use sp1_build::{include_elf, include_elf_with_manifest, ElfWithManifest};
+pub use sp1_core_executor::{
+    CancellationToken, ExecutionError, ExecutionReport, Executor, HookEnv, Program, ProofStage,
+    ProveMonitor, ProveProgress, SP1Context, SP1ContextBuilder,
+};
+#[cfg(feature = "profiling")]
+pub use sp1_core_executor::{FunctionProfile, Profile, SyscallProfile};
 
 // Re-export the machine/prover primitives.
 pub use sp1_core_machine::{io::SP1Stdin, riscv::cost::CostEstimator, SP1_CIRCUIT_VERSION};
-pub use sp1_primitives::io::SP1PublicValues;
+pub use sp1_primitives::{abi, io::SP1PublicValues, public_values};
 pub use sp1_prover::{
-    CoreSC, HashableKey, InnerSC, OuterSC, PlonkBn254Proof, ProverMode, SP1Prover, SP1ProvingKey,
-    SP1VerifyingKey,
+    foreign, CoreSC, HashableKey, InnerSC, OuterSC, PlonkBn254Proof, ProverMode, SP1Prover,
+    SP1ProvingKey, SP1VerifyingKey,
 };
+pub use sp1_stark::SecurityPreset;
 
 // Re-export the utilities.
 pub use utils::setup_logger;
 
 #[cfg(test)]
 mod tests {
-    use sp1_core_machine::riscv::cost::CostEstimator;
+    use std::sync::Arc;
+
+    use p3_baby_bear::BabyBear;
+    use p3_field::AbstractField;
+    use sp1_core_machine::{riscv::cost::CostEstimator, utils::SP1CoreProverError};
     use sp1_primitives::io::SP1PublicValues;
+    use sp1_prover::SP1RecursionProverError;
 
-    use crate::{utils, Prover, ProverClient, SP1Stdin};
+    use crate::{
+        utils, CancellationToken, GuestTestOutcome, ProofStage, ProveProgress, Prover,
+        ProverClient, SP1Stdin,
+    };
 
     #[test]
     fn test_execute() {
@@ -107,6 +128,74 @@ mod tests {
         client.execute(elf, &stdin).cycle_limit(1).run().unwrap();
     }
 
+    #[test]
+    fn test_run_guest_tests() {
+        utils::setup_logger();
+        let client = ProverClient::builder().cpu().build();
+        let elf = test_artifacts::TEST_HARNESS_ELF;
+        assert_eq!(
+            client.list_guest_tests(elf).unwrap(),
+            ["passes", "reads_stdin", "panics", "loops"]
+        );
+
+        let result = client.run_guest_test(elf, "passes", &SP1Stdin::new(), None).unwrap();
+        assert_eq!(result.name, "passes");
+        assert_eq!(result.outcome, GuestTestOutcome::Passed);
+        assert!(result.cycles.is_some_and(|cycles| cycles > 0));
+
+        let mut stdin = SP1Stdin::new();
+        stdin.write(&7u32);
+        let result = client.run_guest_test(elf, "reads_stdin", &stdin, None).unwrap();
+        assert_eq!(result.outcome, GuestTestOutcome::Passed);
+
+        let result = client.run_guest_test(elf, "panics", &SP1Stdin::new(), None).unwrap();
+        let GuestTestOutcome::Panicked(message) = result.outcome else {
+            panic!("expected the test to panic, got {:?}", result.outcome);
+        };
+        assert!(message.contains("addition is broken"), "unexpected panic message: {message}");
+        assert!(result.cycles.is_some());
+
+        let result = client.run_guest_test(elf, "loops", &SP1Stdin::new(), Some(100_000)).unwrap();
+        assert!(
+            matches!(result.outcome, GuestTestOutcome::Failed(_)),
+            "expected the test to exceed the cycle limit, got {:?}",
+            result.outcome
+        );
+        assert!(result.cycles.is_some());
+
+        let result = client.run_guest_test(elf, "missing", &SP1Stdin::new(), None).unwrap();
+        let GuestTestOutcome::Panicked(message) = result.outcome else {
+            panic!("expected the unknown test to panic, got {:?}", result.outcome);
+        };
+        assert!(message.contains("there is no test named missing"), "{message}");
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_execute_async() {
+        use std::sync::{Arc, OnceLock};
+
+        use crate::{AsyncProver, CpuProver};
+
+        static CLIENT: OnceLock<CpuProver> = OnceLock::new();
+
+        utils::setup_logger();
+        let elf = test_artifacts::FIBONACCI_ELF;
+        let mut stdin = SP1Stdin::new();
+        stdin.write(&10usize);
+
+        let client = CLIENT.get_or_init(|| ProverClient::builder().cpu().build());
+        // The test runs on a current-thread runtime, which cannot block in place.
+        let (public_values, report) = client.execute(elf, &stdin).run_async().await.unwrap();
+        tracing::info!("gas = {}", report.estimate_gas());
+
+        let client = Arc::new(ProverClient::builder().cpu().build());
+        let (async_public_values, async_report) =
+            client.execute_async(Arc::from(elf), stdin).await.unwrap();
+        assert_eq!(public_values.as_slice(), async_public_values.as_slice());
+        assert_eq!(report.total_instruction_count(), async_report.total_instruction_count());
+    }
+
     #[test]
     fn test_e2e_core() {
         utils::setup_logger();
@@ -127,6 +216,83 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_e2e_core_cancelled() {
+        utils::setup_logger();
+        let client = ProverClient::builder().cpu().build();
+        let elf = test_artifacts::FIBONACCI_ELF;
+        let (pk, _) = client.setup(elf);
+        let mut stdin = SP1Stdin::new();
+        stdin.write(&10usize);
+
+        // A proof cancelled before it starts stops before proving any shard.
+        let token = CancellationToken::new();
+        token.cancel();
+        let result = client
+            .prove(&pk, &stdin)
+            .on_progress(|progress| assert_eq!(progress.shards_proven, 0))
+            .cancellation_token(token)
+            .run();
+        assert!(result.is_err());
+
+        // A proof cancelled from the callback on the first core progress event stops its worker
+        // threads, which release their clones of the callback, and returns `Cancelled`.
+        let token = CancellationToken::new();
+        let handle = Arc::new(());
+        let observer = {
+            let token = token.clone();
+            let handle = Arc::clone(&handle);
+            move |progress: &ProveProgress| {
+                let _handle = &handle;
+                if progress.stage == ProofStage::Core {
+                    token.cancel();
+                }
+            }
+        };
+        let err = client
+            .prove(&pk, &stdin)
+            .on_progress(observer)
+            .cancellation_token(token)
+            .run()
+            .unwrap_err();
+        assert!(matches!(err.downcast_ref(), Some(SP1CoreProverError::Cancelled)), "{err}");
+        assert_eq!(Arc::strong_count(&handle), 1);
+    }
+
+    #[test]
+    fn test_e2e_compressed_cancelled() {
+        utils::setup_logger();
+        let client = ProverClient::builder().cpu().build();
+        let elf = test_artifacts::FIBONACCI_ELF;
+        let (pk, _) = client.setup(elf);
+        let mut stdin = SP1Stdin::new();
+        stdin.write(&10usize);
+
+        // Cancelling once the compress tree starts stops the workers of the tree while they wait
+        // for their turn.
+        let token = CancellationToken::new();
+        let handle = Arc::new(());
+        let observer = {
+            let token = token.clone();
+            let handle = Arc::clone(&handle);
+            move |progress: &ProveProgress| {
+                let _handle = &handle;
+                if progress.stage == ProofStage::Compress {
+                    token.cancel();
+                }
+            }
+        };
+        let err = client
+            .prove(&pk, &stdin)
+            .compressed()
+            .on_progress(observer)
+            .cancellation_token(token)
+            .run()
+            .unwrap_err();
+        assert!(matches!(err.downcast_ref(), Some(SP1RecursionProverError::Cancelled)), "{err}");
+        assert_eq!(Arc::strong_count(&handle), 1);
+    }
+
     #[test]
     fn test_e2e_compressed() {
         utils::setup_logger();
@@ -167,6 +333,45 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_extended_vk_map_rejects_released_artifacts() {
+        utils::setup_logger();
+        let mut client = ProverClient::builder().cpu().build();
+        client.extend_allowed_vks([[BabyBear::one(); 8]]);
+        let (pk, _) = client.setup(test_artifacts::FIBONACCI_ELF);
+        let mut stdin = SP1Stdin::new();
+        stdin.write(&10usize);
+
+        // The released artifacts are rejected before proving.
+        assert!(client.prove(&pk, &stdin).plonk().run().is_err());
+        assert!(client.prove(&pk, &stdin).groth16().run().is_err());
+    }
+
+    #[cfg(feature = "network")]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_e2e_network_mock() {
+        use crate::{network::mock::MockNetwork, CpuProver};
+
+        utils::setup_logger();
+        let network = MockNetwork::new(CpuProver::mock())
+            .serve("127.0.0.1:0".parse().unwrap())
+            .await
+            .unwrap();
+        let client = ProverClient::builder()
+            .network()
+            .private_key("0x0000000000000000000000000000000000000000000000000000000000000001")
+            .rpc_url(&network.rpc_url())
+            .build();
+        let elf = test_artifacts::FIBONACCI_ELF;
+        let (pk, vk) = client.setup(elf);
+        let mut stdin = SP1Stdin::new();
+        stdin.write(&10usize);
+
+        // Generate a mock proof through the network & verify.
+        let proof = client.prove(&pk, &stdin).run_async().await.unwrap();
+        ProverClient::builder().mock().build().verify(&proof, &vk).unwrap();
+    }
+
     #[test]
     fn test_e2e_prove_plonk_mock() {
         utils::setup_logger();
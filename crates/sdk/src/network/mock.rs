@@ -0,0 +1,686 @@
+//! # Mock Network
+//!
+//! This module provides a local implementation of the prover network service, so that the
+//! [`NetworkProver`](crate::NetworkProver) can be tested end-to-end without the hosted network.
+
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use alloy_primitives::{keccak256, Address};
+use alloy_signer::Signature;
+use anyhow::Result;
+use prost::Message;
+use sp1_core_machine::io::SP1Stdin;
+use sp1_prover::components::CpuProverComponents;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinHandle;
+use tonic::transport::{server::TcpIncoming, Server};
+use tonic::{async_trait, Request, Response, Status};
+
+use crate::network::proto::artifact::{
+    artifact_store_server::{ArtifactStore, ArtifactStoreServer},
+    CreateArtifactRequest, CreateArtifactResponse,
+};
+use crate::network::proto::network::{
+    self as proto,
+    prover_network_server::{ProverNetwork, ProverNetworkServer},
+    CreateProgramRequest, CreateProgramResponse, CreateProgramResponseBody, ExecutionStatus,
+    FulfillmentStatus, GetNonceRequest, GetNonceResponse, GetProgramRequest, GetProgramResponse,
+    GetProofRequestStatusRequest, GetProofRequestStatusResponse, Program, ProofMode,
+    RequestProofRequest, RequestProofResponse, RequestProofResponseBody,
+};
+use crate::{CpuProver, Prover, SP1ProofMode};
+
+/// A local prover network backed by a [`CpuProver`].
+///
+/// # Details
+/// The network serves the `ProverNetwork` and `ArtifactStore` gRPC services, along with a plain
+/// HTTP endpoint standing in for the presigned artifact URLs. Proof requests are fulfilled in the
+/// background by the given prover, which can be a [`CpuProver::mock`] to skip proving.
+///
+/// Only the calls made by the [`NetworkProver`](crate::NetworkProver) are implemented, the others
+/// fail with an unimplemented status.
+///
+/// # Example
+/// ```rust,no_run
+/// use sp1_sdk::{network::mock::MockNetwork, CpuProver, ProverClient};
+///
+/// tokio_test::block_on(async {
+///     let handle = MockNetwork::new(CpuProver::mock())
+///         .serve("127.0.0.1:0".parse().unwrap())
+///         .await
+///         .unwrap();
+///
+///     let client = ProverClient::builder().network()
+///         .private_key("...")
+///         .rpc_url(&handle.rpc_url())
+///         .build();
+/// })
+/// ```
+pub struct MockNetwork {
+    prover: CpuProver,
+}
+
+impl MockNetwork {
+    /// Creates a new [`MockNetwork`] fulfilling the proof requests with the given prover.
+    #[must_use]
+    pub fn new(prover: CpuProver) -> Self {
+        Self { prover }
+    }
+
+    /// Starts serving the network on the given address.
+    ///
+    /// # Details
+    /// The artifacts are served on another port of the same host. The network runs on the current
+    /// tokio runtime until the returned [`MockNetworkHandle`] is dropped.
+    pub async fn serve(self, addr: SocketAddr) -> Result<MockNetworkHandle> {
+        let rpc_listener = TcpListener::bind(addr).await?;
+        let artifact_listener = TcpListener::bind(SocketAddr::new(addr.ip(), 0)).await?;
+        let rpc_addr = rpc_listener.local_addr()?;
+        let artifact_addr = artifact_listener.local_addr()?;
+
+        let state = Arc::new(Mutex::new(MockState::default()));
+        let service = MockService {
+            prover: Arc::new(self.prover),
+            state: state.clone(),
+            artifact_url: format!("http://{artifact_addr}/artifacts").into(),
+        };
+
+        let incoming =
+            TcpIncoming::from_listener(rpc_listener, true, None).map_err(|e| anyhow::anyhow!(e))?;
+        let server = Server::builder()
+            .add_service(ProverNetworkServer::new(service.clone()))
+            .add_service(ArtifactStoreServer::new(service))
+            .serve_with_incoming(incoming);
+
+        let tasks = vec![
+            tokio::spawn(async move {
+                if let Err(e) = server.await {
+                    log::error!("Mock network server failed: {e}");
+                }
+            }),
+            tokio::spawn(serve_artifacts(artifact_listener, state)),
+        ];
+        log::info!("Mock network listening on {rpc_addr}, artifacts on {artifact_addr}");
+
+        Ok(MockNetworkHandle { rpc_addr, tasks })
+    }
+}
+
+/// A handle to a running [`MockNetwork`], which stops the network once dropped.
+pub struct MockNetworkHandle {
+    rpc_addr: SocketAddr,
+    tasks: Vec<JoinHandle<()>>,
+}
+
+impl MockNetworkHandle {
+    /// The RPC URL to connect the [`NetworkProver`](crate::NetworkProver) to.
+    #[must_use]
+    pub fn rpc_url(&self) -> String {
+        format!("http://{}", self.rpc_addr)
+    }
+}
+
+impl Drop for MockNetworkHandle {
+    fn drop(&mut self) {
+        for task in &self.tasks {
+            task.abort();
+        }
+    }
+}
+
+#[derive(Default)]
+struct MockState {
+    nonces: HashMap<Address, u64>,
+    programs: HashMap<Vec<u8>, Program>,
+    requests: HashMap<Vec<u8>, GetProofRequestStatusResponse>,
+    artifacts: HashMap<String, Vec<u8>>,
+    next_artifact: u64,
+}
+
+impl MockState {
+    fn create_artifact(&mut self, content: Vec<u8>) -> String {
+        let id = format!("artifact_{}", self.next_artifact);
+        self.next_artifact += 1;
+        self.artifacts.insert(id.clone(), content);
+        id
+    }
+}
+
+#[derive(Clone)]
+struct MockService {
+    prover: Arc<CpuProver>,
+    state: Arc<Mutex<MockState>>,
+    artifact_url: Arc<str>,
+}
+
+impl MockService {
+    fn state(&self) -> MutexGuard<'_, MockState> {
+        self.state.lock().unwrap()
+    }
+
+    /// Recovers the signer of a request body and checks that the nonce is the expected one.
+    fn authenticate(
+        &self,
+        signature: &[u8],
+        body: &impl Message,
+        nonce: u64,
+    ) -> Result<Address, Status> {
+        let signer = Signature::try_from(signature)
+            .and_then(|signature| signature.recover_address_from_msg(body.encode_to_vec()))
+            .map_err(|e| Status::unauthenticated(e.to_string()))?;
+
+        let mut state = self.state();
+        let expected = state.nonces.entry(signer).or_default();
+        if *expected != nonce {
+            return Err(Status::invalid_argument(format!(
+                "invalid nonce {nonce}, expected {expected}"
+            )));
+        }
+        *expected += 1;
+        Ok(signer)
+    }
+
+    fn artifact(&self, uri: &str) -> Result<Vec<u8>, Status> {
+        let id = uri.rsplit('/').next().unwrap_or_default();
+        self.state()
+            .artifacts
+            .get(id)
+            .cloned()
+            .ok_or_else(|| Status::not_found(format!("artifact {uri} not found")))
+    }
+
+    fn update_request(
+        &self,
+        request_id: &[u8],
+        f: impl FnOnce(&mut GetProofRequestStatusResponse),
+    ) {
+        if let Some(status) = self.state().requests.get_mut(request_id) {
+            f(status);
+        }
+    }
+
+    /// Executes and proves a request, reporting the result through its status.
+    fn fulfill(
+        &self,
+        request_id: &[u8],
+        elf: &[u8],
+        stdin: &SP1Stdin,
+        mode: SP1ProofMode,
+        cycle_limit: u64,
+    ) {
+        self.update_request(request_id, |status| {
+            status.fulfillment_status = FulfillmentStatus::Assigned.into();
+        });
+
+        let executable = match Prover::<CpuProverComponents>::execute(&*self.prover, elf, stdin) {
+            Ok((_, report)) => report.total_instruction_count() <= cycle_limit,
+            Err(e) => {
+                log::warn!("Mock network failed to execute request: {e}");
+                false
+            }
+        };
+        if !executable {
+            self.update_request(request_id, |status| {
+                status.execution_status = ExecutionStatus::Unexecutable.into();
+                status.fulfillment_status = FulfillmentStatus::Unfulfillable.into();
+            });
+            return;
+        }
+        self.update_request(request_id, |status| {
+            status.execution_status = ExecutionStatus::Executed.into();
+        });
+
+        let (pk, _) = self.prover.setup(elf);
+        match Prover::<CpuProverComponents>::prove(&*self.prover, &pk, stdin, mode) {
+            Ok(proof) => {
+                let id = self.state().create_artifact(bincode::serialize(&proof).unwrap());
+                let proof_uri = format!("{}/{id}", self.artifact_url);
+                self.update_request(request_id, |status| {
+                    status.fulfillment_status = FulfillmentStatus::Fulfilled.into();
+                    status.fulfill_tx_hash = Some(keccak256(request_id).to_vec());
+                    status.proof_uri = Some(proof_uri);
+                });
+            }
+            Err(e) => {
+                log::warn!("Mock network failed to prove request: {e}");
+                self.update_request(request_id, |status| {
+                    status.fulfillment_status = FulfillmentStatus::Unfulfillable.into();
+                });
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl ArtifactStore for MockService {
+    async fn create_artifact(
+        &self,
+        request: Request<CreateArtifactRequest>,
+    ) -> Result<Response<CreateArtifactResponse>, Status> {
+        Signature::try_from(request.get_ref().signature.as_slice())
+            .and_then(|signature| signature.recover_address_from_msg("create_artifact"))
+            .map_err(|e| Status::unauthenticated(e.to_string()))?;
+
+        let id = self.state().create_artifact(Vec::new());
+        let uri = format!("{}/{id}", self.artifact_url);
+        Ok(Response::new(CreateArtifactResponse {
+            artifact_uri: uri.clone(),
+            artifact_presigned_url: uri,
+        }))
+    }
+}
+
+#[async_trait]
+impl ProverNetwork for MockService {
+    async fn request_proof(
+        &self,
+        request: Request<RequestProofRequest>,
+    ) -> Result<Response<RequestProofResponse>, Status> {
+        let request = request.into_inner();
+        let body = request.body.ok_or_else(|| Status::invalid_argument("missing body"))?;
+        self.authenticate(&request.signature, &body, body.nonce)?;
+
+        let version = format!("sp1-{}", self.prover.version());
+        if body.version != version {
+            return Err(Status::invalid_argument(format!(
+                "unsupported version {}, expected {version}",
+                body.version
+            )));
+        }
+        let mode = match ProofMode::try_from(body.mode) {
+            Ok(ProofMode::Core) => SP1ProofMode::Core,
+            Ok(ProofMode::Compressed) => SP1ProofMode::Compressed,
+            Ok(ProofMode::Plonk) => SP1ProofMode::Plonk,
+            Ok(ProofMode::Groth16) => SP1ProofMode::Groth16,
+            _ => return Err(Status::invalid_argument("invalid proof mode")),
+        };
+
+        let program_uri = self
+            .state()
+            .programs
+            .get(&body.vk_hash)
+            .map(|program| program.program_uri.clone())
+            .ok_or_else(|| Status::not_found("program not found"))?;
+        let elf: Vec<u8> = bincode::deserialize(&self.artifact(&program_uri)?)
+            .map_err(|e| Status::invalid_argument(format!("invalid program: {e}")))?;
+        let stdin: SP1Stdin = bincode::deserialize(&self.artifact(&body.stdin_uri)?)
+            .map_err(|e| Status::invalid_argument(format!("invalid stdin: {e}")))?;
+
+        let request_id = keccak256(body.encode_to_vec()).to_vec();
+        let tx_hash = keccak256(&request.signature).to_vec();
+        self.state().requests.insert(
+            request_id.clone(),
+            GetProofRequestStatusResponse {
+                fulfillment_status: FulfillmentStatus::Requested.into(),
+                execution_status: ExecutionStatus::Unexecuted.into(),
+                request_tx_hash: tx_hash.clone(),
+                deadline: body.deadline,
+                fulfill_tx_hash: None,
+                proof_uri: None,
+                public_values_hash: None,
+            },
+        );
+
+        let service = self.clone();
+        let id = request_id.clone();
+        tokio::task::spawn_blocking(move || {
+            service.fulfill(&id, &elf, &stdin, mode, body.cycle_limit);
+        });
+
+        Ok(Response::new(RequestProofResponse {
+            tx_hash,
+            body: Some(RequestProofResponseBody { request_id }),
+        }))
+    }
+
+    async fn get_proof_request_status(
+        &self,
+        request: Request<GetProofRequestStatusRequest>,
+    ) -> Result<Response<GetProofRequestStatusResponse>, Status> {
+        self.state()
+            .requests
+            .get(&request.get_ref().request_id)
+            .cloned()
+            .map(Response::new)
+            .ok_or_else(|| Status::not_found("request not found"))
+    }
+
+    async fn get_nonce(
+        &self,
+        request: Request<GetNonceRequest>,
+    ) -> Result<Response<GetNonceResponse>, Status> {
+        let address = Address::try_from(request.get_ref().address.as_slice())
+            .map_err(|e| Status::invalid_argument(e.to_string()))?;
+        let nonce = self.state().nonces.get(&address).copied().unwrap_or_default();
+        Ok(Response::new(GetNonceResponse { nonce }))
+    }
+
+    async fn get_program(
+        &self,
+        request: Request<GetProgramRequest>,
+    ) -> Result<Response<GetProgramResponse>, Status> {
+        self.state()
+            .programs
+            .get(&request.get_ref().vk_hash)
+            .cloned()
+            .map(|program| Response::new(GetProgramResponse { program: Some(program) }))
+            .ok_or_else(|| Status::not_found("program not found"))
+    }
+
+    async fn create_program(
+        &self,
+        request: Request<CreateProgramRequest>,
+    ) -> Result<Response<CreateProgramResponse>, Status> {
+        let request = request.into_inner();
+        let body = request.body.ok_or_else(|| Status::invalid_argument("missing body"))?;
+        let owner = self.authenticate(&request.signature, &body, body.nonce)?;
+        self.artifact(&body.program_uri)?;
+
+        let created_at = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+        self.state().programs.insert(
+            body.vk_hash.clone(),
+            Program {
+                vk_hash: body.vk_hash,
+                vk: body.vk,
+                program_uri: body.program_uri,
+                name: None,
+                owner: owner.to_vec(),
+                created_at,
+            },
+        );
+
+        Ok(Response::new(CreateProgramResponse {
+            tx_hash: keccak256(&request.signature).to_vec(),
+            body: Some(CreateProgramResponseBody {}),
+        }))
+    }
+
+    async fn fulfill_proof(
+        &self,
+        _: Request<proto::FulfillProofRequest>,
+    ) -> Result<Response<proto::FulfillProofResponse>, Status> {
+        Err(Status::unimplemented("fulfill_proof"))
+    }
+
+    async fn execute_proof(
+        &self,
+        _: Request<proto::ExecuteProofRequest>,
+    ) -> Result<Response<proto::ExecuteProofResponse>, Status> {
+        Err(Status::unimplemented("execute_proof"))
+    }
+
+    async fn fail_fulfillment(
+        &self,
+        _: Request<proto::FailFulfillmentRequest>,
+    ) -> Result<Response<proto::FailFulfillmentResponse>, Status> {
+        Err(Status::unimplemented("fail_fulfillment"))
+    }
+
+    async fn fail_execution(
+        &self,
+        _: Request<proto::FailExecutionRequest>,
+    ) -> Result<Response<proto::FailExecutionResponse>, Status> {
+        Err(Status::unimplemented("fail_execution"))
+    }
+
+    async fn get_proof_request_details(
+        &self,
+        _: Request<proto::GetProofRequestDetailsRequest>,
+    ) -> Result<Response<proto::GetProofRequestDetailsResponse>, Status> {
+        Err(Status::unimplemented("get_proof_request_details"))
+    }
+
+    async fn get_filtered_proof_requests(
+        &self,
+        _: Request<proto::GetFilteredProofRequestsRequest>,
+    ) -> Result<Response<proto::GetFilteredProofRequestsResponse>, Status> {
+        Err(Status::unimplemented("get_filtered_proof_requests"))
+    }
+
+    async fn get_search_results(
+        &self,
+        _: Request<proto::GetSearchResultsRequest>,
+    ) -> Result<Response<proto::GetSearchResultsResponse>, Status> {
+        Err(Status::unimplemented("get_search_results"))
+    }
+
+    async fn get_proof_request_metrics(
+        &self,
+        _: Request<proto::GetProofRequestMetricsRequest>,
+    ) -> Result<Response<proto::GetProofRequestMetricsResponse>, Status> {
+        Err(Status::unimplemented("get_proof_request_metrics"))
+    }
+
+    async fn get_proof_request_graph(
+        &self,
+        _: Request<proto::GetProofRequestGraphRequest>,
+    ) -> Result<Response<proto::GetProofRequestGraphResponse>, Status> {
+        Err(Status::unimplemented("get_proof_request_graph"))
+    }
+
+    async fn get_analytics_graphs(
+        &self,
+        _: Request<proto::GetAnalyticsGraphsRequest>,
+    ) -> Result<Response<proto::GetAnalyticsGraphsResponse>, Status> {
+        Err(Status::unimplemented("get_analytics_graphs"))
+    }
+
+    async fn get_filtered_delegations(
+        &self,
+        _: Request<proto::GetFilteredDelegationsRequest>,
+    ) -> Result<Response<proto::GetFilteredDelegationsResponse>, Status> {
+        Err(Status::unimplemented("get_filtered_delegations"))
+    }
+
+    async fn add_delegation(
+        &self,
+        _: Request<proto::AddDelegationRequest>,
+    ) -> Result<Response<proto::AddDelegationResponse>, Status> {
+        Err(Status::unimplemented("add_delegation"))
+    }
+
+    async fn remove_delegation(
+        &self,
+        _: Request<proto::RemoveDelegationRequest>,
+    ) -> Result<Response<proto::RemoveDelegationResponse>, Status> {
+        Err(Status::unimplemented("remove_delegation"))
+    }
+
+    async fn terminate_delegation(
+        &self,
+        _: Request<proto::TerminateDelegationRequest>,
+    ) -> Result<Response<proto::TerminateDelegationResponse>, Status> {
+        Err(Status::unimplemented("terminate_delegation"))
+    }
+
+    async fn accept_delegation(
+        &self,
+        _: Request<proto::AcceptDelegationRequest>,
+    ) -> Result<Response<proto::AcceptDelegationResponse>, Status> {
+        Err(Status::unimplemented("accept_delegation"))
+    }
+
+    async fn set_account_name(
+        &self,
+        _: Request<proto::SetAccountNameRequest>,
+    ) -> Result<Response<proto::SetAccountNameResponse>, Status> {
+        Err(Status::unimplemented("set_account_name"))
+    }
+
+    async fn get_account_name(
+        &self,
+        _: Request<proto::GetAccountNameRequest>,
+    ) -> Result<Response<proto::GetAccountNameResponse>, Status> {
+        Err(Status::unimplemented("get_account_name"))
+    }
+
+    async fn get_terms_signature(
+        &self,
+        _: Request<proto::GetTermsSignatureRequest>,
+    ) -> Result<Response<proto::GetTermsSignatureResponse>, Status> {
+        Err(Status::unimplemented("get_terms_signature"))
+    }
+
+    async fn set_terms_signature(
+        &self,
+        _: Request<proto::SetTermsSignatureRequest>,
+    ) -> Result<Response<proto::SetTermsSignatureResponse>, Status> {
+        Err(Status::unimplemented("set_terms_signature"))
+    }
+
+    async fn set_program_name(
+        &self,
+        _: Request<proto::SetProgramNameRequest>,
+    ) -> Result<Response<proto::SetProgramNameResponse>, Status> {
+        Err(Status::unimplemented("set_program_name"))
+    }
+
+    async fn get_balance(
+        &self,
+        _: Request<proto::GetBalanceRequest>,
+    ) -> Result<Response<proto::GetBalanceResponse>, Status> {
+        Err(Status::unimplemented("get_balance"))
+    }
+
+    async fn get_filtered_balance_logs(
+        &self,
+        _: Request<proto::GetFilteredBalanceLogsRequest>,
+    ) -> Result<Response<proto::GetFilteredBalanceLogsResponse>, Status> {
+        Err(Status::unimplemented("get_filtered_balance_logs"))
+    }
+
+    async fn add_credit(
+        &self,
+        _: Request<proto::AddCreditRequest>,
+    ) -> Result<Response<proto::AddCreditResponse>, Status> {
+        Err(Status::unimplemented("add_credit"))
+    }
+
+    async fn get_latest_bridge_block(
+        &self,
+        _: Request<proto::GetLatestBridgeBlockRequest>,
+    ) -> Result<Response<proto::GetLatestBridgeBlockResponse>, Status> {
+        Err(Status::unimplemented("get_latest_bridge_block"))
+    }
+
+    async fn get_gas_price_estimate(
+        &self,
+        _: Request<proto::GetGasPriceEstimateRequest>,
+    ) -> Result<Response<proto::GetGasPriceEstimateResponse>, Status> {
+        Err(Status::unimplemented("get_gas_price_estimate"))
+    }
+
+    async fn get_transaction_details(
+        &self,
+        _: Request<proto::GetTransactionDetailsRequest>,
+    ) -> Result<Response<proto::GetTransactionDetailsResponse>, Status> {
+        Err(Status::unimplemented("get_transaction_details"))
+    }
+
+    async fn get_filtered_reservations(
+        &self,
+        _: Request<proto::GetFilteredReservationsRequest>,
+    ) -> Result<Response<proto::GetFilteredReservationsResponse>, Status> {
+        Err(Status::unimplemented("get_filtered_reservations"))
+    }
+
+    async fn add_reservation(
+        &self,
+        _: Request<proto::AddReservationRequest>,
+    ) -> Result<Response<proto::AddReservationResponse>, Status> {
+        Err(Status::unimplemented("add_reservation"))
+    }
+
+    async fn remove_reservation(
+        &self,
+        _: Request<proto::RemoveReservationRequest>,
+    ) -> Result<Response<proto::RemoveReservationResponse>, Status> {
+        Err(Status::unimplemented("remove_reservation"))
+    }
+
+    async fn bid(
+        &self,
+        _: Request<proto::BidRequest>,
+    ) -> Result<Response<proto::BidResponse>, Status> {
+        Err(Status::unimplemented("bid"))
+    }
+
+    async fn settle(
+        &self,
+        _: Request<proto::SettleRequest>,
+    ) -> Result<Response<proto::SettleResponse>, Status> {
+        Err(Status::unimplemented("settle"))
+    }
+}
+
+/// Serves the artifacts over HTTP, standing in for the presigned URLs of the hosted network.
+async fn serve_artifacts(listener: TcpListener, state: Arc<Mutex<MockState>>) {
+    loop {
+        let stream = match listener.accept().await {
+            Ok((stream, _)) => stream,
+            Err(e) => {
+                log::error!("Mock network failed to accept artifact connection: {e}");
+                continue;
+            }
+        };
+        let state = state.clone();
+        tokio::spawn(async move {
+            if let Err(e) = handle_artifact(stream, &state).await {
+                log::warn!("Mock network failed to handle artifact request: {e}");
+            }
+        });
+    }
+}
+
+/// Handles a single `GET` or `PUT` of an artifact, then closes the connection.
+async fn handle_artifact(mut stream: TcpStream, state: &Mutex<MockState>) -> Result<()> {
+    let mut reader = BufReader::new(&mut stream);
+
+    // Read the request line and the headers.
+    let mut line = String::new();
+    reader.read_line(&mut line).await?;
+    let mut parts = line.split_whitespace();
+    let method = parts.next().unwrap_or_default().to_string();
+    let path = parts.next().unwrap_or_default().to_string();
+    let mut content_length = 0;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header).await? == 0 || header.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse()?;
+            }
+        }
+    }
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body).await?;
+
+    let id = path.strip_prefix("/artifacts/").unwrap_or_default();
+    let (status, content) = {
+        let mut state = state.lock().unwrap();
+        match (method.as_str(), state.artifacts.get_mut(id)) {
+            ("GET", Some(artifact)) => ("200 OK", artifact.clone()),
+            ("PUT", Some(artifact)) => {
+                *artifact = body;
+                ("200 OK", Vec::new())
+            }
+            (_, None) => ("404 Not Found", Vec::new()),
+            _ => ("405 Method Not Allowed", Vec::new()),
+        }
+    };
+
+    let header = format!(
+        "HTTP/1.1 {status}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        content.len()
+    );
+    stream.write_all(header.as_bytes()).await?;
+    stream.write_all(&content).await?;
+    stream.flush().await?;
+    Ok(())
+}
@@ -3,6 +3,7 @@
 //! A library for interacting with the SP1 prover over the network.
 
 pub mod client;
+pub mod mock;
 pub mod prover;
 #[rustfmt::skip]
 #[allow(missing_docs)]
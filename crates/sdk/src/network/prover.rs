@@ -302,6 +302,9 @@ impl NetworkProver {
         skip_simulation: bool,
         cycle_limit: Option<u64>,
     ) -> Result<B256> {
+        if mode == SP1ProofMode::Fflonk {
+            anyhow::bail!("fflonk proofs are not supported by the prover network");
+        }
         let vk_hash = self.register_program(&pk.vk, &pk.elf).await?;
         let cycle_limit = self.get_cycle_limit(cycle_limit, &pk.elf, stdin, skip_simulation)?;
         self.request_proof(vk_hash, stdin, mode.into(), strategy, cycle_limit, timeout).await
@@ -379,6 +382,8 @@ impl From<SP1ProofMode> for ProofMode {
             SP1ProofMode::Compressed => Self::Compressed,
             SP1ProofMode::Plonk => Self::Plonk,
             SP1ProofMode::Groth16 => Self::Groth16,
+            // The network has no fflonk mode, so requests for it are rejected.
+            SP1ProofMode::Fflonk => Self::UnspecifiedProofMode,
         }
     }
 }
@@ -6,7 +6,7 @@ use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use sp1_core_executor::SP1ReduceProof;
 use sp1_primitives::io::SP1PublicValues;
-use sp1_prover::{CoreSC, Groth16Bn254Proof, InnerSC, PlonkBn254Proof};
+use sp1_prover::{CoreSC, FflonkBn254Proof, Groth16Bn254Proof, InnerSC, PlonkBn254Proof};
 use sp1_stark::ShardProof;
 use strum_macros::{EnumDiscriminants, EnumTryAs};
 
@@ -28,6 +28,10 @@ pub enum SP1Proof {
     Plonk(PlonkBn254Proof),
     /// A proof generated by the Groth16 proof mode.
     Groth16(Groth16Bn254Proof),
+    /// A proof generated by the fflonk proof mode.
+    ///
+    /// Unlike Groth16, the proving key is derived from a universal setup.
+    Fflonk(FflonkBn254Proof),
 }
 
 /// A proof generated by the SP1 RISC-V zkVM bundled together with the public values and the
@@ -56,12 +60,12 @@ impl SP1ProofWithPublicValues {
             .map_err(Into::into)
     }
 
-    /// The proof in the byte encoding the onchain verifiers accepts for [`SP1ProofMode::Groth16`] and
-    /// [`SP1ProofMode::Plonk`] proofs.
+    /// The proof in the byte encoding the onchain verifiers accepts for [`SP1ProofMode::Groth16`],
+    /// [`SP1ProofMode::Plonk`] and [`SP1ProofMode::Fflonk`] proofs.
     ///
     /// # Details
-    /// The bytes consist of the first four bytes of Groth16/Plonk vkey hash followed by the encoded
-    /// proof, in a form optimized for onchain verification.
+    /// The bytes consist of the first four bytes of Groth16/Plonk/fflonk vkey hash followed by the
+    /// encoded proof, in a form optimized for onchain verification.
     #[must_use]
     pub fn bytes(&self) -> Vec<u8> {
         match &self.proof {
@@ -87,9 +91,20 @@ impl SP1ProofWithPublicValues {
                     hex::decode(&groth16_proof.encoded_proof).expect("Invalid Groth16 proof");
                 [groth16_proof.groth16_vkey_hash[..4].to_vec(), proof_bytes].concat()
             }
+            SP1Proof::Fflonk(fflonk_proof) => {
+                // If the proof is empty, then this is a mock proof. The mock SP1 verifier
+                // expects an empty byte array for verification, so return an empty byte array.
+                if fflonk_proof.encoded_proof.is_empty() {
+                    return Vec::new();
+                }
+
+                let proof_bytes =
+                    hex::decode(&fflonk_proof.encoded_proof).expect("Invalid fflonk proof");
+                [fflonk_proof.fflonk_vkey_hash[..4].to_vec(), proof_bytes].concat()
+            }
             proof => panic!(
                 "Proof type {:?} is not supported for onchain verification. \
-                Only Plonk and Groth16 proofs are verifiable onchain",
+                Only Plonk, Groth16 and Fflonk proofs are verifiable onchain",
                 std::mem::discriminant(proof)
             ),
         }
@@ -164,7 +179,7 @@ mod tests {
 
     #[test]
     #[should_panic(
-        expected = "Proof type Discriminant(0) is not supported for onchain verification. Only Plonk and Groth16 proofs are verifiable onchain"
+        expected = "Proof type Discriminant(0) is not supported for onchain verification. Only Plonk, Groth16 and Fflonk proofs are verifiable onchain"
     )]
     fn test_core_proof_bytes_unimplemented() {
         let core_proof = SP1ProofWithPublicValues {
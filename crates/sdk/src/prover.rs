@@ -2,19 +2,24 @@
 //!
 //! A trait that each prover variant must implement.
 
-use std::borrow::Borrow;
+#[cfg(feature = "async")]
+use std::sync::Arc;
+use std::{borrow::Borrow, path::PathBuf};
 
 use anyhow::Result;
 use itertools::Itertools;
 use p3_field::PrimeField32;
-use sp1_core_executor::{ExecutionReport, SP1Context};
-use sp1_core_machine::{io::SP1Stdin, SP1_CIRCUIT_VERSION};
-use sp1_primitives::io::SP1PublicValues;
+use sp1_core_executor::{ExecutionReport, ProveMonitor, SP1Context};
+use sp1_core_machine::{io::SP1Stdin, utils::SP1CoreProverError, SP1_CIRCUIT_VERSION};
+use sp1_primitives::{
+    abi::{AbiError, PublicValuesSchema},
+    io::SP1PublicValues,
+};
 use sp1_prover::{
     components::SP1ProverComponents, CoreSC, InnerSC, SP1CoreProofData, SP1Prover, SP1ProvingKey,
     SP1VerifyingKey,
 };
-use sp1_stark::{air::PublicValues, MachineVerificationError, Word};
+use sp1_stark::{air::PublicValues, MachineVerificationError, SecurityPreset, Word};
 use thiserror::Error;
 
 use crate::install::try_install_circuit_artifacts;
@@ -46,6 +51,23 @@ pub trait Prover<C: SP1ProverComponents>: Send + Sync {
         mode: SP1ProofMode,
     ) -> Result<SP1ProofWithPublicValues>;
 
+    /// Proves the given program on the given input in the given proof mode, reporting the progress
+    /// to the monitor and stopping once it is cancelled.
+    ///
+    /// Provers which cannot observe their progress only check for cancellation before proving.
+    fn prove_with_monitor(
+        &self,
+        pk: &SP1ProvingKey,
+        stdin: &SP1Stdin,
+        mode: SP1ProofMode,
+        monitor: &ProveMonitor,
+    ) -> Result<SP1ProofWithPublicValues> {
+        if monitor.is_cancelled() {
+            return Err(SP1CoreProverError::Cancelled.into());
+        }
+        self.prove(pk, stdin, mode)
+    }
+
     /// Verify that an SP1 proof is valid given its vkey and metadata.
     /// For Plonk proofs, verifies that the public inputs of the `PlonkBn254` proof match
     /// the hash of the VK and the committed public values of the `SP1ProofWithPublicValues`.
@@ -56,8 +78,101 @@ pub trait Prover<C: SP1ProverComponents>: Send + Sync {
     ) -> Result<(), SP1VerificationError> {
         verify_proof(self.inner(), self.version(), bundle, vkey)
     }
+
+    /// Verify that an SP1 proof is valid given its vkey and metadata, and decode its public values
+    /// with an ABI schema.
+    ///
+    /// The public values, including the id of the schema the program committed them with, are
+    /// bound to the proof by the committed value digest, so this fails if the program committed
+    /// them with another schema than `T`.
+    fn verify_abi<T: PublicValuesSchema>(
+        &self,
+        bundle: &SP1ProofWithPublicValues,
+        vkey: &SP1VerifyingKey,
+    ) -> Result<T, SP1VerificationError>
+    where
+        Self: Sized,
+    {
+        self.verify(bundle, vkey)?;
+        bundle.public_values.read_abi().map_err(SP1VerificationError::PublicValuesSchema)
+    }
 }
 
+/// The async variant of [`Prover`], implemented for every prover.
+///
+/// The proving work runs on the blocking thread pool of the tokio runtime, so the other tasks of
+/// the runtime keep making progress. The work is moved to the blocking thread, so the prover is
+/// shared through an [`Arc`] and the inputs are owned.
+///
+/// # Example
+/// ```rust,no_run
+/// use std::sync::Arc;
+/// use sp1_sdk::{AsyncProver, ProverClient, SP1ProofMode, SP1Stdin};
+///
+/// tokio_test::block_on(async {
+///     let client = Arc::new(ProverClient::builder().cpu().build());
+///     let (pk, vk) = client.clone().setup_async(Arc::from([1, 2, 3].as_slice())).await;
+///     let proof = client
+///         .clone()
+///         .prove_async(Arc::new(pk), SP1Stdin::new(), SP1ProofMode::Compressed)
+///         .await
+///         .unwrap();
+///     client.verify_async(proof, vk).await.unwrap();
+/// })
+/// ```
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+pub trait AsyncProver<C: SP1ProverComponents>: Prover<C> + 'static {
+    /// Generate the proving and verifying keys for the given program.
+    async fn setup_async(self: Arc<Self>, elf: Arc<[u8]>) -> (SP1ProvingKey, SP1VerifyingKey) {
+        crate::utils::spawn_blocking(move || self.setup(&elf)).await
+    }
+
+    /// Executes the program on the given input.
+    async fn execute_async(
+        self: Arc<Self>,
+        elf: Arc<[u8]>,
+        stdin: SP1Stdin,
+    ) -> Result<(SP1PublicValues, ExecutionReport)> {
+        crate::utils::spawn_blocking(move || self.execute(&elf, &stdin)).await
+    }
+
+    /// Proves the given program on the given input in the given proof mode.
+    async fn prove_async(
+        self: Arc<Self>,
+        pk: Arc<SP1ProvingKey>,
+        stdin: SP1Stdin,
+        mode: SP1ProofMode,
+    ) -> Result<SP1ProofWithPublicValues> {
+        crate::utils::spawn_blocking(move || self.prove(&pk, &stdin, mode)).await
+    }
+
+    /// Proves the given program on the given input in the given proof mode, reporting the progress
+    /// to the monitor and stopping once it is cancelled.
+    async fn prove_with_monitor_async(
+        self: Arc<Self>,
+        pk: Arc<SP1ProvingKey>,
+        stdin: SP1Stdin,
+        mode: SP1ProofMode,
+        monitor: ProveMonitor,
+    ) -> Result<SP1ProofWithPublicValues> {
+        crate::utils::spawn_blocking(move || self.prove_with_monitor(&pk, &stdin, mode, &monitor))
+            .await
+    }
+
+    /// Verify that an SP1 proof is valid given its vkey and metadata.
+    async fn verify_async(
+        self: Arc<Self>,
+        bundle: SP1ProofWithPublicValues,
+        vkey: SP1VerifyingKey,
+    ) -> Result<(), SP1VerificationError> {
+        crate::utils::spawn_blocking(move || self.verify(&bundle, &vkey)).await
+    }
+}
+
+#[cfg(feature = "async")]
+impl<C: SP1ProverComponents, P: Prover<C> + ?Sized + 'static> AsyncProver<C> for P {}
+
 /// An error that occurs when calling [`Prover::verify`].
 #[derive(Error, Debug)]
 pub enum SP1VerificationError {
@@ -79,6 +194,12 @@ pub enum SP1VerificationError {
     /// An error that occurs when the Groth16 verification fails.
     #[error("Groth16 verification error: {0}")]
     Groth16(anyhow::Error),
+    /// An error that occurs when the fflonk verification fails.
+    #[error("Fflonk verification error: {0}")]
+    Fflonk(anyhow::Error),
+    /// An error that occurs when the public values do not match the ABI schema.
+    #[error("Public values schema mismatch: {0}")]
+    PublicValuesSchema(AbiError),
 }
 
 pub(crate) fn verify_proof<C: SP1ProverComponents>(
@@ -141,8 +262,8 @@ pub(crate) fn verify_proof<C: SP1ProverComponents>(
                 proof,
                 vkey,
                 &bundle.public_values,
-                &if sp1_prover::build::sp1_dev_mode() {
-                    sp1_prover::build::plonk_bn254_artifacts_dev_dir()
+                &if sp1_prover::build::use_dev_artifacts(prover.security_preset) {
+                    sp1_prover::build::plonk_bn254_artifacts_dev_dir(prover.security_preset)
                 } else {
                     try_install_circuit_artifacts("plonk")
                 },
@@ -153,12 +274,30 @@ pub(crate) fn verify_proof<C: SP1ProverComponents>(
                 proof,
                 vkey,
                 &bundle.public_values,
-                &if sp1_prover::build::sp1_dev_mode() {
-                    sp1_prover::build::groth16_bn254_artifacts_dev_dir()
-                } else {
-                    try_install_circuit_artifacts("groth16")
-                },
+                &groth16_bn254_artifacts_dir(prover.security_preset),
             )
             .map_err(SP1VerificationError::Groth16),
+        #[cfg(feature = "fflonk")]
+        SP1Proof::Fflonk(proof) => prover
+            .verify_fflonk_bn254(
+                proof,
+                vkey,
+                &bundle.public_values,
+                &sp1_prover::build::fflonk_bn254_artifacts_dir(prover.security_preset),
+            )
+            .map_err(SP1VerificationError::Fflonk),
+        #[cfg(not(feature = "fflonk"))]
+        SP1Proof::Fflonk(_) => Err(SP1VerificationError::Fflonk(anyhow::anyhow!(
+            "fflonk proofs require the `fflonk` feature of `sp1-sdk`"
+        ))),
+    }
+}
+
+/// The directory of the artifacts Groth16 proofs are verified with.
+fn groth16_bn254_artifacts_dir(security_preset: SecurityPreset) -> PathBuf {
+    if sp1_prover::build::use_dev_artifacts(security_preset) {
+        sp1_prover::build::groth16_bn254_artifacts_dev_dir(security_preset)
+    } else {
+        try_install_circuit_artifacts("groth16")
     }
 }
@@ -0,0 +1,351 @@
+//! # SP1 Solidity Gateways
+//!
+//! A library for generating Solidity contracts that verify proofs of a single program.
+//!
+//! A gateway contract wraps [`ISP1Verifier`] for one program: it holds the verifier address and
+//! the program verification key as immutables, and decodes the public values committed by the
+//! program into a typed struct. The contract is generated along with a Foundry test, which checks
+//! it against a proof fixture exported with [`SolidityGateway::export_fixture`].
+//!
+//! [`ISP1Verifier`]: https://github.com/succinctlabs/sp1-contracts
+
+use std::{fs, path::PathBuf};
+
+use anyhow::{bail, Context, Result};
+use sp1_primitives::abi::PublicValuesSchema;
+use sp1_prover::{HashableKey, SP1VerifyingKey};
+
+use crate::{SP1Proof, SP1ProofWithPublicValues};
+
+/// The default import path of `ISP1Verifier`, as remapped in the SP1 project template.
+pub const DEFAULT_VERIFIER_IMPORT: &str = "@sp1-contracts/ISP1Verifier.sol";
+
+/// A field of the public values committed by a program.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SolidityField {
+    /// The name of the field in the Solidity struct.
+    pub name: String,
+    /// The Solidity ABI type of the field, such as `uint64`, `bytes32` or `address[]`.
+    pub ty: String,
+}
+
+/// A generator of a gateway contract for a program.
+///
+/// The public values of the program must be committed as the ABI encoding of a struct with the
+/// fields of the gateway, in order, for example with `alloy_sol_types::SolValue::abi_encode`. With
+/// a struct defined by [`crate::public_values!`], [`SolidityGateway::schema`] adds its fields, the
+/// program commits it with `sp1_zkvm::io::commit_abi`, and the contract checks the schema id
+/// committed with it.
+///
+/// # Example
+/// ```rust,no_run
+/// use sp1_sdk::{include_elf, solidity::SolidityGateway, ProverClient};
+///
+/// let elf = include_elf!("fibonacci-program");
+/// let client = ProverClient::from_env();
+/// let (_, vk) = client.setup(elf);
+///
+/// SolidityGateway::new("FibonacciGateway", &vk)
+///     .field("n", "uint32")
+///     .field("a", "uint32")
+///     .field("b", "uint32")
+///     .export("../contracts")
+///     .unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct SolidityGateway {
+    name: String,
+    program_vkey: String,
+    fields: Vec<SolidityField>,
+    schema_id: Option<String>,
+    verifier_import: String,
+}
+
+impl SolidityGateway {
+    /// Creates a gateway named `name` for the program with the given verifying key.
+    #[must_use]
+    pub fn new(name: impl Into<String>, vk: &SP1VerifyingKey) -> Self {
+        Self {
+            name: name.into(),
+            program_vkey: vk.bytes32(),
+            fields: Vec::new(),
+            schema_id: None,
+            verifier_import: DEFAULT_VERIFIER_IMPORT.to_string(),
+        }
+    }
+
+    /// Appends a field to the public values struct.
+    ///
+    /// # Panics
+    /// Panics if the fields were set with [`Self::schema`].
+    #[must_use]
+    pub fn field(mut self, name: impl Into<String>, ty: impl Into<String>) -> Self {
+        assert!(self.schema_id.is_none(), "the fields were set with a schema");
+        self.fields.push(SolidityField { name: name.into(), ty: ty.into() });
+        self
+    }
+
+    /// Sets the public values struct to the fields of a public values schema, and checks the
+    /// schema id committed with them.
+    ///
+    /// # Panics
+    /// Panics if fields were already added.
+    #[must_use]
+    pub fn schema<T: PublicValuesSchema>(mut self) -> Self {
+        assert!(self.fields.is_empty(), "the public values already have fields");
+        for (name, ty) in T::fields() {
+            self = self.field(name, ty);
+        }
+        self.schema_id = Some(format!("0x{}", hex::encode(T::schema_id())));
+        self
+    }
+
+    /// Sets the path `ISP1Verifier` is imported from, which defaults to
+    /// [`DEFAULT_VERIFIER_IMPORT`].
+    #[must_use]
+    pub fn verifier_import(mut self, path: impl Into<String>) -> Self {
+        self.verifier_import = path.into();
+        self
+    }
+
+    /// The name of the contract.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The fields of the public values struct.
+    #[must_use]
+    pub fn fields(&self) -> &[SolidityField] {
+        &self.fields
+    }
+
+    /// Generates the source of the contract.
+    pub fn contract(&self) -> Result<String> {
+        self.validate()?;
+        let fields = self
+            .fields
+            .iter()
+            .map(|field| format!("        {} {};", field.ty, field.name))
+            .collect::<Vec<_>>()
+            .join("\n");
+        Ok(self
+            .render(include_str!("../assets/SP1Gateway.txt"))
+            .replace("{PUBLIC_VALUES_FIELDS}", &fields))
+    }
+
+    /// Generates the source of the Foundry test of the contract.
+    pub fn test(&self) -> Result<String> {
+        self.validate()?;
+        Ok(self.render(include_str!("../assets/SP1GatewayTest.txt")))
+    }
+
+    /// Writes the contract to `src/<name>.sol` and its test to `test/<name>.t.sol` in the
+    /// Foundry project at `output_dir`.
+    pub fn export(&self, output_dir: impl Into<PathBuf>) -> Result<()> {
+        let output_dir: PathBuf = output_dir.into();
+        let files = [
+            (output_dir.join("src"), format!("{}.sol", self.name), self.contract()?),
+            (output_dir.join("test"), format!("{}.t.sol", self.name), self.test()?),
+        ];
+        for (dir, file, source) in files {
+            fs::create_dir_all(&dir).context("Failed to create output directory.")?;
+            let path = dir.join(file);
+            fs::write(&path, source).context("Failed to write contract file.")?;
+            tracing::info!("exported {} to {}", self.name, path.display());
+        }
+
+        Ok(())
+    }
+
+    /// Writes the proof fixture read by the test to `test/fixtures/<name>.json` in the Foundry
+    /// project at `output_dir`.
+    ///
+    /// The proof must be an onchain proof of the program, or a mock one.
+    pub fn export_fixture(
+        &self,
+        proof: &SP1ProofWithPublicValues,
+        output_dir: impl Into<PathBuf>,
+    ) -> Result<()> {
+        if !matches!(proof.proof, SP1Proof::Plonk(_) | SP1Proof::Groth16(_) | SP1Proof::Fflonk(_)) {
+            bail!("only Plonk, Groth16 and Fflonk proofs are verifiable onchain");
+        }
+
+        let fixture = serde_json::json!({
+            "vkey": self.program_vkey,
+            "publicValues": format!("0x{}", hex::encode(proof.public_values.as_slice())),
+            "proof": format!("0x{}", hex::encode(proof.bytes())),
+        });
+
+        let dir = output_dir.into().join("test").join("fixtures");
+        fs::create_dir_all(&dir).context("Failed to create output directory.")?;
+        let path = dir.join(format!("{}.json", self.name));
+        fs::write(&path, serde_json::to_string_pretty(&fixture)?)
+            .context("Failed to write fixture file.")?;
+        tracing::info!("exported fixture of {} to {}", self.name, path.display());
+
+        Ok(())
+    }
+
+    fn render(&self, template: &str) -> String {
+        let (notice, decode, encode) = match &self.schema_id {
+            Some(schema_id) => (
+                format!(", preceded by the schema id `{schema_id}`"),
+                [
+                    "(bytes32 schemaId, PublicValues memory values) =".to_string(),
+                    "    abi.decode(publicValues, (bytes32, PublicValues));".to_string(),
+                    format!("require(schemaId == bytes32({schema_id}), \"invalid schema\");"),
+                    "return values;".to_string(),
+                ]
+                .map(|line| format!("        {line}"))
+                .join("\n"),
+                format!("abi.encode(bytes32({schema_id}), values)"),
+            ),
+            None => (
+                String::new(),
+                "        return abi.decode(publicValues, (PublicValues));".to_string(),
+                "abi.encode(values)".to_string(),
+            ),
+        };
+        template
+            .replace("{SCHEMA_ID_NOTICE}", &notice)
+            .replace("{DECODE_PUBLIC_VALUES}", &decode)
+            .replace("{ENCODE_PUBLIC_VALUES}", &encode)
+            .replace("{CONTRACT_NAME}", &self.name)
+            .replace("{PROGRAM_VKEY}", &self.program_vkey)
+            .replace("{VERIFIER_IMPORT}", &self.verifier_import)
+    }
+
+    fn validate(&self) -> Result<()> {
+        if !is_identifier(&self.name) {
+            bail!("invalid contract name: {}", self.name);
+        }
+        if self.fields.is_empty() {
+            bail!("the public values of {} have no fields", self.name);
+        }
+        for (i, field) in self.fields.iter().enumerate() {
+            if !is_identifier(&field.name) {
+                bail!("invalid field name: {}", field.name);
+            }
+            if self.fields[..i].iter().any(|other| other.name == field.name) {
+                bail!("duplicate field name: {}", field.name);
+            }
+            if !is_abi_type(&field.ty) {
+                bail!("unsupported type of field {}: {}", field.name, field.ty);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Whether `name` is a valid Solidity identifier.
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$')
+}
+
+/// Whether `ty` is a Solidity ABI type, other than a tuple.
+fn is_abi_type(ty: &str) -> bool {
+    if let Some(inner) = ty.strip_suffix(']') {
+        let Some((element, length)) = inner.rsplit_once('[') else {
+            return false;
+        };
+        let valid_length = length.is_empty() || length.parse::<usize>().is_ok_and(|n| n > 0);
+        return valid_length && is_abi_type(element);
+    }
+
+    let sized = |prefix: &str, max: usize, step: usize| {
+        ty.strip_prefix(prefix).is_some_and(|bits| {
+            bits.is_empty()
+                || (!bits.starts_with('0')
+                    && bits.parse::<usize>().is_ok_and(|n| n > 0 && n <= max && n % step == 0))
+        })
+    };
+    matches!(ty, "bool" | "address" | "string" | "bytes")
+        || sized("uint", 256, 8)
+        || sized("int", 256, 8)
+        || (ty != "bytes" && sized("bytes", 32, 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_abi_types() {
+        for ty in ["uint32", "int256", "uint", "bool", "address", "bytes32", "string", "bytes"] {
+            assert!(is_abi_type(ty), "{ty}");
+        }
+        for ty in ["uint32[]", "bytes32[4]", "address[][2]"] {
+            assert!(is_abi_type(ty), "{ty}");
+        }
+        for ty in ["uint7", "uint264", "bytes33", "bytes0", "uint08", "u32", "uint32[0]", "[]"] {
+            assert!(!is_abi_type(ty), "{ty}");
+        }
+    }
+
+    #[test]
+    fn test_schema_fields() {
+        crate::public_values! {
+            struct PublicValues {
+                n: u32,
+                digest: [u8; 32],
+                owners: Vec<crate::abi::Address>,
+            }
+        }
+
+        let gateway = SolidityGateway {
+            name: "Gateway".to_string(),
+            program_vkey: format!("0x{}", "00".repeat(32)),
+            fields: Vec::new(),
+            schema_id: None,
+            verifier_import: DEFAULT_VERIFIER_IMPORT.to_string(),
+        }
+        .schema::<PublicValues>();
+        let fields = gateway.fields().iter().map(|f| (f.name.as_str(), f.ty.as_str()));
+        assert_eq!(
+            fields.collect::<Vec<_>>(),
+            [("n", "uint32"), ("digest", "bytes32"), ("owners", "address[]")]
+        );
+        let contract = gateway.contract().unwrap();
+        assert!(contract.contains("        address[] owners;"));
+
+        // The contract and its test expect the schema id before the values.
+        let schema_id = format!("0x{}", hex::encode(PublicValues::schema_id()));
+        assert!(contract.contains(&format!("require(schemaId == bytes32({schema_id}), ")));
+        assert!(gateway
+            .test()
+            .unwrap()
+            .contains(&format!("abi.encode(bytes32({schema_id}), values)")));
+    }
+
+    #[test]
+    fn test_program_vkey() {
+        let program_vkey = format!("0x{}", "ab".repeat(32));
+        let gateway = SolidityGateway {
+            name: "Gateway".to_string(),
+            program_vkey: program_vkey.clone(),
+            fields: Vec::new(),
+            schema_id: None,
+            verifier_import: DEFAULT_VERIFIER_IMPORT.to_string(),
+        }
+        .field("n", "uint32");
+
+        // The key is set in the constructor, so a deployment cannot pass another one.
+        let contract = gateway.contract().unwrap();
+        assert!(contract.contains(&format!("        programVKey = {program_vkey};")));
+        assert!(contract.contains("    constructor(address _verifier) {"));
+        assert!(gateway.test().unwrap().contains("new Gateway(verifier);"));
+    }
+
+    #[test]
+    fn test_identifiers() {
+        assert!(is_identifier("FibonacciGateway"));
+        assert!(is_identifier("_n"));
+        assert!(!is_identifier("1n"));
+        assert!(!is_identifier("n-1"));
+        assert!(!is_identifier(""));
+    }
+}
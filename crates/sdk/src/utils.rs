@@ -16,18 +16,51 @@ pub(crate) fn sp1_dump(elf: &[u8], stdin: &SP1Stdin) {
 
 /// Utility method for blocking on an async function.
 ///
-/// If we're already in a tokio runtime, we'll block in place. Otherwise, we'll create a new
-/// runtime.
+/// If we're already in a multi-threaded tokio runtime, we'll block in place. A current-thread
+/// runtime cannot drive the future while its only thread is blocked, so the future runs on a new
+/// runtime on a separate thread instead. Otherwise, we'll create a new runtime.
 #[cfg(feature = "network")]
-pub(crate) fn block_on<T>(fut: impl std::future::Future<Output = T>) -> T {
-    use tokio::task::block_in_place;
+pub(crate) fn block_on<T: Send>(fut: impl std::future::Future<Output = T> + Send) -> T {
+    use tokio::{
+        runtime::{Handle, Runtime, RuntimeFlavor},
+        task::block_in_place,
+    };
 
-    // Handle case if we're already in an tokio runtime.
-    if let Ok(handle) = tokio::runtime::Handle::try_current() {
-        block_in_place(|| handle.block_on(fut))
-    } else {
+    match Handle::try_current() {
+        // Handle case if we're already in a multi-threaded tokio runtime.
+        Ok(handle) if handle.runtime_flavor() == RuntimeFlavor::MultiThread => {
+            block_in_place(|| handle.block_on(fut))
+        }
+        // Handle case if we're in a current-thread tokio runtime.
+        Ok(_) => std::thread::scope(|s| {
+            s.spawn(move || {
+                let rt = Runtime::new().expect("Failed to create a new runtime");
+                rt.block_on(fut)
+            })
+            .join()
+            .unwrap_or_else(|err| std::panic::resume_unwind(err))
+        }),
         // Otherwise create a new runtime.
-        let rt = tokio::runtime::Runtime::new().expect("Failed to create a new runtime");
-        rt.block_on(fut)
+        Err(_) => {
+            let rt = Runtime::new().expect("Failed to create a new runtime");
+            rt.block_on(fut)
+        }
+    }
+}
+
+/// Utility method for running blocking work from an async function.
+///
+/// `f` runs on the blocking thread pool of the current tokio runtime, which works with both
+/// multi-threaded and current-thread runtimes, and the returned future completes once it is done.
+/// A panic in `f` is resumed in the caller.
+///
+/// # Panics
+/// Panics if called outside of a tokio runtime.
+#[cfg(feature = "async")]
+pub(crate) async fn spawn_blocking<T: Send + 'static>(f: impl FnOnce() -> T + Send + 'static) -> T {
+    match tokio::task::spawn_blocking(f).await {
+        Ok(result) => result,
+        Err(err) if err.is_panic() => std::panic::resume_unwind(err.into_panic()),
+        Err(err) => panic!("blocking task failed: {err}"),
     }
 }
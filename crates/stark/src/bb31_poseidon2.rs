@@ -164,7 +164,9 @@ pub mod baby_bear_poseidon2 {
     use serde::{Deserialize, Serialize};
     use sp1_primitives::RC_16_30;
 
-    use crate::{Com, StarkGenericConfig, ZeroCommitment, DIGEST_SIZE};
+    use crate::{
+        Com, FriParameters, SecurityPreset, StarkGenericConfig, ZeroCommitment, DIGEST_SIZE,
+    };
 
     pub type Val = BabyBear;
     pub type Challenge = BinomialExtensionField<Val, 4>;
@@ -207,102 +209,91 @@ pub mod baby_bear_poseidon2 {
         )
     }
 
+    /// The FRI config with the given parameters.
+    ///
+    /// The number of queries can be overridden with the `FRI_QUERIES` environment variable.
     #[must_use]
-    pub fn default_fri_config() -> FriConfig<ChallengeMmcs> {
+    pub fn fri_config(parameters: FriParameters) -> FriConfig<ChallengeMmcs> {
         let perm = my_perm();
         let hash = MyHash::new(perm.clone());
         let compress = MyCompress::new(perm.clone());
         let challenge_mmcs = ChallengeMmcs::new(ValMmcs::new(hash, compress));
         let num_queries = match std::env::var("FRI_QUERIES") {
             Ok(value) => value.parse().unwrap(),
-            Err(_) => 100,
+            Err(_) => parameters.num_queries,
         };
-        FriConfig { log_blowup: 1, num_queries, proof_of_work_bits: 16, mmcs: challenge_mmcs }
+        FriConfig {
+            log_blowup: parameters.log_blowup,
+            num_queries,
+            proof_of_work_bits: parameters.proof_of_work_bits,
+            mmcs: challenge_mmcs,
+        }
+    }
+
+    #[must_use]
+    pub fn default_fri_config() -> FriConfig<ChallengeMmcs> {
+        fri_config(SecurityPreset::Standard.compress())
     }
 
     #[must_use]
     pub fn compressed_fri_config() -> FriConfig<ChallengeMmcs> {
-        let perm = my_perm();
-        let hash = MyHash::new(perm.clone());
-        let compress = MyCompress::new(perm.clone());
-        let challenge_mmcs = ChallengeMmcs::new(ValMmcs::new(hash, compress));
-        let num_queries = match std::env::var("FRI_QUERIES") {
-            Ok(value) => value.parse().unwrap(),
-            Err(_) => 50,
-        };
-        FriConfig { log_blowup: 2, num_queries, proof_of_work_bits: 16, mmcs: challenge_mmcs }
+        fri_config(SecurityPreset::Standard.shrink())
     }
 
     #[must_use]
     pub fn ultra_compressed_fri_config() -> FriConfig<ChallengeMmcs> {
-        let perm = my_perm();
-        let hash = MyHash::new(perm.clone());
-        let compress = MyCompress::new(perm.clone());
-        let challenge_mmcs = ChallengeMmcs::new(ValMmcs::new(hash, compress));
-        let num_queries = match std::env::var("FRI_QUERIES") {
-            Ok(value) => value.parse().unwrap(),
-            Err(_) => 33,
-        };
-        FriConfig { log_blowup: 3, num_queries, proof_of_work_bits: 16, mmcs: challenge_mmcs }
+        fri_config(ULTRA_COMPRESSED_FRI_PARAMETERS)
     }
 
-    enum BabyBearPoseidon2Type {
-        Default,
-        Compressed,
-    }
+    const ULTRA_COMPRESSED_FRI_PARAMETERS: FriParameters =
+        FriParameters { log_blowup: 3, num_queries: 33, proof_of_work_bits: 16 };
 
     #[derive(Deserialize)]
     #[serde(from = "std::marker::PhantomData<BabyBearPoseidon2>")]
     pub struct BabyBearPoseidon2 {
         pub perm: Perm,
         pcs: Pcs,
-        config_type: BabyBearPoseidon2Type,
+        fri_parameters: FriParameters,
     }
 
     impl BabyBearPoseidon2 {
         #[must_use]
         pub fn new() -> Self {
-            let perm = my_perm();
-            let hash = MyHash::new(perm.clone());
-            let compress = MyCompress::new(perm.clone());
-            let val_mmcs = ValMmcs::new(hash, compress);
-            let dft = Dft {};
-            let fri_config = default_fri_config();
-            let pcs = Pcs::new(27, dft, val_mmcs, fri_config);
-            Self { pcs, perm, config_type: BabyBearPoseidon2Type::Default }
+            Self::with_fri_parameters(SecurityPreset::Standard.compress())
         }
 
         #[must_use]
         pub fn compressed() -> Self {
-            let perm = my_perm();
-            let hash = MyHash::new(perm.clone());
-            let compress = MyCompress::new(perm.clone());
-            let val_mmcs = ValMmcs::new(hash, compress);
-            let dft = Dft {};
-            let fri_config = compressed_fri_config();
-            let pcs = Pcs::new(27, dft, val_mmcs, fri_config);
-            Self { pcs, perm, config_type: BabyBearPoseidon2Type::Compressed }
+            Self::with_fri_parameters(SecurityPreset::Standard.shrink())
         }
 
         #[must_use]
         pub fn ultra_compressed() -> Self {
+            Self::with_fri_parameters(ULTRA_COMPRESSED_FRI_PARAMETERS)
+        }
+
+        /// A config with the given FRI parameters.
+        #[must_use]
+        pub fn with_fri_parameters(fri_parameters: FriParameters) -> Self {
             let perm = my_perm();
             let hash = MyHash::new(perm.clone());
             let compress = MyCompress::new(perm.clone());
             let val_mmcs = ValMmcs::new(hash, compress);
             let dft = Dft {};
-            let fri_config = ultra_compressed_fri_config();
-            let pcs = Pcs::new(27, dft, val_mmcs, fri_config);
-            Self { pcs, perm, config_type: BabyBearPoseidon2Type::Compressed }
+            let pcs = Pcs::new(27, dft, val_mmcs, fri_config(fri_parameters));
+            Self { pcs, perm, fri_parameters }
+        }
+
+        /// The FRI parameters of the config.
+        #[must_use]
+        pub fn fri_parameters(&self) -> FriParameters {
+            self.fri_parameters
         }
     }
 
     impl Clone for BabyBearPoseidon2 {
         fn clone(&self) -> Self {
-            match self.config_type {
-                BabyBearPoseidon2Type::Default => Self::new(),
-                BabyBearPoseidon2Type::Compressed => Self::compressed(),
-            }
+            Self::with_fri_parameters(self.fri_parameters)
         }
     }
 
@@ -1,4 +1,4 @@
-use std::env;
+use std::{env, fmt, str::FromStr};
 
 use serde::{Deserialize, Serialize};
 use sysinfo::System;
@@ -20,11 +20,155 @@ pub struct SP1ProverOpts {
     pub core_opts: SP1CoreOpts,
     /// Options for the recursion prover.
     pub recursion_opts: SP1CoreOpts,
+    /// The FRI parameters of every stage of the prover.
+    pub security_preset: SecurityPreset,
 }
 
 impl Default for SP1ProverOpts {
     fn default() -> Self {
-        Self { core_opts: SP1CoreOpts::default(), recursion_opts: SP1CoreOpts::recursion() }
+        Self {
+            core_opts: SP1CoreOpts::default(),
+            recursion_opts: SP1CoreOpts::recursion(),
+            security_preset: SecurityPreset::default(),
+        }
+    }
+}
+
+impl SP1ProverOpts {
+    /// Creates the options for the core and recursion provers, with the default security preset.
+    #[must_use]
+    pub fn new(core_opts: SP1CoreOpts, recursion_opts: SP1CoreOpts) -> Self {
+        Self { core_opts, recursion_opts, security_preset: SecurityPreset::default() }
+    }
+
+    /// Sets the security preset, which must be the one the prover was initialized with.
+    #[must_use]
+    pub fn with_security_preset(mut self, security_preset: SecurityPreset) -> Self {
+        self.security_preset = security_preset;
+        self
+    }
+}
+
+/// The parameters of the FRI protocol.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct FriParameters {
+    /// The log2 of the blowup factor of the low degree extension.
+    pub log_blowup: usize,
+    /// The number of queries.
+    pub num_queries: usize,
+    /// The number of bits of the proof of work before the query phase.
+    pub proof_of_work_bits: usize,
+}
+
+impl FriParameters {
+    /// The parameters with the fewest queries reaching `security_bits` of conjectured security,
+    /// where each query contributes `log_blowup` bits.
+    #[must_use]
+    pub fn conjectured(security_bits: usize, log_blowup: usize, proof_of_work_bits: usize) -> Self {
+        let num_queries = (security_bits - proof_of_work_bits).div_ceil(log_blowup);
+        Self { log_blowup, num_queries, proof_of_work_bits }
+    }
+
+    /// The parameters with the fewest queries reaching `security_bits` of provable security in the
+    /// query phase, where each query contributes `log_blowup / 2` bits by the Johnson bound.
+    ///
+    /// The soundness of the commit phase is bounded by the size of the extension field, and is not
+    /// accounted for.
+    #[must_use]
+    pub fn provable(security_bits: usize, log_blowup: usize, proof_of_work_bits: usize) -> Self {
+        let num_queries = (2 * (security_bits - proof_of_work_bits)).div_ceil(log_blowup);
+        Self { log_blowup, num_queries, proof_of_work_bits }
+    }
+
+    /// The conjectured security of the parameters in bits.
+    #[must_use]
+    pub fn conjectured_security_bits(&self) -> usize {
+        self.log_blowup * self.num_queries + self.proof_of_work_bits
+    }
+}
+
+/// Named settings of the FRI parameters of each proving stage, trading proof size against prover
+/// time.
+///
+/// The wrap stage needs a log blowup of at least 3 for its degree 9 constraints.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SecurityPreset {
+    /// About 116 bits of conjectured security, with a blowup of 2 for the core and compress
+    /// stages.
+    #[default]
+    Standard,
+    /// 100 bits of conjectured security with a blowup of 4, for smaller proofs at the cost of a
+    /// slower prover.
+    Conjectured100,
+    /// 100 bits of provable security in the query phase, with a blowup of 4.
+    Provable100,
+}
+
+impl SecurityPreset {
+    /// All the presets.
+    pub const ALL: [Self; 3] = [Self::Standard, Self::Conjectured100, Self::Provable100];
+
+    /// The FRI parameters of the core stage, which are the same as those of the compress stage.
+    #[must_use]
+    pub fn core(self) -> FriParameters {
+        self.compress()
+    }
+
+    /// The FRI parameters of the compress stage.
+    #[must_use]
+    pub fn compress(self) -> FriParameters {
+        match self {
+            Self::Standard => FriParameters::conjectured(116, 1, 16),
+            Self::Conjectured100 => FriParameters::conjectured(100, 2, 16),
+            Self::Provable100 => FriParameters::provable(100, 2, 16),
+        }
+    }
+
+    /// The FRI parameters of the shrink stage.
+    #[must_use]
+    pub fn shrink(self) -> FriParameters {
+        match self {
+            Self::Standard => FriParameters::conjectured(116, 2, 16),
+            Self::Conjectured100 => FriParameters::conjectured(100, 2, 16),
+            Self::Provable100 => FriParameters::provable(100, 2, 16),
+        }
+    }
+
+    /// The FRI parameters of the wrap stage.
+    #[must_use]
+    pub fn wrap(self) -> FriParameters {
+        match self {
+            Self::Standard => FriParameters::conjectured(116, 4, 16),
+            Self::Conjectured100 => FriParameters::conjectured(100, 4, 16),
+            Self::Provable100 => FriParameters::provable(100, 4, 16),
+        }
+    }
+
+    /// The name of the preset, as accepted by [`SecurityPreset::from_str`].
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Standard => "standard",
+            Self::Conjectured100 => "conjectured-100",
+            Self::Provable100 => "provable-100",
+        }
+    }
+}
+
+impl fmt::Display for SecurityPreset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for SecurityPreset {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL.into_iter().find(|preset| preset.name() == s).ok_or_else(|| {
+            let names = Self::ALL.map(Self::name).join(", ");
+            format!("unknown security preset `{s}`, expected one of: {names}")
+        })
     }
 }
 
@@ -159,3 +303,28 @@ impl SplitOpts {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_security_presets() {
+        let standard = SecurityPreset::Standard;
+        assert_eq!(standard.compress(), FriParameters::conjectured(116, 1, 16));
+        assert_eq!(standard.compress().num_queries, 100);
+        assert_eq!(standard.shrink().num_queries, 50);
+        assert_eq!(standard.wrap().num_queries, 25);
+
+        for preset in SecurityPreset::ALL {
+            assert_eq!(preset.name().parse::<SecurityPreset>(), Ok(preset));
+            for parameters in [preset.core(), preset.compress(), preset.shrink(), preset.wrap()] {
+                assert!(parameters.conjectured_security_bits() >= 100);
+            }
+            assert!(preset.wrap().log_blowup >= 3);
+        }
+        assert_eq!(SecurityPreset::Conjectured100.compress().num_queries, 42);
+        assert_eq!(SecurityPreset::Provable100.compress().num_queries, 84);
+        assert!("fast".parse::<SecurityPreset>().is_err());
+    }
+}
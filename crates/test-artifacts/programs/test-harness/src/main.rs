@@ -0,0 +1,26 @@
+#![no_main]
+
+sp1_zkvm::test_harness! {
+    #[test]
+    fn passes() {
+        assert_eq!(1 + 1, 2);
+    }
+
+    #[test]
+    fn reads_stdin() {
+        assert_eq!(sp1_zkvm::io::read::<u32>(), 7);
+    }
+
+    #[test]
+    fn panics() {
+        assert_eq!(1 + 1, 3, "addition is broken");
+    }
+
+    #[test]
+    fn loops() {
+        let mut n = 0u64;
+        loop {
+            n = core::hint::black_box(n.wrapping_add(1));
+        }
+    }
+}
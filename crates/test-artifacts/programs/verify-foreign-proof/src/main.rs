@@ -0,0 +1,14 @@
+//! This is a test program that takes in the vkey digest of a foreign machine and a list of public
+//! values digests, and then verifies the deferred foreign proof for each digest.
+
+#![no_main]
+sp1_zkvm::entrypoint!(main);
+
+use sp1_zkvm::lib::verify::verify_sp1_proof;
+
+pub fn main() {
+    let vkey = sp1_zkvm::io::read::<[u32; 8]>();
+    let pv_digests = sp1_zkvm::io::read::<Vec<[u8; 32]>>();
+    pv_digests.iter().for_each(|pv_digest| verify_sp1_proof(&vkey, pv_digest));
+    sp1_zkvm::io::commit(&pv_digests);
+}
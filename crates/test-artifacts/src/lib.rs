@@ -60,8 +60,12 @@ pub const BLS12381_DECOMPRESS_ELF: &[u8] = include_elf!("bls-decompress-test");
 
 pub const VERIFY_PROOF_ELF: &[u8] = include_elf!("verify-proof");
 
+pub const VERIFY_FOREIGN_PROOF_ELF: &[u8] = include_elf!("verify-foreign-proof");
+
 pub const PANIC_ELF: &[u8] = include_elf!("panic-test");
 
+pub const TEST_HARNESS_ELF: &[u8] = include_elf!("test-harness-test");
+
 pub const BLS12381_FP_ELF: &[u8] = include_elf!("bls12381-fp-test");
 
 pub const BLS12381_FP2_MUL_ELF: &[u8] = include_elf!("bls12381-fp2-mul-test");
@@ -0,0 +1,88 @@
+use bn::Fr;
+
+use crate::{
+    converter::{uncompressed_bytes_to_g1_point, uncompressed_bytes_to_g2_point},
+    error::Error,
+};
+
+use super::{
+    error::FflonkError,
+    verify::{FflonkEvaluations, FflonkProof, FflonkVerifyingKey},
+};
+
+/// The length of a proof: four commitments followed by sixteen evaluations.
+const PROOF_LENGTH: usize = 4 * 64 + 16 * 32;
+
+/// The length of a verifying key.
+const VK_LENGTH: usize = 8 + 7 * 32 + 128 + 64;
+
+fn fr_from_slice(buffer: &[u8]) -> Result<Fr, FflonkError> {
+    Fr::from_slice(buffer).map_err(|e| FflonkError::GeneralError(Error::Field(e)))
+}
+
+/// Loads a verifying key in the layout written by `sp1-recursion-gnark-ffi`: the power and the
+/// number of public inputs as big-endian u32s, then `k1`, `k2`, `w`, `w3`, `w4`, `w8`, `wr`,
+/// `[x]₂` and `C0`.
+pub(crate) fn load_fflonk_verifying_key_from_bytes(
+    buffer: &[u8],
+) -> Result<FflonkVerifyingKey, FflonkError> {
+    if buffer.len() != VK_LENGTH {
+        return Err(FflonkError::GeneralError(Error::InvalidData));
+    }
+
+    let power = u32::from_be_bytes([buffer[0], buffer[1], buffer[2], buffer[3]]);
+    let n_public = u32::from_be_bytes([buffer[4], buffer[5], buffer[6], buffer[7]]) as usize;
+    let scalar = |i: usize| fr_from_slice(&buffer[8 + 32 * i..40 + 32 * i]);
+
+    Ok(FflonkVerifyingKey {
+        power,
+        n_public,
+        k1: scalar(0)?,
+        k2: scalar(1)?,
+        w: scalar(2)?,
+        w3: scalar(3)?,
+        w4: scalar(4)?,
+        w8: scalar(5)?,
+        wr: scalar(6)?,
+        x2: uncompressed_bytes_to_g2_point(&buffer[232..360])?,
+        c0: uncompressed_bytes_to_g1_point(&buffer[360..424])?,
+    })
+}
+
+/// Loads a proof in the layout of the `bytes32[24]` calldata of the snarkjs verifier contract:
+/// `C1`, `C2`, `W1` and `W2` uncompressed, followed by the evaluations.
+pub(crate) fn load_fflonk_proof_from_bytes(buffer: &[u8]) -> Result<FflonkProof, FflonkError> {
+    if buffer.len() != PROOF_LENGTH {
+        return Err(FflonkError::GeneralError(Error::InvalidData));
+    }
+
+    let eval = |i: usize| fr_from_slice(&buffer[256 + 32 * i..288 + 32 * i]);
+
+    // The last evaluation is the inverse the verifier contract uses to batch its inversions, which
+    // is not needed here, but it must still be a field element.
+    eval(15)?;
+
+    Ok(FflonkProof {
+        c1: uncompressed_bytes_to_g1_point(&buffer[..64])?,
+        c2: uncompressed_bytes_to_g1_point(&buffer[64..128])?,
+        w1: uncompressed_bytes_to_g1_point(&buffer[128..192])?,
+        w2: uncompressed_bytes_to_g1_point(&buffer[192..256])?,
+        evaluations: FflonkEvaluations {
+            ql: eval(0)?,
+            qr: eval(1)?,
+            qm: eval(2)?,
+            qo: eval(3)?,
+            qc: eval(4)?,
+            s1: eval(5)?,
+            s2: eval(6)?,
+            s3: eval(7)?,
+            a: eval(8)?,
+            b: eval(9)?,
+            c: eval(10)?,
+            z: eval(11)?,
+            zw: eval(12)?,
+            t1w: eval(13)?,
+            t2w: eval(14)?,
+        },
+    })
+}
@@ -0,0 +1,15 @@
+use thiserror_no_std::Error;
+
+#[derive(Error, Debug)]
+pub enum FflonkError {
+    #[error("Inverse not found")]
+    InverseNotFound,
+    #[error("Invalid witness")]
+    InvalidWitness,
+    #[error("Pairing check failed")]
+    PairingCheckFailed,
+    #[error("Fflonk vkey hash mismatch")]
+    FflonkVkeyHashMismatch,
+    #[error("General error")]
+    GeneralError(#[from] crate::error::Error),
+}
@@ -0,0 +1,70 @@
+mod converter;
+mod transcript;
+mod verify;
+
+pub(crate) mod error;
+
+pub(crate) use converter::{load_fflonk_proof_from_bytes, load_fflonk_verifying_key_from_bytes};
+pub(crate) use verify::verify_fflonk_raw;
+
+use error::FflonkError;
+use sha2::{Digest, Sha256};
+
+use crate::{bn254_public_values, decode_sp1_vkey_hash, error::Error};
+
+/// A verifier for fflonk zero-knowledge proofs.
+///
+/// fflonk proofs are generated with snarkjs from a universal powers of tau setup, so there are no
+/// verifying keys bundled with this crate: the key is the `fflonk_vk.bin` file written alongside
+/// the proving key when the fflonk artifacts are built.
+#[derive(Debug)]
+pub struct FflonkVerifier;
+
+impl FflonkVerifier {
+    /// # Arguments
+    ///
+    /// * `proof` - The proof bytes.
+    /// * `public_inputs` - The SP1 public inputs.
+    /// * `sp1_vkey_hash` - The SP1 vkey hash.
+    ///   This is generated in the following manner:
+    ///
+    /// ```ignore
+    /// use sp1_sdk::ProverClient;
+    /// let client = ProverClient::new();
+    /// let (pk, vk) = client.setup(ELF);
+    /// let sp1_vkey_hash = vk.bytes32();
+    /// ```
+    /// * `fflonk_vk` - The fflonk verifying key bytes.
+    ///
+    /// # Returns
+    ///
+    /// A success [`Result`] if verification succeeds, or a [`FflonkError`] if verification fails.
+    pub fn verify(
+        proof: &[u8],
+        sp1_public_inputs: &[u8],
+        sp1_vkey_hash: &str,
+        fflonk_vk: &[u8],
+    ) -> Result<(), FflonkError> {
+        // Hash the vk and get the first 4 bytes.
+        let fflonk_vk_hash: [u8; 4] = Sha256::digest(fflonk_vk)[..4]
+            .try_into()
+            .map_err(|_| FflonkError::GeneralError(Error::InvalidData))?;
+
+        // Check to make sure that this proof was generated by the fflonk proving key corresponding
+        // to the given fflonk vk.
+        //
+        // SP1 prepends the raw fflonk proof with the first 4 bytes of the fflonk vkey to
+        // facilitate this check.
+        if proof.len() < 4 || fflonk_vk_hash != proof[..4] {
+            return Err(FflonkError::FflonkVkeyHashMismatch);
+        }
+
+        let sp1_vkey_hash = decode_sp1_vkey_hash(sp1_vkey_hash)?;
+        let public_inputs = bn254_public_values(&sp1_vkey_hash, sp1_public_inputs);
+
+        let fflonk_vk = load_fflonk_verifying_key_from_bytes(fflonk_vk)?;
+        let proof = load_fflonk_proof_from_bytes(&proof[4..])?;
+
+        verify_fflonk_raw(&fflonk_vk, &proof, &public_inputs)
+    }
+}
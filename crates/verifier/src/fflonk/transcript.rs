@@ -0,0 +1,37 @@
+use alloc::vec::Vec;
+use bn::{AffineG1, Fr};
+use sha3::{Digest, Keccak256};
+
+use crate::error::Error;
+
+use super::error::FflonkError;
+
+/// The Keccak256 transcript of the snarkjs fflonk prover.
+///
+/// Scalars are absorbed as 32 big-endian bytes and commitments as their uncompressed big-endian
+/// coordinates. Each challenge hashes everything absorbed since the previous one.
+#[derive(Debug, Default)]
+pub(crate) struct Transcript {
+    data: Vec<u8>,
+}
+
+impl Transcript {
+    pub(crate) fn add_scalar(&mut self, scalar: &Fr) {
+        self.data.extend_from_slice(&scalar.into_u256().to_bytes_be());
+    }
+
+    pub(crate) fn add_commitment(&mut self, point: &AffineG1) -> Result<(), FflonkError> {
+        let mut bytes = [0u8; 64];
+        point.x().to_big_endian(&mut bytes[..32]).map_err(Error::Field)?;
+        point.y().to_big_endian(&mut bytes[32..]).map_err(Error::Field)?;
+        self.data.extend_from_slice(&bytes);
+        Ok(())
+    }
+
+    /// Derives a challenge from the absorbed data and resets the transcript.
+    pub(crate) fn get_challenge(&mut self) -> Result<Fr, FflonkError> {
+        let hash = Keccak256::digest(&self.data);
+        self.data.clear();
+        Fr::from_bytes_be_mod_order(&hash).map_err(|e| FflonkError::GeneralError(Error::Field(e)))
+    }
+}
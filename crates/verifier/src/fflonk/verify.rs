@@ -0,0 +1,289 @@
+use bn::{pairing_batch, AffineG1, AffineG2, Fr, Group, Gt, G1, G2};
+
+use super::{error::FflonkError, transcript::Transcript};
+
+/// Verification key for the fflonk proof.
+pub(crate) struct FflonkVerifyingKey {
+    /// The log2 of the domain size.
+    pub(crate) power: u32,
+    pub(crate) n_public: usize,
+    /// The coset shifts of the permutation argument.
+    pub(crate) k1: Fr,
+    pub(crate) k2: Fr,
+    /// The generator of the domain.
+    pub(crate) w: Fr,
+    /// Primitive third, fourth and eighth roots of unity.
+    pub(crate) w3: Fr,
+    pub(crate) w4: Fr,
+    pub(crate) w8: Fr,
+    /// A cube root of the generator of the domain.
+    pub(crate) wr: Fr,
+    /// `[x]₂` from the powers of tau.
+    pub(crate) x2: AffineG2,
+    /// The commitment to the selector and permutation polynomials.
+    pub(crate) c0: AffineG1,
+}
+
+/// The evaluations of the proof, at ξ except for the ones at ξω.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct FflonkEvaluations {
+    pub(crate) ql: Fr,
+    pub(crate) qr: Fr,
+    pub(crate) qm: Fr,
+    pub(crate) qo: Fr,
+    pub(crate) qc: Fr,
+    pub(crate) s1: Fr,
+    pub(crate) s2: Fr,
+    pub(crate) s3: Fr,
+    pub(crate) a: Fr,
+    pub(crate) b: Fr,
+    pub(crate) c: Fr,
+    pub(crate) z: Fr,
+    pub(crate) zw: Fr,
+    pub(crate) t1w: Fr,
+    pub(crate) t2w: Fr,
+}
+
+/// Proof for the fflonk verification.
+#[derive(Debug)]
+pub(crate) struct FflonkProof {
+    pub(crate) c1: AffineG1,
+    pub(crate) c2: AffineG1,
+    pub(crate) w1: AffineG1,
+    pub(crate) w2: AffineG1,
+    pub(crate) evaluations: FflonkEvaluations,
+}
+
+/// Verifies an fflonk proof generated by snarkjs.
+///
+/// The polynomials are committed in three batches, `C0` in the verifying key, and `C1` and `C2` in
+/// the proof, which are opened on the roots of ξ, with the opening of `C2` also covering the roots
+/// of ξω. The openings are checked with a single pairing, and the remainders on each set of roots
+/// are interpolated from the claimed evaluations.
+pub(crate) fn verify_fflonk_raw(
+    vk: &FflonkVerifyingKey,
+    proof: &FflonkProof,
+    public_inputs: &[Fr],
+) -> Result<(), FflonkError> {
+    if public_inputs.len() != vk.n_public {
+        return Err(FflonkError::InvalidWitness);
+    }
+    let e = proof.evaluations;
+
+    // Derive the challenges β, γ, the seed of ξ, α and y.
+    let mut transcript = Transcript::default();
+    transcript.add_commitment(&vk.c0)?;
+    for public_input in public_inputs {
+        transcript.add_scalar(public_input);
+    }
+    transcript.add_commitment(&proof.c1)?;
+    let beta = transcript.get_challenge()?;
+
+    transcript.add_scalar(&beta);
+    let gamma = transcript.get_challenge()?;
+
+    transcript.add_scalar(&gamma);
+    transcript.add_commitment(&proof.c2)?;
+    let xi_seed = transcript.get_challenge()?;
+
+    transcript.add_scalar(&xi_seed);
+    for evaluation in
+        [e.ql, e.qr, e.qm, e.qo, e.qc, e.s1, e.s2, e.s3, e.a, e.b, e.c, e.z, e.zw, e.t1w, e.t2w]
+    {
+        transcript.add_scalar(&evaluation);
+    }
+    let alpha = transcript.get_challenge()?;
+
+    transcript.add_scalar(&alpha);
+    transcript.add_commitment(&proof.w1)?;
+    let y = transcript.get_challenge()?;
+
+    // The opening sets are the roots of h₀⁸ = ξ, h₁⁴ = ξ, h₂³ = ξ and h₃³ = ξω, where h₀, h₁ and
+    // h₂ are the seed to the powers 3, 6 and 8, so that ξ is the seed to the power 24.
+    let xi_seed_square = xi_seed * xi_seed;
+    let h0 = xi_seed_square * xi_seed;
+    let h1 = h0 * h0;
+    let h2 = h1 * xi_seed_square;
+    let h3 = h2 * vk.wr;
+    let s0 = roots::<8>(h0, vk.w8);
+    let s1 = roots::<4>(h1, vk.w4);
+    let s2 = roots::<3>(h2, vk.w3);
+    let s3 = roots::<3>(h3, vk.w3);
+    let xi = h2 * h2 * h2;
+
+    // Compute Z_H(ξ) = ξⁿ - 1.
+    let mut xi_n = xi;
+    let mut n = Fr::one();
+    for _ in 0..vk.power {
+        xi_n = xi_n * xi_n;
+        n = n + n;
+    }
+    let zh = xi_n - Fr::one();
+    let zh_inv = zh.inverse().ok_or(FflonkError::InverseNotFound)?;
+
+    // Compute Lᵢ(ξ) = ωⁱ⁻¹(ξⁿ - 1) / (n(ξ - ωⁱ⁻¹)) and PI(ξ) = -∑ᵢ wᵢLᵢ(ξ).
+    let lagrange = |wi: Fr| -> Result<Fr, FflonkError> {
+        let den = (n * (xi - wi)).inverse().ok_or(FflonkError::InverseNotFound)?;
+        Ok(wi * zh * den)
+    };
+    let lagrange_one = lagrange(Fr::one())?;
+    let mut pi = Fr::zero();
+    let mut wi = Fr::one();
+    for public_input in public_inputs {
+        pi = pi - *public_input * lagrange(wi)?;
+        wi = wi * vk.w;
+    }
+
+    // C0(X) = QL(X⁸) + X·QR(X⁸) + X²·QO(X⁸) + X³·QM(X⁸) + X⁴·QC(X⁸) + X⁵·S1(X⁸) + X⁶·S2(X⁸) +
+    // X⁷·S3(X⁸), so its evaluations on S₀ follow from the claimed evaluations at ξ.
+    let c0_coefficients = [e.ql, e.qr, e.qo, e.qm, e.qc, e.s1, e.s2, e.s3];
+    let r0 = interpolate(&s0, &s0.map(|x| horner(&c0_coefficients, x)), y)?;
+
+    // C1(X) = a(X⁴) + X·b(X⁴) + X²·c(X⁴) + X³·T0(X⁴), with the quotient of the gate constraint
+    // T0(ξ) = (qL·a + qR·b + qM·a·b + qO·c + qC + PI(ξ)) / Z_H(ξ).
+    let t0 = (e.ql * e.a + e.qr * e.b + e.qm * e.a * e.b + e.qo * e.c + e.qc + pi) * zh_inv;
+    let r1 = interpolate(&s1, &s1.map(|x| horner(&[e.a, e.b, e.c, t0], x)), y)?;
+
+    // C2(X) = z(X³) + X·T1(X³) + X²·T2(X³), with the quotients of the permutation constraints
+    // T1(ξ) = L₁(ξ)(z - 1) / Z_H(ξ) and
+    // T2(ξ) = ((a + βξ + γ)(b + βk₁ξ + γ)(c + βk₂ξ + γ)z - (a + βs₁ + γ)(b + βs₂ + γ)(c + βs₃ + γ)zω)
+    //         / Z_H(ξ).
+    let t1 = (e.z - Fr::one()) * lagrange_one * zh_inv;
+    let beta_xi = beta * xi;
+    let t21 = (e.a + beta_xi + gamma)
+        * (e.b + beta_xi * vk.k1 + gamma)
+        * (e.c + beta_xi * vk.k2 + gamma)
+        * e.z;
+    let t22 = (e.a + beta * e.s1 + gamma)
+        * (e.b + beta * e.s2 + gamma)
+        * (e.c + beta * e.s3 + gamma)
+        * e.zw;
+    let t2 = (t21 - t22) * zh_inv;
+    let c2_evaluations =
+        [s2.map(|x| horner(&[e.z, t1, t2], x)), s3.map(|x| horner(&[e.zw, e.t1w, e.t2w], x))];
+    let r2 = interpolate(&[s2, s3].concat(), &c2_evaluations.concat(), y)?;
+
+    // Compute the vanishing polynomials of the opening sets at y, to scale the batches to the
+    // one of C0.
+    let vanishing = |points: &[Fr]| points.iter().fold(Fr::one(), |acc, &p| acc * (y - p));
+    let z0 = vanishing(&s0);
+    let z1 = vanishing(&s1);
+    let z2 = vanishing(&s2) * vanishing(&s3);
+    let quotient1 = alpha * z0 * z1.inverse().ok_or(FflonkError::InverseNotFound)?;
+    let quotient2 = alpha * alpha * z0 * z2.inverse().ok_or(FflonkError::InverseNotFound)?;
+
+    // F = C0 + q₁·C1 + q₂·C2, E = (r₀ + q₁·r₁ + q₂·r₂)·[1]₁ and J = Z₀(y)·W1.
+    let f_point = G1::from(vk.c0) + G1::from(proof.c1) * quotient1 + G1::from(proof.c2) * quotient2;
+    let e_point = G1::one() * (r0 + quotient1 * r1 + quotient2 * r2);
+    let j_point = G1::from(proof.w1) * z0;
+
+    // Check that e(F - E - J + y·W2, [1]₂) = e(W2, [x]₂).
+    let w2 = G1::from(proof.w2);
+    let lhs = f_point - e_point - j_point + w2 * y;
+    if pairing_batch(&[(lhs, G2::one()), (-w2, G2::from(vk.x2))]) == Gt::one() {
+        Ok(())
+    } else {
+        Err(FflonkError::PairingCheckFailed)
+    }
+}
+
+/// Computes `h·wⁱ` for `i < N`.
+fn roots<const N: usize>(h: Fr, w: Fr) -> [Fr; N] {
+    let mut root = h;
+    core::array::from_fn(|_| {
+        let current = root;
+        root = root * w;
+        current
+    })
+}
+
+/// Evaluates the polynomial with the given coefficients at `x`.
+fn horner(coefficients: &[Fr], x: Fr) -> Fr {
+    coefficients.iter().rev().fold(Fr::zero(), |acc, &coefficient| acc * x + coefficient)
+}
+
+/// Evaluates at `x` the polynomial of degree less than `points.len()` taking the given values on
+/// the points.
+fn interpolate(points: &[Fr], values: &[Fr], x: Fr) -> Result<Fr, FflonkError> {
+    let mut result = Fr::zero();
+    for (i, (&point, &value)) in points.iter().zip(values).enumerate() {
+        let mut num = Fr::one();
+        let mut den = Fr::one();
+        for (j, &other) in points.iter().enumerate() {
+            if i != j {
+                num = num * (x - other);
+                den = den * (point - other);
+            }
+        }
+        result = result + value * num * den.inverse().ok_or(FflonkError::InverseNotFound)?;
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fflonk::{load_fflonk_proof_from_bytes, load_fflonk_verifying_key_from_bytes};
+
+    #[test]
+    fn test_interpolate() {
+        let fr = |x: u64| Fr::from_str(&x.to_string()).unwrap();
+
+        let points = roots::<4>(fr(2), fr(3));
+        assert_eq!(points, [fr(2), fr(6), fr(18), fr(54)]);
+
+        // p(X) = 3 + 2X + X², interpolated from its values on the points.
+        let values = points.map(|x| horner(&[fr(3), fr(2), fr(1)], x));
+        assert_eq!(interpolate(&points, &values, fr(11)).unwrap(), fr(3 + 2 * 11 + 11 * 11));
+
+        // Repeated points cannot be interpolated.
+        assert!(interpolate(&[fr(1), fr(1)], &[fr(2), fr(3)], fr(11)).is_err());
+    }
+
+    /// Loads the snarkjs proof of `7 * 3 = 21` with the public inputs `1` and `2` in
+    /// `test_binaries`, see the README for how it is generated.
+    fn load_test_vector() -> (FflonkVerifyingKey, FflonkProof, [Fr; 2]) {
+        extern crate std;
+
+        let vk = std::fs::read("test_binaries/fflonk-vk.bin").unwrap();
+        let proof = std::fs::read("test_binaries/fflonk-proof.bin").unwrap();
+        let vk = load_fflonk_verifying_key_from_bytes(&vk).unwrap();
+        let proof = load_fflonk_proof_from_bytes(&proof).unwrap();
+        (vk, proof, [Fr::one(), Fr::one() + Fr::one()])
+    }
+
+    #[test]
+    #[ignore]
+    fn test_verify_snarkjs_proof() {
+        // A snarkjs proof only verifies if the transcript absorbs the same values in the same
+        // order, and if C0 is opened with the coefficient order of the snarkjs setup.
+        let (vk, proof, public_inputs) = load_test_vector();
+        verify_fflonk_raw(&vk, &proof, &public_inputs).expect("fflonk proof is invalid");
+    }
+
+    #[test]
+    #[ignore]
+    fn test_verify_tampered_snarkjs_proof() {
+        let (vk, mut proof, public_inputs) = load_test_vector();
+
+        // Other public inputs.
+        let other_inputs = [public_inputs[1], public_inputs[0]];
+        assert!(verify_fflonk_raw(&vk, &proof, &other_inputs).is_err());
+        assert!(verify_fflonk_raw(&vk, &proof, &public_inputs[..1]).is_err());
+
+        // Swapped evaluations of qm and qo, which are in a different order in the transcript and
+        // in C0.
+        let evaluations = proof.evaluations;
+        proof.evaluations.qm = evaluations.qo;
+        proof.evaluations.qo = evaluations.qm;
+        assert!(verify_fflonk_raw(&vk, &proof, &public_inputs).is_err());
+        proof.evaluations = evaluations;
+
+        // A tampered evaluation and commitment.
+        proof.evaluations.a = evaluations.a + Fr::one();
+        assert!(verify_fflonk_raw(&vk, &proof, &public_inputs).is_err());
+        proof.evaluations = evaluations;
+        proof.w2 = AffineG1::from_jacobian(G1::from(proof.w2) + G1::one()).unwrap();
+        assert!(verify_fflonk_raw(&vk, &proof, &public_inputs).is_err());
+    }
+}
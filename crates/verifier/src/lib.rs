@@ -1,4 +1,5 @@
-//! This crate provides verifiers for SP1 Groth16 and Plonk BN254 proofs in a no-std environment.
+//! This crate provides verifiers for SP1 Groth16, Plonk and fflonk BN254 proofs in a no-std
+//! environment.
 //! It is patched for efficient verification within the SP1 ZKVM context.
 
 #![cfg_attr(not(feature = "std"), no_std)]
@@ -27,6 +28,10 @@ pub use groth16::error::Groth16Error;
 pub use groth16::Groth16Verifier;
 mod groth16;
 
+pub use fflonk::error::FflonkError;
+pub use fflonk::FflonkVerifier;
+mod fflonk;
+
 pub use plonk::error::PlonkError;
 pub use plonk::PlonkVerifier;
 mod plonk;
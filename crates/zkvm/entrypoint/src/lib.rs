@@ -6,13 +6,34 @@ pub mod syscalls;
 #[cfg(feature = "lib")]
 pub mod io {
     pub use sp1_lib::io::*;
+
+    /// Commits the ABI encoding of public values defined with `sp1_zkvm::public_values!`, along
+    /// with the id of their schema.
+    ///
+    /// This must be the only value committed by the program, since the host decodes the whole
+    /// public values buffer with the schema.
+    #[cfg(feature = "abi")]
+    pub fn commit_abi<T: sp1_primitives::abi::PublicValuesSchema>(value: &T) {
+        commit_slice(&value.abi_encode());
+    }
+}
+
+#[cfg(feature = "abi")]
+pub mod abi {
+    pub use sp1_primitives::abi::*;
 }
 
+#[cfg(feature = "abi")]
+pub use sp1_primitives::public_values;
+
 #[cfg(feature = "lib")]
 pub mod lib {
     pub use sp1_lib::*;
 }
 
+#[cfg(feature = "lib")]
+pub mod test;
+
 #[cfg(all(target_os = "zkvm", feature = "libm"))]
 mod libm;
 
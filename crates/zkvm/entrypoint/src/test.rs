@@ -0,0 +1,75 @@
+//! A test harness running the `#[test]` functions of a guest crate inside the zkVM.
+//!
+//! The tests are declared with [`test_harness!`](crate::test_harness) in an integration test
+//! target with `harness = false`, and run by `cargo prove test`. Each execution of the test binary
+//! runs a single test, whose name the host writes to the stdin, so that a panic, which aborts the
+//! program, only fails that test. Without a name, the program commits the names of its tests.
+
+use crate::io;
+
+/// A test declared with [`test_harness!`](crate::test_harness).
+pub struct TestCase {
+    /// The name of the test function.
+    pub name: &'static str,
+    /// The test function.
+    pub run: fn(),
+}
+
+/// Runs the test whose name the host wrote to the stdin, or commits the names of the tests when
+/// there is none.
+///
+/// The panic message of a failing test is reported to the host through
+/// [`FD_TEST_PANIC`](io::FD_TEST_PANIC), before the program halts with a non-zero exit code.
+pub fn run_tests(tests: &[TestCase]) {
+    let Some(name) = io::read::<Option<String>>() else {
+        io::commit(&tests.iter().map(|test| test.name).collect::<Vec<_>>());
+        return;
+    };
+
+    std::panic::set_hook(Box::new(|info| {
+        eprintln!("{info}");
+        io::write(io::FD_TEST_PANIC, info.to_string().as_bytes());
+    }));
+
+    match tests.iter().find(|test| test.name == name) {
+        Some(test) => (test.run)(),
+        None => panic!("there is no test named {name}"),
+    }
+}
+
+/// Declares the `#[test]` functions of an integration test target run inside the zkVM by
+/// `cargo prove test`.
+///
+/// The test target must set `harness = false` in its `Cargo.toml`, and its crate must be
+/// `#![no_main]`, like a program.
+///
+/// ```ignore
+/// #![no_main]
+///
+/// sp1_zkvm::test_harness! {
+///     #[test]
+///     fn addition() {
+///         assert_eq!(1 + 1, 2);
+///     }
+///
+///     #[test]
+///     fn allocation() {
+///         let words = vec![1u32; 1024];
+///         assert_eq!(words.iter().sum::<u32>(), 1024);
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! test_harness {
+    ($(#[test] $(#[$attr:meta])* fn $name:ident() $body:block)*) => {
+        $($(#[$attr])* fn $name() $body)*
+
+        fn __sp1_run_tests() {
+            $crate::test::run_tests(&[
+                $($crate::test::TestCase { name: stringify!($name), run: $name }),*
+            ]);
+        }
+
+        $crate::entrypoint!(__sp1_run_tests);
+    };
+}
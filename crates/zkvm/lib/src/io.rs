@@ -22,6 +22,9 @@ pub const FD_ECRECOVER_HOOK_2: u32 = 7;
 /// The file descriptor through which to access `hook_ed_decompress`.
 pub const FD_EDDECOMPRESS: u32 = 8;
 
+/// The file descriptor through which a failing guest test reports its panic message.
+pub const FD_TEST_PANIC: u32 = 9;
+
 /// A writer that writes to a file descriptor inside the zkVM.
 struct SyscallWriter {
     fd: u32,